@@ -1,6 +1,6 @@
+use std::cell::RefCell;
 use std::rc::Rc;
-use std::time::Duration;
-use std::{cell::RefCell, collections::HashMap};
+use std::time::{Duration, SystemTime};
 
 use color_eyre::eyre::Result;
 use ratatui::{
@@ -8,64 +8,67 @@ use ratatui::{
     crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
     layout::{Alignment, Constraint, Layout, Rect},
     style::Stylize,
-    widgets::{Clear, Paragraph, Tabs, Widget},
+    widgets::{Clear, LineGauge, Paragraph, Tabs, Widget},
     DefaultTerminal,
 };
 
 use crate::common::event::Event;
 use crate::common::generic_message::InterfaceType;
+use crate::connections::playback::{Playback, PLAYBACK_RATES};
 use crate::connections::ros2::ConnectionROS2;
-use crate::connections::ConnectionType;
+use crate::connections::{Connection, ConnectionType, NodeName};
+use crate::popups::connection_views_popup::{ConnectionViewsPopupState, FROM_CONNECTION_FACTORIES};
+use crate::popups::input_popup::InputPopup;
+use crate::popups::new_connection_popup::NewConnectionPopupState;
 use crate::popups::new_field_popup::NewFieldPopupState;
 use crate::popups::new_node_popup::NewNodePopupState;
 use crate::popups::new_topic_popup::NewTopicPopupState;
 use crate::popups::text_popup::TextPopup;
 use crate::popups::TuiPopup;
+use crate::views::action_list::ActionListState;
+use crate::views::bitrate_plot::BitratePlotState;
+use crate::views::delay_plot::DelayPlotState;
 use crate::views::hz_plot::HzPlotState;
+use crate::views::parameter_events::ParameterEventsState;
 use crate::views::raw_message::RawMessageState;
+use crate::views::rosout::RosoutState;
+use crate::views::tf_tree::TfTreeState;
 use crate::views::topic_publisher::TopicPublisherState;
-use crate::views::{node_list::NodeListState, topic_list::TopicListState, FromConnection, TuiView};
-use crate::views::{FieldInfo, NodeInfo};
+use crate::views::{node_list::NodeListState, topic_list::TopicListState, TuiView};
 use crate::{common::style::SELECTED_STYLE, views::ConnectionInfo};
 
+/// Seconds skipped by the seek backward/forward keys.
+const PLAYBACK_SEEK_STEP: f64 = 5.0;
+
 #[derive(Default)]
 pub struct AppMetrics {
     pub draw_count: u32,
 }
 
-type NewConnectionFactoryClosure = dyn Fn(ConnectionInfo) -> Rc<RefCell<dyn TuiView>> + Send + Sync;
-type NewFieldFactoryClosure = dyn Fn(FieldInfo) -> Rc<RefCell<dyn TuiView>> + Send + Sync;
-type NewNodeFactoryClosure = dyn Fn(NodeInfo) -> Rc<RefCell<dyn TuiView>> + Send + Sync;
-
-// NOTE: This factory map is currently unused — it is kept as scaffolding for future
-// multi-connection support (see Event::NewConnection and the commented-out connection types
-// in connections/mod.rs).
-static FROM_NEW_CONNECTION_FACTORIES: once_cell::sync::Lazy<
-    HashMap<&'static str, Box<NewConnectionFactoryClosure>>,
-> = once_cell::sync::Lazy::new(|| {
-    let mut m = HashMap::new();
-    m.insert(
-        "topic_list",
-        Box::new(|connection_info: ConnectionInfo| {
-            Rc::new(RefCell::new(TopicListState::from_connection(
-                connection_info,
-            ))) as Rc<RefCell<dyn TuiView>>
-        }) as Box<NewConnectionFactoryClosure>,
-    );
-    m.insert(
-        "node_list",
-        Box::new(|connection_info: ConnectionInfo| {
-            Rc::new(RefCell::new(NodeListState::from_connection(
-                connection_info,
-            ))) as Rc<RefCell<dyn TuiView>>
-        }) as Box<NewConnectionFactoryClosure>,
-    );
-    m
-});
+// Views opened for every new connection, in tab order. The others are opened from the
+// connection views popup.
+const NEW_CONNECTION_VIEWS: [&str; 2] = ["topic_list", "node_list"];
+
+/// A connection opened in the app, shared by the tabs displaying its data.
+struct OpenConnection {
+    /// Unique among the open connections, prefixes the tab titles.
+    label: String,
+
+    /// Transport controls of the connection, when it replays recorded data.
+    playback: Option<Playback>,
+
+    /// To open more views of the connection.
+    info: ConnectionInfo,
+}
+
+struct Tab {
+    view: Rc<RefCell<dyn TuiView>>,
+    connection: Rc<OpenConnection>,
+}
 
 pub struct App {
     should_exit: bool,
-    widgets: Vec<Rc<RefCell<dyn TuiView>>>,
+    widgets: Vec<Tab>,
     active_widget_index: usize,
 
     popup_view: Option<Box<dyn TuiPopup>>,
@@ -78,47 +81,49 @@ pub struct App {
 }
 
 // List of TuiViews supported:
+// - ActionGoal -> opened from ActionList
+// - ActionList -> FromConnection
+// - BitratePlot -> FromTopic, AcceptsTopic
+// - DelayPlot -> FromTopic, AcceptsTopic
 // - HzPlot -> FromTopic, AcceptsTopic
+// - InterfaceDefinition -> FromTopic, FromField, opened from RawMessage, NodeDetails,
+//   ServiceCall and ActionList
 // - LiveHzPlot -> FromField, AcceptsField
 // - NodeDetails -> FromNode
 // - NodeList -> FromConnection
+// - ParameterEvents -> FromConnection
 // - RawMessage -> FromTopic
+// - Rosout -> FromConnection, opened from NodeDetails
 // - StateGraph -> FromField, AcceptsField (Integer, String)
+// - TfTree -> FromConnection
 // - TopicList -> FromConnection
 // - TopicPublisher -> FromTopic
 //
 // So on:
-// - NewConnection -> NodeList, TopicList
+// - NewConnection -> TopicList, NodeList
+// - ConnectionViews ('v') -> TopicList, NodeList, ActionList, ParameterEvents, Rosout, TfTree
 // - NewNode -> NodeDetails
-// - NewTopic -> RawMessage, TopicPublisher, HzPlot
-// - NewField -> LiveHzPlot, StateGraph
+// - NewTopic -> RawMessage, TopicPublisher, HzPlot, DelayPlot, BitratePlot, InterfaceDefinition
+// - NewField -> LiveHzPlot, StateGraph, InterfaceDefinition
 // for any existing view
 
 pub enum AppArgs {
     TopicList,
     NodeList,
+    ActionList,
+    ParameterEvents,
+    Rosout(Option<String>),
+    TfTree,
     RawMessage(String),
     TopicPublisher(String, String),
     HzPlot(String),
+    DelayPlot(String),
+    BitratePlot(String),
 }
 
 impl Default for App {
     fn default() -> Self {
-        let should_exit = false;
-        let connection = Rc::new(RefCell::new(ConnectionType::ROS2(ConnectionROS2::new())));
-        let topic_list = TopicListState::new(connection.clone());
-        let node_list = NodeListState::new(connection.clone());
-        Self {
-            should_exit,
-            widgets: vec![
-                Rc::new(RefCell::new(topic_list)),
-                Rc::new(RefCell::new(node_list)),
-            ],
-            active_widget_index: 0,
-            popup_view: None,
-            needs_redraw: true,
-            metrics: None,
-        }
+        Self::from_connection(ConnectionType::ROS2(ConnectionROS2::new()))
     }
 }
 
@@ -136,9 +141,34 @@ fn check_ctrl_c(event: &CrosstermEvent) -> bool {
 }
 
 impl App {
-    pub fn new(args: AppArgs) -> Result<Self> {
+    /// Create the default layout (topic list and node list) for `connection`.
+    pub fn from_connection(connection: ConnectionType) -> Self {
+        let mut app = Self {
+            should_exit: false,
+            widgets: Vec::new(),
+            active_widget_index: 0,
+            popup_view: None,
+            needs_redraw: true,
+            metrics: None,
+        };
+        app.add_connection(ConnectionInfo {
+            connection: Rc::new(RefCell::new(connection)),
+        });
+        app
+    }
+
+    pub fn new(connection: ConnectionType, args: AppArgs) -> Result<Self> {
         let should_exit = false;
-        let connection = Rc::new(RefCell::new(ConnectionType::ROS2(ConnectionROS2::new())));
+        let label = connection.name().to_string();
+        let playback = connection.playback();
+        let connection = Rc::new(RefCell::new(connection));
+        let open_connection = Rc::new(OpenConnection {
+            label,
+            playback,
+            info: ConnectionInfo {
+                connection: connection.clone(),
+            },
+        });
         let view: Rc<RefCell<dyn TuiView>> = match args {
             AppArgs::TopicList => {
                 let topic_list = TopicListState::new(connection.clone());
@@ -148,26 +178,59 @@ impl App {
                 let node_list = NodeListState::new(connection.clone());
                 Rc::new(RefCell::new(node_list))
             }
+            AppArgs::ActionList => {
+                let action_list = ActionListState::new(connection.clone());
+                Rc::new(RefCell::new(action_list))
+            }
+            AppArgs::ParameterEvents => {
+                let parameter_events = ParameterEventsState::new(connection.clone());
+                Rc::new(RefCell::new(parameter_events))
+            }
+            AppArgs::Rosout(node) => {
+                let rosout = match node {
+                    Some(node) => {
+                        RosoutState::for_node(&NodeName::from_full_name(&node), connection.clone())
+                    }
+                    None => RosoutState::new(connection.clone()),
+                };
+                Rc::new(RefCell::new(rosout))
+            }
+            AppArgs::TfTree => {
+                let tf_tree = TfTreeState::new(connection.clone());
+                Rc::new(RefCell::new(tf_tree))
+            }
             AppArgs::RawMessage(topic) => {
-                let raw_message_state = RawMessageState::new(topic, connection.clone());
+                let raw_message_state = RawMessageState::new(topic, None, connection.clone());
                 Rc::new(RefCell::new(raw_message_state))
             }
             AppArgs::TopicPublisher(topic, topic_type) => {
                 let topic_type =
                     InterfaceType::new(&topic_type).map_err(color_eyre::eyre::Error::msg)?;
                 let topic_publisher_state =
-                    TopicPublisherState::new(topic, topic_type, connection.clone());
+                    TopicPublisherState::new(topic, topic_type, None, connection.clone())
+                        .map_err(color_eyre::eyre::Error::msg)?;
                 Rc::new(RefCell::new(topic_publisher_state))
             }
             AppArgs::HzPlot(topic) => {
                 let hz_plot_state = HzPlotState::new(topic, connection.clone());
                 Rc::new(RefCell::new(hz_plot_state))
             }
+            AppArgs::DelayPlot(topic) => {
+                let delay_plot_state = DelayPlotState::new(topic, connection.clone());
+                Rc::new(RefCell::new(delay_plot_state))
+            }
+            AppArgs::BitratePlot(topic) => {
+                let bitrate_plot_state = BitratePlotState::new(topic, connection.clone());
+                Rc::new(RefCell::new(bitrate_plot_state))
+            }
         };
 
         Ok(Self {
             should_exit,
-            widgets: vec![view],
+            widgets: vec![Tab {
+                view,
+                connection: open_connection,
+            }],
             active_widget_index: 0,
             popup_view: None,
             needs_redraw: true,
@@ -175,10 +238,51 @@ impl App {
         })
     }
 
+    /// Open the default views of a new connection, and switch to the first one.
+    fn add_connection(&mut self, connection_info: ConnectionInfo) {
+        let name = connection_info.connection.borrow().name().to_string();
+        let mut label = name.clone();
+        let mut index = 1;
+        while self.widgets.iter().any(|tab| tab.connection.label == label) {
+            index += 1;
+            label = format!("{} #{}", name, index);
+        }
+        let connection = Rc::new(OpenConnection {
+            label,
+            playback: connection_info.connection.borrow().playback(),
+            info: connection_info.clone(),
+        });
+
+        self.active_widget_index = self.widgets.len();
+        for key in NEW_CONNECTION_VIEWS {
+            self.widgets.push(Tab {
+                view: FROM_CONNECTION_FACTORIES[key](connection_info.clone()),
+                connection: connection.clone(),
+            });
+        }
+        self.popup_view = None;
+        self.needs_redraw = true;
+    }
+
+    /// Transport controls of the connection displayed by the active tab.
+    fn playback(&self) -> Option<&Playback> {
+        self.widgets
+            .get(self.active_widget_index)
+            .and_then(|tab| tab.connection.playback.as_ref())
+    }
+
+    /// Whether the tabs display data from more than one connection.
+    fn has_multiple_connections(&self) -> bool {
+        self.widgets
+            .iter()
+            .any(|tab| !Rc::ptr_eq(&tab.connection, &self.widgets[0].connection))
+    }
+
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         while !self.should_exit {
             let popup_needs_redraw = self.popup_view.as_mut().is_some_and(|p| p.needs_redraw());
             if self.widgets[self.active_widget_index]
+                .view
                 .borrow_mut()
                 .needs_redraw()
                 || self.needs_redraw
@@ -209,6 +313,7 @@ impl App {
     fn handle_event(&mut self, event: Event) {
         let event = match &mut self.popup_view {
             None => self.widgets[self.active_widget_index]
+                .view
                 .borrow_mut()
                 .handle_event(event),
             Some(popup) => popup.handle_event(event),
@@ -248,9 +353,28 @@ impl App {
                         }
                         self.needs_redraw = true;
                     }
+                    KeyCode::Char(' ')
+                    | KeyCode::Char('.')
+                    | KeyCode::Char('[')
+                    | KeyCode::Char(']')
+                    | KeyCode::Char('<')
+                    | KeyCode::Char('>')
+                    | KeyCode::Char('g') => {
+                        self.handle_playback_key(key_event.code);
+                    }
+                    KeyCode::Char('n') => {
+                        self.popup_view = Some(Box::new(NewConnectionPopupState::new()));
+                    }
+                    KeyCode::Char('v') => {
+                        let info = self.widgets[self.active_widget_index]
+                            .connection
+                            .info
+                            .clone();
+                        self.popup_view = Some(Box::new(ConnectionViewsPopupState::new(info)));
+                    }
                     KeyCode::Char('?') => {
-                        if let Some(active_view) = self.widgets.get(self.active_widget_index) {
-                            let mut help_text = active_view.borrow().get_help_text();
+                        if let Some(active_tab) = self.widgets.get(self.active_widget_index) {
+                            let mut help_text = active_tab.view.borrow().get_help_text();
                             help_text.push_str("\n\n");
                             help_text.push_str(&self.get_help_text());
                             self.popup_view = Some(Box::new(TextPopup::info(help_text)));
@@ -259,15 +383,16 @@ impl App {
                     _ => {}
                 }
             }
-            Event::NewConnection(_) => {
-                // TODO: Handle new connection event once multi-connection support is implemented.
+            Event::NewConnection(connection_info) => {
+                self.add_connection(connection_info);
             }
             Event::NewNode(node_info) => {
                 // List existing TuiViews that accept nodes
                 let candidate_views = self
                     .widgets
                     .iter()
-                    .filter_map(|w| {
+                    .filter_map(|tab| {
+                        let w = &tab.view;
                         let mut w_borrowed = w.borrow_mut();
                         w_borrowed.as_node_acceptor().map(|_| w.clone())
                     })
@@ -280,7 +405,8 @@ impl App {
                 let candidate_views = self
                     .widgets
                     .iter()
-                    .filter_map(|w| {
+                    .filter_map(|tab| {
+                        let w = &tab.view;
                         let mut w_borrowed = w.borrow_mut();
                         w_borrowed.as_topic_acceptor().map(|_| w.clone())
                     })
@@ -295,7 +421,8 @@ impl App {
                 let candidate_views = self
                     .widgets
                     .iter()
-                    .filter_map(|w| {
+                    .filter_map(|tab| {
+                        let w = &tab.view;
                         let mut w_borrowed = w.borrow_mut();
                         w_borrowed.as_field_acceptor().and_then(|acceptor| {
                             if acceptor.accepts_field_type(&field_info.field_type) {
@@ -309,7 +436,12 @@ impl App {
                 self.popup_view = Some(NewFieldPopupState::new(field_info, candidate_views));
             }
             Event::NewView(new_view) => {
-                self.widgets.push(new_view);
+                // Views opened from a tab display data from the same connection
+                let connection = self.widgets[self.active_widget_index].connection.clone();
+                self.widgets.push(Tab {
+                    view: new_view,
+                    connection,
+                });
                 self.active_widget_index = self.widgets.len() - 1;
                 self.popup_view = None;
             }
@@ -321,35 +453,158 @@ impl App {
                 self.popup_view = Some(Box::new(TextPopup::error(err_msg)));
             }
             Event::Key(_) => {}
-            Event::None => {}
+            Event::None => {
+                // Keep the transport bar moving while playing.
+                if self.playback().is_some_and(|p| p.status().playing) {
+                    self.needs_redraw = true;
+                }
+            }
         }
     }
 
+    fn handle_playback_key(&mut self, code: KeyCode) {
+        let Some(playback) = self.playback() else {
+            return;
+        };
+        match code {
+            KeyCode::Char(' ') => playback.toggle_pause(),
+            KeyCode::Char('.') => playback.step(),
+            KeyCode::Char('[') => playback.seek_relative(-PLAYBACK_SEEK_STEP),
+            KeyCode::Char(']') => playback.seek_relative(PLAYBACK_SEEK_STEP),
+            KeyCode::Char('<') | KeyCode::Char('>') => {
+                let rate = playback.status().rate;
+                let index = PLAYBACK_RATES.iter().position(|&r| r == rate).unwrap_or(1);
+                let index = if code == KeyCode::Char('<') {
+                    index.saturating_sub(1)
+                } else {
+                    (index + 1).min(PLAYBACK_RATES.len() - 1)
+                };
+                playback.set_rate(PLAYBACK_RATES[index]);
+            }
+            KeyCode::Char('g') => {
+                let playback = playback.clone();
+                self.popup_view = Some(Box::new(InputPopup::new(
+                    "Seek",
+                    "Seconds from the start of the recording (e.g. 12.5 or +12.5), or '@' and a \
+                    UNIX timestamp (e.g. @1700000000.5):",
+                    move |input| {
+                        playback.seek(parse_seek_target(input, playback.status().start)?);
+                        Ok(Event::ClosePopup)
+                    },
+                )));
+            }
+            _ => {}
+        }
+        self.needs_redraw = true;
+    }
+
     fn get_help_text(&self) -> String {
-        "App Help:\n\
+        let mut help_text = "App Help:\n\
         - 'Tab': Switch to the next panel.\n\
         - 'Shift+Tab': Switch to the previous panel.\n\
         - 'q' or 'Esc': Exit the application.\n\
         - 'x': Close the current panel (if multiple panels are open).\n\
+        - 'n': Open a new connection (ROS2 domain, MCAP file, rosbag2 bag, rosbridge or Foxglove).\n\
+        - 'v': Open another view of the current connection (topics, nodes, actions, parameter \
+        events, logs or TF tree).\n\
         - '?': Show this help message."
-            .to_string()
+            .to_string();
+        if self.playback().is_some() {
+            help_text.push_str(
+                "\n\n\
+                Playback Help:\n\
+                - 'Space': Play/pause.\n\
+                - '.': Pause and step one message.\n\
+                - '[' / ']': Seek 5 seconds backward/forward.\n\
+                - '<' / '>': Decrease/increase the playback speed.\n\
+                - 'g': Seek to a time, in seconds from the start or '@' and a UNIX timestamp.",
+            );
+        }
+        help_text
     }
 }
 
+/// Playback position entered in the seek popup: seconds from `start`, optionally prefixed with
+/// '+', or an absolute UNIX timestamp prefixed with '@'.
+fn parse_seek_target(input: &str, start: SystemTime) -> Result<SystemTime, String> {
+    let input = input.trim();
+    let (origin, seconds) = match input.strip_prefix('@') {
+        Some(timestamp) => (SystemTime::UNIX_EPOCH, timestamp),
+        None => (start, input.strip_prefix('+').unwrap_or(input)),
+    };
+    seconds
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .and_then(|offset| origin.checked_add(offset))
+        .ok_or(format!("'{}' is not a valid time", input))
+}
+
+fn render_transport_bar(playback: &Playback, area: Rect, buf: &mut Buffer) {
+    let status = playback.status();
+    let seconds_since = |time: SystemTime, origin: SystemTime| {
+        time.duration_since(origin)
+            .unwrap_or_default()
+            .as_secs_f64()
+    };
+    let position = seconds_since(status.position, status.start);
+    let duration = seconds_since(status.end, status.start);
+
+    let label = format!(
+        " {} {}x {:.3}/{:.3}s ",
+        if status.playing { "▶" } else { "⏸" },
+        status.rate,
+        position,
+        duration,
+    );
+    let [label_area, gauge_area] = Layout::horizontal([
+        Constraint::Length(label.chars().count() as u16),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    Paragraph::new(label).bold().render(label_area, buf);
+    LineGauge::default()
+        .filled_style(SELECTED_STYLE)
+        .ratio(if duration > 0.0 {
+            (position / duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        })
+        .render(gauge_area, buf);
+}
+
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [tab_area, widget_area] =
-            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        let transport_height = if self.playback().is_some() { 1 } else { 0 };
+        let [tab_area, widget_area, transport_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(transport_height),
+        ])
+        .areas(area);
 
-        let widget_names = self.widgets.iter().map(|w| w.borrow().name());
+        let show_connection = self.has_multiple_connections();
+        let widget_names = self.widgets.iter().map(|tab| {
+            let name = tab.view.borrow().name();
+            if show_connection {
+                format!("{}: {}", tab.connection.label, name)
+            } else {
+                name
+            }
+        });
         Tabs::new(widget_names)
             .highlight_style(SELECTED_STYLE)
             .select(self.active_widget_index)
             .divider(" ")
             .render(tab_area, buf);
-        let widget = &mut self.widgets[self.active_widget_index];
+        let widget = &mut self.widgets[self.active_widget_index].view;
         widget.borrow_mut().render(widget_area, buf);
 
+        if let Some(playback) = self.playback() {
+            render_transport_bar(playback, transport_area, buf);
+        }
+
         let popup_area = Rect {
             x: area.width / 4,
             y: area.height / 4,
@@ -361,3 +616,29 @@ impl Widget for &mut App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seek_target() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let offset = start + Duration::from_millis(12_500);
+        assert_eq!(parse_seek_target("12.5", start), Ok(offset));
+        assert_eq!(parse_seek_target("+12.5", start), Ok(offset));
+        assert_eq!(parse_seek_target("@1700000012.5", start), Ok(offset));
+        // A large offset stays relative to the start
+        assert_eq!(
+            parse_seek_target("1700000000", start),
+            Ok(start + Duration::from_secs(1_700_000_000))
+        );
+        assert!(parse_seek_target("-3", start).is_err());
+        assert!(parse_seek_target("@", start).is_err());
+        assert!(parse_seek_target("soon", start).is_err());
+        // Out of range for a SystemTime
+        assert!(parse_seek_target("1e30", start).is_err());
+        assert!(parse_seek_target("@1e20", start).is_err());
+        assert!(parse_seek_target("inf", start).is_err());
+    }
+}
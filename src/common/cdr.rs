@@ -0,0 +1,522 @@
+// Conversion between CDR-serialized ROS 2 messages and `GenericMessage`. Decoding is driven by
+// the message definitions in a `MessageRegistry`, encoding only needs the message itself. This is
+// what lets recorded and bridged data be handled without going through the rmw layer.
+
+use indexmap::IndexMap;
+
+use crate::common::generic_message::{
+    ArrayField, BoundedSequenceField, GenericField, GenericMessage, InterfaceType, Length,
+    SequenceField, SimpleField,
+};
+use crate::common::message_definition::{BaseType, FieldKind, MessageRegistry};
+
+/// Size of the encapsulation header preceding the CDR payload. Alignment is relative to the end
+/// of this header.
+const ENCAPSULATION_HEADER_SIZE: usize = 4;
+
+/// Name of the placeholder field the ROS 2 type support adds to messages without any field.
+pub(crate) const EMPTY_MESSAGE_FIELD: &str = "structure_needs_at_least_one_member";
+
+pub struct CdrReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    little_endian: bool,
+}
+
+macro_rules! read_primitive {
+    ($name:ident, $t:ty) => {
+        pub fn $name(&mut self) -> Result<$t, String> {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            self.align(SIZE);
+            let bytes: [u8; SIZE] = self.take(SIZE)?.try_into().unwrap();
+            Ok(if self.little_endian {
+                <$t>::from_le_bytes(bytes)
+            } else {
+                <$t>::from_be_bytes(bytes)
+            })
+        }
+    };
+}
+
+impl<'a> CdrReader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, String> {
+        if data.len() < ENCAPSULATION_HEADER_SIZE {
+            return Err("CDR payload is shorter than its encapsulation header".to_string());
+        }
+        let little_endian = match data[1] {
+            0x00 | 0x02 => false,
+            0x01 | 0x03 => true,
+            kind => return Err(format!("Unsupported CDR encapsulation kind {:#04x}", kind)),
+        };
+        Ok(Self {
+            data,
+            position: ENCAPSULATION_HEADER_SIZE,
+            little_endian,
+        })
+    }
+
+    fn align(&mut self, size: usize) {
+        let offset = self.position - ENCAPSULATION_HEADER_SIZE;
+        self.position += (size - offset % size) % size;
+    }
+
+    fn take(&mut self, size: usize) -> Result<&'a [u8], String> {
+        let end = self.position + size;
+        if end > self.data.len() {
+            return Err(format!(
+                "CDR payload truncated: needed {} bytes at offset {}, only {} available",
+                size,
+                self.position,
+                self.data.len()
+            ));
+        }
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    read_primitive!(read_i8, i8);
+    read_primitive!(read_u8, u8);
+    read_primitive!(read_i16, i16);
+    read_primitive!(read_u16, u16);
+    read_primitive!(read_i32, i32);
+    read_primitive!(read_u32, u32);
+    read_primitive!(read_i64, i64);
+    read_primitive!(read_u64, u64);
+    read_primitive!(read_f32, f32);
+    read_primitive!(read_f64, f64);
+
+    pub fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_string(&mut self) -> Result<String, String> {
+        let length = self.read_u32()? as usize;
+        let bytes = self.take(length)?;
+        // The length includes the null terminator.
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn read_wstring(&mut self) -> Result<String, String> {
+        let length = self.read_u32()? as usize;
+        let units = (0..length)
+            .map(|_| self.read_u16())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(String::from_utf16_lossy(&units))
+    }
+}
+
+fn read_vec<'a, T>(
+    reader: &mut CdrReader<'a>,
+    length: usize,
+    mut read: impl FnMut(&mut CdrReader<'a>) -> Result<T, String>,
+) -> Result<Vec<T>, String> {
+    // Don't trust the length prefix for the allocation, a corrupted payload could claim billions
+    // of elements.
+    let mut values = Vec::with_capacity(length.min(reader.remaining()));
+    for _ in 0..length {
+        values.push(read(reader)?);
+    }
+    Ok(values)
+}
+
+/// Read `$length` elements of `$base_type` into the `$field` collection variant, passing the
+/// optional trailing `$bound` for bounded sequences.
+macro_rules! read_collection {
+    ($reader:expr, $registry:expr, $base_type:expr, $length:expr, $field:ident $(, $bound:expr)?) => {
+        match $base_type {
+            BaseType::Bool => $field::Boolean(read_vec($reader, $length, CdrReader::read_bool)? $(, $bound)?),
+            BaseType::Byte => $field::Octet(read_vec($reader, $length, CdrReader::read_u8)? $(, $bound)?),
+            BaseType::Float32 => $field::Float(read_vec($reader, $length, CdrReader::read_f32)? $(, $bound)?),
+            BaseType::Float64 => $field::Double(read_vec($reader, $length, CdrReader::read_f64)? $(, $bound)?),
+            BaseType::Int8 => $field::Int8(read_vec($reader, $length, CdrReader::read_i8)? $(, $bound)?),
+            BaseType::Uint8 => $field::Uint8(read_vec($reader, $length, CdrReader::read_u8)? $(, $bound)?),
+            BaseType::Int16 => $field::Int16(read_vec($reader, $length, CdrReader::read_i16)? $(, $bound)?),
+            BaseType::Uint16 => $field::Uint16(read_vec($reader, $length, CdrReader::read_u16)? $(, $bound)?),
+            BaseType::Int32 => $field::Int32(read_vec($reader, $length, CdrReader::read_i32)? $(, $bound)?),
+            BaseType::Uint32 => $field::Uint32(read_vec($reader, $length, CdrReader::read_u32)? $(, $bound)?),
+            BaseType::Int64 => $field::Int64(read_vec($reader, $length, CdrReader::read_i64)? $(, $bound)?),
+            BaseType::Uint64 => $field::Uint64(read_vec($reader, $length, CdrReader::read_u64)? $(, $bound)?),
+            BaseType::String(None) => $field::String(read_vec($reader, $length, CdrReader::read_string)? $(, $bound)?),
+            BaseType::String(Some(_)) => $field::BoundedString(read_vec($reader, $length, CdrReader::read_string)? $(, $bound)?),
+            BaseType::WString(None) => $field::WString(read_vec($reader, $length, CdrReader::read_wstring)? $(, $bound)?),
+            BaseType::WString(Some(_)) => $field::BoundedWString(read_vec($reader, $length, CdrReader::read_wstring)? $(, $bound)?),
+            BaseType::Message(type_name) => $field::Message(read_vec($reader, $length, |r| read_message(r, $registry, type_name))? $(, $bound)?),
+        }
+    };
+}
+
+fn read_simple(
+    reader: &mut CdrReader,
+    registry: &MessageRegistry,
+    base_type: &BaseType,
+) -> Result<SimpleField, String> {
+    Ok(match base_type {
+        BaseType::Bool => SimpleField::Boolean(reader.read_bool()?),
+        BaseType::Byte => SimpleField::Octet(reader.read_u8()?),
+        BaseType::Float32 => SimpleField::Float(reader.read_f32()?),
+        BaseType::Float64 => SimpleField::Double(reader.read_f64()?),
+        BaseType::Int8 => SimpleField::Int8(reader.read_i8()?),
+        BaseType::Uint8 => SimpleField::Uint8(reader.read_u8()?),
+        BaseType::Int16 => SimpleField::Int16(reader.read_i16()?),
+        BaseType::Uint16 => SimpleField::Uint16(reader.read_u16()?),
+        BaseType::Int32 => SimpleField::Int32(reader.read_i32()?),
+        BaseType::Uint32 => SimpleField::Uint32(reader.read_u32()?),
+        BaseType::Int64 => SimpleField::Int64(reader.read_i64()?),
+        BaseType::Uint64 => SimpleField::Uint64(reader.read_u64()?),
+        BaseType::String(None) => SimpleField::String(reader.read_string()?),
+        BaseType::String(Some(_)) => SimpleField::BoundedString(reader.read_string()?),
+        BaseType::WString(None) => SimpleField::WString(reader.read_wstring()?),
+        BaseType::WString(Some(_)) => SimpleField::BoundedWString(reader.read_wstring()?),
+        BaseType::Message(type_name) => {
+            SimpleField::Message(read_message(reader, registry, type_name)?)
+        }
+    })
+}
+
+fn read_message(
+    reader: &mut CdrReader,
+    registry: &MessageRegistry,
+    type_name: &InterfaceType,
+) -> Result<GenericMessage, String> {
+    let definition = registry
+        .get(type_name)
+        .ok_or_else(|| format!("No definition for message type '{}'", type_name))?;
+
+    let mut fields = IndexMap::new();
+    if definition.fields.is_empty() {
+        fields.insert(
+            EMPTY_MESSAGE_FIELD.to_string(),
+            GenericField::Simple(SimpleField::Uint8(reader.read_u8()?)),
+        );
+    }
+
+    for field in &definition.fields {
+        let value = match field.kind {
+            FieldKind::Single => {
+                GenericField::Simple(read_simple(reader, registry, &field.base_type)?)
+            }
+            FieldKind::Array(length) => GenericField::Array(read_collection!(
+                reader,
+                registry,
+                &field.base_type,
+                length,
+                ArrayField
+            )),
+            FieldKind::Sequence => {
+                let length = reader.read_u32()? as usize;
+                GenericField::Sequence(read_collection!(
+                    reader,
+                    registry,
+                    &field.base_type,
+                    length,
+                    SequenceField
+                ))
+            }
+            FieldKind::BoundedSequence(bound) => {
+                let length = reader.read_u32()? as usize;
+                GenericField::BoundedSequence(read_collection!(
+                    reader,
+                    registry,
+                    &field.base_type,
+                    length,
+                    BoundedSequenceField,
+                    bound
+                ))
+            }
+        };
+        fields.insert(field.name.clone(), value);
+    }
+
+    Ok(GenericMessage::new(type_name.clone(), fields))
+}
+
+/// Decode a CDR payload (including its encapsulation header) as a message of `type_name`.
+pub fn deserialize(
+    registry: &MessageRegistry,
+    type_name: &InterfaceType,
+    data: &[u8],
+) -> Result<GenericMessage, String> {
+    let mut reader = CdrReader::new(data)?;
+    read_message(&mut reader, registry, type_name)
+}
+
+/// Encapsulation header of little-endian plain CDR.
+const CDR_LE_HEADER: [u8; ENCAPSULATION_HEADER_SIZE] = [0x00, 0x01, 0x00, 0x00];
+
+pub struct CdrWriter {
+    /// Encoded bytes, `None` when the encoding is only measured.
+    data: Option<Vec<u8>>,
+    /// Length of the encoding, encapsulation header included.
+    len: usize,
+}
+
+macro_rules! write_primitive {
+    ($name:ident, $t:ty) => {
+        pub fn $name(&mut self, value: $t) {
+            self.align(std::mem::size_of::<$t>());
+            self.put(&value.to_le_bytes());
+        }
+    };
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CdrWriter {
+    pub fn new() -> Self {
+        Self {
+            data: Some(CDR_LE_HEADER.to_vec()),
+            len: ENCAPSULATION_HEADER_SIZE,
+        }
+    }
+
+    /// Writer that only counts the bytes of the encoding.
+    fn counter() -> Self {
+        Self {
+            data: None,
+            len: ENCAPSULATION_HEADER_SIZE,
+        }
+    }
+
+    fn put(&mut self, bytes: &[u8]) {
+        self.len += bytes.len();
+        if let Some(data) = &mut self.data {
+            data.extend_from_slice(bytes);
+        }
+    }
+
+    fn align(&mut self, size: usize) {
+        let offset = self.len - ENCAPSULATION_HEADER_SIZE;
+        let padding = (size - offset % size) % size;
+        self.put(&[0; 8][..padding]);
+    }
+
+    write_primitive!(write_i8, i8);
+    write_primitive!(write_u8, u8);
+    write_primitive!(write_i16, i16);
+    write_primitive!(write_u16, u16);
+    write_primitive!(write_i32, i32);
+    write_primitive!(write_u32, u32);
+    write_primitive!(write_i64, i64);
+    write_primitive!(write_u64, u64);
+    write_primitive!(write_f32, f32);
+    write_primitive!(write_f64, f64);
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_long_double(&mut self, value: &[u8; 16]) {
+        self.align(8);
+        self.put(value);
+    }
+
+    pub fn write_string(&mut self, value: &str) {
+        self.write_u32(value.len() as u32 + 1);
+        self.put(value.as_bytes());
+        self.put(&[0]);
+    }
+
+    pub fn write_wstring(&mut self, value: &str) {
+        let units: Vec<u16> = value.encode_utf16().collect();
+        self.write_u32(units.len() as u32);
+        for unit in units {
+            self.write_u16(unit);
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data.unwrap_or_default()
+    }
+}
+
+/// Write every element of the `$field` collection, `$bound` matches the trailing bound of
+/// bounded sequences.
+macro_rules! write_collection {
+    ($writer:expr, $value:expr, $field:ident $(, $bound:pat)?) => {
+        match $value {
+            $field::Float(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_f32(*x)),
+            $field::Double(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_f64(*x)),
+            $field::LongDouble(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_long_double(x)),
+            $field::Char(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u8(*x)),
+            $field::WChar(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u16(*x)),
+            $field::Boolean(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_bool(*x)),
+            $field::Octet(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u8(*x)),
+            $field::Uint8(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u8(*x)),
+            $field::Int8(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_i8(*x)),
+            $field::Uint16(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u16(*x)),
+            $field::Int16(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_i16(*x)),
+            $field::Uint32(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u32(*x)),
+            $field::Int32(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_i32(*x)),
+            $field::Uint64(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_u64(*x)),
+            $field::Int64(v $(, $bound)?) => v.iter().for_each(|x| $writer.write_i64(*x)),
+            $field::String(v $(, $bound)?) | $field::BoundedString(v $(, $bound)?) => {
+                v.iter().for_each(|x| $writer.write_string(x))
+            }
+            $field::WString(v $(, $bound)?) | $field::BoundedWString(v $(, $bound)?) => {
+                v.iter().for_each(|x| $writer.write_wstring(x))
+            }
+            $field::Message(v $(, $bound)?) => v.iter().for_each(|x| write_message($writer, x)),
+        }
+    };
+}
+
+fn write_simple(writer: &mut CdrWriter, value: &SimpleField) {
+    match value {
+        SimpleField::Float(v) => writer.write_f32(*v),
+        SimpleField::Double(v) => writer.write_f64(*v),
+        SimpleField::LongDouble(v) => writer.write_long_double(v),
+        SimpleField::Char(v) => writer.write_u8(*v),
+        SimpleField::WChar(v) => writer.write_u16(*v),
+        SimpleField::Boolean(v) => writer.write_bool(*v),
+        SimpleField::Octet(v) => writer.write_u8(*v),
+        SimpleField::Uint8(v) => writer.write_u8(*v),
+        SimpleField::Int8(v) => writer.write_i8(*v),
+        SimpleField::Uint16(v) => writer.write_u16(*v),
+        SimpleField::Int16(v) => writer.write_i16(*v),
+        SimpleField::Uint32(v) => writer.write_u32(*v),
+        SimpleField::Int32(v) => writer.write_i32(*v),
+        SimpleField::Uint64(v) => writer.write_u64(*v),
+        SimpleField::Int64(v) => writer.write_i64(*v),
+        SimpleField::String(v) | SimpleField::BoundedString(v) => writer.write_string(v),
+        SimpleField::WString(v) | SimpleField::BoundedWString(v) => writer.write_wstring(v),
+        SimpleField::Message(v) => write_message(writer, v),
+    }
+}
+
+fn write_message(writer: &mut CdrWriter, message: &GenericMessage) {
+    for (_, field) in message.iter() {
+        match field {
+            GenericField::Simple(value) => write_simple(writer, value),
+            GenericField::Array(value) => write_collection!(writer, value, ArrayField),
+            GenericField::Sequence(value) => {
+                writer.write_u32(value.len() as u32);
+                write_collection!(writer, value, SequenceField);
+            }
+            GenericField::BoundedSequence(value) => {
+                writer.write_u32(value.len() as u32);
+                write_collection!(writer, value, BoundedSequenceField, _);
+            }
+        }
+    }
+}
+
+/// Encode `message` as little-endian CDR, including the encapsulation header.
+pub fn serialize(message: &GenericMessage) -> Vec<u8> {
+    let mut writer = CdrWriter::new();
+    write_message(&mut writer, message);
+    writer.into_bytes()
+}
+
+/// Length of the CDR encoding of `message`, encapsulation header included, without encoding it.
+pub fn serialized_size(message: &GenericMessage) -> usize {
+    let mut writer = CdrWriter::counter();
+    write_message(&mut writer, message);
+    writer.len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::message_definition::MessageDefinition;
+
+    fn registry() -> MessageRegistry {
+        let mut registry = MessageRegistry::new();
+        registry.insert(
+            MessageDefinition::parse(
+                InterfaceType::new("test_pkg/msg/Sample").unwrap(),
+                "builtin_interfaces/Time stamp\nstring frame_id\nuint16[] values\nfloat64 value\n",
+            )
+            .unwrap(),
+        );
+        registry.insert(
+            MessageDefinition::parse(
+                InterfaceType::new("builtin_interfaces/msg/Time").unwrap(),
+                "int32 sec\nuint32 nanosec\n",
+            )
+            .unwrap(),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_deserialize_little_endian() {
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(b"map\0");
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&7u16.to_le_bytes());
+        data.extend_from_slice(&8u16.to_le_bytes());
+        data.extend_from_slice(&1.5f64.to_le_bytes());
+
+        let message = deserialize(
+            &registry(),
+            &InterfaceType::new("test_pkg/msg/Sample").unwrap(),
+            &data,
+        )
+        .unwrap();
+
+        let GenericField::Simple(SimpleField::Message(stamp)) = &message["stamp"] else {
+            panic!("stamp is not a message");
+        };
+        assert_eq!(stamp["sec"], GenericField::Simple(SimpleField::Int32(1)));
+        assert_eq!(
+            stamp["nanosec"],
+            GenericField::Simple(SimpleField::Uint32(2))
+        );
+        assert_eq!(
+            message["frame_id"],
+            GenericField::Simple(SimpleField::String("map".to_string()))
+        );
+        assert_eq!(
+            message["values"],
+            GenericField::Sequence(SequenceField::Uint16(vec![7, 8]))
+        );
+        assert_eq!(
+            message["value"],
+            GenericField::Simple(SimpleField::Double(1.5))
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let registry = registry();
+        let type_name = InterfaceType::new("test_pkg/msg/Sample").unwrap();
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(b"map\0");
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&7u16.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&1.5f64.to_le_bytes());
+
+        let message = deserialize(&registry, &type_name, &data).unwrap();
+        assert_eq!(serialize(&message), data);
+        assert_eq!(serialized_size(&message), data.len());
+    }
+
+    #[test]
+    fn test_deserialize_truncated_payload() {
+        let data = vec![0x00, 0x01, 0x00, 0x00, 0x01];
+        let result = deserialize(
+            &registry(),
+            &InterfaceType::new("test_pkg/msg/Sample").unwrap(),
+            &data,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -2,9 +2,12 @@ use indexmap::IndexMap;
 use rclrs::{
     ArrayValue, BoundedSequenceValue, DynamicMessageView, SequenceValue, SimpleValue, Value,
 };
-use std::{ops::Index, time::SystemTime};
+use std::{
+    ops::Index,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InterfaceType {
     pub package_name: String,
     pub category: String,
@@ -1302,6 +1305,9 @@ pub struct GenericMessage {
 
 pub struct MessageMetadata {
     pub received_time: SystemTime,
+    /// Size of the received payload: the CDR payload, encapsulation header included, or the JSON
+    /// text of connections exchanging JSON (rosbridge). `None` when the connection can't tell.
+    pub serialized_size: Option<usize>,
 }
 
 impl From<SimpleValue<'_>> for SimpleField {
@@ -1582,6 +1588,10 @@ impl GenericMessage {
         self.fields.get(field_name)
     }
 
+    pub fn get_mut(&mut self, field_name: &str) -> Option<&mut GenericField> {
+        self.fields.get_mut(field_name)
+    }
+
     pub fn get_index(&self, index: usize) -> Option<&GenericField> {
         self.fields.get_index(index).map(|(_, field)| field)
     }
@@ -1656,6 +1666,35 @@ impl GenericMessage {
     pub fn field_count(&self) -> usize {
         self.fields.len()
     }
+
+    /// The `header` field of the message, when it is a `std_msgs/msg/Header`.
+    pub fn header(&self) -> Option<&GenericMessage> {
+        let Some(GenericField::Simple(SimpleField::Message(header))) = self.get("header") else {
+            return None;
+        };
+        let t = header.type_name();
+        (t.package_name == "std_msgs" && t.category == "msg" && t.type_name == "Header")
+            .then_some(header)
+    }
+
+    /// Stamp of the header of the message, `None` without header.
+    pub fn header_stamp(&self) -> Option<SystemTime> {
+        let Some(GenericField::Simple(SimpleField::Message(stamp))) = self.header()?.get("stamp")
+        else {
+            return None;
+        };
+        match (stamp.get("sec"), stamp.get("nanosec")) {
+            (
+                Some(GenericField::Simple(SimpleField::Int32(sec))),
+                Some(GenericField::Simple(SimpleField::Uint32(nanosec))),
+            ) => Some(
+                UNIX_EPOCH
+                    + Duration::from_secs(u64::try_from(*sec).ok()?)
+                    + Duration::from_nanos(*nanosec as u64),
+            ),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1707,4 +1746,15 @@ mod tests {
         let field_name = generic_message.get_field_name(&[2, 1, 6]).unwrap();
         assert_eq!(field_name, "pose.covariance[6]");
     }
+
+    #[test]
+    fn test_header_stamp() {
+        let message = DynamicMessage::new("nav_msgs/msg/Odometry".try_into().unwrap()).unwrap();
+        let generic_message = GenericMessage::from(message.view());
+        assert_eq!(generic_message.header_stamp(), Some(UNIX_EPOCH));
+
+        let message = DynamicMessage::new("std_msgs/msg/String".try_into().unwrap()).unwrap();
+        let generic_message = GenericMessage::from(message.view());
+        assert!(generic_message.header().is_none());
+    }
 }
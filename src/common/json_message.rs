@@ -0,0 +1,413 @@
+// Conversion between the JSON representation of ROS messages used by rosbridge and
+// `GenericMessage`. Like CDR decoding, reading JSON is driven by the definitions of a
+// `MessageRegistry`, so that missing fields get their default value and numbers get their exact
+// ROS type.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+
+use crate::common::cdr::EMPTY_MESSAGE_FIELD;
+use crate::common::generic_message::{
+    ArrayField, BoundedSequenceField, GenericField, GenericMessage, InterfaceType, SequenceField,
+    SimpleField,
+};
+use crate::common::message_definition::{BaseType, FieldKind, MessageRegistry};
+
+// Element conversions. `null` stands for a missing value and converts to the default of the type.
+
+fn bool_from_json(value: &Value) -> Result<bool, String> {
+    match value {
+        Value::Null => Ok(false),
+        Value::Bool(v) => Ok(*v),
+        _ => Err(format!("expected bool, got {}", value)),
+    }
+}
+
+macro_rules! integer_from_json {
+    ($name:ident, $t:ty) => {
+        fn $name(value: &Value) -> Result<$t, String> {
+            if value.is_null() {
+                return Ok(0);
+            }
+            value
+                .as_i64()
+                .and_then(|v| <$t>::try_from(v).ok())
+                .or_else(|| value.as_u64().and_then(|v| <$t>::try_from(v).ok()))
+                .ok_or_else(|| format!("expected {}, got {}", stringify!($t), value))
+        }
+    };
+}
+
+integer_from_json!(i8_from_json, i8);
+integer_from_json!(u8_from_json, u8);
+integer_from_json!(i16_from_json, i16);
+integer_from_json!(u16_from_json, u16);
+integer_from_json!(i32_from_json, i32);
+integer_from_json!(u32_from_json, u32);
+integer_from_json!(i64_from_json, i64);
+integer_from_json!(u64_from_json, u64);
+
+fn f64_from_json(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Null => Ok(0.0),
+        _ => value
+            .as_f64()
+            .ok_or_else(|| format!("expected number, got {}", value)),
+    }
+}
+
+fn f32_from_json(value: &Value) -> Result<f32, String> {
+    f64_from_json(value).map(|v| v as f32)
+}
+
+fn string_from_json(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Null => Ok(String::new()),
+        Value::String(v) => Ok(v.clone()),
+        _ => Err(format!("expected string, got {}", value)),
+    }
+}
+
+/// Elements of a collection field. Byte arrays are sent as base64 strings by rosbridge.
+fn elements_from_json(base_type: &BaseType, value: &Value) -> Result<Vec<Value>, String> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(values) => Ok(values.clone()),
+        Value::String(encoded) if matches!(base_type, BaseType::Byte | BaseType::Uint8) => BASE64
+            .decode(encoded)
+            .map(|bytes| bytes.into_iter().map(Value::from).collect())
+            .map_err(|e| format!("invalid base64 byte array: {}", e)),
+        _ => Err(format!("expected array, got {}", value)),
+    }
+}
+
+fn convert_all<T>(
+    values: &[Value],
+    convert: impl Fn(&Value) -> Result<T, String>,
+) -> Result<Vec<T>, String> {
+    values.iter().map(convert).collect()
+}
+
+/// Convert `$values` to the `$field` collection variant matching `$base_type`, passing the
+/// optional trailing `$bound` for bounded sequences.
+macro_rules! collection_from_json {
+    ($registry:expr, $base_type:expr, $values:expr, $field:ident $(, $bound:expr)?) => {
+        match $base_type {
+            BaseType::Bool => $field::Boolean(convert_all($values, bool_from_json)? $(, $bound)?),
+            BaseType::Byte => $field::Octet(convert_all($values, u8_from_json)? $(, $bound)?),
+            BaseType::Float32 => $field::Float(convert_all($values, f32_from_json)? $(, $bound)?),
+            BaseType::Float64 => $field::Double(convert_all($values, f64_from_json)? $(, $bound)?),
+            BaseType::Int8 => $field::Int8(convert_all($values, i8_from_json)? $(, $bound)?),
+            BaseType::Uint8 => $field::Uint8(convert_all($values, u8_from_json)? $(, $bound)?),
+            BaseType::Int16 => $field::Int16(convert_all($values, i16_from_json)? $(, $bound)?),
+            BaseType::Uint16 => $field::Uint16(convert_all($values, u16_from_json)? $(, $bound)?),
+            BaseType::Int32 => $field::Int32(convert_all($values, i32_from_json)? $(, $bound)?),
+            BaseType::Uint32 => $field::Uint32(convert_all($values, u32_from_json)? $(, $bound)?),
+            BaseType::Int64 => $field::Int64(convert_all($values, i64_from_json)? $(, $bound)?),
+            BaseType::Uint64 => $field::Uint64(convert_all($values, u64_from_json)? $(, $bound)?),
+            BaseType::String(None) => $field::String(convert_all($values, string_from_json)? $(, $bound)?),
+            BaseType::String(Some(_)) => $field::BoundedString(convert_all($values, string_from_json)? $(, $bound)?),
+            BaseType::WString(None) => $field::WString(convert_all($values, string_from_json)? $(, $bound)?),
+            BaseType::WString(Some(_)) => $field::BoundedWString(convert_all($values, string_from_json)? $(, $bound)?),
+            BaseType::Message(type_name) => $field::Message(convert_all($values, |v| from_json($registry, type_name, v))? $(, $bound)?),
+        }
+    };
+}
+
+fn array_from_json(
+    registry: &MessageRegistry,
+    base_type: &BaseType,
+    values: &[Value],
+) -> Result<ArrayField, String> {
+    Ok(collection_from_json!(
+        registry, base_type, values, ArrayField
+    ))
+}
+
+fn sequence_from_json(
+    registry: &MessageRegistry,
+    base_type: &BaseType,
+    values: &[Value],
+) -> Result<SequenceField, String> {
+    Ok(collection_from_json!(
+        registry,
+        base_type,
+        values,
+        SequenceField
+    ))
+}
+
+fn bounded_sequence_from_json(
+    registry: &MessageRegistry,
+    base_type: &BaseType,
+    values: &[Value],
+    bound: usize,
+) -> Result<BoundedSequenceField, String> {
+    Ok(collection_from_json!(
+        registry,
+        base_type,
+        values,
+        BoundedSequenceField,
+        bound
+    ))
+}
+
+fn simple_from_json(
+    registry: &MessageRegistry,
+    base_type: &BaseType,
+    value: &Value,
+) -> Result<SimpleField, String> {
+    Ok(match base_type {
+        BaseType::Bool => SimpleField::Boolean(bool_from_json(value)?),
+        BaseType::Byte => SimpleField::Octet(u8_from_json(value)?),
+        BaseType::Float32 => SimpleField::Float(f32_from_json(value)?),
+        BaseType::Float64 => SimpleField::Double(f64_from_json(value)?),
+        BaseType::Int8 => SimpleField::Int8(i8_from_json(value)?),
+        BaseType::Uint8 => SimpleField::Uint8(u8_from_json(value)?),
+        BaseType::Int16 => SimpleField::Int16(i16_from_json(value)?),
+        BaseType::Uint16 => SimpleField::Uint16(u16_from_json(value)?),
+        BaseType::Int32 => SimpleField::Int32(i32_from_json(value)?),
+        BaseType::Uint32 => SimpleField::Uint32(u32_from_json(value)?),
+        BaseType::Int64 => SimpleField::Int64(i64_from_json(value)?),
+        BaseType::Uint64 => SimpleField::Uint64(u64_from_json(value)?),
+        BaseType::String(None) => SimpleField::String(string_from_json(value)?),
+        BaseType::String(Some(_)) => SimpleField::BoundedString(string_from_json(value)?),
+        BaseType::WString(None) => SimpleField::WString(string_from_json(value)?),
+        BaseType::WString(Some(_)) => SimpleField::BoundedWString(string_from_json(value)?),
+        BaseType::Message(type_name) => {
+            SimpleField::Message(from_json(registry, type_name, value)?)
+        }
+    })
+}
+
+/// Convert a JSON object to a message of `type_name`. Fields missing from `value` take the
+/// default value of their definition, so `Value::Null` gives a default-initialized message.
+pub fn from_json(
+    registry: &MessageRegistry,
+    type_name: &InterfaceType,
+    value: &Value,
+) -> Result<GenericMessage, String> {
+    let definition = registry
+        .get(type_name)
+        .ok_or_else(|| format!("No definition for message type '{}'", type_name))?;
+    if !value.is_null() && !value.is_object() {
+        return Err(format!("{}: expected object, got {}", type_name, value));
+    }
+
+    let mut fields = IndexMap::new();
+    if definition.fields.is_empty() {
+        fields.insert(
+            EMPTY_MESSAGE_FIELD.to_string(),
+            GenericField::Simple(SimpleField::Uint8(0)),
+        );
+    }
+
+    for field in &definition.fields {
+        let field_value = match value.get(&field.name) {
+            Some(field_value) => field_value.clone(),
+            // Defaults are written in a syntax close enough to JSON for numbers, booleans and
+            // arrays. Anything else falls back to the default of the type.
+            None => field
+                .default
+                .as_deref()
+                .and_then(|default| serde_json::from_str(default).ok())
+                .unwrap_or(Value::Null),
+        };
+        let context = |e: String| format!("{}.{}: {}", type_name, field.name, e);
+
+        let converted = match field.kind {
+            FieldKind::Single => GenericField::Simple(
+                simple_from_json(registry, &field.base_type, &field_value).map_err(context)?,
+            ),
+            FieldKind::Array(length) => {
+                let mut values =
+                    elements_from_json(&field.base_type, &field_value).map_err(context)?;
+                if field_value.is_null() {
+                    values.resize(length, Value::Null);
+                } else if values.len() != length {
+                    return Err(context(format!(
+                        "expected {} elements, got {}",
+                        length,
+                        values.len()
+                    )));
+                }
+                GenericField::Array(
+                    array_from_json(registry, &field.base_type, &values).map_err(context)?,
+                )
+            }
+            FieldKind::Sequence => {
+                let values = elements_from_json(&field.base_type, &field_value).map_err(context)?;
+                GenericField::Sequence(
+                    sequence_from_json(registry, &field.base_type, &values).map_err(context)?,
+                )
+            }
+            FieldKind::BoundedSequence(bound) => {
+                let values = elements_from_json(&field.base_type, &field_value).map_err(context)?;
+                if values.len() > bound {
+                    return Err(context(format!(
+                        "expected at most {} elements, got {}",
+                        bound,
+                        values.len()
+                    )));
+                }
+                GenericField::BoundedSequence(
+                    bounded_sequence_from_json(registry, &field.base_type, &values, bound)
+                        .map_err(context)?,
+                )
+            }
+        };
+        fields.insert(field.name.clone(), converted);
+    }
+
+    Ok(GenericMessage::new(type_name.clone(), fields))
+}
+
+fn f64_to_json(value: f64) -> Value {
+    // JSON has no representation of NaN and infinities.
+    serde_json::Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+/// Convert every element of the `$field` collection, `$bound` matches the trailing bound of
+/// bounded sequences.
+macro_rules! collection_to_json {
+    ($value:expr, $field:ident $(, $bound:pat)?) => {
+        match $value {
+            $field::Float(v $(, $bound)?) => v.iter().map(|x| f64_to_json(*x as f64)).collect(),
+            $field::Double(v $(, $bound)?) => v.iter().map(|x| f64_to_json(*x)).collect(),
+            $field::LongDouble(v $(, $bound)?) => v.iter().map(|_| Value::Null).collect(),
+            $field::Char(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::WChar(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Boolean(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Octet(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Uint8(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Int8(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Uint16(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Int16(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Uint32(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Int32(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Uint64(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::Int64(v $(, $bound)?) => v.iter().map(|x| Value::from(*x)).collect(),
+            $field::String(v $(, $bound)?)
+            | $field::BoundedString(v $(, $bound)?)
+            | $field::WString(v $(, $bound)?)
+            | $field::BoundedWString(v $(, $bound)?) => {
+                v.iter().map(|x| Value::from(x.as_str())).collect()
+            }
+            $field::Message(v $(, $bound)?) => v.iter().map(to_json).collect(),
+        }
+    };
+}
+
+fn simple_to_json(value: &SimpleField) -> Value {
+    match value {
+        SimpleField::Float(v) => f64_to_json(*v as f64),
+        SimpleField::Double(v) => f64_to_json(*v),
+        SimpleField::LongDouble(_) => Value::Null,
+        SimpleField::Char(v) => Value::from(*v),
+        SimpleField::WChar(v) => Value::from(*v),
+        SimpleField::Boolean(v) => Value::from(*v),
+        SimpleField::Octet(v) => Value::from(*v),
+        SimpleField::Uint8(v) => Value::from(*v),
+        SimpleField::Int8(v) => Value::from(*v),
+        SimpleField::Uint16(v) => Value::from(*v),
+        SimpleField::Int16(v) => Value::from(*v),
+        SimpleField::Uint32(v) => Value::from(*v),
+        SimpleField::Int32(v) => Value::from(*v),
+        SimpleField::Uint64(v) => Value::from(*v),
+        SimpleField::Int64(v) => Value::from(*v),
+        SimpleField::String(v)
+        | SimpleField::BoundedString(v)
+        | SimpleField::WString(v)
+        | SimpleField::BoundedWString(v) => Value::from(v.as_str()),
+        SimpleField::Message(v) => to_json(v),
+    }
+}
+
+/// Convert `message` to a JSON object. Byte arrays are written as arrays of numbers, which
+/// rosbridge accepts as well as base64 strings.
+pub fn to_json(message: &GenericMessage) -> Value {
+    let mut object = Map::new();
+    for (name, field) in message.iter() {
+        if name == EMPTY_MESSAGE_FIELD {
+            continue;
+        }
+        let value = match field {
+            GenericField::Simple(value) => simple_to_json(value),
+            GenericField::Array(value) => collection_to_json!(value, ArrayField),
+            GenericField::Sequence(value) => collection_to_json!(value, SequenceField),
+            GenericField::BoundedSequence(value) => {
+                collection_to_json!(value, BoundedSequenceField, _)
+            }
+        };
+        object.insert(name.clone(), value);
+    }
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::message_definition::MessageDefinition;
+
+    fn registry() -> MessageRegistry {
+        let mut registry = MessageRegistry::new();
+        registry.insert(
+            MessageDefinition::parse(
+                InterfaceType::new("test_pkg/msg/Sample").unwrap(),
+                "int32 id 7\nstring frame\nuint8[] data\nfloat64[2] pair\ntest_pkg/Empty empty\n",
+            )
+            .unwrap(),
+        );
+        registry.insert(
+            MessageDefinition::parse(InterfaceType::new("test_pkg/msg/Empty").unwrap(), "")
+                .unwrap(),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_from_json_defaults_and_base64() {
+        let type_name = InterfaceType::new("test_pkg/msg/Sample").unwrap();
+        let message = from_json(
+            &registry(),
+            &type_name,
+            &json!({"frame": "map", "data": "AQID"}),
+        )
+        .unwrap();
+
+        assert_eq!(message["id"], GenericField::Simple(SimpleField::Int32(7)));
+        assert_eq!(
+            message["frame"],
+            GenericField::Simple(SimpleField::String("map".to_string()))
+        );
+        assert_eq!(
+            message["data"],
+            GenericField::Sequence(SequenceField::Uint8(vec![1, 2, 3]))
+        );
+        assert_eq!(
+            message["pair"],
+            GenericField::Array(ArrayField::Double(vec![0.0, 0.0]))
+        );
+        assert_eq!(
+            to_json(&message),
+            json!({"id": 7, "frame": "map", "data": [1, 2, 3], "pair": [0.0, 0.0], "empty": {}})
+        );
+    }
+
+    #[test]
+    fn test_from_json_type_errors() {
+        let type_name = InterfaceType::new("test_pkg/msg/Sample").unwrap();
+        let registry = registry();
+
+        let error = from_json(&registry, &type_name, &json!({"id": "seven"})).unwrap_err();
+        assert!(error.contains("test_pkg/msg/Sample.id"));
+        assert!(from_json(&registry, &type_name, &json!({"id": 1u64 << 40})).is_err());
+        assert!(from_json(&registry, &type_name, &json!({"pair": [1.0]})).is_err());
+    }
+}
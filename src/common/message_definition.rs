@@ -0,0 +1,585 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::common::generic_message::InterfaceType;
+
+/// Primitive (or nested message) type of a field in a `.msg` definition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseType {
+    Bool,
+    Byte,
+    Float32,
+    Float64,
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Int64,
+    Uint64,
+    String(Option<usize>),
+    WString(Option<usize>),
+    Message(InterfaceType),
+}
+
+/// How many values of the base type a field holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Single,
+    Array(usize),
+    Sequence,
+    BoundedSequence(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDefinition {
+    pub name: String,
+    pub base_type: BaseType,
+    pub kind: FieldKind,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstantDefinition {
+    pub name: String,
+    pub base_type: BaseType,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDefinition {
+    pub type_name: InterfaceType,
+    pub fields: Vec<FieldDefinition>,
+    pub constants: Vec<ConstantDefinition>,
+}
+
+impl MessageDefinition {
+    /// Parse the text of a single `.msg` file. `type_name` is used to resolve the package of
+    /// unqualified nested types.
+    pub fn parse(type_name: InterfaceType, text: &str) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        let mut constants = Vec::new();
+
+        for (line_number, raw_line) in text.lines().enumerate() {
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (type_token, rest) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("{}:{}: missing field name", type_name, line_number + 1))?;
+            let rest = rest.trim();
+            let (base_type, kind) = parse_type(type_token, &type_name.package_name)
+                .map_err(|e| format!("{}:{}: {}", type_name, line_number + 1, e))?;
+
+            let name_end = rest
+                .find(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or(rest.len());
+            let (name, value) = rest.split_at(name_end);
+            let value = value.trim();
+
+            if let Some(constant_value) = value.strip_prefix('=') {
+                constants.push(ConstantDefinition {
+                    name: name.to_string(),
+                    base_type,
+                    value: constant_value.trim().to_string(),
+                });
+            } else {
+                fields.push(FieldDefinition {
+                    name: name.to_string(),
+                    base_type,
+                    kind,
+                    default: (!value.is_empty()).then(|| value.to_string()),
+                });
+            }
+        }
+
+        Ok(Self {
+            type_name,
+            fields,
+            constants,
+        })
+    }
+}
+
+/// Remove a trailing `# comment`, ignoring `#` characters inside quoted strings.
+pub fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_type(token: &str, package: &str) -> Result<(BaseType, FieldKind), String> {
+    let (base, kind) = match token.split_once('[') {
+        Some((base, suffix)) => {
+            let bound = suffix
+                .strip_suffix(']')
+                .ok_or_else(|| format!("invalid array type '{}'", token))?;
+            let kind = if bound.is_empty() {
+                FieldKind::Sequence
+            } else if let Some(upper) = bound.strip_prefix("<=") {
+                FieldKind::BoundedSequence(
+                    upper
+                        .parse()
+                        .map_err(|_| format!("invalid sequence bound '{}'", token))?,
+                )
+            } else {
+                FieldKind::Array(
+                    bound
+                        .parse()
+                        .map_err(|_| format!("invalid array size '{}'", token))?,
+                )
+            };
+            (base, kind)
+        }
+        None => (token, FieldKind::Single),
+    };
+
+    Ok((parse_base_type(base, package)?, kind))
+}
+
+fn parse_base_type(base: &str, package: &str) -> Result<BaseType, String> {
+    let parse_bound = |bound: &str| -> Result<usize, String> {
+        bound
+            .parse()
+            .map_err(|_| format!("invalid string bound '{}'", base))
+    };
+
+    Ok(match base {
+        "bool" => BaseType::Bool,
+        "byte" => BaseType::Byte,
+        // `char` is an alias of `uint8` in ROS 2 interfaces.
+        "char" | "uint8" => BaseType::Uint8,
+        "float32" => BaseType::Float32,
+        "float64" => BaseType::Float64,
+        "int8" => BaseType::Int8,
+        "int16" => BaseType::Int16,
+        "uint16" => BaseType::Uint16,
+        "int32" => BaseType::Int32,
+        "uint32" => BaseType::Uint32,
+        "int64" => BaseType::Int64,
+        "uint64" => BaseType::Uint64,
+        "string" => BaseType::String(None),
+        "wstring" => BaseType::WString(None),
+        "time" => BaseType::Message(msg_type("builtin_interfaces", "Time")),
+        "duration" => BaseType::Message(msg_type("builtin_interfaces", "Duration")),
+        "Header" => BaseType::Message(msg_type("std_msgs", "Header")),
+        _ => {
+            if let Some(bound) = base.strip_prefix("string<=") {
+                BaseType::String(Some(parse_bound(bound)?))
+            } else if let Some(bound) = base.strip_prefix("wstring<=") {
+                BaseType::WString(Some(parse_bound(bound)?))
+            } else {
+                BaseType::Message(resolve_type_name(base, package)?)
+            }
+        }
+    })
+}
+
+fn msg_type(package: &str, type_name: &str) -> InterfaceType {
+    InterfaceType {
+        package_name: package.to_string(),
+        category: "msg".to_string(),
+        type_name: type_name.to_string(),
+    }
+}
+
+/// Resolve a message type reference as written in a `.msg` file (`Type`, `pkg/Type` or
+/// `pkg/msg/Type`) to a fully qualified interface type.
+pub fn resolve_type_name(name: &str, package: &str) -> Result<InterfaceType, String> {
+    let parts: Vec<&str> = name.split('/').collect();
+    match parts.as_slice() {
+        [type_name] => Ok(msg_type(package, type_name)),
+        [package, type_name] => Ok(msg_type(package, type_name)),
+        [_, _, _] => InterfaceType::new(name),
+        _ => Err(format!("invalid type name '{}'", name)),
+    }
+}
+
+/// A set of message definitions, keyed by their fully qualified type name.
+#[derive(Debug, Clone, Default)]
+pub struct MessageRegistry {
+    definitions: HashMap<String, MessageDefinition>,
+}
+
+impl MessageRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a registry from a concatenated `ros2msg` schema as stored in MCAP files and sent by
+    /// the Foxglove bridge: the root definition followed by its dependencies, each introduced by
+    /// a line of `=` and a `MSG: pkg/Type` header.
+    pub fn from_schema(root: &InterfaceType, schema: &str) -> Result<Self, String> {
+        let mut registry = Self::new();
+        let mut current_type = root.clone();
+        let mut current_text = String::new();
+        let mut lines = schema.lines();
+
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '=') {
+                registry.insert(MessageDefinition::parse(current_type, &current_text)?);
+                current_text.clear();
+
+                let header = lines
+                    .by_ref()
+                    .map(str::trim)
+                    .find(|l| !l.is_empty())
+                    .ok_or_else(|| "Schema ends with a separator".to_string())?;
+                let name = header
+                    .strip_prefix("MSG:")
+                    .ok_or_else(|| format!("Expected 'MSG: <type>' header, got '{}'", header))?;
+                current_type = resolve_type_name(name.trim(), &root.package_name)?;
+            } else {
+                current_text.push_str(line);
+                current_text.push('\n');
+            }
+        }
+        registry.insert(MessageDefinition::parse(current_type, &current_text)?);
+
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, definition: MessageDefinition) {
+        self.definitions
+            .insert(definition.type_name.to_string(), definition);
+    }
+
+    pub fn get(&self, type_name: &InterfaceType) -> Option<&MessageDefinition> {
+        self.definitions.get(&type_name.to_string())
+    }
+
+    pub fn contains(&self, type_name: &InterfaceType) -> bool {
+        self.definitions.contains_key(&type_name.to_string())
+    }
+
+    /// Load `type_name` and all of its nested types from the interface files installed in the
+    /// packages listed in `AMENT_PREFIX_PATH`. Types already in the registry are not reloaded.
+    pub fn load_installed(&mut self, type_name: &InterfaceType) -> Result<(), String> {
+        for (definition, _) in installed_definitions(type_name, |t| self.contains(t))? {
+            self.insert(definition);
+        }
+        Ok(())
+    }
+}
+
+/// Read the installed definition of `type_name` and of its nested types, root first. Types for
+/// which `skip` returns true are not read.
+fn installed_definitions(
+    type_name: &InterfaceType,
+    skip: impl Fn(&InterfaceType) -> bool,
+) -> Result<Vec<(MessageDefinition, String)>, String> {
+    let mut definitions: Vec<(MessageDefinition, String)> = Vec::new();
+    let mut pending = std::collections::VecDeque::from([type_name.clone()]);
+    while let Some(current) = pending.pop_front() {
+        if skip(&current) || definitions.iter().any(|(d, _)| d.type_name == current) {
+            continue;
+        }
+        let text = read_interface_file(&current)?;
+        let definition = MessageDefinition::parse(current, &text)?;
+        for field in &definition.fields {
+            if let BaseType::Message(nested) = &field.base_type {
+                pending.push_back(nested.clone());
+            }
+        }
+        definitions.push((definition, text));
+    }
+    Ok(definitions)
+}
+
+/// Build the `ros2msg` schema of `type_name` from the installed interface files, in the format
+/// read by `MessageRegistry::from_schema`.
+pub fn installed_schema(type_name: &InterfaceType) -> Result<String, String> {
+    let mut schema = String::new();
+    for (index, (definition, text)) in installed_definitions(type_name, |_| false)?
+        .into_iter()
+        .enumerate()
+    {
+        if index > 0 {
+            schema.push_str(&format!(
+                "\n{}\nMSG: {}/{}\n",
+                "=".repeat(80),
+                definition.type_name.package_name,
+                definition.type_name.type_name
+            ));
+        }
+        schema.push_str(&text);
+    }
+    Ok(schema)
+}
+
+/// A line of an interface definition, with the definitions of its nested messages expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionLine {
+    /// Nesting level, 0 for the lines of the interface itself.
+    pub depth: usize,
+    /// The line as written in its file, comment included.
+    pub text: String,
+    /// Index of the field declared on the line at each level, `[1, 0]` for the first field of
+    /// the message in the second field. `None` for comments, constants and separators.
+    pub field: Option<Vec<usize>>,
+    /// Whether the line declares a constant.
+    pub constant: bool,
+    /// Whether the field declared on the line is an array or a sequence.
+    pub collection: bool,
+}
+
+/// Lines of `text`, the interface file of `type_name`, with the definition of each nested
+/// message inserted under the field using it, like `ros2 interface show`. `read` returns the
+/// text of a message definition, nested definitions it fails to read are replaced by a comment.
+pub fn expand_definition(
+    type_name: &InterfaceType,
+    text: &str,
+    read: &dyn Fn(&InterfaceType) -> Result<String, String>,
+) -> Vec<DefinitionLine> {
+    let mut lines = Vec::new();
+    let mut stack = vec![type_name.clone()];
+    expand_into(&mut lines, type_name, text, &[], &mut stack, read);
+    lines
+}
+
+fn expand_into(
+    lines: &mut Vec<DefinitionLine>,
+    type_name: &InterfaceType,
+    text: &str,
+    path: &[usize],
+    stack: &mut Vec<InterfaceType>,
+    read: &dyn Fn(&InterfaceType) -> Result<String, String>,
+) {
+    let depth = path.len();
+    let mut index = 0;
+    for raw_line in text.lines() {
+        let line = strip_comment(raw_line).trim();
+        // Blank lines only help to read the interface itself
+        if raw_line.trim().is_empty() && depth > 0 {
+            continue;
+        }
+        // Request, response and feedback of services and actions are separate messages
+        if line == "---" {
+            index = 0;
+        }
+        let definition = MessageDefinition::parse(type_name.clone(), line).ok();
+        let constant = definition
+            .as_ref()
+            .is_some_and(|definition| !definition.constants.is_empty());
+        let field = definition.and_then(|definition| definition.fields.into_iter().next());
+        let field_path = field.as_ref().map(|_| [path, &[index]].concat());
+        lines.push(DefinitionLine {
+            depth,
+            text: raw_line.trim_end().to_string(),
+            field: field_path.clone(),
+            constant,
+            collection: field
+                .as_ref()
+                .is_some_and(|field| field.kind != FieldKind::Single),
+        });
+        let (Some(field), Some(field_path)) = (field, field_path) else {
+            continue;
+        };
+        index += 1;
+        let BaseType::Message(nested) = field.base_type else {
+            continue;
+        };
+        if stack.contains(&nested) {
+            continue;
+        }
+        match read(&nested) {
+            Ok(nested_text) => {
+                stack.push(nested.clone());
+                expand_into(lines, &nested, &nested_text, &field_path, stack, read);
+                stack.pop();
+            }
+            Err(e) => lines.push(DefinitionLine {
+                depth: depth + 1,
+                text: format!("# {}", e),
+                field: None,
+                constant: false,
+                collection: false,
+            }),
+        }
+    }
+}
+
+/// Index of the line declaring the field at `path`, a path in a `GenericMessage` where the
+/// elements of arrays and sequences are indexed too. The deepest field found when the path goes
+/// past the definition.
+pub fn find_field_line(lines: &[DefinitionLine], path: &[usize]) -> Option<usize> {
+    let mut definition_path = Vec::new();
+    let mut found = None;
+    let mut indices = path.iter();
+    while let Some(&index) = indices.next() {
+        definition_path.push(index);
+        let Some(line) = lines
+            .iter()
+            .position(|line| line.field.as_ref() == Some(&definition_path))
+        else {
+            break;
+        };
+        found = Some(line);
+        if lines[line].collection {
+            // Index of the element
+            indices.next();
+        }
+    }
+    found
+}
+
+/// Installed definition of `type_name`, a message, service or action, with its nested
+/// messages expanded.
+pub fn installed_definition(type_name: &InterfaceType) -> Result<Vec<DefinitionLine>, String> {
+    let text = read_interface_file(type_name)?;
+    Ok(expand_definition(type_name, &text, &read_interface_file))
+}
+
+fn read_interface_file(type_name: &InterfaceType) -> Result<String, String> {
+    let path = find_interface_file(type_name)?;
+    std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))
+}
+
+/// Locate `share/<pkg>/<category>/<Type>.<category>` in the prefixes of `AMENT_PREFIX_PATH`.
+pub fn find_interface_file(type_name: &InterfaceType) -> Result<PathBuf, String> {
+    let prefixes = std::env::var("AMENT_PREFIX_PATH")
+        .map_err(|_| "AMENT_PREFIX_PATH is not set, is ROS 2 sourced?".to_string())?;
+    std::env::split_paths(&prefixes)
+        .map(|prefix| {
+            prefix
+                .join("share")
+                .join(&type_name.package_name)
+                .join(&type_name.category)
+                .join(format!("{}.{}", type_name.type_name, type_name.category))
+        })
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("Interface definition for '{}' not found", type_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields_constants_and_defaults() {
+        let text = "\
+# A comment line
+int32 FOO=3 # trailing comment
+string BAR = \"a # b\"
+uint8[3] fixed
+float64[] dynamic
+string<=8[<=4] bounded
+bool flag true
+Point position
+";
+        let definition =
+            MessageDefinition::parse(InterfaceType::new("geometry_msgs/msg/Pose").unwrap(), text)
+                .unwrap();
+
+        assert_eq!(definition.constants.len(), 2);
+        assert_eq!(definition.constants[0].name, "FOO");
+        assert_eq!(definition.constants[0].value, "3");
+        assert_eq!(definition.constants[1].value, "\"a # b\"");
+
+        assert_eq!(definition.fields.len(), 5);
+        assert_eq!(definition.fields[0].kind, FieldKind::Array(3));
+        assert_eq!(definition.fields[1].kind, FieldKind::Sequence);
+        assert_eq!(definition.fields[2].base_type, BaseType::String(Some(8)));
+        assert_eq!(definition.fields[2].kind, FieldKind::BoundedSequence(4));
+        assert_eq!(definition.fields[3].default, Some("true".to_string()));
+        assert_eq!(
+            definition.fields[4].base_type,
+            BaseType::Message(InterfaceType::new("geometry_msgs/msg/Point").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_registry_from_schema() {
+        let schema = "\
+std_msgs/Header header
+string child_frame_id
+================================================================================
+MSG: std_msgs/Header
+builtin_interfaces/Time stamp
+string frame_id
+================================================================================
+MSG: builtin_interfaces/Time
+int32 sec
+uint32 nanosec
+";
+        let root = InterfaceType::new("nav_msgs/msg/Thing").unwrap();
+        let registry = MessageRegistry::from_schema(&root, schema).unwrap();
+
+        assert_eq!(registry.get(&root).unwrap().fields.len(), 2);
+        let header = registry
+            .get(&InterfaceType::new("std_msgs/msg/Header").unwrap())
+            .unwrap();
+        assert_eq!(header.fields[1].name, "frame_id");
+        assert!(registry.contains(&InterfaceType::new("builtin_interfaces/msg/Time").unwrap()));
+    }
+
+    #[test]
+    fn test_expand_definition() {
+        let text = "\
+# Mode of the robot
+uint8 IDLE=0 # Waiting
+uint8 MOVING=1
+uint8 mode
+std_msgs/Header[] headers
+---
+bool success true
+Missing missing
+";
+        let read = |type_name: &InterfaceType| match type_name.to_string().as_str() {
+            "std_msgs/msg/Header" => Ok("\n# Stamp\nbuiltin_interfaces/Time stamp\n".to_string()),
+            "builtin_interfaces/msg/Time" => Ok("int32 sec\nuint32 nanosec\n".to_string()),
+            _ => Err(format!(
+                "Interface definition for '{}' not found",
+                type_name
+            )),
+        };
+        let type_name = InterfaceType::new("test_msgs/srv/SetMode").unwrap();
+        let lines = expand_definition(&type_name, text, &read);
+
+        let summary = lines
+            .iter()
+            .map(|line| (line.depth, line.text.as_str(), line.field.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "# Mode of the robot", None),
+                (0, "uint8 IDLE=0 # Waiting", None),
+                (0, "uint8 MOVING=1", None),
+                (0, "uint8 mode", Some(vec![0])),
+                (0, "std_msgs/Header[] headers", Some(vec![1])),
+                (1, "# Stamp", None),
+                (1, "builtin_interfaces/Time stamp", Some(vec![1, 0])),
+                (2, "int32 sec", Some(vec![1, 0, 0])),
+                (2, "uint32 nanosec", Some(vec![1, 0, 1])),
+                (0, "---", None),
+                (0, "bool success true", Some(vec![0])),
+                (0, "Missing missing", Some(vec![1])),
+                (
+                    1,
+                    "# Interface definition for 'test_msgs/msg/Missing' not found",
+                    None
+                ),
+            ]
+        );
+        assert!(lines[1].constant && lines[2].constant);
+        assert!(!lines[3].constant);
+
+        // `headers[5].stamp.nanosec`
+        assert_eq!(find_field_line(&lines, &[1, 5, 0, 1]), Some(8));
+        assert_eq!(find_field_line(&lines, &[1, 5]), Some(4));
+        assert_eq!(find_field_line(&lines, &[7]), None);
+    }
+}
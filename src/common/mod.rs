@@ -1,6 +1,11 @@
 pub mod app;
+pub mod cdr;
 pub mod event;
 pub mod generic_message;
 pub mod generic_message_selector;
+pub mod json_message;
+pub mod message_definition;
+pub mod rate;
+pub mod recorder;
 pub mod style;
 pub mod utils;
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+
+/// Statistics on the periods between the messages of a window, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodStats {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    /// Mean absolute difference between consecutive periods.
+    pub jitter: f64,
+}
+
+impl PeriodStats {
+    pub fn rate(&self) -> f64 {
+        1.0 / self.mean
+    }
+}
+
+/// Receive times of the last `window` messages of a topic, with the gaps seen so far.
+///
+/// An interval is a gap when it is longer than `gap_factor` times the mean period of the
+/// window before it.
+#[derive(Debug, Clone)]
+pub struct RateWindow {
+    window: usize,
+    gap_factor: f64,
+    stamps: VecDeque<f64>,
+    /// Start and end of each gap.
+    pub gaps: Vec<(f64, f64)>,
+    /// Number of gaps since the start, or the last `clear`.
+    pub dropouts: usize,
+}
+
+impl RateWindow {
+    pub fn new(window: usize, gap_factor: f64) -> Self {
+        Self {
+            window: window.max(2),
+            gap_factor,
+            stamps: VecDeque::new(),
+            gaps: Vec::new(),
+            dropouts: 0,
+        }
+    }
+
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(2);
+        while self.stamps.len() > self.window {
+            self.stamps.pop_front();
+        }
+    }
+
+    pub fn set_gap_factor(&mut self, gap_factor: f64) {
+        self.gap_factor = gap_factor;
+    }
+
+    pub fn last_stamp(&self) -> Option<f64> {
+        self.stamps.back().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.stamps.clear();
+        self.gaps.clear();
+        self.dropouts = 0;
+    }
+
+    /// Add the receive time of a message, returns the mean rate of the window once it is full.
+    pub fn push(&mut self, stamp: f64) -> Option<f64> {
+        if let (Some(&first), Some(&last)) = (self.stamps.front(), self.stamps.back()) {
+            // Two intervals at least, one alone is no expectation
+            if self.stamps.len() > 2 {
+                let expected = (last - first) / (self.stamps.len() - 1) as f64;
+                if stamp - last > self.gap_factor * expected {
+                    self.gaps.push((last, stamp));
+                    self.dropouts += 1;
+                }
+            }
+        }
+
+        self.stamps.push_back(stamp);
+        if self.stamps.len() > self.window {
+            self.stamps.pop_front();
+        }
+        if self.stamps.len() < self.window {
+            return None;
+        }
+        let duration = self.stamps[self.window - 1] - self.stamps[0];
+        (duration > 0.0).then(|| (self.window - 1) as f64 / duration)
+    }
+
+    /// Statistics of the periods in the window, `None` before two messages.
+    pub fn stats(&self) -> Option<PeriodStats> {
+        let periods = self
+            .stamps
+            .iter()
+            .zip(self.stamps.iter().skip(1))
+            .map(|(previous, stamp)| stamp - previous)
+            .collect::<Vec<_>>();
+        if periods.is_empty() {
+            return None;
+        }
+        let count = periods.len() as f64;
+        let mean = periods.iter().sum::<f64>() / count;
+        if mean <= 0.0 {
+            return None;
+        }
+        let variance = periods.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / count;
+        let jitter = if periods.len() > 1 {
+            periods
+                .iter()
+                .zip(periods.iter().skip(1))
+                .map(|(previous, period)| (period - previous).abs())
+                .sum::<f64>()
+                / (count - 1.0)
+        } else {
+            0.0
+        };
+        Some(PeriodStats {
+            mean,
+            min: periods.iter().copied().fold(f64::MAX, f64::min),
+            max: periods.iter().copied().fold(f64::MIN, f64::max),
+            std_dev: variance.sqrt(),
+            jitter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_rate_and_stats() {
+        let mut window = RateWindow::new(4, 2.0);
+        assert_eq!(window.push(0.0), None);
+        assert_eq!(window.stats(), None);
+        assert_eq!(window.push(0.1), None);
+        assert_eq!(window.push(0.3), None);
+        // 3 periods over 0.4 s
+        assert_close(window.push(0.4).unwrap(), 7.5);
+
+        let stats = window.stats().unwrap();
+        assert_close(stats.mean, 0.4 / 3.0);
+        assert_close(stats.min, 0.1);
+        assert_close(stats.max, 0.2);
+        assert_close(stats.jitter, 0.1);
+        assert_close(stats.std_dev, 0.1 * 2.0_f64.sqrt() / 3.0);
+        assert_eq!(window.dropouts, 0);
+
+        // The window slides
+        window.set_window(2);
+        assert_close(window.push(0.5).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let mut window = RateWindow::new(10, 2.0);
+        for stamp in [0.0, 0.1, 0.2, 0.3] {
+            window.push(stamp);
+        }
+        window.push(0.45);
+        assert_eq!(window.dropouts, 0);
+        window.push(0.9);
+        assert_eq!(window.dropouts, 1);
+        assert_eq!(window.gaps, vec![(0.45, 0.9)]);
+
+        window.clear();
+        assert_eq!(window.dropouts, 0);
+        assert_eq!(window.last_stamp(), None);
+    }
+}
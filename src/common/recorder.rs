@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::connections::{qos::QosProfile, Connection, ConnectionType, SubscriptionHandle};
+
+type McapWriter = ::mcap::Writer<BufWriter<File>>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecorderStats {
+    pub message_count: u64,
+    /// Payload bytes written, excluding the MCAP framing.
+    pub bytes_written: u64,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    /// `None` once the recording is stopped.
+    writer: Option<McapWriter>,
+    stats: RecorderStats,
+    error: Option<String>,
+}
+
+/// Records topics of a connection to an MCAP file, with their `ros2msg` schema and the receive
+/// time as log time.
+pub struct McapRecorder {
+    path: PathBuf,
+    topic_count: usize,
+    state: Arc<Mutex<RecorderState>>,
+    subscriptions: Vec<SubscriptionHandle>,
+    started: Instant,
+}
+
+impl McapRecorder {
+    /// Create `path` and start recording `topics`, subscribed with `qos` or with the QoS of their
+    /// publishers when `None`.
+    pub fn start(
+        path: &Path,
+        connection: &mut ConnectionType,
+        topics: &[String],
+        qos: Option<QosProfile>,
+    ) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create '{}': {}", path.display(), e))?;
+        let mut writer = McapWriter::new(BufWriter::new(file))
+            .map_err(|e| format!("Failed to start MCAP file: {}", e))?;
+
+        let mut channels = Vec::new();
+        for topic in topics {
+            let type_name = connection
+                .get_topic_type(topic)
+                .ok_or(format!("Failed to get topic type for topic: {}", topic))?;
+            let definition = connection.get_message_definition(&type_name)?;
+            let schema_id = writer
+                .add_schema(&type_name.to_string(), "ros2msg", definition.as_bytes())
+                .map_err(|e| format!("Failed to write schema of '{}': {}", type_name, e))?;
+            let channel_id = writer
+                .add_channel(schema_id, topic, "cdr", &BTreeMap::new())
+                .map_err(|e| format!("Failed to write channel of '{}': {}", topic, e))?;
+            channels.push((topic, channel_id));
+        }
+
+        let state = Arc::new(Mutex::new(RecorderState {
+            writer: Some(writer),
+            ..Default::default()
+        }));
+
+        let mut subscriptions = Vec::new();
+        for (topic, channel_id) in channels {
+            let state = state.clone();
+            let sequence = AtomicU32::new(0);
+            let subscription = connection.subscribe_raw(topic, qos, move |data, metadata| {
+                let mut guard = state.lock().unwrap();
+                let state = &mut *guard;
+                let Some(writer) = state.writer.as_mut() else {
+                    return;
+                };
+                let log_time = metadata
+                    .received_time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64);
+                let header = ::mcap::records::MessageHeader {
+                    channel_id,
+                    sequence: sequence.fetch_add(1, Ordering::Relaxed),
+                    log_time,
+                    publish_time: log_time,
+                };
+                match writer.write_to_known_channel(&header, &data) {
+                    Ok(()) => {
+                        state.stats.message_count += 1;
+                        state.stats.bytes_written += data.len() as u64;
+                    }
+                    Err(e) => state.error = Some(format!("Failed to write message: {}", e)),
+                }
+            })?;
+            subscriptions.push(subscription);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            topic_count: topics.len(),
+            state,
+            subscriptions,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn topic_count(&self) -> usize {
+        self.topic_count
+    }
+
+    pub fn stats(&self) -> RecorderStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// Last write error, if any.
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().unwrap().error.clone()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Stop recording and write the MCAP summary. Does nothing if already stopped.
+    pub fn stop(&mut self) -> Result<(), String> {
+        self.subscriptions.clear();
+        let writer = self.state.lock().unwrap().writer.take();
+        if let Some(mut writer) = writer {
+            writer
+                .finish()
+                .map_err(|e| format!("Failed to finish '{}': {}", self.path.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for McapRecorder {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::generic_message::InterfaceType;
+    use crate::connections::mcap::ConnectionMcap;
+
+    #[test]
+    fn test_record_from_recording() {
+        let source =
+            std::env::temp_dir().join(format!("splinter_rec_src_{}.mcap", std::process::id()));
+        let target =
+            std::env::temp_dir().join(format!("splinter_rec_dst_{}.mcap", std::process::id()));
+
+        {
+            let file = BufWriter::new(File::create(&source).unwrap());
+            let mut writer = McapWriter::new(file).unwrap();
+            let schema_id = writer
+                .add_schema("std_msgs/msg/String", "ros2msg", b"string data\n")
+                .unwrap();
+            let channel_id = writer
+                .add_channel(schema_id, "/chatter", "cdr", &BTreeMap::new())
+                .unwrap();
+            let mut data = vec![0x00, 0x01, 0x00, 0x00];
+            data.extend_from_slice(&6u32.to_le_bytes());
+            data.extend_from_slice(b"hello\0");
+            writer
+                .write_to_known_channel(
+                    &::mcap::records::MessageHeader {
+                        channel_id,
+                        sequence: 0,
+                        log_time: 1_000_000,
+                        publish_time: 1_000_000,
+                    },
+                    &data,
+                )
+                .unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut connection = ConnectionType::Mcap(ConnectionMcap::open(&source).unwrap());
+        let playback = connection.playback().unwrap();
+        playback.toggle_pause();
+        playback.seek(SystemTime::UNIX_EPOCH);
+
+        let mut recorder =
+            McapRecorder::start(&target, &mut connection, &["/chatter".to_string()], None).unwrap();
+        playback.step();
+        assert_eq!(recorder.stats().message_count, 1);
+        recorder.stop().unwrap();
+
+        let recorded = ConnectionMcap::open(&target).unwrap();
+        assert_eq!(recorded.list_topics().unwrap().len(), 1);
+        assert_eq!(
+            recorded.get_topic_type("/chatter"),
+            Some(InterfaceType::new("std_msgs/msg/String").unwrap())
+        );
+
+        std::fs::remove_file(&source).unwrap();
+        std::fs::remove_file(&target).unwrap();
+    }
+}
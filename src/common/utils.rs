@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use ratatui::{
     style::{Modifier, Style},
     text::Span,
@@ -76,10 +78,29 @@ pub fn truncate_namespaces(name: &str, indices: &[u32], max_len: usize) -> (Stri
     (truncated_name, new_indices)
 }
 
+/// Time of day of `time` in UTC, `HH:MM:SS.mmm`, for the timestamps of logs.
+pub fn format_time_of_day(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_time_of_day() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_123);
+        assert_eq!(format_time_of_day(time), "22:13:20.123");
+    }
+
     #[test]
     fn test_truncate_namespaces() {
         let name = "/a/very/long/topic/name/that/needs/truncation";
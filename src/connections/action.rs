@@ -0,0 +1,377 @@
+use std::fmt::Display;
+
+use uuid::Uuid;
+
+use crate::common::generic_message::{
+    ArrayField, GenericField, GenericMessage, InterfaceType, SequenceField, SimpleField,
+};
+
+use super::NamedInterface;
+
+/// Actions are implemented with hidden services and topics under `<action>/_action/`.
+const ACTION_PREFIX: &str = "/_action/";
+
+/// Unique identifier of a goal, `unique_identifier_msgs/msg/UUID`.
+pub type GoalId = [u8; 16];
+
+/// Random goal identifier, a version 4 UUID like the ones rclcpp and rclpy generate.
+pub fn new_goal_id() -> GoalId {
+    *Uuid::new_v4().as_bytes()
+}
+
+/// Canonical text form of a goal identifier, 8-4-4-4-12 hexadecimal digits.
+pub fn goal_id_string(goal_id: &GoalId) -> String {
+    let hex: String = goal_id.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Status of a goal, `action_msgs/msg/GoalStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    Unknown,
+    Accepted,
+    Executing,
+    Canceling,
+    Succeeded,
+    Canceled,
+    Aborted,
+}
+
+impl GoalStatus {
+    pub fn from_code(code: i8) -> Self {
+        match code {
+            1 => GoalStatus::Accepted,
+            2 => GoalStatus::Executing,
+            3 => GoalStatus::Canceling,
+            4 => GoalStatus::Succeeded,
+            5 => GoalStatus::Canceled,
+            6 => GoalStatus::Aborted,
+            _ => GoalStatus::Unknown,
+        }
+    }
+
+    /// Whether the goal is done, no feedback or status change follows.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            GoalStatus::Succeeded | GoalStatus::Canceled | GoalStatus::Aborted
+        )
+    }
+}
+
+impl Display for GoalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GoalStatus::Unknown => "unknown",
+            GoalStatus::Accepted => "accepted",
+            GoalStatus::Executing => "executing",
+            GoalStatus::Canceling => "canceling",
+            GoalStatus::Succeeded => "succeeded",
+            GoalStatus::Canceled => "canceled",
+            GoalStatus::Aborted => "aborted",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Name of one of the hidden services or topics of an action, e.g. `send_goal` or `feedback`.
+pub fn action_endpoint(action_name: &str, part: &str) -> String {
+    format!("{}{}{}", action_name, ACTION_PREFIX, part)
+}
+
+/// Type of one of the hidden services or messages of an action, e.g. `Fibonacci_SendGoal`.
+pub fn action_part(action_type: &InterfaceType, part: &str) -> InterfaceType {
+    InterfaceType {
+        package_name: action_type.package_name.clone(),
+        category: "action".to_string(),
+        type_name: format!("{}_{}", action_type.type_name, part),
+    }
+}
+
+/// Type of the service cancelling goals, shared by all the actions.
+pub fn cancel_goal_type() -> InterfaceType {
+    InterfaceType {
+        package_name: "action_msgs".to_string(),
+        category: "srv".to_string(),
+        type_name: "CancelGoal".to_string(),
+    }
+}
+
+/// The actions behind the hidden `<action>/_action/send_goal` services of `services`.
+pub fn actions_from_services(services: &[(String, InterfaceType)]) -> Vec<NamedInterface> {
+    let mut actions: Vec<NamedInterface> = services
+        .iter()
+        .filter_map(|(name, type_name)| {
+            let action_name = name.strip_suffix(&format!("{}send_goal", ACTION_PREFIX))?;
+            Some(NamedInterface {
+                name: action_name.to_string(),
+                type_name: InterfaceType {
+                    package_name: type_name.package_name.clone(),
+                    category: "action".to_string(),
+                    type_name: type_name.type_name.strip_suffix("_SendGoal")?.to_string(),
+                },
+            })
+        })
+        .collect();
+    actions.sort_by(|a, b| a.name.cmp(&b.name));
+    actions
+}
+
+/// Message field `name`, for the nested messages of the action types.
+fn message_field<'a>(
+    message: &'a GenericMessage,
+    name: &str,
+) -> Result<&'a GenericMessage, String> {
+    match message.get(name) {
+        Some(GenericField::Simple(SimpleField::Message(field))) => Ok(field),
+        _ => Err(format!(
+            "'{}' has no '{}' message field",
+            message.type_name(),
+            name
+        )),
+    }
+}
+
+fn message_field_mut<'a>(
+    message: &'a mut GenericMessage,
+    name: &str,
+) -> Result<&'a mut GenericMessage, String> {
+    let type_name = message.type_name().clone();
+    match message.get_mut(name) {
+        Some(GenericField::Simple(SimpleField::Message(field))) => Ok(field),
+        _ => Err(format!("'{}' has no '{}' message field", type_name, name)),
+    }
+}
+
+/// Goal identifier held by the UUID message field `name`.
+pub fn get_goal_id(message: &GenericMessage, name: &str) -> Result<GoalId, String> {
+    let uuid = message_field(message, name)?;
+    match uuid.get("uuid") {
+        Some(GenericField::Array(ArrayField::Uint8(bytes))) => bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid goal id".to_string()),
+        _ => Err(format!("'{}' is not a UUID", name)),
+    }
+}
+
+/// Write `goal_id` in the UUID message field `name`.
+pub fn set_goal_id(
+    message: &mut GenericMessage,
+    name: &str,
+    goal_id: &GoalId,
+) -> Result<(), String> {
+    let uuid = message_field_mut(message, name)?;
+    match uuid.get_mut("uuid") {
+        Some(GenericField::Array(ArrayField::Uint8(bytes))) => {
+            *bytes = goal_id.to_vec();
+            Ok(())
+        }
+        _ => Err(format!("'{}' is not a UUID", name)),
+    }
+}
+
+/// The goal of a `SendGoal` request template.
+pub fn goal_from_request(request: &GenericMessage) -> Result<GenericMessage, String> {
+    message_field(request, "goal").cloned()
+}
+
+/// `SendGoal` request sending `goal` with the identifier `goal_id`, built from the request
+/// template of the connection.
+pub fn send_goal_request(
+    template: &GenericMessage,
+    goal_id: &GoalId,
+    goal: &GenericMessage,
+) -> Result<GenericMessage, String> {
+    let mut request = template.clone();
+    set_goal_id(&mut request, "goal_id", goal_id)?;
+    *message_field_mut(&mut request, "goal")? = goal.clone();
+    Ok(request)
+}
+
+/// Whether the server accepted the goal, from a `SendGoal` response.
+pub fn goal_accepted(response: &GenericMessage) -> Result<bool, String> {
+    match response.get("accepted") {
+        Some(GenericField::Simple(SimpleField::Boolean(accepted))) => Ok(*accepted),
+        _ => Err("Invalid SendGoal response".to_string()),
+    }
+}
+
+/// `CancelGoal` request for `goal_id`, built from the request template of the connection.
+pub fn cancel_goal_request(
+    template: &GenericMessage,
+    goal_id: &GoalId,
+) -> Result<GenericMessage, String> {
+    let mut request = template.clone();
+    set_goal_id(
+        message_field_mut(&mut request, "goal_info")?,
+        "goal_id",
+        goal_id,
+    )?;
+    Ok(request)
+}
+
+/// Error explaining a rejected cancel request, from a `CancelGoal` response.
+pub fn cancel_error(response: &GenericMessage) -> Option<String> {
+    match response.get("return_code") {
+        Some(GenericField::Simple(SimpleField::Int8(0))) => None,
+        Some(GenericField::Simple(SimpleField::Int8(1))) => {
+            Some("The server rejected the cancel request".to_string())
+        }
+        Some(GenericField::Simple(SimpleField::Int8(2))) => {
+            Some("The server doesn't know the goal".to_string())
+        }
+        Some(GenericField::Simple(SimpleField::Int8(3))) => {
+            Some("The goal is already done".to_string())
+        }
+        _ => Some("Invalid CancelGoal response".to_string()),
+    }
+}
+
+/// Final status and result, from a `GetResult` response.
+pub fn goal_result(response: &GenericMessage) -> Result<(GoalStatus, GenericMessage), String> {
+    let status = match response.get("status") {
+        Some(GenericField::Simple(SimpleField::Int8(status))) => GoalStatus::from_code(*status),
+        _ => return Err("Invalid GetResult response".to_string()),
+    };
+    Ok((status, message_field(response, "result")?.clone()))
+}
+
+/// Feedback of `goal_id` in a `FeedbackMessage`, `None` for the feedback of other goals.
+pub fn goal_feedback(message: &GenericMessage, goal_id: &GoalId) -> Option<GenericMessage> {
+    if get_goal_id(message, "goal_id").ok()? != *goal_id {
+        return None;
+    }
+    message_field(message, "feedback").ok().cloned()
+}
+
+/// Status of `goal_id` in an `action_msgs/msg/GoalStatusArray`.
+pub fn goal_status(message: &GenericMessage, goal_id: &GoalId) -> Option<GoalStatus> {
+    let Some(GenericField::Sequence(SequenceField::Message(statuses))) = message.get("status_list")
+    else {
+        return None;
+    };
+    statuses.iter().find_map(|status| {
+        let goal_info = message_field(status, "goal_info").ok()?;
+        if get_goal_id(goal_info, "goal_id").ok()? != *goal_id {
+            return None;
+        }
+        match status.get("status") {
+            Some(GenericField::Simple(SimpleField::Int8(code))) => {
+                Some(GoalStatus::from_code(*code))
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::json_message::from_json;
+    use crate::common::message_definition::MessageRegistry;
+
+    const SEND_GOAL: &str = "\
+unique_identifier_msgs/UUID goal_id
+Fibonacci_Goal goal
+================================================================================
+MSG: unique_identifier_msgs/UUID
+uint8[16] uuid
+================================================================================
+MSG: example_interfaces/Fibonacci_Goal
+int32 order";
+
+    fn send_goal_template() -> GenericMessage {
+        let type_name =
+            InterfaceType::new("example_interfaces/action/Fibonacci_SendGoal_Request").unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, SEND_GOAL).unwrap();
+        from_json(&registry, &type_name, &serde_json::Value::Null).unwrap()
+    }
+
+    #[test]
+    fn test_actions_from_services() {
+        let services = vec![
+            (
+                "/fibonacci/_action/send_goal".to_string(),
+                InterfaceType::new("example_interfaces/action/Fibonacci_SendGoal").unwrap(),
+            ),
+            (
+                "/fibonacci/_action/get_result".to_string(),
+                InterfaceType::new("example_interfaces/action/Fibonacci_GetResult").unwrap(),
+            ),
+            (
+                "/add_two_ints".to_string(),
+                InterfaceType::new("example_interfaces/srv/AddTwoInts").unwrap(),
+            ),
+        ];
+        let actions = actions_from_services(&services);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].name, "/fibonacci");
+        assert_eq!(
+            actions[0].type_name,
+            InterfaceType::new("example_interfaces/action/Fibonacci").unwrap()
+        );
+        assert_eq!(
+            action_part(&actions[0].type_name, "GetResult"),
+            InterfaceType::new("example_interfaces/action/Fibonacci_GetResult").unwrap()
+        );
+        assert_eq!(
+            action_endpoint("/fibonacci", "feedback"),
+            "/fibonacci/_action/feedback"
+        );
+    }
+
+    #[test]
+    fn test_send_goal_request() {
+        let template = send_goal_template();
+        let mut goal = goal_from_request(&template).unwrap();
+        assert_eq!(goal["order"], GenericField::Simple(SimpleField::Int32(0)));
+        goal = from_json(
+            &MessageRegistry::from_schema(goal.type_name(), "int32 order").unwrap(),
+            goal.type_name(),
+            &json!({ "order": 5 }),
+        )
+        .unwrap();
+
+        let goal_id = new_goal_id();
+        let request = send_goal_request(&template, &goal_id, &goal).unwrap();
+        assert_eq!(get_goal_id(&request, "goal_id").unwrap(), goal_id);
+        assert_eq!(goal_from_request(&request).unwrap(), goal);
+        assert!(goal_feedback(&request, &goal_id).is_none());
+    }
+
+    #[test]
+    fn test_goal_id() {
+        assert_ne!(new_goal_id(), new_goal_id());
+        let random = new_goal_id();
+        assert_eq!(random[6] >> 4, 4);
+        assert_eq!(random[8] >> 6, 0b10);
+        let goal_id = [
+            0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef,
+        ];
+        assert_eq!(
+            goal_id_string(&goal_id),
+            "12345678-9abc-def0-0123-456789abcdef"
+        );
+    }
+
+    #[test]
+    fn test_goal_status() {
+        assert_eq!(GoalStatus::from_code(4), GoalStatus::Succeeded);
+        assert!(GoalStatus::from_code(6).is_terminal());
+        assert!(!GoalStatus::from_code(2).is_terminal());
+        assert_eq!(GoalStatus::from_code(42), GoalStatus::Unknown);
+        assert_eq!(GoalStatus::Canceling.to_string(), "canceling");
+    }
+}
@@ -0,0 +1,1130 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{spawn, JoinHandle};
+use std::time::SystemTime;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+use crate::common::cdr;
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::json_message::from_json;
+use crate::common::message_definition::{installed_schema, resolve_type_name, MessageRegistry};
+use crate::connections::pending::{call_timeout, PendingCall};
+use crate::connections::qos::QosProfile;
+use crate::connections::websocket;
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, PublisherFunc, SubscriptionHandle,
+    TopicEndpointInfo,
+};
+
+const SUBPROTOCOL: &str = "foxglove.websocket.v1";
+
+// Binary opcodes, server to client
+const OPCODE_MESSAGE_DATA: u8 = 0x01;
+const OPCODE_SERVICE_CALL_RESPONSE: u8 = 0x03;
+
+// Binary opcodes, client to server
+const OPCODE_CLIENT_MESSAGE_DATA: u8 = 0x01;
+const OPCODE_SERVICE_CALL_REQUEST: u8 = 0x02;
+
+/// Parameter names are `<fully qualified node name>.<parameter name>` in the foxglove bridge.
+const PARAMETER_SEPARATOR: char = '.';
+
+enum Callback {
+    Message(Box<dyn Fn(GenericMessage, MessageMetadata) + Send + Sync>),
+    Raw(Box<dyn Fn(Vec<u8>, MessageMetadata) + Send + Sync>),
+}
+
+/// A topic advertised by the server.
+struct Channel {
+    topic: String,
+    type_name: InterfaceType,
+    /// Definitions needed to decode the channel, or the reason they could not be loaded.
+    registry: Result<Arc<MessageRegistry>, String>,
+    /// `ros2msg` schema of the channel, empty if the server didn't send one.
+    schema: String,
+}
+
+struct Subscription {
+    channel_id: u64,
+    /// Local callbacks served by the server subscription, with their id.
+    callbacks: Vec<(u32, Callback)>,
+}
+
+/// A service advertised by the server.
+struct Service {
+    id: u64,
+    type_name: InterfaceType,
+    /// Request and response types with their definitions.
+    request: Result<(InterfaceType, Arc<MessageRegistry>), String>,
+    response: Result<(InterfaceType, Arc<MessageRegistry>), String>,
+}
+
+/// Nodes involved with each topic and service, from the connection graph updates.
+#[derive(Default)]
+struct Graph {
+    publishers: HashMap<String, Vec<String>>,
+    subscribers: HashMap<String, Vec<String>>,
+    providers: HashMap<String, Vec<String>>,
+}
+
+/// State shared with the socket thread.
+#[derive(Default)]
+struct Shared {
+    capabilities: Mutex<Vec<String>>,
+    channels: Mutex<HashMap<u64, Channel>>,
+    subscriptions: Mutex<HashMap<u32, Subscription>>,
+    services: Mutex<HashMap<String, Service>>,
+    graph: Mutex<Graph>,
+    /// Service calls waiting for their response, by call id.
+    pending_calls: Mutex<HashMap<u32, Sender<Result<Vec<u8>, String>>>>,
+    /// Parameter requests waiting for their values, by request id.
+    pending_parameters: Mutex<HashMap<String, Sender<Value>>>,
+    connected: AtomicBool,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Definitions of `type_name` from a `ros2msg` schema, or from the installed interfaces when the
+/// server sent none.
+fn load_registry(
+    type_name: &InterfaceType,
+    encoding: &str,
+    schema: &str,
+) -> Result<Arc<MessageRegistry>, String> {
+    let registry = if schema.is_empty() {
+        let mut registry = MessageRegistry::new();
+        registry.load_installed(type_name)?;
+        registry
+    } else if encoding == "ros2msg" {
+        MessageRegistry::from_schema(type_name, schema)?
+    } else {
+        return Err(format!("Unsupported schema encoding '{}'", encoding));
+    };
+    Ok(Arc::new(registry))
+}
+
+fn parse_channel(channel: &Value) -> Option<(u64, Channel)> {
+    let id = channel["id"].as_u64()?;
+    let topic = channel["topic"].as_str()?.to_string();
+    let type_name = resolve_type_name(channel["schemaName"].as_str()?, "").ok()?;
+    let schema = channel["schema"].as_str().unwrap_or_default().to_string();
+    let registry = match channel["encoding"].as_str() {
+        Some("cdr") => load_registry(
+            &type_name,
+            channel["schemaEncoding"].as_str().unwrap_or("ros2msg"),
+            &schema,
+        ),
+        encoding => Err(format!(
+            "Unsupported message encoding '{}'",
+            encoding.unwrap_or_default()
+        )),
+    };
+    Some((
+        id,
+        Channel {
+            topic,
+            type_name,
+            registry,
+            schema,
+        },
+    ))
+}
+
+fn parse_service(service: &Value) -> Option<(String, Service)> {
+    let id = service["id"].as_u64()?;
+    let name = service["name"].as_str()?.to_string();
+    let type_name = service["type"].as_str()?;
+    let parts: Vec<&str> = type_name.split('/').collect();
+    let type_name = match parts.as_slice() {
+        [package, type_name] => InterfaceType::new(&format!("{}/srv/{}", package, type_name)),
+        _ => InterfaceType::new(type_name),
+    }
+    .ok()?;
+
+    // Newer servers describe each part in an object, older ones only send the schemas.
+    let part = |key: &str, suffix: &str| {
+        let part_type = InterfaceType {
+            package_name: type_name.package_name.clone(),
+            category: "srv".to_string(),
+            type_name: format!("{}_{}", type_name.type_name, suffix),
+        };
+        let (encoding, schema) = match &service[key] {
+            Value::Object(_) => (
+                service[key]["schemaEncoding"].as_str().unwrap_or("ros2msg"),
+                service[key]["schema"].as_str().unwrap_or_default(),
+            ),
+            _ => (
+                "ros2msg",
+                service[&format!("{}Schema", key)]
+                    .as_str()
+                    .unwrap_or_default(),
+            ),
+        };
+        let registry = if schema.is_empty() {
+            Err(format!("No {} schema for '{}'", key, type_name))
+        } else {
+            load_registry(&part_type, encoding, schema)
+        };
+        registry.map(|registry| (part_type, registry))
+    };
+
+    Some((
+        name,
+        Service {
+            id,
+            request: part("request", "Request"),
+            response: part("response", "Response"),
+            type_name,
+        },
+    ))
+}
+
+fn parameter_from_foxglove(parameter: &Value) -> Option<Parameters> {
+    let value = &parameter["value"];
+    // Types that can't be told from the JSON value are given explicitly.
+    match parameter["type"].as_str() {
+        Some("byte_array") => Some(Parameters::ByteArray(BASE64.decode(value.as_str()?).ok()?)),
+        Some("float64") => Some(Parameters::Double(value.as_f64()?)),
+        Some("float64_array") => Some(Parameters::DoubleArray(
+            value.as_array()?.iter().filter_map(Value::as_f64).collect(),
+        )),
+        _ => Parameters::from_json(value),
+    }
+}
+
+fn parameter_to_foxglove(name: &str, parameter: &Parameters) -> Value {
+    match parameter {
+        Parameters::ByteArray(v) => {
+            json!({ "name": name, "value": BASE64.encode(v), "type": "byte_array" })
+        }
+        Parameters::Double(_) => {
+            json!({ "name": name, "value": parameter.to_json(), "type": "float64" })
+        }
+        Parameters::DoubleArray(_) => {
+            json!({ "name": name, "value": parameter.to_json(), "type": "float64_array" })
+        }
+        _ => json!({ "name": name, "value": parameter.to_json() }),
+    }
+}
+
+fn nodes_by_name(entries: &Value, ids_key: &str) -> HashMap<String, Vec<String>> {
+    entries
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry["name"].as_str()?.to_string();
+            let ids = entry[ids_key]
+                .as_array()?
+                .iter()
+                .filter_map(|id| id.as_str().map(str::to_string))
+                .collect();
+            Some((name, ids))
+        })
+        .collect()
+}
+
+impl Shared {
+    fn handle_text(&self, text: &str, outgoing: &Sender<Message>) {
+        let Ok(message) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+        match message["op"].as_str() {
+            Some("serverInfo") => {
+                let capabilities: Vec<String> = message["capabilities"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| c.as_str().map(str::to_string))
+                    .collect();
+                if capabilities.iter().any(|c| c == "connectionGraph") {
+                    let _ = outgoing.send(Message::Text(
+                        json!({ "op": "subscribeConnectionGraph" }).to_string(),
+                    ));
+                }
+                *self.capabilities.lock().unwrap() = capabilities;
+            }
+            Some("advertise") => {
+                let mut channels = self.channels.lock().unwrap();
+                for channel in message["channels"].as_array().into_iter().flatten() {
+                    if let Some((id, channel)) = parse_channel(channel) {
+                        channels.insert(id, channel);
+                    }
+                }
+            }
+            Some("unadvertise") => {
+                let mut channels = self.channels.lock().unwrap();
+                for id in message["channelIds"].as_array().into_iter().flatten() {
+                    if let Some(id) = id.as_u64() {
+                        channels.remove(&id);
+                    }
+                }
+            }
+            Some("advertiseServices") => {
+                let mut services = self.services.lock().unwrap();
+                for service in message["services"].as_array().into_iter().flatten() {
+                    if let Some((name, service)) = parse_service(service) {
+                        services.insert(name, service);
+                    }
+                }
+            }
+            Some("unadvertiseServices") => {
+                let ids: HashSet<u64> = message["serviceIds"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_u64)
+                    .collect();
+                self.services
+                    .lock()
+                    .unwrap()
+                    .retain(|_, service| !ids.contains(&service.id));
+            }
+            Some("parameterValues") => {
+                let Some(id) = message["id"].as_str() else {
+                    return;
+                };
+                if let Some(sender) = self.pending_parameters.lock().unwrap().remove(id) {
+                    let _ = sender.send(message["parameters"].clone());
+                }
+            }
+            Some("serviceCallFailure") => {
+                let Some(call_id) = message["callId"].as_u64() else {
+                    return;
+                };
+                if let Some(sender) = self.pending_calls.lock().unwrap().remove(&(call_id as u32)) {
+                    let _ = sender.send(Err(message["message"]
+                        .as_str()
+                        .unwrap_or("Service call failed")
+                        .to_string()));
+                }
+            }
+            Some("connectionGraphUpdate") => {
+                let mut graph = self.graph.lock().unwrap();
+                graph
+                    .publishers
+                    .extend(nodes_by_name(&message["publishedTopics"], "publisherIds"));
+                graph
+                    .subscribers
+                    .extend(nodes_by_name(&message["subscribedTopics"], "subscriberIds"));
+                graph
+                    .providers
+                    .extend(nodes_by_name(&message["advertisedServices"], "providerIds"));
+                for topic in message["removedTopics"].as_array().into_iter().flatten() {
+                    if let Some(topic) = topic.as_str() {
+                        graph.publishers.remove(topic);
+                        graph.subscribers.remove(topic);
+                    }
+                }
+                for service in message["removedServices"].as_array().into_iter().flatten() {
+                    if let Some(service) = service.as_str() {
+                        graph.providers.remove(service);
+                    }
+                }
+            }
+            // Status messages and anything else are not handled
+            _ => {}
+        }
+    }
+
+    fn handle_binary(&self, data: &[u8]) {
+        match data.first() {
+            Some(&OPCODE_MESSAGE_DATA) => {
+                // opcode, subscription id (u32), timestamp (u64), payload
+                let (Some(subscription_id), Some(payload)) = (read_u32(data, 1), data.get(13..))
+                else {
+                    return;
+                };
+                let subscriptions = self.subscriptions.lock().unwrap();
+                let Some(subscription) = subscriptions.get(&subscription_id) else {
+                    return;
+                };
+                let channels = self.channels.lock().unwrap();
+                let Some(channel) = channels.get(&subscription.channel_id) else {
+                    return;
+                };
+
+                // Only decode when someone wants the decoded message, and only once.
+                let mut generic_message = None;
+                for (_, callback) in &subscription.callbacks {
+                    let metadata = MessageMetadata {
+                        received_time: SystemTime::now(),
+                        serialized_size: Some(payload.len()),
+                    };
+                    match callback {
+                        Callback::Message(callback) => {
+                            if generic_message.is_none() {
+                                generic_message =
+                                    Some(channel.registry.as_ref().ok().and_then(|registry| {
+                                        cdr::deserialize(registry, &channel.type_name, payload).ok()
+                                    }));
+                            }
+                            if let Some(Some(generic_message)) = &generic_message {
+                                callback(generic_message.clone(), metadata);
+                            }
+                        }
+                        Callback::Raw(callback) => callback(payload.to_vec(), metadata),
+                    }
+                }
+            }
+            Some(&OPCODE_SERVICE_CALL_RESPONSE) => {
+                // opcode, service id (u32), call id (u32), encoding length (u32), encoding, payload
+                let (Some(call_id), Some(encoding_length)) = (read_u32(data, 5), read_u32(data, 9))
+                else {
+                    return;
+                };
+                let Some(payload) = data.get(13 + encoding_length as usize..) else {
+                    return;
+                };
+                if let Some(sender) = self.pending_calls.lock().unwrap().remove(&call_id) {
+                    let _ = sender.send(Ok(payload.to_vec()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Connection to a server implementing the Foxglove WebSocket protocol, such as
+/// `foxglove_bridge`.
+///
+/// Topics are the channels advertised by the server, decoded with the `ros2msg` schemas it sends.
+/// Services, parameters, publishing and the node graph depend on the capabilities of the server.
+pub struct ConnectionFoxglove {
+    name: String,
+    outgoing: Sender<Message>,
+    shared: Arc<Shared>,
+    next_id: AtomicU32,
+    #[allow(unused)]
+    thread: JoinHandle<()>,
+}
+
+impl Debug for ConnectionFoxglove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionFoxglove")
+            .field("url", &self.name)
+            .field("connected", &self.shared.connected.load(Ordering::Relaxed))
+            .field("channels", &self.shared.channels.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl ConnectionFoxglove {
+    /// Connect to the Foxglove WebSocket server at `url`, e.g. `ws://localhost:8765`.
+    pub fn new(url: &str) -> Result<Self, String> {
+        let socket = websocket::connect(url, Some(SUBPROTOCOL))?;
+
+        let shared = Arc::new(Shared::default());
+        shared.connected.store(true, Ordering::Relaxed);
+        let (outgoing, outgoing_receiver) = channel();
+        let thread = {
+            let shared = shared.clone();
+            let outgoing = outgoing.clone();
+            spawn(move || {
+                websocket::run(socket, outgoing_receiver, |message| match message {
+                    Message::Text(text) => shared.handle_text(&text, &outgoing),
+                    Message::Binary(data) => shared.handle_binary(&data),
+                    _ => {}
+                });
+                shared.connected.store(false, Ordering::Relaxed);
+                // Dropping the senders wakes up the pending requests.
+                shared.pending_calls.lock().unwrap().clear();
+                shared.pending_parameters.lock().unwrap().clear();
+            })
+        };
+
+        Ok(Self {
+            name: url.to_string(),
+            outgoing,
+            shared,
+            next_id: AtomicU32::new(1),
+            thread,
+        })
+    }
+
+    fn send(&self, message: Message) -> Result<(), String> {
+        if !self.shared.connected.load(Ordering::Relaxed) {
+            return Err(format!("Connection to '{}' is closed", self.name));
+        }
+        self.outgoing
+            .send(message)
+            .map_err(|_| format!("Connection to '{}' is closed", self.name))
+    }
+
+    fn next_id(&self) -> u32 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn require_capability(&self, capability: &str) -> Result<(), String> {
+        if self
+            .shared
+            .capabilities
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|c| c == capability)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "The server at '{}' doesn't support '{}'",
+                self.name, capability
+            ))
+        }
+    }
+
+    fn add_callback(&self, topic: &str, callback: Callback) -> Result<SubscriptionHandle, String> {
+        let channel_id = self
+            .shared
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, channel)| channel.topic == topic)
+            .map(|(id, channel)| {
+                channel
+                    .registry
+                    .as_ref()
+                    .map(|_| *id)
+                    .map_err(|e| format!("Cannot decode topic '{}': {}", topic, e))
+            })
+            .ok_or_else(|| format!("Topic '{}' is not advertised by the server", topic))??;
+
+        let callback_id = self.next_id();
+        let mut subscriptions = self.shared.subscriptions.lock().unwrap();
+        // A single server subscription serves all the local subscribers of a channel.
+        let existing = subscriptions
+            .iter_mut()
+            .find(|(_, subscription)| subscription.channel_id == channel_id);
+        let subscription_id = match existing {
+            Some((subscription_id, subscription)) => {
+                subscription.callbacks.push((callback_id, callback));
+                *subscription_id
+            }
+            None => {
+                let subscription_id = self.next_id();
+                self.send(Message::Text(
+                    json!({
+                        "op": "subscribe",
+                        "subscriptions": [{ "id": subscription_id, "channelId": channel_id }],
+                    })
+                    .to_string(),
+                ))?;
+                subscriptions.insert(
+                    subscription_id,
+                    Subscription {
+                        channel_id,
+                        callbacks: vec![(callback_id, callback)],
+                    },
+                );
+                subscription_id
+            }
+        };
+
+        let shared = Arc::downgrade(&self.shared);
+        let outgoing = self.outgoing.clone();
+        Ok(SubscriptionHandle::new(move || {
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            let mut subscriptions = shared.subscriptions.lock().unwrap();
+            let Some(subscription) = subscriptions.get_mut(&subscription_id) else {
+                return;
+            };
+            subscription.callbacks.retain(|(id, _)| *id != callback_id);
+            // The last local subscriber is gone, stop the server subscription.
+            if subscription.callbacks.is_empty() {
+                subscriptions.remove(&subscription_id);
+                let operation = json!({
+                    "op": "unsubscribe",
+                    "subscriptionIds": [subscription_id],
+                });
+                let _ = outgoing.send(Message::Text(operation.to_string()));
+            }
+        }))
+    }
+
+    /// Request the values of `names`, or of all the parameters if empty. The response is waited
+    /// for in the background and passed to `convert`.
+    fn get_parameters<T: Send + 'static>(
+        &self,
+        names: &[String],
+        convert: impl FnOnce(Vec<Value>) -> T + Send + 'static,
+    ) -> PendingCall<T> {
+        if let Err(e) = self.require_capability("parameters") {
+            return PendingCall::ready(Err(e));
+        }
+        let id = format!("splinter_parameters_{}", self.next_id());
+        let (sender, receiver) = channel();
+        self.shared
+            .pending_parameters
+            .lock()
+            .unwrap()
+            .insert(id.clone(), sender);
+
+        let sent = self.send(Message::Text(
+            json!({ "op": "getParameters", "parameterNames": names, "id": id }).to_string(),
+        ));
+        if let Err(e) = sent {
+            self.shared.pending_parameters.lock().unwrap().remove(&id);
+            return PendingCall::ready(Err(e));
+        }
+
+        let shared = self.shared.clone();
+        PendingCall::spawn(move || {
+            let response = receiver
+                .recv_timeout(call_timeout())
+                .map_err(|_| "No response to the parameter request".to_string());
+            shared.pending_parameters.lock().unwrap().remove(&id);
+            Ok(convert(response?.as_array().cloned().unwrap_or_default()))
+        })
+    }
+
+    fn parameter_prefix(node_name: &NodeName) -> String {
+        format!("{}{}", node_name.full_name(), PARAMETER_SEPARATOR)
+    }
+
+    /// Topics of the channels whose nodes (from `nodes`) include `node_name`.
+    fn topics_of_node(
+        &self,
+        node_name: &NodeName,
+        nodes: impl Fn(&Graph) -> &HashMap<String, Vec<String>>,
+    ) -> Vec<NamedInterface> {
+        let full_name = node_name.full_name();
+        let graph = self.shared.graph.lock().unwrap();
+        let topics = self.list_topics().unwrap_or_default();
+        nodes(&graph)
+            .iter()
+            .filter(|(_, node_names)| node_names.contains(&full_name))
+            .filter_map(|(topic, _)| topics.iter().find(|t| &t.name == topic).cloned())
+            .collect()
+    }
+}
+
+impl Connection for ConnectionFoxglove {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_topics(&self) -> Result<Vec<NamedInterface>, String> {
+        Ok(self
+            .shared
+            .channels
+            .lock()
+            .unwrap()
+            .values()
+            .map(|channel| NamedInterface {
+                name: channel.topic.clone(),
+                type_name: channel.type_name.clone(),
+            })
+            .collect())
+    }
+
+    fn list_nodes(&self) -> Result<Vec<NodeName>, String> {
+        let graph = self.shared.graph.lock().unwrap();
+        let node_names: HashSet<&String> = graph
+            .publishers
+            .values()
+            .chain(graph.subscribers.values())
+            .chain(graph.providers.values())
+            .flatten()
+            .collect();
+        Ok(node_names
+            .into_iter()
+            .map(|name| NodeName::from_full_name(name))
+            .collect())
+    }
+
+    fn get_topic_type(&self, topic: &str) -> Option<InterfaceType> {
+        self.shared
+            .channels
+            .lock()
+            .unwrap()
+            .values()
+            .find(|channel| channel.topic == topic)
+            .map(|channel| channel.type_name.clone())
+    }
+
+    fn subscribe(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.add_callback(topic, Callback::Message(Box::new(callback)))
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.add_callback(topic, Callback::Raw(Box::new(callback)))
+    }
+
+    fn get_message_definition(&self, message_type: &InterfaceType) -> Result<String, String> {
+        let schema = self
+            .shared
+            .channels
+            .lock()
+            .unwrap()
+            .values()
+            .find(|channel| &channel.type_name == message_type && !channel.schema.is_empty())
+            .map(|channel| channel.schema.clone());
+        match schema {
+            Some(schema) => Ok(schema),
+            None => installed_schema(message_type),
+        }
+    }
+
+    fn can_publish(&self) -> bool {
+        self.require_capability("clientPublish").is_ok()
+    }
+
+    fn create_publisher(
+        &mut self,
+        topic: &str,
+        message_type: &InterfaceType,
+        _qos: Option<QosProfile>,
+    ) -> Result<Box<PublisherFunc>, String> {
+        self.require_capability("clientPublish")?;
+        let channel_id = self.next_id();
+        self.send(Message::Text(
+            json!({
+                "op": "advertise",
+                "channels": [{
+                    "id": channel_id,
+                    "topic": topic,
+                    "encoding": "cdr",
+                    "schemaName": message_type.to_string(),
+                }],
+            })
+            .to_string(),
+        ))?;
+
+        let outgoing = self.outgoing.clone();
+        Ok(Box::new(move |message: &GenericMessage| {
+            let mut data = vec![OPCODE_CLIENT_MESSAGE_DATA];
+            data.extend_from_slice(&channel_id.to_le_bytes());
+            data.extend_from_slice(&cdr::serialize(message));
+            outgoing
+                .send(Message::Binary(data))
+                .map_err(|_| "Connection is closed".to_string())?;
+            Ok(Vec::new())
+        }))
+    }
+
+    fn get_publisher_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.topics_of_node(node_name, |graph| &graph.publishers))
+    }
+
+    fn get_subscription_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.topics_of_node(node_name, |graph| &graph.subscribers))
+    }
+
+    fn get_client_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        // The connection graph doesn't include service clients.
+        Ok(Vec::new())
+    }
+
+    fn get_service_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        let full_name = node_name.full_name();
+        let graph = self.shared.graph.lock().unwrap();
+        let services = self.shared.services.lock().unwrap();
+        Ok(graph
+            .providers
+            .iter()
+            .filter(|(_, node_names)| node_names.contains(&full_name))
+            .filter_map(|(name, _)| {
+                Some(NamedInterface {
+                    name: name.clone(),
+                    type_name: services.get(name)?.type_name.clone(),
+                })
+            })
+            .collect())
+    }
+
+    fn get_parameters_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        let prefix = Self::parameter_prefix(node_name);
+        self.get_parameters(&[], move |parameters| {
+            parameters
+                .iter()
+                .filter_map(|parameter| {
+                    let name = parameter["name"].as_str()?.strip_prefix(&prefix)?;
+                    Some((name.to_string(), parameter_from_foxglove(parameter)?))
+                })
+                .collect()
+        })
+    }
+
+    fn set_parameter_by_node(
+        &mut self,
+        node_name: &NodeName,
+        parameter_name: &str,
+        parameter: Parameters,
+    ) -> PendingCall<()> {
+        let name = format!("{}{}", Self::parameter_prefix(node_name), parameter_name);
+        // The server doesn't acknowledge the new value unless asked to, sending it is enough.
+        PendingCall::ready(self.require_capability("parameters").and_then(|()| {
+            self.send(Message::Text(
+                json!({
+                    "op": "setParameters",
+                    "parameters": [parameter_to_foxglove(&name, &parameter)],
+                })
+                .to_string(),
+            ))
+        }))
+    }
+
+    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
+        let graph = self.shared.graph.lock().unwrap();
+        Ok(graph
+            .publishers
+            .get(topic)
+            .into_iter()
+            .flatten()
+            .map(|name| NodeName::from_full_name(name).into())
+            .collect())
+    }
+
+    fn get_subscriptions_info_by_topic(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
+        let graph = self.shared.graph.lock().unwrap();
+        Ok(graph
+            .subscribers
+            .get(topic)
+            .into_iter()
+            .flatten()
+            .map(|name| NodeName::from_full_name(name).into())
+            .collect())
+    }
+
+    fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
+        Ok(self
+            .shared
+            .services
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, service)| (name.clone(), service.type_name.clone()))
+            .collect())
+    }
+
+    fn get_service_type(&self, service_name: &str) -> Option<InterfaceType> {
+        self.shared
+            .services
+            .lock()
+            .unwrap()
+            .get(service_name)
+            .map(|service| service.type_name.clone())
+    }
+
+    fn get_service_request_template(
+        &self,
+        service_type: &InterfaceType,
+    ) -> Result<GenericMessage, String> {
+        let services = self.shared.services.lock().unwrap();
+        let (type_name, registry) = services
+            .values()
+            .find(|service| &service.type_name == service_type)
+            .ok_or_else(|| format!("No service of type '{}' is advertised", service_type))?
+            .request
+            .as_ref()
+            .map_err(|e| e.clone())?;
+        from_json(registry, type_name, &Value::Null)
+    }
+
+    fn call_service(
+        &self,
+        service_name: &str,
+        _service_type: &InterfaceType,
+        request: &GenericMessage,
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        if let Err(e) = self.require_capability("services") {
+            return PendingCall::ready(Err(e));
+        }
+        let service = self
+            .shared
+            .services
+            .lock()
+            .unwrap()
+            .get(service_name)
+            .map(|service| (service.id as u32, service.response.clone()));
+        let (service_id, (response_type, response_registry)) = match service {
+            Some((service_id, Ok(response))) => (service_id, response),
+            Some((_, Err(e))) => return PendingCall::ready(Err(e)),
+            None => {
+                return PendingCall::ready(Err(format!(
+                    "Service '{}' is not advertised",
+                    service_name
+                )))
+            }
+        };
+
+        let call_id = self.next_id();
+        let (sender, receiver) = channel();
+        self.shared
+            .pending_calls
+            .lock()
+            .unwrap()
+            .insert(call_id, sender);
+
+        let encoding = b"cdr";
+        let mut data = vec![OPCODE_SERVICE_CALL_REQUEST];
+        data.extend_from_slice(&service_id.to_le_bytes());
+        data.extend_from_slice(&call_id.to_le_bytes());
+        data.extend_from_slice(&(encoding.len() as u32).to_le_bytes());
+        data.extend_from_slice(encoding);
+        data.extend_from_slice(&cdr::serialize(request));
+
+        if let Err(e) = self.send(Message::Binary(data)) {
+            self.shared.pending_calls.lock().unwrap().remove(&call_id);
+            return PendingCall::ready(Err(e));
+        }
+
+        let shared = self.shared.clone();
+        let service_name = service_name.to_string();
+        PendingCall::spawn(move || {
+            let response = receiver
+                .recv_timeout(call_timeout())
+                .map_err(|_| format!("No response from service '{}'", service_name));
+            shared.pending_calls.lock().unwrap().remove(&call_id);
+            let payload = response??;
+
+            Ok((
+                cdr::deserialize(&response_registry, &response_type, &payload)?,
+                Vec::new(),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use tungstenite::handshake::server::{Request, Response};
+
+    use crate::common::generic_message::{GenericField, SimpleField};
+
+    fn string_payload(value: &str) -> Vec<u8> {
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&(value.len() as u32 + 1).to_le_bytes());
+        data.extend_from_slice(value.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// Serve a single client, replaying a canned `/chatter` channel and a `/add_two_ints`
+    /// service.
+    fn fake_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket =
+                tungstenite::accept_hdr(stream, |request: &Request, mut response: Response| {
+                    assert_eq!(request.headers()["Sec-WebSocket-Protocol"], SUBPROTOCOL);
+                    response
+                        .headers_mut()
+                        .insert("Sec-WebSocket-Protocol", SUBPROTOCOL.parse().unwrap());
+                    Ok(response)
+                })
+                .unwrap();
+
+            for message in [
+                json!({
+                    "op": "serverInfo",
+                    "name": "fake",
+                    "capabilities": ["services", "parameters", "connectionGraph"],
+                }),
+                json!({
+                    "op": "advertise",
+                    "channels": [{
+                        "id": 7,
+                        "topic": "/chatter",
+                        "encoding": "cdr",
+                        "schemaName": "std_msgs/msg/String",
+                        "schema": "string data",
+                        "schemaEncoding": "ros2msg",
+                    }],
+                }),
+                json!({
+                    "op": "advertiseServices",
+                    "services": [{
+                        "id": 3,
+                        "name": "/add_two_ints",
+                        "type": "example_interfaces/srv/AddTwoInts",
+                        "requestSchema": "int64 a\nint64 b",
+                        "responseSchema": "int64 sum",
+                    }],
+                }),
+            ] {
+                socket.send(Message::Text(message.to_string())).unwrap();
+            }
+
+            while let Ok(message) = socket.read() {
+                let reply = match message {
+                    Message::Text(text) => {
+                        let request: Value = serde_json::from_str(&text).unwrap();
+                        match request["op"].as_str().unwrap() {
+                            "subscribe" => {
+                                let subscription_id =
+                                    request["subscriptions"][0]["id"].as_u64().unwrap() as u32;
+                                let mut data = vec![OPCODE_MESSAGE_DATA];
+                                data.extend_from_slice(&subscription_id.to_le_bytes());
+                                data.extend_from_slice(&0u64.to_le_bytes());
+                                data.extend_from_slice(&string_payload("hello"));
+                                Message::Binary(data)
+                            }
+                            "subscribeConnectionGraph" => Message::Text(
+                                json!({
+                                    "op": "connectionGraphUpdate",
+                                    "publishedTopics": [
+                                        { "name": "/chatter", "publisherIds": ["/talker"] }
+                                    ],
+                                    "subscribedTopics": [],
+                                    "advertisedServices": [
+                                        { "name": "/add_two_ints", "providerIds": ["/adder"] }
+                                    ],
+                                    "removedTopics": [],
+                                    "removedServices": [],
+                                })
+                                .to_string(),
+                            ),
+                            "getParameters" => Message::Text(
+                                json!({
+                                    "op": "parameterValues",
+                                    "id": request["id"],
+                                    "parameters": [
+                                        { "name": "/talker.rate", "value": 2, "type": "float64" },
+                                        { "name": "/adder.enabled", "value": true },
+                                    ],
+                                })
+                                .to_string(),
+                            ),
+                            _ => continue,
+                        }
+                    }
+                    Message::Binary(data) if data[0] == OPCODE_SERVICE_CALL_REQUEST => {
+                        let payload = &data[13 + read_u32(&data, 9).unwrap() as usize..];
+                        let a = i64::from_le_bytes(payload[4..12].try_into().unwrap());
+                        let b = i64::from_le_bytes(payload[12..20].try_into().unwrap());
+                        let mut response = vec![OPCODE_SERVICE_CALL_RESPONSE];
+                        response.extend_from_slice(&data[1..13]);
+                        response.extend_from_slice(b"cdr");
+                        response.extend_from_slice(&[0x00, 0x01, 0x00, 0x00]);
+                        response.extend_from_slice(&(a + b).to_le_bytes());
+                        Message::Binary(response)
+                    }
+                    _ => continue,
+                };
+                socket.send(reply).unwrap();
+            }
+        });
+        format!("ws://{}", address)
+    }
+
+    fn wait_for(condition: impl Fn() -> bool) {
+        let start = std::time::Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(1), "timed out");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_channels_and_subscribe() {
+        let mut connection = ConnectionFoxglove::new(&fake_server()).unwrap();
+        wait_for(|| !connection.list_topics().unwrap().is_empty());
+
+        assert_eq!(
+            connection.get_topic_type("/chatter"),
+            Some(InterfaceType::new("std_msgs/msg/String").unwrap())
+        );
+        assert_eq!(
+            connection
+                .get_message_definition(&InterfaceType::new("std_msgs/msg/String").unwrap())
+                .unwrap(),
+            "string data"
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let _subscription = connection
+            .subscribe("/chatter", None, move |message, _| {
+                let _ = sender.lock().unwrap().send(message);
+            })
+            .unwrap();
+        let message = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            message["data"],
+            GenericField::Simple(SimpleField::String("hello".to_string()))
+        );
+
+        wait_for(|| !connection.list_nodes().unwrap().is_empty());
+        assert_eq!(
+            connection.get_publishers_info_by_topic("/chatter").unwrap(),
+            vec![NodeName::new("/", "talker").into()]
+        );
+    }
+
+    #[test]
+    fn test_services_and_parameters() {
+        let connection = ConnectionFoxglove::new(&fake_server()).unwrap();
+        wait_for(|| !connection.list_services().unwrap().is_empty());
+        let service_type = InterfaceType::new("example_interfaces/srv/AddTwoInts").unwrap();
+
+        let template = connection
+            .get_service_request_template(&service_type)
+            .unwrap();
+        let registry =
+            MessageRegistry::from_schema(template.type_name(), "int64 a\nint64 b").unwrap();
+        let request =
+            from_json(&registry, template.type_name(), &json!({ "a": 2, "b": 3 })).unwrap();
+        let (response, _) = connection
+            .call_service("/add_two_ints", &service_type, &request)
+            .wait(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(response["sum"], GenericField::Simple(SimpleField::Int64(5)));
+
+        let parameters = connection
+            .get_parameters_by_node(&NodeName::new("/", "talker"))
+            .wait(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(parameters["rate"], Parameters::Double(2.0));
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+
+use crate::common::generic_message::{
+    GenericField, GenericMessage, InterfaceType, SequenceField, SimpleField,
+};
+
+use super::{pending::PendingCall, Connection, ConnectionType, NodeName};
+
+/// Hidden services of a managed node, `<node>/get_state` and so on.
+pub const GET_STATE: &str = "get_state";
+pub const CHANGE_STATE: &str = "change_state";
+pub const GET_AVAILABLE_TRANSITIONS: &str = "get_available_transitions";
+
+/// Transition of a managed node, `lifecycle_msgs/msg/Transition`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifecycleTransition {
+    pub id: u8,
+    pub label: String,
+    /// Label of the state the transition leads to.
+    pub goal_state: String,
+}
+
+/// Name of one of the lifecycle services of `node`.
+pub fn lifecycle_service(node: &NodeName, service: &str) -> String {
+    format!("{}/{}", node.full_name(), service)
+}
+
+/// Type of one of the lifecycle services, e.g. `lifecycle_msgs/srv/GetState` for `get_state`.
+pub fn lifecycle_service_type(service: &str) -> InterfaceType {
+    let type_name = service
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    InterfaceType {
+        package_name: "lifecycle_msgs".to_string(),
+        category: "srv".to_string(),
+        type_name,
+    }
+}
+
+/// Call one of the lifecycle services of `node`, with the default request unless one is given.
+pub fn call_lifecycle_service(
+    connection: &ConnectionType,
+    node: &NodeName,
+    service: &str,
+    request: Option<GenericMessage>,
+) -> PendingCall<(GenericMessage, Vec<String>)> {
+    let service_type = lifecycle_service_type(service);
+    let request = match request {
+        Some(request) => request,
+        None => match connection.get_service_request_template(&service_type) {
+            Ok(request) => request,
+            Err(e) => return PendingCall::ready(Err(e)),
+        },
+    };
+    connection.call_service(&lifecycle_service(node, service), &service_type, &request)
+}
+
+/// The nodes of `services` that can be managed, those with both a `get_state` and a
+/// `change_state` service.
+pub fn managed_nodes(services: &[(String, InterfaceType)]) -> HashSet<NodeName> {
+    let with_service = |service: &str| -> HashSet<NodeName> {
+        let suffix = format!("/{}", service);
+        services
+            .iter()
+            .filter(|(_, type_name)| *type_name == lifecycle_service_type(service))
+            .filter_map(|(name, _)| name.strip_suffix(&suffix))
+            .map(NodeName::from_full_name)
+            .collect()
+    };
+    with_service(GET_STATE)
+        .intersection(&with_service(CHANGE_STATE))
+        .cloned()
+        .collect()
+}
+
+fn state_label(state: Option<&GenericField>) -> Result<String, String> {
+    let Some(GenericField::Simple(SimpleField::Message(state))) = state else {
+        return Err("Invalid lifecycle state".to_string());
+    };
+    match state.get("label") {
+        Some(GenericField::Simple(SimpleField::String(label))) => Ok(label.clone()),
+        _ => Err("Invalid lifecycle state".to_string()),
+    }
+}
+
+/// Label of the current state, from a `GetState` response.
+pub fn current_state(response: &GenericMessage) -> Result<String, String> {
+    state_label(response.get("current_state"))
+}
+
+/// Transitions from a `GetAvailableTransitions` response.
+pub fn available_transitions(
+    response: &GenericMessage,
+) -> Result<Vec<LifecycleTransition>, String> {
+    let Some(GenericField::Sequence(SequenceField::Message(descriptions))) =
+        response.get("available_transitions")
+    else {
+        return Err("Invalid GetAvailableTransitions response".to_string());
+    };
+    descriptions
+        .iter()
+        .map(|description| {
+            let Some(GenericField::Simple(SimpleField::Message(transition))) =
+                description.get("transition")
+            else {
+                return Err("Invalid lifecycle transition".to_string());
+            };
+            match (transition.get("id"), transition.get("label")) {
+                (
+                    Some(GenericField::Simple(SimpleField::Uint8(id))),
+                    Some(GenericField::Simple(SimpleField::String(label))),
+                ) => Ok(LifecycleTransition {
+                    id: *id,
+                    label: label.clone(),
+                    goal_state: state_label(description.get("goal_state"))?,
+                }),
+                _ => Err("Invalid lifecycle transition".to_string()),
+            }
+        })
+        .collect()
+}
+
+/// `ChangeState` request triggering `transition`, built from the request template of the
+/// connection.
+pub fn change_state_request(
+    template: &GenericMessage,
+    transition: &LifecycleTransition,
+) -> Result<GenericMessage, String> {
+    let mut request = template.clone();
+    let Some(GenericField::Simple(SimpleField::Message(request_transition))) =
+        request.get_mut("transition")
+    else {
+        return Err("Invalid ChangeState request".to_string());
+    };
+    match request_transition.get_mut("id") {
+        Some(GenericField::Simple(SimpleField::Uint8(id))) => *id = transition.id,
+        _ => return Err("Invalid ChangeState request".to_string()),
+    }
+    if let Some(GenericField::Simple(SimpleField::String(label))) =
+        request_transition.get_mut("label")
+    {
+        *label = transition.label.clone();
+    }
+    Ok(request)
+}
+
+/// Whether the node went through the transition, from a `ChangeState` response.
+pub fn change_state_succeeded(response: &GenericMessage) -> Result<bool, String> {
+    match response.get("success") {
+        Some(GenericField::Simple(SimpleField::Boolean(success))) => Ok(*success),
+        _ => Err("Invalid ChangeState response".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::json_message::from_json;
+    use crate::common::message_definition::MessageRegistry;
+
+    const STATE: &str = "\
+================================================================================
+MSG: lifecycle_msgs/State
+uint8 id
+string label";
+
+    const TRANSITION: &str = "\
+================================================================================
+MSG: lifecycle_msgs/Transition
+uint8 id
+string label";
+
+    fn message(type_name: &str, definition: &str, value: serde_json::Value) -> GenericMessage {
+        let type_name = InterfaceType::new(type_name).unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, definition).unwrap();
+        from_json(&registry, &type_name, &value).unwrap()
+    }
+
+    #[test]
+    fn test_managed_nodes() {
+        let service =
+            |name: &str, service: &str| (name.to_string(), lifecycle_service_type(service));
+        let services = vec![
+            service("/driver/get_state", GET_STATE),
+            service("/driver/change_state", CHANGE_STATE),
+            service("/ns/camera/get_state", GET_STATE),
+            (
+                "/talker/change_state".to_string(),
+                InterfaceType::new("example_interfaces/srv/Trigger").unwrap(),
+            ),
+        ];
+        let nodes = managed_nodes(&services);
+        assert_eq!(nodes.len(), 1);
+        assert!(nodes.contains(&NodeName::new("/", "driver")));
+
+        assert_eq!(
+            lifecycle_service_type(GET_AVAILABLE_TRANSITIONS),
+            InterfaceType::new("lifecycle_msgs/srv/GetAvailableTransitions").unwrap()
+        );
+        assert_eq!(
+            lifecycle_service(&NodeName::new("/ns", "camera"), CHANGE_STATE),
+            "/ns/camera/change_state"
+        );
+    }
+
+    #[test]
+    fn test_current_state() {
+        let response = message(
+            "lifecycle_msgs/srv/GetState_Response",
+            &format!("lifecycle_msgs/State current_state\n{}", STATE),
+            json!({ "current_state": { "id": 2, "label": "inactive" } }),
+        );
+        assert_eq!(current_state(&response), Ok("inactive".to_string()));
+    }
+
+    #[test]
+    fn test_transitions() {
+        let response = message(
+            "lifecycle_msgs/srv/GetAvailableTransitions_Response",
+            &format!(
+                "lifecycle_msgs/TransitionDescription[] available_transitions\n\
+                ================================================================================\n\
+                MSG: lifecycle_msgs/TransitionDescription\n\
+                lifecycle_msgs/Transition transition\n\
+                lifecycle_msgs/State start_state\n\
+                lifecycle_msgs/State goal_state\n{}\n{}",
+                TRANSITION, STATE
+            ),
+            json!({ "available_transitions": [
+                {
+                    "transition": { "id": 3, "label": "activate" },
+                    "start_state": { "id": 2, "label": "inactive" },
+                    "goal_state": { "id": 13, "label": "activating" },
+                },
+            ] }),
+        );
+        let transitions = available_transitions(&response).unwrap();
+        assert_eq!(
+            transitions,
+            vec![LifecycleTransition {
+                id: 3,
+                label: "activate".to_string(),
+                goal_state: "activating".to_string(),
+            }]
+        );
+
+        let template = message(
+            "lifecycle_msgs/srv/ChangeState_Request",
+            &format!("lifecycle_msgs/Transition transition\n{}", TRANSITION),
+            serde_json::Value::Null,
+        );
+        let request = change_state_request(&template, &transitions[0]).unwrap();
+        let GenericField::Simple(SimpleField::Message(transition)) = &request["transition"] else {
+            panic!("Missing transition");
+        };
+        assert_eq!(
+            transition["id"],
+            GenericField::Simple(SimpleField::Uint8(3))
+        );
+
+        let response = message(
+            "lifecycle_msgs/srv/ChangeState_Response",
+            "bool success",
+            json!({ "success": true }),
+        );
+        assert_eq!(change_state_succeeded(&response), Ok(true));
+    }
+}
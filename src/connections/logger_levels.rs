@@ -0,0 +1,234 @@
+use indexmap::IndexMap;
+
+use crate::common::generic_message::{
+    GenericField, GenericMessage, InterfaceType, SequenceField, SimpleField,
+};
+
+use super::{pending::PendingCall, rosout::LogLevel, Connection, ConnectionType, NodeName};
+
+/// Hidden services of a node started with the logger service enabled, `<node>/get_logger_levels`
+/// and `<node>/set_logger_levels`.
+pub const GET_LOGGER_LEVELS: &str = "get_logger_levels";
+pub const SET_LOGGER_LEVELS: &str = "set_logger_levels";
+
+/// Level of a logger, `rcl_interfaces/msg/LoggerLevel`. `None` when the level is not set, the
+/// logger then follows its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggerLevel {
+    pub name: String,
+    pub level: Option<LogLevel>,
+}
+
+/// Name of one of the logger services of `node`.
+pub fn logger_service(node: &NodeName, service: &str) -> String {
+    format!("{}/{}", node.full_name(), service)
+}
+
+/// Type of one of the logger services, `rcl_interfaces/srv/GetLoggerLevels` for
+/// `get_logger_levels`.
+pub fn logger_service_type(service: &str) -> InterfaceType {
+    let type_name = match service {
+        GET_LOGGER_LEVELS => "GetLoggerLevels",
+        _ => "SetLoggerLevels",
+    };
+    InterfaceType {
+        package_name: "rcl_interfaces".to_string(),
+        category: "srv".to_string(),
+        type_name: type_name.to_string(),
+    }
+}
+
+/// Call one of the logger services of `node`.
+pub fn call_logger_service(
+    connection: &ConnectionType,
+    node: &NodeName,
+    service: &str,
+    request: &GenericMessage,
+) -> PendingCall<(GenericMessage, Vec<String>)> {
+    connection.call_service(
+        &logger_service(node, service),
+        &logger_service_type(service),
+        request,
+    )
+}
+
+/// `GetLoggerLevels` request for the loggers `names`, built from the request template of the
+/// connection.
+pub fn get_logger_levels_request(
+    template: &GenericMessage,
+    names: &[String],
+) -> Result<GenericMessage, String> {
+    let mut request = template.clone();
+    match request.get_mut("names") {
+        Some(GenericField::Sequence(SequenceField::String(request_names))) => {
+            *request_names = names.to_vec();
+        }
+        _ => return Err("Invalid GetLoggerLevels request".to_string()),
+    }
+    Ok(request)
+}
+
+/// Levels of the loggers, from a `GetLoggerLevels` response.
+pub fn logger_levels(response: &GenericMessage) -> Result<Vec<LoggerLevel>, String> {
+    let Some(GenericField::Sequence(SequenceField::Message(levels))) = response.get("levels")
+    else {
+        return Err("Invalid GetLoggerLevels response".to_string());
+    };
+    levels
+        .iter()
+        .map(|level| match (level.get("name"), level.get("level")) {
+            (
+                Some(GenericField::Simple(SimpleField::String(name))),
+                Some(GenericField::Simple(SimpleField::Uint32(level))),
+            ) => Ok(LoggerLevel {
+                name: name.clone(),
+                level: (*level != 0).then(|| LogLevel::from_level(*level as u64)),
+            }),
+            _ => Err("Invalid logger level".to_string()),
+        })
+        .collect()
+}
+
+/// `SetLoggerLevels` request setting the logger `name` to `level`, built from the request
+/// template of the connection.
+pub fn set_logger_levels_request(
+    template: &GenericMessage,
+    name: &str,
+    level: LogLevel,
+) -> Result<GenericMessage, String> {
+    let mut request = template.clone();
+    let Some(GenericField::Sequence(SequenceField::Message(levels))) = request.get_mut("levels")
+    else {
+        return Err("Invalid SetLoggerLevels request".to_string());
+    };
+    // The template has no element to copy, the fields are matched by name when sent
+    let fields = IndexMap::from([
+        (
+            "name".to_string(),
+            GenericField::Simple(SimpleField::String(name.to_string())),
+        ),
+        (
+            "level".to_string(),
+            GenericField::Simple(SimpleField::Uint32(level.severity())),
+        ),
+    ]);
+    *levels = vec![GenericMessage::new(
+        InterfaceType::new("rcl_interfaces/msg/LoggerLevel")?,
+        fields,
+    )];
+    Ok(request)
+}
+
+/// Whether the level was set, from a `SetLoggerLevels` response: the reason it wasn't
+/// otherwise.
+pub fn set_logger_levels_result(response: &GenericMessage) -> Result<(), String> {
+    let Some(GenericField::Sequence(SequenceField::Message(results))) = response.get("results")
+    else {
+        return Err("Invalid SetLoggerLevels response".to_string());
+    };
+    for result in results {
+        match (result.get("successful"), result.get("reason")) {
+            (Some(GenericField::Simple(SimpleField::Boolean(true))), _) => {}
+            (_, Some(GenericField::Simple(SimpleField::String(reason)))) => {
+                return Err(reason.clone())
+            }
+            _ => return Err("Invalid SetLoggerLevels response".to_string()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::json_message::from_json;
+    use crate::common::message_definition::MessageRegistry;
+
+    const LOGGER_LEVEL: &str = "\
+================================================================================
+MSG: rcl_interfaces/LoggerLevel
+string name
+uint32 level";
+
+    fn message(type_name: &str, definition: &str, value: serde_json::Value) -> GenericMessage {
+        let type_name = InterfaceType::new(type_name).unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, definition).unwrap();
+        from_json(&registry, &type_name, &value).unwrap()
+    }
+
+    #[test]
+    fn test_get_logger_levels() {
+        let template = message(
+            "rcl_interfaces/srv/GetLoggerLevels_Request",
+            "string[] names",
+            serde_json::Value::Null,
+        );
+        let request = get_logger_levels_request(&template, &["ns.camera".to_string()]).unwrap();
+        assert_eq!(
+            request["names"],
+            GenericField::Sequence(SequenceField::String(vec!["ns.camera".to_string()]))
+        );
+
+        let response = message(
+            "rcl_interfaces/srv/GetLoggerLevels_Response",
+            &format!("rcl_interfaces/LoggerLevel[] levels\n{}", LOGGER_LEVEL),
+            json!({ "levels": [
+                { "name": "ns.camera", "level": 10 },
+                { "name": "ns.camera.driver", "level": 0 },
+            ] }),
+        );
+        assert_eq!(
+            logger_levels(&response),
+            Ok(vec![
+                LoggerLevel {
+                    name: "ns.camera".to_string(),
+                    level: Some(LogLevel::Debug),
+                },
+                LoggerLevel {
+                    name: "ns.camera.driver".to_string(),
+                    level: None,
+                },
+            ])
+        );
+        assert_eq!(
+            logger_service_type(GET_LOGGER_LEVELS),
+            InterfaceType::new("rcl_interfaces/srv/GetLoggerLevels").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_logger_levels() {
+        let template = message(
+            "rcl_interfaces/srv/SetLoggerLevels_Request",
+            &format!("rcl_interfaces/LoggerLevel[] levels\n{}", LOGGER_LEVEL),
+            serde_json::Value::Null,
+        );
+        let request = set_logger_levels_request(&template, "ns.camera", LogLevel::Warn).unwrap();
+        let GenericField::Sequence(SequenceField::Message(levels)) = &request["levels"] else {
+            panic!("Missing levels");
+        };
+        assert_eq!(
+            levels[0]["level"],
+            GenericField::Simple(SimpleField::Uint32(30))
+        );
+
+        let definition = "\
+rcl_interfaces/SetLoggerLevelsResult[] results
+================================================================================
+MSG: rcl_interfaces/SetLoggerLevelsResult
+bool successful
+string reason";
+        let response = message(
+            "rcl_interfaces/srv/SetLoggerLevels_Response",
+            definition,
+            json!({ "results": [{ "successful": false, "reason": "Unknown logger" }] }),
+        );
+        assert_eq!(
+            set_logger_levels_result(&response),
+            Err("Unknown logger".to_string())
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::message_definition::{installed_schema, resolve_type_name, MessageRegistry};
+use crate::connections::pending::PendingCall;
+use crate::connections::playback::{
+    Payload, PayloadStore, Playback, Player, RecordedMessage, RecordedTopic, Recording,
+};
+use crate::connections::qos::QosProfile;
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, PublisherFunc, SubscriptionHandle,
+    TopicEndpointInfo,
+};
+
+/// Replays an MCAP recording as if the recorded topics were live.
+///
+/// The file is memory mapped on open and only the index of its messages is kept in memory,
+/// payloads are read from the file, decompressing their chunk, as they are played back by a
+/// `Player` whose transport controls are exposed through `Connection::playback`. Channels
+/// without a schema are skipped.
+pub struct ConnectionMcap {
+    name: String,
+    path: PathBuf,
+    player: Player,
+    /// `ros2msg` schemas stored in the file, by type.
+    schemas: HashMap<InterfaceType, String>,
+}
+
+impl Debug for ConnectionMcap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionMcap")
+            .field("path", &self.path)
+            .field("topics", &self.player.recording().topics.len())
+            .field("messages", &self.player.recording().messages.len())
+            .finish()
+    }
+}
+
+fn load_registry(
+    type_name: &InterfaceType,
+    channel: &::mcap::Channel,
+) -> Result<MessageRegistry, String> {
+    if channel.message_encoding != "cdr" {
+        return Err(format!(
+            "Unsupported message encoding '{}'",
+            channel.message_encoding
+        ));
+    }
+    match &channel.schema {
+        Some(schema) if schema.encoding == "ros2msg" => {
+            let text = std::str::from_utf8(&schema.data)
+                .map_err(|e| format!("Schema of '{}' is not valid UTF-8: {}", type_name, e))?;
+            MessageRegistry::from_schema(type_name, text)
+        }
+        // No usable schema in the file, fall back to the interfaces installed locally.
+        _ => {
+            let mut registry = MessageRegistry::new();
+            registry.load_installed(type_name)?;
+            Ok(registry)
+        }
+    }
+}
+
+/// Decompressed chunks kept around, playback reads the messages of a chunk in a row.
+const CHUNK_CACHE_SIZE: usize = 4;
+
+/// Where the payload of a message is in the file.
+enum Location {
+    /// Uncompressed, at this range of the file.
+    Mapped { offset: usize, len: usize },
+    /// Message `index` of the chunk at `chunk` in the chunk indexes of the summary.
+    Chunk { chunk: usize, index: usize },
+}
+
+/// Payloads of an MCAP file, read from its memory map as they are played back.
+struct McapStore {
+    mmap: memmap2::Mmap,
+    summary: Option<::mcap::Summary>,
+    locations: Vec<Location>,
+    /// Payloads of the last decompressed chunks, most recently used last.
+    chunks: Mutex<Vec<(usize, Arc<Vec<Vec<u8>>>)>>,
+}
+
+impl McapStore {
+    fn chunk(&self, chunk: usize) -> Result<Arc<Vec<Vec<u8>>>, String> {
+        let mut chunks = self.chunks.lock().unwrap();
+        if let Some(position) = chunks.iter().position(|(index, _)| *index == chunk) {
+            let cached = chunks.remove(position);
+            chunks.push(cached.clone());
+            return Ok(cached.1);
+        }
+
+        let summary = self.summary.as_ref().ok_or("No summary to read chunks")?;
+        let chunk_index = summary
+            .chunk_indexes
+            .get(chunk)
+            .ok_or_else(|| format!("No chunk {}", chunk))?;
+        let payloads = summary
+            .stream_chunk(&self.mmap, chunk_index)
+            .and_then(|messages| {
+                messages
+                    .map(|message| message.map(|message| message.data.into_owned()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| format!("Corrupted MCAP chunk: {}", e))?;
+        let payloads = Arc::new(payloads);
+        if chunks.len() >= CHUNK_CACHE_SIZE {
+            chunks.remove(0);
+        }
+        chunks.push((chunk, payloads.clone()));
+        Ok(payloads)
+    }
+}
+
+impl PayloadStore for McapStore {
+    fn read(&self, key: usize) -> Result<Cow<'_, [u8]>, String> {
+        match self.locations.get(key) {
+            Some(Location::Mapped { offset, len }) => self
+                .mmap
+                .get(*offset..offset + len)
+                .map(Cow::Borrowed)
+                .ok_or_else(|| "Message outside of the MCAP file".to_string()),
+            Some(Location::Chunk { chunk, index }) => self
+                .chunk(*chunk)?
+                .get(*index)
+                .map(|data| Cow::Owned(data.clone()))
+                .ok_or_else(|| format!("No message {} in chunk {}", index, chunk)),
+            None => Err(format!("No message {}", key)),
+        }
+    }
+}
+
+/// Topics of the recording and their `ros2msg` schemas, by channel id.
+#[derive(Default)]
+struct Channels {
+    topics: Vec<RecordedTopic>,
+    topic_indices: HashMap<String, usize>,
+    by_id: HashMap<u16, Option<usize>>,
+    schemas: HashMap<InterfaceType, String>,
+}
+
+impl Channels {
+    /// Index of the topic of `channel`, `None` for channels without a schema or with a type
+    /// that can't be resolved, whose messages are skipped.
+    fn add(&mut self, channel: &::mcap::Channel) -> Option<usize> {
+        if let Some(topic) = self.by_id.get(&channel.id) {
+            return *topic;
+        }
+        let topic = self.add_topic(channel);
+        self.by_id.insert(channel.id, topic);
+        topic
+    }
+
+    fn add_topic(&mut self, channel: &::mcap::Channel) -> Option<usize> {
+        if let Some(index) = self.topic_indices.get(&channel.topic) {
+            return Some(*index);
+        }
+        let schema = channel.schema.as_ref()?;
+        let type_name = resolve_type_name(&schema.name, "").ok()?;
+        let registry = load_registry(&type_name, channel);
+        if schema.encoding == "ros2msg" {
+            if let Ok(text) = std::str::from_utf8(&schema.data) {
+                self.schemas.insert(type_name.clone(), text.to_string());
+            }
+        }
+        self.topics.push(RecordedTopic {
+            name: channel.topic.clone(),
+            type_name,
+            registry,
+        });
+        self.topic_indices
+            .insert(channel.topic.clone(), self.topics.len() - 1);
+        Some(self.topics.len() - 1)
+    }
+}
+
+/// Index the messages of the chunks of `summary`, from their message indexes when the file
+/// has them.
+fn index_chunks(
+    mmap: &[u8],
+    summary: &::mcap::Summary,
+    channels: &mut Channels,
+    locations: &mut Vec<Location>,
+    messages: &mut Vec<RecordedMessage>,
+) -> Result<(), String> {
+    let corrupted = |e: ::mcap::McapError| format!("Corrupted MCAP file: {}", e);
+    for (chunk, chunk_index) in summary.chunk_indexes.iter().enumerate() {
+        // (offset in the chunk, topic, log time) of each message
+        let mut entries = Vec::new();
+        if chunk_index.message_index_offsets.is_empty() {
+            // No message indexes, go through the chunk once, its offsets are in order.
+            for (offset, message) in summary
+                .stream_chunk(mmap, chunk_index)
+                .map_err(corrupted)?
+                .enumerate()
+            {
+                let message = message.map_err(corrupted)?;
+                let topic = channels.add(&message.channel);
+                entries.push((offset as u64, topic, message.log_time));
+            }
+        } else {
+            for (channel, channel_entries) in summary
+                .read_message_indexes(mmap, chunk_index)
+                .map_err(corrupted)?
+            {
+                let topic = channels.add(&channel);
+                entries.extend(
+                    channel_entries
+                        .iter()
+                        .map(|entry| (entry.offset, topic, entry.log_time)),
+                );
+            }
+            entries.sort_by_key(|&(offset, _, _)| offset);
+        }
+
+        // Messages come out of a chunk in offset order, their rank is their index there.
+        for (index, (_, topic, log_time)) in entries.into_iter().enumerate() {
+            let Some(topic) = topic else {
+                continue;
+            };
+            messages.push(RecordedMessage {
+                topic,
+                log_time,
+                data: Payload::Stored(locations.len()),
+            });
+            locations.push(Location::Chunk { chunk, index });
+        }
+    }
+    Ok(())
+}
+
+fn read_recording(path: &Path) -> Result<(Recording, HashMap<InterfaceType, String>), String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    // SAFETY: the file must not be modified while it is mapped. Recordings are written once
+    // and only read by splinter, truncating one while it is open is not supported.
+    let mmap = unsafe { memmap2::Mmap::map(&file) }
+        .map_err(|e| format!("Failed to map '{}': {}", path.display(), e))?;
+    let summary = ::mcap::Summary::read(&mmap)
+        .map_err(|e| format!("Failed to open MCAP file '{}': {}", path.display(), e))?;
+
+    let mut channels = Channels::default();
+    let mut locations = Vec::new();
+    let mut messages = Vec::new();
+
+    match &summary {
+        Some(summary) if !summary.chunk_indexes.is_empty() => {
+            index_chunks(&mmap, summary, &mut channels, &mut locations, &mut messages)?;
+        }
+        // No chunk index, read the messages in file order.
+        _ => {
+            let stream = ::mcap::MessageStream::new(&mmap)
+                .map_err(|e| format!("Failed to open MCAP file '{}': {}", path.display(), e))?;
+            for message in stream {
+                let message = message.map_err(|e| format!("Corrupted MCAP file: {}", e))?;
+                let Some(topic) = channels.add(&message.channel) else {
+                    continue;
+                };
+                let data = match message.data {
+                    // Uncompressed, leave it in the file
+                    Cow::Borrowed(data) => {
+                        let offset = data.as_ptr() as usize - mmap.as_ptr() as usize;
+                        locations.push(Location::Mapped {
+                            offset,
+                            len: data.len(),
+                        });
+                        Payload::Stored(locations.len() - 1)
+                    }
+                    Cow::Owned(data) => Payload::Owned(data),
+                };
+                messages.push(RecordedMessage {
+                    topic,
+                    log_time: message.log_time,
+                    data,
+                });
+            }
+        }
+    }
+
+    let store = McapStore {
+        mmap,
+        summary,
+        locations,
+        chunks: Mutex::new(Vec::new()),
+    };
+    // Chunks may overlap in time, `Recording::new` restores the global log time order.
+    let recording = Recording::new(channels.topics, messages).with_store(store);
+    Ok((recording, channels.schemas))
+}
+
+impl ConnectionMcap {
+    /// Open an MCAP file and start replaying it.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (recording, schemas) = read_recording(path)?;
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            path: path.to_path_buf(),
+            player: Player::new(recording),
+            schemas,
+        })
+    }
+
+    fn unsupported<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("{} is not supported for MCAP files", what))
+    }
+}
+
+impl Connection for ConnectionMcap {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_topics(&self) -> Result<Vec<NamedInterface>, String> {
+        Ok(self
+            .player
+            .recording()
+            .topics
+            .iter()
+            .map(|topic| NamedInterface {
+                name: topic.name.clone(),
+                type_name: topic.type_name.clone(),
+            })
+            .collect())
+    }
+
+    fn list_nodes(&self) -> Result<Vec<NodeName>, String> {
+        // Recordings don't carry any graph information.
+        Ok(Vec::new())
+    }
+
+    fn get_topic_type(&self, topic: &str) -> Option<InterfaceType> {
+        let recording = self.player.recording();
+        recording
+            .topic_index(topic)
+            .map(|index| recording.topics[index].type_name.clone())
+    }
+
+    fn subscribe(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.player.subscribe(topic, callback)
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.player.subscribe_raw(topic, callback)
+    }
+
+    fn create_publisher(
+        &mut self,
+        _topic: &str,
+        _message_type: &InterfaceType,
+        _qos: Option<QosProfile>,
+    ) -> Result<Box<PublisherFunc>, String> {
+        self.unsupported("Publishing")
+    }
+
+    fn can_publish(&self) -> bool {
+        false
+    }
+
+    fn get_publisher_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_subscription_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_client_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_service_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_parameters_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        PendingCall::ready(self.unsupported("Parameters"))
+    }
+
+    fn set_parameter_by_node(
+        &mut self,
+        _node_name: &NodeName,
+        _parameter_name: &str,
+        _parameter: Parameters,
+    ) -> PendingCall<()> {
+        PendingCall::ready(self.unsupported("Parameters"))
+    }
+
+    fn get_publishers_info_by_topic(&self, _topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_subscriptions_info_by_topic(
+        &self,
+        _topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_service_type(&self, _service_name: &str) -> Option<InterfaceType> {
+        None
+    }
+
+    fn get_service_request_template(
+        &self,
+        _service_type: &InterfaceType,
+    ) -> Result<GenericMessage, String> {
+        self.unsupported("Services")
+    }
+
+    fn call_service(
+        &self,
+        _service_name: &str,
+        _service_type: &InterfaceType,
+        _request: &GenericMessage,
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        PendingCall::ready(self.unsupported("Services"))
+    }
+
+    fn get_message_definition(&self, message_type: &InterfaceType) -> Result<String, String> {
+        match self.schemas.get(message_type) {
+            Some(schema) => Ok(schema.clone()),
+            None => installed_schema(message_type),
+        }
+    }
+
+    fn now(&self) -> SystemTime {
+        self.player.playback().now()
+    }
+
+    fn playback(&self) -> Option<Playback> {
+        Some(self.player.playback())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+    use std::sync::{mpsc, Mutex};
+    use std::time::Duration;
+
+    use crate::common::generic_message::{GenericField, SimpleField};
+
+    fn write_test_file(path: &Path, chunked: bool) {
+        let file = std::io::BufWriter::new(std::fs::File::create(path).unwrap());
+        let mut writer = ::mcap::WriteOptions::new()
+            .use_chunks(chunked)
+            .create(file)
+            .unwrap();
+        let schema_id = writer
+            .add_schema("std_msgs/msg/String", "ros2msg", b"string data\n")
+            .unwrap();
+        let channel_id = writer
+            .add_channel(schema_id, "/chatter", "cdr", &BTreeMap::new())
+            .unwrap();
+        // Without a schema, skipped on open
+        let raw_channel_id = writer
+            .add_channel(0, "/raw", "cdr", &BTreeMap::new())
+            .unwrap();
+        writer
+            .write_to_known_channel(
+                &::mcap::records::MessageHeader {
+                    channel_id: raw_channel_id,
+                    sequence: 0,
+                    log_time: 0,
+                    publish_time: 0,
+                },
+                b"raw",
+            )
+            .unwrap();
+
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&6u32.to_le_bytes());
+        data.extend_from_slice(b"hello\0");
+        for sequence in 0..2 {
+            writer
+                .write_to_known_channel(
+                    &::mcap::records::MessageHeader {
+                        channel_id,
+                        sequence,
+                        log_time: 1_000_000 * sequence as u64,
+                        publish_time: 1_000_000 * sequence as u64,
+                    },
+                    &data,
+                )
+                .unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_list_topics_and_subscribe() {
+        for chunked in [true, false] {
+            check_list_topics_and_subscribe(chunked);
+        }
+    }
+
+    fn check_list_topics_and_subscribe(chunked: bool) {
+        let path = std::env::temp_dir().join(format!(
+            "splinter_test_{}_{}.mcap",
+            std::process::id(),
+            chunked
+        ));
+        write_test_file(&path, chunked);
+
+        let mut connection = ConnectionMcap::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let topics = connection.list_topics().unwrap();
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].name, "/chatter");
+        assert_eq!(
+            connection.get_topic_type("/chatter"),
+            Some(InterfaceType::new("std_msgs/msg/String").unwrap())
+        );
+        assert_eq!(connection.get_topic_type("/missing"), None);
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let _subscription = connection
+            .subscribe("/chatter", None, move |message, metadata| {
+                let _ = tx.lock().unwrap().send((message, metadata.serialized_size));
+            })
+            .unwrap();
+
+        let (message, serialized_size) = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            message["data"],
+            GenericField::Simple(SimpleField::String("hello".to_string()))
+        );
+        assert_eq!(serialized_size, Some(14));
+        assert_eq!(connection.player.recording().messages.len(), 2);
+    }
+}
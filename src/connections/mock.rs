@@ -0,0 +1,1021 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{sleep, spawn, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::common::cdr;
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::json_message::from_json;
+use crate::common::message_definition::{self, MessageRegistry};
+use crate::connections::parameter_events::{parameter_value_json, PARAMETER_EVENTS};
+use crate::connections::pending::PendingCall;
+use crate::connections::qos::QosProfile;
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, PublisherFunc, SubscriptionHandle,
+    TopicEndpointInfo,
+};
+
+/// Longest sleep of the scheduler thread, bounds the time it takes to stop.
+const SCHEDULER_TICK: Duration = Duration::from_millis(50);
+
+// Configuration file, e.g.:
+//
+// name: demo
+// definitions:
+//   std_msgs/msg/String: string data
+//   example_interfaces/srv/AddTwoInts: "int64 a\nint64 b\n---\nint64 sum"
+// nodes:
+//   - name: /talker
+//     publishers: [/chatter]
+//     services: [/add_two_ints]
+//     parameters: { rate: 2.0, frame_id: base_link }
+//     parameter_errors: { frame_id: "frame_id is read-only" }
+// topics:
+//   - name: /parameter_events   # parameters set through the connection are reported here
+//     type: rcl_interfaces/msg/ParameterEvent
+//   - name: /chatter
+//     type: std_msgs/msg/String
+//     rate: 2.0
+//     messages: [{ data: hello }, { data: world }]
+// services:
+//   - name: /add_two_ints
+//     type: example_interfaces/srv/AddTwoInts
+//     response: { sum: 42 }
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MockConfig {
+    name: Option<String>,
+    /// `ros2msg` schemas by type name, installed definitions are used for the other types. A
+    /// service definition holds its request and response separated by `---`.
+    definitions: HashMap<String, String>,
+    nodes: Vec<MockNodeConfig>,
+    topics: Vec<MockTopicConfig>,
+    services: Vec<MockServiceConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MockNodeConfig {
+    name: String,
+    #[serde(default)]
+    publishers: Vec<String>,
+    #[serde(default)]
+    subscriptions: Vec<String>,
+    #[serde(default)]
+    services: Vec<String>,
+    #[serde(default)]
+    clients: Vec<String>,
+    #[serde(default)]
+    parameters: HashMap<String, Value>,
+    /// Error returned when setting one of the parameters.
+    #[serde(default)]
+    parameter_errors: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MockTopicConfig {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    /// Publishing rate in Hz. Without it, messages are only sent by `ConnectionMock::emit`.
+    rate: Option<f64>,
+    /// Published in turn, starting over after the last one.
+    #[serde(default)]
+    messages: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MockServiceConfig {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    #[serde(default)]
+    response: Value,
+    /// Error returned instead of the response.
+    error: Option<String>,
+}
+
+enum Callback {
+    Message(Box<dyn Fn(GenericMessage, MessageMetadata) + Send + Sync>),
+    Raw(Box<dyn Fn(Vec<u8>, MessageMetadata) + Send + Sync>),
+}
+
+struct MockTopic {
+    name: String,
+    type_name: InterfaceType,
+    period: Option<Duration>,
+    messages: Vec<GenericMessage>,
+    /// Index of the next message to publish.
+    next: AtomicUsize,
+}
+
+struct MockNode {
+    name: NodeName,
+    publishers: Vec<String>,
+    subscriptions: Vec<String>,
+    services: Vec<String>,
+    clients: Vec<String>,
+    parameters: HashMap<String, Parameters>,
+    parameter_errors: HashMap<String, String>,
+}
+
+struct MockService {
+    name: String,
+    type_name: InterfaceType,
+    request_registry: MessageRegistry,
+    response: Result<GenericMessage, String>,
+}
+
+/// State shared with the scheduler thread.
+struct Shared {
+    topics: Vec<MockTopic>,
+    /// Callbacks of each topic, with the id of their subscription.
+    callbacks: Mutex<HashMap<String, Vec<(u64, Callback)>>>,
+    next_subscription_id: AtomicU64,
+    running: AtomicBool,
+}
+
+impl Shared {
+    fn publish(&self, topic: &str, message: &GenericMessage) {
+        self.publish_at(topic, message, SystemTime::now());
+    }
+
+    fn publish_at(&self, topic: &str, message: &GenericMessage, received_time: SystemTime) {
+        let callbacks = self.callbacks.lock().unwrap();
+        for (_, callback) in callbacks.get(topic).into_iter().flatten() {
+            match callback {
+                Callback::Message(callback) => callback(
+                    message.clone(),
+                    MessageMetadata {
+                        received_time,
+                        serialized_size: Some(cdr::serialized_size(message)),
+                    },
+                ),
+                Callback::Raw(callback) => {
+                    let data = cdr::serialize(message);
+                    let metadata = MessageMetadata {
+                        received_time,
+                        serialized_size: Some(data.len()),
+                    };
+                    callback(data, metadata)
+                }
+            }
+        }
+    }
+
+    fn publish_next(&self, topic: &MockTopic, received_time: SystemTime) {
+        if topic.messages.is_empty() {
+            return;
+        }
+        let index = topic.next.fetch_add(1, Ordering::Relaxed) % topic.messages.len();
+        self.publish_at(&topic.name, &topic.messages[index], received_time);
+    }
+}
+
+/// In-memory connection serving the graph declared in a configuration file, for tests and demos.
+pub struct ConnectionMock {
+    name: String,
+    schemas: HashMap<String, String>,
+    nodes: Vec<MockNode>,
+    services: Vec<MockService>,
+    shared: Arc<Shared>,
+
+    thread: Option<JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for ConnectionMock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionMock")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl Drop for ConnectionMock {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Publish the messages of the scheduled topics until `shared.running` is cleared.
+fn run_scheduler(shared: Arc<Shared>) {
+    let start = Instant::now();
+    let mut deadlines: Vec<Option<Instant>> = shared
+        .topics
+        .iter()
+        .map(|topic| topic.period.map(|_| start))
+        .collect();
+
+    while shared.running.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        for (topic, deadline) in shared.topics.iter().zip(deadlines.iter_mut()) {
+            let (Some(period), Some(time)) = (topic.period, deadline.as_mut()) else {
+                continue;
+            };
+            if *time <= now {
+                shared.publish_next(topic, SystemTime::now());
+                // Skip the deadlines missed while sleeping rather than bursting.
+                while *time <= now {
+                    *time += period;
+                }
+            }
+        }
+        let next = deadlines.iter().flatten().min().copied();
+        let wait = next.map_or(SCHEDULER_TICK, |next| {
+            next.saturating_duration_since(Instant::now())
+                .min(SCHEDULER_TICK)
+        });
+        sleep(wait);
+    }
+}
+
+impl ConnectionMock {
+    /// Load the configuration file at `path` and start publishing the scheduled topics.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let default_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Self::from_yaml(&text, &default_name)
+            .map_err(|e| format!("Invalid mock configuration '{}': {}", path.display(), e))
+    }
+
+    /// Build a connection from a YAML configuration, named `default_name` unless it has a name.
+    pub fn from_yaml(text: &str, default_name: &str) -> Result<Self, String> {
+        let config: MockConfig = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+        let schemas = config.definitions;
+        let load_registry = |type_name: &InterfaceType| registry(&schemas, type_name);
+
+        let mut topics = Vec::new();
+        for topic in config.topics {
+            let type_name = InterfaceType::new(&topic.type_name)?;
+            let messages = if topic.messages.is_empty() {
+                Vec::new()
+            } else {
+                load_registry(&type_name)
+                    .and_then(|registry| {
+                        topic
+                            .messages
+                            .iter()
+                            .map(|value| from_json(&registry, &type_name, value))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .map_err(|e| format!("{}: {}", topic.name, e))?
+            };
+            let period = match topic.rate {
+                Some(rate) if rate > 0.0 && rate.is_finite() => {
+                    Some(Duration::from_secs_f64(1.0 / rate))
+                }
+                Some(rate) => return Err(format!("{}: invalid rate {}", topic.name, rate)),
+                None => None,
+            };
+            topics.push(MockTopic {
+                name: topic.name,
+                type_name,
+                period,
+                messages,
+                next: AtomicUsize::new(0),
+            });
+        }
+
+        let mut services = Vec::new();
+        for service in config.services {
+            let type_name = InterfaceType::new(&service.type_name)?;
+            let request_type = service_part(&type_name, "Request");
+            let response_type = service_part(&type_name, "Response");
+            let request_registry =
+                load_registry(&request_type).map_err(|e| format!("{}: {}", service.name, e))?;
+            let response = match service.error {
+                Some(error) => Err(error),
+                None => {
+                    let response_registry = load_registry(&response_type)
+                        .map_err(|e| format!("{}: {}", service.name, e))?;
+                    Ok(
+                        from_json(&response_registry, &response_type, &service.response)
+                            .map_err(|e| format!("{}: {}", service.name, e))?,
+                    )
+                }
+            };
+            services.push(MockService {
+                name: service.name,
+                type_name,
+                request_registry,
+                response,
+            });
+        }
+
+        let mut nodes = Vec::new();
+        for node in config.nodes {
+            let undeclared_topic = node
+                .publishers
+                .iter()
+                .chain(&node.subscriptions)
+                .find(|name| !topics.iter().any(|topic| &topic.name == *name));
+            if let Some(topic) = undeclared_topic {
+                return Err(format!("{}: undeclared topic '{}'", node.name, topic));
+            }
+            let undeclared_service = node
+                .services
+                .iter()
+                .chain(&node.clients)
+                .find(|name| !services.iter().any(|service| &service.name == *name));
+            if let Some(service) = undeclared_service {
+                return Err(format!("{}: undeclared service '{}'", node.name, service));
+            }
+            let parameters = node
+                .parameters
+                .iter()
+                .map(|(name, value)| {
+                    Parameters::from_json(value)
+                        .map(|parameter| (name.clone(), parameter))
+                        .ok_or(format!(
+                            "{}: unsupported value for parameter '{}'",
+                            node.name, name
+                        ))
+                })
+                .collect::<Result<HashMap<_, _>, _>>()?;
+            nodes.push(MockNode {
+                name: NodeName::from_full_name(&node.name),
+                publishers: node.publishers,
+                subscriptions: node.subscriptions,
+                services: node.services,
+                clients: node.clients,
+                parameters,
+                parameter_errors: node.parameter_errors,
+            });
+        }
+
+        let shared = Arc::new(Shared {
+            topics,
+            callbacks: Mutex::new(HashMap::new()),
+            next_subscription_id: AtomicU64::new(0),
+            running: AtomicBool::new(true),
+        });
+        let thread = if shared.topics.iter().any(|topic| topic.period.is_some()) {
+            let shared = shared.clone();
+            Some(spawn(move || run_scheduler(shared)))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name: config.name.unwrap_or_else(|| default_name.to_string()),
+            schemas,
+            nodes,
+            services,
+            shared,
+            thread,
+        })
+    }
+
+    /// Publish the next message of `topic` now, regardless of its schedule.
+    pub fn emit(&self, topic: &str) -> Result<(), String> {
+        self.emit_at(topic, SystemTime::now())
+    }
+
+    /// Publish the next message of `topic` as if it was received at `received_time`.
+    pub fn emit_at(&self, topic: &str, received_time: SystemTime) -> Result<(), String> {
+        let topic = self.topic(topic)?;
+        if topic.messages.is_empty() {
+            return Err(format!("Topic '{}' has no messages", topic.name));
+        }
+        self.shared.publish_next(topic, received_time);
+        Ok(())
+    }
+
+    fn topic(&self, name: &str) -> Result<&MockTopic, String> {
+        self.shared
+            .topics
+            .iter()
+            .find(|topic| topic.name == name)
+            .ok_or(format!("Unknown topic '{}'", name))
+    }
+
+    fn node(&self, node_name: &NodeName) -> Result<&MockNode, String> {
+        self.nodes
+            .iter()
+            .find(|node| &node.name == node_name)
+            .ok_or(format!("Unknown node '{}'", node_name.full_name()))
+    }
+
+    fn service(&self, name: &str) -> Option<&MockService> {
+        self.services.iter().find(|service| service.name == name)
+    }
+
+    fn add_callback(&self, topic: &str, callback: Callback) -> Result<SubscriptionHandle, String> {
+        self.topic(topic)?;
+        let id = self
+            .shared
+            .next_subscription_id
+            .fetch_add(1, Ordering::Relaxed);
+        self.shared
+            .callbacks
+            .lock()
+            .unwrap()
+            .entry(topic.to_string())
+            .or_default()
+            .push((id, callback));
+
+        let shared = Arc::downgrade(&self.shared);
+        let topic = topic.to_string();
+        Ok(SubscriptionHandle::new(move || {
+            if let Some(shared) = shared.upgrade() {
+                if let Some(callbacks) = shared.callbacks.lock().unwrap().get_mut(&topic) {
+                    callbacks.retain(|(callback_id, _)| *callback_id != id);
+                }
+            }
+        }))
+    }
+
+    /// Report a parameter set through the connection on `/parameter_events`, when the
+    /// configuration declares the topic.
+    fn publish_parameter_event(
+        &self,
+        node_name: &NodeName,
+        parameter_name: &str,
+        parameter: &Parameters,
+        is_new: bool,
+    ) {
+        let Ok(topic) = self.topic(PARAMETER_EVENTS) else {
+            return;
+        };
+        let field = if is_new {
+            "new_parameters"
+        } else {
+            "changed_parameters"
+        };
+        let event = json!({
+            "node": node_name.full_name(),
+            field: [{ "name": parameter_name, "value": parameter_value_json(parameter) }],
+        });
+        let message = registry(&self.schemas, &topic.type_name)
+            .and_then(|registry| from_json(&registry, &topic.type_name, &event));
+        if let Ok(message) = message {
+            self.shared.publish(PARAMETER_EVENTS, &message);
+        }
+    }
+
+    fn topics_named(&self, names: &[String]) -> Vec<NamedInterface> {
+        names
+            .iter()
+            .filter_map(|name| self.topic(name).ok())
+            .map(|topic| NamedInterface {
+                name: topic.name.clone(),
+                type_name: topic.type_name.clone(),
+            })
+            .collect()
+    }
+
+    fn services_named(&self, names: &[String]) -> Vec<NamedInterface> {
+        names
+            .iter()
+            .filter_map(|name| self.service(name))
+            .map(|service| NamedInterface {
+                name: service.name.clone(),
+                type_name: service.type_name.clone(),
+            })
+            .collect()
+    }
+}
+
+fn service_part(service_type: &InterfaceType, suffix: &str) -> InterfaceType {
+    InterfaceType {
+        package_name: service_type.package_name.clone(),
+        category: service_type.category.clone(),
+        type_name: format!("{}_{}", service_type.type_name, suffix),
+    }
+}
+
+/// The `ros2msg` schema of `type_name` declared in the configuration. The parts of a service are
+/// also looked up in the definition of the service.
+fn declared_schema(schemas: &HashMap<String, String>, type_name: &InterfaceType) -> Option<String> {
+    if let Some(schema) = schemas.get(&type_name.to_string()) {
+        return Some(schema.clone());
+    }
+    let (service, part) = type_name.type_name.rsplit_once('_')?;
+    let service_type = format!(
+        "{}/{}/{}",
+        type_name.package_name, type_name.category, service
+    );
+    let schema = schemas.get(&service_type)?;
+    if !schema.lines().any(|line| line.trim() == "---") {
+        return None;
+    }
+    let mut lines = schema.lines();
+    let request: Vec<&str> = lines
+        .by_ref()
+        .take_while(|line| line.trim() != "---")
+        .collect();
+    match part {
+        "Request" => Some(request.join("\n")),
+        "Response" => Some(lines.collect::<Vec<_>>().join("\n")),
+        _ => None,
+    }
+}
+
+fn registry(
+    schemas: &HashMap<String, String>,
+    type_name: &InterfaceType,
+) -> Result<MessageRegistry, String> {
+    match declared_schema(schemas, type_name) {
+        Some(schema) => MessageRegistry::from_schema(type_name, &schema),
+        None => {
+            let mut registry = MessageRegistry::new();
+            registry.load_installed(type_name)?;
+            Ok(registry)
+        }
+    }
+}
+
+impl Connection for ConnectionMock {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_topics(&self) -> Result<Vec<NamedInterface>, String> {
+        Ok(self
+            .shared
+            .topics
+            .iter()
+            .map(|topic| NamedInterface {
+                name: topic.name.clone(),
+                type_name: topic.type_name.clone(),
+            })
+            .collect())
+    }
+
+    fn list_nodes(&self) -> Result<Vec<NodeName>, String> {
+        Ok(self.nodes.iter().map(|node| node.name.clone()).collect())
+    }
+
+    fn get_topic_type(&self, topic: &str) -> Option<InterfaceType> {
+        self.topic(topic).ok().map(|topic| topic.type_name.clone())
+    }
+
+    fn subscribe(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.add_callback(topic, Callback::Message(Box::new(callback)))
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.add_callback(topic, Callback::Raw(Box::new(callback)))
+    }
+
+    fn get_message_definition(&self, message_type: &InterfaceType) -> Result<String, String> {
+        declared_schema(&self.schemas, message_type)
+            .map_or_else(|| message_definition::installed_schema(message_type), Ok)
+    }
+
+    fn create_publisher(
+        &mut self,
+        topic: &str,
+        message_type: &InterfaceType,
+        _qos: Option<QosProfile>,
+    ) -> Result<Box<PublisherFunc>, String> {
+        let declared_type = &self.topic(topic)?.type_name;
+        if declared_type != message_type {
+            return Err(format!(
+                "Topic '{}' has type '{}', not '{}'",
+                topic, declared_type, message_type
+            ));
+        }
+        // Published messages are looped back to the subscribers of the topic.
+        let shared = self.shared.clone();
+        let topic = topic.to_string();
+        Ok(Box::new(move |message: &GenericMessage| {
+            shared.publish(&topic, message);
+            Ok(Vec::new())
+        }))
+    }
+
+    fn get_publisher_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.topics_named(&self.node(node_name)?.publishers))
+    }
+
+    fn get_subscription_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.topics_named(&self.node(node_name)?.subscriptions))
+    }
+
+    fn get_client_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.services_named(&self.node(node_name)?.clients))
+    }
+
+    fn get_service_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(self.services_named(&self.node(node_name)?.services))
+    }
+
+    fn get_parameters_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        PendingCall::ready(self.node(node_name).map(|node| node.parameters.clone()))
+    }
+
+    fn set_parameter_by_node(
+        &mut self,
+        node_name: &NodeName,
+        parameter_name: &str,
+        parameter: Parameters,
+    ) -> PendingCall<()> {
+        let Some(node) = self.nodes.iter_mut().find(|node| &node.name == node_name) else {
+            return PendingCall::ready(Err(format!("Unknown node '{}'", node_name.full_name())));
+        };
+        if let Some(error) = node.parameter_errors.get(parameter_name) {
+            return PendingCall::ready(Err(error.clone()));
+        }
+        let is_new = node
+            .parameters
+            .insert(parameter_name.to_string(), parameter.clone())
+            .is_none();
+        self.publish_parameter_event(node_name, parameter_name, &parameter, is_new);
+        PendingCall::ready(Ok(()))
+    }
+
+    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(self
+            .nodes
+            .iter()
+            .filter(|node| node.publishers.iter().any(|name| name == topic))
+            .map(|node| TopicEndpointInfo {
+                topic_type: self.get_topic_type(topic),
+                ..node.name.clone().into()
+            })
+            .collect())
+    }
+
+    fn get_subscriptions_info_by_topic(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(self
+            .nodes
+            .iter()
+            .filter(|node| node.subscriptions.iter().any(|name| name == topic))
+            .map(|node| TopicEndpointInfo {
+                topic_type: self.get_topic_type(topic),
+                ..node.name.clone().into()
+            })
+            .collect())
+    }
+
+    fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
+        Ok(self
+            .services
+            .iter()
+            .map(|service| (service.name.clone(), service.type_name.clone()))
+            .collect())
+    }
+
+    fn get_service_type(&self, service_name: &str) -> Option<InterfaceType> {
+        self.service(service_name)
+            .map(|service| service.type_name.clone())
+    }
+
+    fn get_service_request_template(
+        &self,
+        service_type: &InterfaceType,
+    ) -> Result<GenericMessage, String> {
+        let service = self
+            .services
+            .iter()
+            .find(|service| &service.type_name == service_type)
+            .ok_or(format!("Unknown service type '{}'", service_type))?;
+        from_json(
+            &service.request_registry,
+            &service_part(service_type, "Request"),
+            &Value::Null,
+        )
+    }
+
+    fn call_service(
+        &self,
+        service_name: &str,
+        _service_type: &InterfaceType,
+        _request: &GenericMessage,
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        PendingCall::ready(
+            self.service(service_name)
+                .ok_or(format!("Unknown service '{}'", service_name))
+                .and_then(|service| service.response.clone())
+                .map(|response| (response, Vec::new())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::common::generic_message::{GenericField, SimpleField};
+    use crate::connections::parameter_events::{
+        parameter_event, ParameterEventKind, ParameterUpdate,
+    };
+
+    const CONFIG: &str = r#"
+name: demo
+definitions:
+  std_msgs/msg/String: string data
+  example_interfaces/srv/AddTwoInts: "int64 a\nint64 b\n---\nint64 sum"
+  std_srvs/srv/Empty: "---"
+nodes:
+  - name: /ns/talker
+    publishers: [/chatter]
+    services: [/add_two_ints, /reset]
+    parameters: { rate: 2.0, frame_id: base_link }
+    parameter_errors: { frame_id: frame_id is read-only }
+  - name: /listener
+    subscriptions: [/chatter]
+topics:
+  - name: /chatter
+    type: std_msgs/msg/String
+    messages: [{ data: hello }, { data: world }]
+services:
+  - name: /add_two_ints
+    type: example_interfaces/srv/AddTwoInts
+    response: { sum: 42 }
+  - name: /reset
+    type: std_srvs/srv/Empty
+    error: Service is not available
+"#;
+
+    fn data(message: &GenericMessage) -> String {
+        match message.get("data") {
+            Some(GenericField::Simple(SimpleField::String(data))) => data.clone(),
+            field => panic!("Unexpected field {:?}", field),
+        }
+    }
+
+    #[test]
+    fn test_graph_and_emit() {
+        let mut connection = ConnectionMock::from_yaml(CONFIG, "mock.yaml").unwrap();
+        assert_eq!(connection.name(), "demo");
+        assert_eq!(connection.list_nodes().unwrap().len(), 2);
+
+        let talker = NodeName::new("/ns", "talker");
+        let publishers = connection
+            .get_publisher_names_and_types_by_node(&talker)
+            .unwrap();
+        assert_eq!(publishers[0].name, "/chatter");
+        assert_eq!(publishers[0].type_name.to_string(), "std_msgs/msg/String");
+        assert_eq!(
+            connection
+                .get_subscriptions_info_by_topic("/chatter")
+                .unwrap(),
+            vec![TopicEndpointInfo {
+                topic_type: Some(InterfaceType::new("std_msgs/msg/String").unwrap()),
+                ..NodeName::new("/", "listener").into()
+            }]
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let subscription = connection
+            .subscribe("/chatter", None, move |message, _| {
+                sender.send(data(&message)).unwrap();
+            })
+            .unwrap();
+        connection.emit("/chatter").unwrap();
+        connection.emit("/chatter").unwrap();
+        connection.emit("/chatter").unwrap();
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            ["hello", "world", "hello"]
+        );
+
+        // Published messages are looped back
+        let publisher = connection
+            .create_publisher(
+                "/chatter",
+                &InterfaceType::new("std_msgs/msg/String").unwrap(),
+                None,
+            )
+            .unwrap();
+        let registry = registry(
+            &connection.schemas,
+            &InterfaceType::new("std_msgs/msg/String").unwrap(),
+        )
+        .unwrap();
+        let message = from_json(
+            &registry,
+            &InterfaceType::new("std_msgs/msg/String").unwrap(),
+            &serde_json::json!({ "data": "published" }),
+        )
+        .unwrap();
+        publisher(&message).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), "published");
+
+        // Dropping the handle unsubscribes
+        drop(subscription);
+        connection.emit("/chatter").unwrap();
+        assert!(receiver.try_recv().is_err());
+        // Tests can set the receive time
+        let (sender, receiver) = mpsc::channel();
+        let _subscription = connection
+            .subscribe("/chatter", None, move |_, metadata| {
+                sender.send(metadata.received_time).unwrap();
+            })
+            .unwrap();
+        let received_time = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        connection.emit_at("/chatter", received_time).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), received_time);
+    }
+
+    #[test]
+    fn test_scripted_services_and_parameters() {
+        let mut connection = ConnectionMock::from_yaml(CONFIG, "mock.yaml").unwrap();
+
+        let service_type = connection.get_service_type("/add_two_ints").unwrap();
+        let request = connection
+            .get_service_request_template(&service_type)
+            .unwrap();
+        let (response, warnings) = connection
+            .call_service("/add_two_ints", &service_type, &request)
+            .poll()
+            .unwrap()
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert!(matches!(
+            response.get("sum"),
+            Some(GenericField::Simple(SimpleField::Int64(42)))
+        ));
+        let service_type = connection.get_service_type("/reset").unwrap();
+        let request = connection
+            .get_service_request_template(&service_type)
+            .unwrap();
+        assert_eq!(
+            connection
+                .call_service("/reset", &service_type, &request)
+                .poll()
+                .unwrap(),
+            Err("Service is not available".to_string())
+        );
+
+        let talker = NodeName::new("/ns", "talker");
+        connection
+            .set_parameter_by_node(&talker, "rate", Parameters::Double(5.0))
+            .poll()
+            .unwrap()
+            .unwrap();
+        assert!(connection
+            .set_parameter_by_node(&talker, "frame_id", Parameters::String("map".into()))
+            .poll()
+            .unwrap()
+            .is_err());
+        let parameters = connection
+            .get_parameters_by_node(&talker)
+            .poll()
+            .unwrap()
+            .unwrap();
+        assert_eq!(parameters["rate"], Parameters::Double(5.0));
+        assert_eq!(
+            parameters["frame_id"],
+            Parameters::String("base_link".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parameter_events() {
+        let config = "\
+definitions:
+  rcl_interfaces/msg/ParameterEvent: |
+    string node
+    Parameter[] new_parameters
+    Parameter[] changed_parameters
+    Parameter[] deleted_parameters
+    ================================================================================
+    MSG: rcl_interfaces/Parameter
+    string name
+    ParameterValue value
+    ================================================================================
+    MSG: rcl_interfaces/ParameterValue
+    uint8 type
+    bool bool_value
+    int64 integer_value
+    float64 double_value
+    string string_value
+    byte[] byte_array_value
+    bool[] bool_array_value
+    int64[] integer_array_value
+    float64[] double_array_value
+    string[] string_array_value
+nodes:
+  - { name: /talker, parameters: { rate: 2.0 } }
+topics:
+  - { name: /parameter_events, type: rcl_interfaces/msg/ParameterEvent }
+";
+        let mut connection = ConnectionMock::from_yaml(config, "mock.yaml").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let _subscription = connection
+            .subscribe(PARAMETER_EVENTS, None, move |message, _| {
+                sender.send(parameter_event(&message).unwrap()).unwrap();
+            })
+            .unwrap();
+
+        let talker = NodeName::new("/", "talker");
+        for (name, value) in [("rate", 5.0), ("gain", 0.5)] {
+            connection
+                .set_parameter_by_node(&talker, name, Parameters::Double(value))
+                .poll()
+                .unwrap()
+                .unwrap();
+        }
+        let events = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    talker.clone(),
+                    vec![ParameterUpdate {
+                        kind: ParameterEventKind::Changed,
+                        name: "rate".to_string(),
+                        value: Some(Parameters::Double(5.0)),
+                    }]
+                ),
+                (
+                    talker,
+                    vec![ParameterUpdate {
+                        kind: ParameterEventKind::New,
+                        name: "gain".to_string(),
+                        value: Some(Parameters::Double(0.5)),
+                    }]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scheduled_topic() {
+        let config = "\
+definitions:
+  std_msgs/msg/String: string data
+topics:
+  - name: /chatter
+    type: std_msgs/msg/String
+    rate: 100
+    messages: [{ data: tick }]
+";
+        let mut connection = ConnectionMock::from_yaml(config, "mock.yaml").unwrap();
+        assert_eq!(connection.name(), "mock.yaml");
+        let (sender, receiver) = mpsc::channel();
+        let _subscription = connection
+            .subscribe("/chatter", None, move |message, _| {
+                let _ = sender.send(data(&message));
+            })
+            .unwrap();
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(1)).unwrap(),
+            "tick"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let undeclared = "nodes: [{ name: /talker, publishers: [/chatter] }]";
+        assert!(ConnectionMock::from_yaml(undeclared, "mock.yaml")
+            .unwrap_err()
+            .contains("undeclared topic '/chatter'"));
+        let bad_message = "\
+definitions:
+  std_msgs/msg/String: string data
+topics:
+  - { name: /chatter, type: std_msgs/msg/String, messages: [{ data: 1 }] }
+";
+        assert!(ConnectionMock::from_yaml(bad_message, "mock.yaml")
+            .unwrap_err()
+            .starts_with("/chatter: "));
+    }
+}
@@ -1,11 +1,23 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::time::SystemTime;
 
 use enum_dispatch::enum_dispatch;
+use serde_json::{json, Value};
 
+use self::mcap::ConnectionMcap;
+use foxglove::ConnectionFoxglove;
+use mock::ConnectionMock;
+use parameter_descriptor::ParameterDescriptor;
+use pending::PendingCall;
+use playback::Playback;
+use qos::QosProfile;
 use ros2::ConnectionROS2;
+use rosbag2::ConnectionRosbag2;
+use rosbridge::ConnectionRosbridge;
 
 use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::message_definition;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Parameters {
@@ -36,6 +48,70 @@ impl Display for Parameters {
     }
 }
 
+impl Parameters {
+    /// Convert a JSON parameter value, as sent by the bridges. Arrays get the type of their
+    /// elements, empty arrays are read as string arrays.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(match value {
+            Value::Bool(v) => Parameters::Bool(*v),
+            Value::Number(v) => match v.as_i64() {
+                Some(v) => Parameters::Integer(v),
+                None => Parameters::Double(v.as_f64()?),
+            },
+            Value::String(v) => Parameters::String(v.clone()),
+            Value::Array(values) if values.is_empty() => Parameters::StringArray(Vec::new()),
+            Value::Array(values) => {
+                if values.iter().all(Value::is_boolean) {
+                    Parameters::BoolArray(values.iter().filter_map(Value::as_bool).collect())
+                } else if values.iter().all(Value::is_i64) {
+                    Parameters::IntegerArray(values.iter().filter_map(Value::as_i64).collect())
+                } else if values.iter().all(Value::is_number) {
+                    Parameters::DoubleArray(values.iter().filter_map(Value::as_f64).collect())
+                } else if values.iter().all(Value::is_string) {
+                    Parameters::StringArray(
+                        values
+                            .iter()
+                            .filter_map(|v| v.as_str().map(str::to_string))
+                            .collect(),
+                    )
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Name of the type, as in `ros2 param describe`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Parameters::Bool(_) => "bool",
+            Parameters::Integer(_) => "integer",
+            Parameters::Double(_) => "double",
+            Parameters::String(_) => "string",
+            Parameters::ByteArray(_) => "byte_array",
+            Parameters::BoolArray(_) => "bool_array",
+            Parameters::IntegerArray(_) => "integer_array",
+            Parameters::DoubleArray(_) => "double_array",
+            Parameters::StringArray(_) => "string_array",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Parameters::Bool(v) => json!(v),
+            Parameters::Integer(v) => json!(v),
+            Parameters::Double(v) => json!(v),
+            Parameters::String(v) => json!(v),
+            Parameters::ByteArray(v) => json!(v),
+            Parameters::BoolArray(v) => json!(v),
+            Parameters::IntegerArray(v) => json!(v),
+            Parameters::DoubleArray(v) => json!(v),
+            Parameters::StringArray(v) => json!(v),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NodeName {
     pub namespace: String,
@@ -50,6 +126,15 @@ impl NodeName {
         }
     }
 
+    /// Split a fully qualified node name (`/namespace/name`).
+    pub fn from_full_name(full_name: &str) -> Self {
+        match full_name.rsplit_once('/') {
+            Some(("", name)) => Self::new("/", name),
+            Some((namespace, name)) => Self::new(namespace, name),
+            None => Self::new("/", full_name),
+        }
+    }
+
     pub fn full_name(&self) -> String {
         if self.namespace.ends_with('/') {
             format!("{}{}", self.namespace, self.name)
@@ -59,12 +144,71 @@ impl NodeName {
     }
 }
 
+/// A publisher or a subscription of a topic. Connections that only know the node fill the other
+/// fields with `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TopicEndpointInfo {
+    pub node_name: NodeName,
+    pub topic_type: Option<InterfaceType>,
+    pub qos: Option<QosProfile>,
+    /// Global identifier of the endpoint in the DDS graph.
+    pub gid: Option<Vec<u8>>,
+    pub type_hash: Option<String>,
+}
+
+impl From<NodeName> for TopicEndpointInfo {
+    fn from(node_name: NodeName) -> Self {
+        Self {
+            node_name,
+            topic_type: None,
+            qos: None,
+            gid: None,
+            type_hash: None,
+        }
+    }
+}
+
+impl TopicEndpointInfo {
+    /// Hexadecimal GID, bytes separated by dots like `ros2 topic info --verbose` prints it.
+    pub fn gid_string(&self) -> Option<String> {
+        self.gid.as_ref().map(|gid| {
+            gid.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct NamedInterface {
     pub name: String,
     pub type_name: InterfaceType,
 }
 
+/// Handle of a subscription, the callback keeps being called until the handle is dropped.
+#[must_use = "dropping the handle unsubscribes"]
+pub struct SubscriptionHandle {
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl SubscriptionHandle {
+    /// `unsubscribe` is called once, when the handle is dropped.
+    pub fn new(unsubscribe: impl FnOnce() + 'static) -> Self {
+        Self {
+            unsubscribe: Some(Box::new(unsubscribe)),
+        }
+    }
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
+
 /// The publisher closure returns the (possibly empty) list of field-level conversion warnings on
 /// success, or a fatal error string on failure. A non-empty warnings list means the message was
 /// sent but some fields could not be converted and were left at their default values.
@@ -85,18 +229,55 @@ pub trait Connection {
     /// Get the type of a specific topic.
     fn get_topic_type(&self, topic: &str) -> Option<InterfaceType>;
 
+    /// Subscribe to a topic with the given QoS, or with a QoS matching the existing publishers of
+    /// the topic when `None`. The subscription lasts as long as the returned handle.
     fn subscribe(
         &mut self,
         topic: &str,
+        qos: Option<QosProfile>,
         callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
-    ) -> Result<(), String>;
+    ) -> Result<SubscriptionHandle, String>;
 
+    /// Subscribe to the CDR payload of a topic, encapsulation header included, with the same QoS
+    /// rules as `subscribe`. Used to record topics. Recordings and Foxglove hand out the payload
+    /// as received, connections that only deliver decoded messages (ROS 2, rosbridge) encode the
+    /// message again, which may differ byte for byte from what the publisher sent.
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String>;
+
+    /// Get the `ros2msg` definition of a message type: its `.msg` text followed by the
+    /// definitions of the types it uses, in the format stored in MCAP schemas.
+    fn get_message_definition(&self, message_type: &InterfaceType) -> Result<String, String> {
+        message_definition::installed_schema(message_type)
+    }
+
+    /// Create a publisher with the given QoS, or with the QoS of the existing publishers of the
+    /// topic when `None`.
     fn create_publisher(
         &mut self,
         topic: &str,
         message_type: &InterfaceType,
+        qos: Option<QosProfile>,
     ) -> Result<Box<PublisherFunc>, String>;
 
+    /// Whether `create_publisher` is supported. Views that publish are only offered when it is.
+    fn can_publish(&self) -> bool {
+        true
+    }
+
+    /// Get the QoS offered by the publishers of the given topic, for connections that know it.
+    fn get_publishers_qos_by_topic(&self, topic: &str) -> Result<Vec<QosProfile>, String> {
+        Ok(self
+            .get_publishers_info_by_topic(topic)?
+            .into_iter()
+            .filter_map(|info| info.qos)
+            .collect())
+    }
+
     fn get_publisher_names_and_types_by_node(
         &self,
         node_name: &NodeName,
@@ -117,27 +298,48 @@ pub trait Connection {
         node_name: &NodeName,
     ) -> Result<Vec<NamedInterface>, String>;
 
+    /// Request the parameters of a node. The request runs in the background, poll the returned
+    /// call for its result.
     fn get_parameters_by_node(
         &self,
         node_name: &NodeName,
-    ) -> Result<HashMap<String, Parameters>, String>;
+    ) -> PendingCall<HashMap<String, Parameters>>;
 
+    /// Request the descriptors of the parameters `names` of a node, in the background like
+    /// `get_parameters_by_node`. Connections without descriptors answer with none.
+    fn describe_parameters_by_node(
+        &self,
+        _node_name: &NodeName,
+        _names: &[String],
+    ) -> PendingCall<HashMap<String, ParameterDescriptor>> {
+        PendingCall::ready(Ok(HashMap::new()))
+    }
+
+    /// Set a parameter of a node, in the background like `get_parameters_by_node`.
     fn set_parameter_by_node(
         &mut self,
         node_name: &NodeName,
         parameter_name: &str,
         parameter: Parameters,
-    ) -> Result<(), String>;
+    ) -> PendingCall<()>;
 
-    /// Get the nodes that publish to the given topic.
-    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<NodeName>, String>;
+    /// Get the publishers of the given topic.
+    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>, String>;
 
-    /// Get the nodes that subscribe to the given topic.
-    fn get_subscriptions_info_by_topic(&self, topic: &str) -> Result<Vec<NodeName>, String>;
+    /// Get the subscriptions of the given topic.
+    fn get_subscriptions_info_by_topic(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String>;
 
     /// List all services available in the connection.
     fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String>;
 
+    /// List the actions, found through the hidden `<action>/_action/send_goal` services.
+    fn list_actions(&self) -> Result<Vec<NamedInterface>, String> {
+        Ok(action::actions_from_services(&self.list_services()?))
+    }
+
     /// Get the type of a specific service.
     fn get_service_type(&self, service_name: &str) -> Option<InterfaceType>;
 
@@ -148,25 +350,55 @@ pub trait Connection {
         service_type: &InterfaceType,
     ) -> Result<GenericMessage, String>;
 
-    /// Call a service. Sends `request`, blocks until the response arrives (or timeout), and
-    /// returns the response together with any field-level conversion warnings that were produced
-    /// while building the request. A non-empty warnings list means some request fields could not
-    /// be converted and were sent as their default values.
+    /// Call a service. Sends `request` without waiting for the response: the returned call
+    /// completes with the response together with any field-level conversion warnings that were
+    /// produced while building the request. A non-empty warnings list means some request fields
+    /// could not be converted and were sent as their default values.
     fn call_service(
         &self,
         service_name: &str,
         service_type: &InterfaceType,
         request: &GenericMessage,
-    ) -> Result<(GenericMessage, Vec<String>), String>;
+    ) -> PendingCall<(GenericMessage, Vec<String>)>;
+
+    /// Current time of the connection's clock. Views plot against it rather than the wall clock
+    /// so that they follow the playback position of recorded data.
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Transport controls, for connections replaying recorded data.
+    fn playback(&self) -> Option<Playback> {
+        None
+    }
 }
 
 #[enum_dispatch]
 #[derive(Debug)]
 pub enum ConnectionType {
-    // Mcap(ConnectionMcap),
-    // Foxglove(ConnectionFoxglove),
-    // Rosbridge(ConnectionRosbridge),
+    Mcap(ConnectionMcap),
     ROS2(ConnectionROS2),
+    Rosbag2(ConnectionRosbag2),
+    Rosbridge(ConnectionRosbridge),
+    Foxglove(ConnectionFoxglove),
+    Mock(ConnectionMock),
 }
 
+pub mod action;
+pub mod foxglove;
+pub mod lifecycle;
+pub mod logger_levels;
+pub mod mcap;
+pub mod mock;
+pub mod parameter_descriptor;
+pub mod parameter_events;
+pub mod parameter_file;
+pub mod pending;
+pub mod playback;
+pub mod qos;
 pub mod ros2;
+pub mod rosbag2;
+pub mod rosbridge;
+pub mod rosout;
+pub mod tf;
+mod websocket;
@@ -0,0 +1,151 @@
+use std::fmt::Display;
+
+use super::Parameters;
+
+/// Values accepted by a numeric parameter: from `from` to `to` included, in increments of `step`
+/// from `from` unless `step` is 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRange<T> {
+    pub from: T,
+    pub to: T,
+    pub step: T,
+}
+
+impl<T: Display + PartialEq + Default> Display for ParameterRange<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.from, self.to)?;
+        if self.step != T::default() {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Description of a parameter, `rcl_interfaces/msg/ParameterDescriptor`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterDescriptor {
+    /// Type of the values, as named by `Parameters::type_name`, `None` when the parameter can
+    /// take any type.
+    pub type_name: Option<String>,
+    pub description: String,
+    pub additional_constraints: String,
+    pub read_only: bool,
+    pub integer_range: Option<ParameterRange<i64>>,
+    pub floating_point_range: Option<ParameterRange<f64>>,
+}
+
+impl ParameterDescriptor {
+    /// Check `value` the way the node will, before sending it.
+    pub fn validate(&self, value: &Parameters) -> Result<(), String> {
+        if self.read_only {
+            return Err("The parameter is read-only".to_string());
+        }
+        if let Some(type_name) = &self.type_name {
+            if type_name != value.type_name() {
+                return Err(format!("Expected a value of type {}", type_name));
+            }
+        }
+        match (value, &self.integer_range, &self.floating_point_range) {
+            (Parameters::Integer(v), Some(range), _) => {
+                if *v < range.from || *v > range.to {
+                    return Err(format!("{} is out of the range {}", v, range));
+                }
+                if range.step != 0 && *v != range.to && (v - range.from) % range.step != 0 {
+                    return Err(format!("{} is not a step of the range {}", v, range));
+                }
+            }
+            (Parameters::Double(v), _, Some(range)) => {
+                // Same tolerance as rclcpp
+                const TOLERANCE: f64 = 1e-9;
+                if *v < range.from - TOLERANCE || *v > range.to + TOLERANCE {
+                    return Err(format!("{} is out of the range {}", v, range));
+                }
+                if range.step != 0.0 && (v - range.to).abs() > TOLERANCE {
+                    let steps = ((v - range.from) / range.step).round();
+                    if (range.from + steps * range.step - v).abs() > TOLERANCE {
+                        return Err(format!("{} is not a step of the range {}", v, range));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// One line summary: type, constraints and description.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(type_name) = &self.type_name {
+            parts.push(type_name.clone());
+        }
+        if self.read_only {
+            parts.push("read-only".to_string());
+        }
+        if let Some(range) = &self.integer_range {
+            parts.push(range.to_string());
+        }
+        if let Some(range) = &self.floating_point_range {
+            parts.push(range.to_string());
+        }
+        if !self.additional_constraints.is_empty() {
+            parts.push(self.additional_constraints.clone());
+        }
+        if !self.description.is_empty() {
+            parts.push(self.description.clone());
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ranges() {
+        let descriptor = ParameterDescriptor {
+            type_name: Some("integer".to_string()),
+            integer_range: Some(ParameterRange {
+                from: 0,
+                to: 10,
+                step: 4,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(descriptor.validate(&Parameters::Integer(4)), Ok(()));
+        // The upper bound is always accepted
+        assert_eq!(descriptor.validate(&Parameters::Integer(10)), Ok(()));
+        assert!(descriptor.validate(&Parameters::Integer(5)).is_err());
+        assert!(descriptor.validate(&Parameters::Integer(11)).is_err());
+        assert_eq!(
+            descriptor.validate(&Parameters::Double(4.0)),
+            Err("Expected a value of type integer".to_string())
+        );
+
+        let descriptor = ParameterDescriptor {
+            floating_point_range: Some(ParameterRange {
+                from: 0.0,
+                to: 1.0,
+                step: 0.1,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(descriptor.validate(&Parameters::Double(0.3)), Ok(()));
+        assert!(descriptor.validate(&Parameters::Double(0.35)).is_err());
+        assert!(descriptor.validate(&Parameters::Double(-0.1)).is_err());
+        assert_eq!(descriptor.summary(), "[0, 1] step 0.1");
+    }
+
+    #[test]
+    fn test_validate_read_only() {
+        let descriptor = ParameterDescriptor {
+            read_only: true,
+            description: "Serial port".to_string(),
+            ..Default::default()
+        };
+        assert!(descriptor
+            .validate(&Parameters::String("/dev/ttyUSB1".to_string()))
+            .is_err());
+        assert_eq!(descriptor.summary(), "read-only, Serial port");
+    }
+}
@@ -0,0 +1,210 @@
+use serde_json::{json, Value};
+
+use crate::common::{generic_message::GenericMessage, json_message::to_json};
+
+use super::{NodeName, Parameters};
+
+/// Topic on which the nodes publish the changes of their parameters.
+pub const PARAMETER_EVENTS: &str = "/parameter_events";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterEventKind {
+    New,
+    Changed,
+    Deleted,
+}
+
+/// One parameter of a `rcl_interfaces/msg/ParameterEvent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterUpdate {
+    pub kind: ParameterEventKind,
+    pub name: String,
+    /// `None` for deleted parameters, and values of unknown type.
+    pub value: Option<Parameters>,
+}
+
+/// Value of a `rcl_interfaces/msg/ParameterValue`, `None` when not set.
+fn parameter_value(value: &Value) -> Option<Parameters> {
+    let field = |name: &str| value.get(name);
+    let array = |name: &str| field(name).and_then(Value::as_array);
+    Some(match value.get("type").and_then(Value::as_u64)? {
+        1 => Parameters::Bool(field("bool_value")?.as_bool()?),
+        2 => Parameters::Integer(field("integer_value")?.as_i64()?),
+        3 => Parameters::Double(field("double_value")?.as_f64()?),
+        4 => Parameters::String(field("string_value")?.as_str()?.to_string()),
+        5 => Parameters::ByteArray(
+            array("byte_array_value")?
+                .iter()
+                .map(|v| v.as_u64().and_then(|v| u8::try_from(v).ok()))
+                .collect::<Option<_>>()?,
+        ),
+        6 => Parameters::BoolArray(
+            array("bool_array_value")?
+                .iter()
+                .map(Value::as_bool)
+                .collect::<Option<_>>()?,
+        ),
+        7 => Parameters::IntegerArray(
+            array("integer_array_value")?
+                .iter()
+                .map(Value::as_i64)
+                .collect::<Option<_>>()?,
+        ),
+        8 => Parameters::DoubleArray(
+            array("double_array_value")?
+                .iter()
+                .map(Value::as_f64)
+                .collect::<Option<_>>()?,
+        ),
+        9 => Parameters::StringArray(
+            array("string_array_value")?
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+/// `rcl_interfaces/msg/ParameterValue` holding `value`, as JSON.
+pub fn parameter_value_json(value: &Parameters) -> Value {
+    let (type_id, field) = match value {
+        Parameters::Bool(_) => (1, "bool_value"),
+        Parameters::Integer(_) => (2, "integer_value"),
+        Parameters::Double(_) => (3, "double_value"),
+        Parameters::String(_) => (4, "string_value"),
+        Parameters::ByteArray(_) => (5, "byte_array_value"),
+        Parameters::BoolArray(_) => (6, "bool_array_value"),
+        Parameters::IntegerArray(_) => (7, "integer_array_value"),
+        Parameters::DoubleArray(_) => (8, "double_array_value"),
+        Parameters::StringArray(_) => (9, "string_array_value"),
+    };
+    json!({ "type": type_id, field: value.to_json() })
+}
+
+/// Node and parameters of a `rcl_interfaces/msg/ParameterEvent`.
+pub fn parameter_event(
+    message: &GenericMessage,
+) -> Result<(NodeName, Vec<ParameterUpdate>), String> {
+    let event = to_json(message);
+    let Some(node) = event.get("node").and_then(Value::as_str) else {
+        return Err("Invalid ParameterEvent".to_string());
+    };
+    let mut updates = Vec::new();
+    for (field, kind) in [
+        ("new_parameters", ParameterEventKind::New),
+        ("changed_parameters", ParameterEventKind::Changed),
+        ("deleted_parameters", ParameterEventKind::Deleted),
+    ] {
+        for parameter in event
+            .get(field)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(name) = parameter.get("name").and_then(Value::as_str) else {
+                return Err("Invalid parameter in ParameterEvent".to_string());
+            };
+            updates.push(ParameterUpdate {
+                kind,
+                name: name.to_string(),
+                value: match kind {
+                    ParameterEventKind::Deleted => None,
+                    _ => parameter.get("value").and_then(parameter_value),
+                },
+            });
+        }
+    }
+    Ok((NodeName::from_full_name(node), updates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::{
+        generic_message::InterfaceType, json_message::from_json,
+        message_definition::MessageRegistry,
+    };
+
+    const PARAMETER_EVENT: &str = "\
+builtin_interfaces/Time stamp
+string node
+Parameter[] new_parameters
+Parameter[] changed_parameters
+Parameter[] deleted_parameters
+================================================================================
+MSG: builtin_interfaces/Time
+int32 sec
+uint32 nanosec
+================================================================================
+MSG: rcl_interfaces/Parameter
+string name
+ParameterValue value
+================================================================================
+MSG: rcl_interfaces/ParameterValue
+uint8 type
+bool bool_value
+int64 integer_value
+float64 double_value
+string string_value
+byte[] byte_array_value
+bool[] bool_array_value
+int64[] integer_array_value
+float64[] double_array_value
+string[] string_array_value";
+
+    #[test]
+    fn test_parameter_event() {
+        let type_name = InterfaceType::new("rcl_interfaces/msg/ParameterEvent").unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, PARAMETER_EVENT).unwrap();
+        let message = from_json(
+            &registry,
+            &type_name,
+            &json!({
+                "node": "/ns/camera",
+                "new_parameters": [
+                    { "name": "offsets", "value": { "type": 8, "double_array_value": [0.5, 1.0] } },
+                ],
+                "changed_parameters": [
+                    { "name": "fps", "value": { "type": 2, "integer_value": 15 } },
+                ],
+                "deleted_parameters": [
+                    { "name": "legacy", "value": { "type": 0 } },
+                ],
+            }),
+        )
+        .unwrap();
+
+        let (node, updates) = parameter_event(&message).unwrap();
+        assert_eq!(node, NodeName::new("/ns", "camera"));
+        assert_eq!(
+            updates,
+            vec![
+                ParameterUpdate {
+                    kind: ParameterEventKind::New,
+                    name: "offsets".to_string(),
+                    value: Some(Parameters::DoubleArray(vec![0.5, 1.0])),
+                },
+                ParameterUpdate {
+                    kind: ParameterEventKind::Changed,
+                    name: "fps".to_string(),
+                    value: Some(Parameters::Integer(15)),
+                },
+                ParameterUpdate {
+                    kind: ParameterEventKind::Deleted,
+                    name: "legacy".to_string(),
+                    value: None,
+                },
+            ]
+        );
+
+        for value in [
+            Parameters::Bool(true),
+            Parameters::Double(2.5),
+            Parameters::StringArray(vec!["a".to_string()]),
+        ] {
+            assert_eq!(parameter_value(&parameter_value_json(&value)), Some(value));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+
+use serde_yaml::{Mapping, Value};
+
+use super::{NodeName, Parameters};
+
+/// Key of the parameters of a node in a parameter file.
+const ROS_PARAMETERS: &str = "ros__parameters";
+
+/// Change of a parameter loaded from a file, `old` is `None` for a parameter the node doesn't
+/// have yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterChange {
+    pub name: String,
+    pub old: Option<Parameters>,
+    pub new: Parameters,
+}
+
+impl Display for ParameterChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.old {
+            Some(old) => write!(f, "{}: {} → {}", self.name, old, self.new),
+            None => write!(f, "{}: {} (new)", self.name, self.new),
+        }
+    }
+}
+
+/// Default file of the parameters of `node`, in the current directory: `ns_camera.yaml` for
+/// `/ns/camera`.
+pub fn parameter_file_path(node: &NodeName) -> PathBuf {
+    let full_name = node.full_name();
+    PathBuf::from(format!(
+        "{}.yaml",
+        full_name.trim_start_matches('/').replace('/', "_")
+    ))
+}
+
+fn to_yaml(parameter: &Parameters) -> Value {
+    match parameter {
+        Parameters::Bool(v) => Value::from(*v),
+        Parameters::Integer(v) => Value::from(*v),
+        Parameters::Double(v) => Value::from(*v),
+        Parameters::String(v) => Value::from(v.as_str()),
+        Parameters::ByteArray(v) => Value::from(v.clone()),
+        Parameters::BoolArray(v) => Value::from(v.clone()),
+        Parameters::IntegerArray(v) => Value::from(v.clone()),
+        Parameters::DoubleArray(v) => Value::from(v.clone()),
+        Parameters::StringArray(v) => Value::from(v.clone()),
+    }
+}
+
+/// Parameters of `node` in the layout of `ros2 param dump`, with the dotted names nested:
+/// `/node: ros__parameters: qos_overrides: ...`.
+pub fn dump_parameters(
+    node: &NodeName,
+    parameters: &BTreeMap<String, Parameters>,
+) -> Result<String, String> {
+    let mut root = Mapping::new();
+    for (name, parameter) in parameters {
+        let mut mapping = &mut root;
+        let mut parts = name.split('.').peekable();
+        while let Some(part) = parts.next() {
+            let key = Value::from(part);
+            if parts.peek().is_none() {
+                mapping.insert(key, to_yaml(parameter));
+                break;
+            }
+            let entry = mapping
+                .entry(key)
+                .or_insert_with(|| Value::Mapping(Mapping::new()));
+            mapping = entry.as_mapping_mut().ok_or_else(|| {
+                format!(
+                    "Parameter '{}' is nested in the value of another parameter",
+                    name
+                )
+            })?;
+        }
+    }
+
+    let mut node_parameters = Mapping::new();
+    node_parameters.insert(Value::from(ROS_PARAMETERS), Value::Mapping(root));
+    let mut file = Mapping::new();
+    file.insert(
+        Value::from(node.full_name()),
+        Value::Mapping(node_parameters),
+    );
+    serde_yaml::to_string(&file).map_err(|e| format!("Failed to write the parameters: {}", e))
+}
+
+/// Whether the node key of a parameter file applies to `node`: its full name with or without
+/// the leading slash, its name alone, or the `/**` wildcards.
+fn matches_node(key: &str, node: &NodeName) -> bool {
+    let full_name = node.full_name();
+    key == "/**"
+        || key.trim_start_matches('/') == full_name.trim_start_matches('/')
+        || key == node.name
+        || key.strip_prefix("/**/") == Some(node.name.as_str())
+}
+
+fn flatten(prefix: &str, mapping: &Mapping, parameters: &mut BTreeMap<String, Value>) {
+    for (key, value) in mapping {
+        let key = match key {
+            Value::String(key) => key.clone(),
+            other => serde_yaml::to_string(other)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        };
+        let name = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Mapping(nested) => flatten(&name, nested, parameters),
+            value => {
+                parameters.insert(name, value.clone());
+            }
+        }
+    }
+}
+
+/// Values of the parameters of `node` in a parameter file, by dotted name. The entries of the
+/// wildcards and of the node are merged in the order of the file.
+pub fn load_parameters(node: &NodeName, text: &str) -> Result<BTreeMap<String, Value>, String> {
+    let file: Mapping =
+        serde_yaml::from_str(text).map_err(|e| format!("Invalid parameter file: {}", e))?;
+    let mut parameters = BTreeMap::new();
+    let mut found = false;
+    for (key, value) in &file {
+        if !key.as_str().is_some_and(|key| matches_node(key, node)) {
+            continue;
+        }
+        found = true;
+        match value.get(ROS_PARAMETERS) {
+            Some(Value::Mapping(node_parameters)) => flatten("", node_parameters, &mut parameters),
+            Some(Value::Null) => {}
+            _ => {
+                return Err(format!(
+                    "Missing '{}' under '{}'",
+                    ROS_PARAMETERS,
+                    key.as_str().unwrap_or_default()
+                ))
+            }
+        }
+    }
+    if !found {
+        return Err(format!(
+            "No parameters for '{}' in the file",
+            node.full_name()
+        ));
+    }
+    Ok(parameters)
+}
+
+/// Read a value of a parameter file, with the type of the `current` value where YAML is
+/// ambiguous: integers for a double, and empty arrays.
+fn from_yaml(
+    name: &str,
+    value: &Value,
+    current: Option<&Parameters>,
+) -> Result<Parameters, String> {
+    let invalid = || format!("Invalid value for '{}'", name);
+    let json = serde_json::to_value(value).map_err(|_| invalid())?;
+    let parameter = Parameters::from_json(&json).ok_or_else(invalid)?;
+    Ok(match (parameter, current) {
+        (Parameters::Integer(v), Some(Parameters::Double(_))) => Parameters::Double(v as f64),
+        (Parameters::IntegerArray(v), Some(Parameters::DoubleArray(_))) => {
+            Parameters::DoubleArray(v.into_iter().map(|v| v as f64).collect())
+        }
+        (Parameters::IntegerArray(v), Some(Parameters::ByteArray(_))) => Parameters::ByteArray(
+            v.into_iter()
+                .map(u8::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("Invalid byte in '{}'", name))?,
+        ),
+        (Parameters::StringArray(v), Some(current)) if v.is_empty() => match current {
+            Parameters::ByteArray(_) => Parameters::ByteArray(Vec::new()),
+            Parameters::BoolArray(_) => Parameters::BoolArray(Vec::new()),
+            Parameters::IntegerArray(_) => Parameters::IntegerArray(Vec::new()),
+            Parameters::DoubleArray(_) => Parameters::DoubleArray(Vec::new()),
+            _ => Parameters::StringArray(Vec::new()),
+        },
+        (parameter, _) => parameter,
+    })
+}
+
+/// Changes that loading `loaded` makes to the `current` parameters of a node, the unchanged
+/// values left out.
+pub fn diff_parameters(
+    current: &BTreeMap<String, Parameters>,
+    loaded: &BTreeMap<String, Value>,
+) -> Result<Vec<ParameterChange>, String> {
+    let mut changes = Vec::new();
+    for (name, value) in loaded {
+        let old = current.get(name);
+        let new = from_yaml(name, value, old)?;
+        if old != Some(&new) {
+            changes.push(ParameterChange {
+                name: name.clone(),
+                old: old.cloned(),
+                new,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_and_load() {
+        let node = NodeName::new("/ns", "camera");
+        let parameters = BTreeMap::from([
+            ("fps".to_string(), Parameters::Double(30.0)),
+            (
+                "qos_overrides./image.publisher.depth".to_string(),
+                Parameters::Integer(10),
+            ),
+            ("use_sim_time".to_string(), Parameters::Bool(false)),
+            (
+                "frames".to_string(),
+                Parameters::StringArray(vec!["base".to_string(), "optical".to_string()]),
+            ),
+        ]);
+        let text = dump_parameters(&node, &parameters).unwrap();
+        assert!(text.starts_with("/ns/camera:\n  ros__parameters:\n"));
+        assert!(text.contains("    qos_overrides:\n      /image:\n        publisher:\n"));
+
+        let loaded = load_parameters(&node, &text).unwrap();
+        assert_eq!(diff_parameters(&parameters, &loaded), Ok(vec![]));
+        assert_eq!(parameter_file_path(&node), PathBuf::from("ns_camera.yaml"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let node = NodeName::new("/", "driver");
+        let text = "\
+/**:
+  ros__parameters:
+    use_sim_time: true
+    rate: 10
+driver:
+  ros__parameters:
+    rate: 20
+    offsets: []
+    port: /dev/ttyUSB1
+/other:
+  ros__parameters:
+    rate: 5
+";
+        let loaded = load_parameters(&node, text).unwrap();
+        let current = BTreeMap::from([
+            ("use_sim_time".to_string(), Parameters::Bool(true)),
+            ("rate".to_string(), Parameters::Double(10.0)),
+            ("offsets".to_string(), Parameters::DoubleArray(vec![0.5])),
+        ]);
+        let changes = diff_parameters(&current, &loaded).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                ParameterChange {
+                    name: "offsets".to_string(),
+                    old: Some(Parameters::DoubleArray(vec![0.5])),
+                    new: Parameters::DoubleArray(vec![]),
+                },
+                ParameterChange {
+                    name: "port".to_string(),
+                    old: None,
+                    new: Parameters::String("/dev/ttyUSB1".to_string()),
+                },
+                ParameterChange {
+                    name: "rate".to_string(),
+                    old: Some(Parameters::Double(10.0)),
+                    new: Parameters::Double(20.0),
+                },
+            ]
+        );
+        assert_eq!(changes[2].to_string(), "rate: 10 → 20");
+
+        assert!(load_parameters(&NodeName::new("/", "talker"), "/listener: {}").is_err());
+    }
+}
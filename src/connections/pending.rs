@@ -0,0 +1,297 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Timeout of service calls and parameter requests, in milliseconds.
+static CALL_TIMEOUT_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// How long views wait for the result of a pending call before giving up.
+pub fn call_timeout() -> Duration {
+    Duration::from_millis(CALL_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+pub fn set_call_timeout(timeout: Duration) {
+    CALL_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+#[derive(Default)]
+struct CallState {
+    /// The call was dropped, or the task computing its result is done.
+    finished: bool,
+    /// Waker of the task, to stop it when the call is dropped or times out.
+    waker: Option<Waker>,
+}
+
+/// State shared by a pending call and the task computing its result.
+#[derive(Default)]
+struct CallShared {
+    state: Mutex<CallState>,
+    finished: Condvar,
+}
+
+impl CallShared {
+    fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.finished = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.finished.notify_all();
+    }
+}
+
+/// Result of a request that is still running in the background, e.g. a service call.
+///
+/// The connection completes the call through the sender returned by `PendingCall::new`. Dropping
+/// the call cancels it: the result is discarded when it arrives, and a task wrapped with
+/// `PendingCall::bound` stops.
+pub struct PendingCall<T> {
+    receiver: Receiver<Result<T, String>>,
+    started: Instant,
+    shared: Arc<CallShared>,
+}
+
+impl<T> PendingCall<T> {
+    pub fn new() -> (Self, Sender<Result<T, String>>) {
+        let (sender, receiver) = channel();
+        (
+            Self {
+                receiver,
+                started: Instant::now(),
+                shared: Arc::default(),
+            },
+            sender,
+        )
+    }
+
+    /// Wrap the async task computing the result of this call, so that it stops once the call is
+    /// dropped or after `timeout`, e.g. while waiting for a service that never comes up.
+    pub fn bound<F>(&self, future: F, timeout: Duration) -> Bounded<F>
+    where
+        F: Future<Output = Result<T, String>>,
+    {
+        Bounded {
+            future: Box::pin(future),
+            shared: self.shared.clone(),
+            deadline: self.started + timeout,
+            timeout,
+            timer_started: false,
+        }
+    }
+
+    /// A call whose result is already known, for connections answering synchronously.
+    pub fn ready(result: Result<T, String>) -> Self {
+        let (pending, sender) = Self::new();
+        let _ = sender.send(result);
+        pending
+    }
+
+    /// Run the blocking `f` on a thread of its own, for connections without an executor.
+    pub fn spawn(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Self
+    where
+        T: Send + 'static,
+    {
+        let (pending, sender) = Self::new();
+        thread::spawn(move || {
+            let _ = sender.send(f());
+        });
+        pending
+    }
+
+    /// Result of the call, or `None` while it is still running.
+    pub fn poll(&self) -> Option<Result<T, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(
+                "The connection dropped the call without answering".to_string(),
+            )),
+        }
+    }
+
+    /// Block until the result arrives, for at most `timeout`.
+    pub fn wait(self, timeout: Duration) -> Result<T, String> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                Err(format!("No response after {:.1}s", timeout.as_secs_f64()))
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                Err("The connection dropped the call without answering".to_string())
+            }
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Like `poll`, with an error once the call has been running for longer than the configured
+    /// timeout.
+    pub fn poll_timeout(&self) -> Option<Result<T, String>> {
+        match self.poll() {
+            None if self.elapsed() > call_timeout() => Some(Err(format!(
+                "No response after {:.1}s",
+                call_timeout().as_secs_f64()
+            ))),
+            result => result,
+        }
+    }
+}
+
+impl<T> Drop for PendingCall<T> {
+    fn drop(&mut self) {
+        self.shared.finish();
+    }
+}
+
+/// Task computing the result of a `PendingCall`, see `PendingCall::bound`.
+pub struct Bounded<F> {
+    future: Pin<Box<F>>,
+    shared: Arc<CallShared>,
+    deadline: Instant,
+    timeout: Duration,
+    timer_started: bool,
+}
+
+impl<T, F: Future<Output = Result<T, String>>> Future for Bounded<F> {
+    type Output = Result<T, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(result) = self.future.as_mut().poll(cx) {
+            self.shared.finish();
+            return Poll::Ready(result);
+        }
+        if Instant::now() >= self.deadline {
+            self.shared.finish();
+            return Poll::Ready(Err(format!(
+                "No response after {:.1}s",
+                self.timeout.as_secs_f64()
+            )));
+        }
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            if state.finished {
+                return Poll::Ready(Err("The call was cancelled".to_string()));
+            }
+            state.waker = Some(cx.waker().clone());
+        }
+        if !self.timer_started {
+            // Wake the task at the deadline, unless it is done before
+            self.timer_started = true;
+            let shared = self.shared.clone();
+            let deadline = self.deadline;
+            thread::spawn(move || {
+                let state = shared.state.lock().unwrap();
+                let (mut state, _) = shared
+                    .finished
+                    .wait_timeout_while(
+                        state,
+                        deadline.saturating_duration_since(Instant::now()),
+                        |state| !state.finished,
+                    )
+                    .unwrap();
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+        }
+        Poll::Pending
+    }
+}
+
+/// Spinner frame for a call that has been running for `elapsed`.
+pub fn spinner(elapsed: Duration) -> char {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    FRAMES[(elapsed.as_millis() / 100) as usize % FRAMES.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::task::Wake;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Minimal executor, polling `future` on the current thread until it is done.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn test_pending_call() {
+        let (pending, sender) = PendingCall::<i32>::new();
+        assert_eq!(pending.poll(), None);
+        sender.send(Ok(42)).unwrap();
+        assert_eq!(pending.poll(), Some(Ok(42)));
+
+        let pending = PendingCall::<i32>::ready(Err("failed".to_string()));
+        assert_eq!(pending.poll(), Some(Err("failed".to_string())));
+
+        // A connection dropping the sender must not leave the view waiting forever
+        let (pending, sender) = PendingCall::<i32>::new();
+        drop(sender);
+        assert!(matches!(pending.poll(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_wait() {
+        let (pending, sender) = PendingCall::<i32>::new();
+        thread::spawn(move || sender.send(Ok(7)).unwrap());
+        assert_eq!(pending.wait(Duration::from_secs(1)), Ok(7));
+
+        let (pending, _sender) = PendingCall::<i32>::new();
+        assert!(pending.wait(Duration::from_millis(10)).is_err());
+
+        let pending = PendingCall::spawn(|| Ok("done"));
+        assert_eq!(pending.wait(Duration::from_secs(1)), Ok("done"));
+
+        // Cancelling a call discards its result
+        let (pending, sender) = PendingCall::<i32>::new();
+        drop(pending);
+        assert!(sender.send(Ok(1)).is_err());
+    }
+
+    #[test]
+    fn test_bound() {
+        let (pending, _sender) = PendingCall::<i32>::new();
+        let task = pending.bound(async { Ok(3) }, Duration::from_secs(10));
+        assert_eq!(block_on(task), Ok(3));
+
+        // A service that never answers
+        let (pending, _sender) = PendingCall::<i32>::new();
+        let task = pending.bound(std::future::pending(), Duration::from_millis(20));
+        assert!(block_on(task).is_err());
+
+        // Dropping the call stops the task long before the timeout
+        let (pending, _sender) = PendingCall::<i32>::new();
+        let task = pending.bound(std::future::pending(), Duration::from_secs(60));
+        let started = Instant::now();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            drop(pending);
+        });
+        assert!(block_on(task).is_err());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,527 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{spawn, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::common::cdr;
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::message_definition::MessageRegistry;
+use crate::connections::SubscriptionHandle;
+
+/// Playback speeds offered by the transport controls.
+pub const PLAYBACK_RATES: [f64; 4] = [0.5, 1.0, 2.0, 10.0];
+
+/// Interval at which the player thread advances the clock.
+const PLAYER_TICK: Duration = Duration::from_millis(10);
+
+#[derive(Clone)]
+enum Callback {
+    Message(Arc<dyn Fn(GenericMessage, MessageMetadata) + Send + Sync>),
+    Raw(Arc<dyn Fn(Vec<u8>, MessageMetadata) + Send + Sync>),
+}
+
+pub struct RecordedTopic {
+    pub name: String,
+    pub type_name: InterfaceType,
+    /// Definitions needed to decode the topic, or the reason they could not be loaded.
+    pub registry: Result<MessageRegistry, String>,
+}
+
+/// Payload of a recorded message.
+pub enum Payload {
+    /// Loaded in memory.
+    Owned(Vec<u8>),
+    /// Left in the recording file, read through the `PayloadStore` of the recording with this
+    /// key.
+    Stored(usize),
+}
+
+/// Payloads left in a recording file and read as they are played back, so that recordings
+/// larger than the memory can be opened.
+pub trait PayloadStore: Send + Sync {
+    fn read(&self, key: usize) -> Result<Cow<'_, [u8]>, String>;
+}
+
+pub struct RecordedMessage {
+    pub topic: usize,
+    /// Log time in nanoseconds since the UNIX epoch.
+    pub log_time: u64,
+    pub data: Payload,
+}
+
+/// Recorded data of a file-based connection. The index of the messages is in memory, their
+/// payload may be left in the file.
+pub struct Recording {
+    pub topics: Vec<RecordedTopic>,
+    /// Messages of all topics, sorted by log time.
+    pub messages: Vec<RecordedMessage>,
+    store: Option<Box<dyn PayloadStore>>,
+}
+
+impl Recording {
+    pub fn new(topics: Vec<RecordedTopic>, mut messages: Vec<RecordedMessage>) -> Self {
+        // Stable sort, messages logged at the same time keep their file order.
+        messages.sort_by_key(|message| message.log_time);
+        Self {
+            topics,
+            messages,
+            store: None,
+        }
+    }
+
+    /// Read the `Payload::Stored` messages from `store`.
+    pub fn with_store(mut self, store: impl PayloadStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    pub fn topic_index(&self, topic: &str) -> Option<usize> {
+        self.topics.iter().position(|t| t.name == topic)
+    }
+
+    pub fn payload(&self, message: &RecordedMessage) -> Result<Cow<'_, [u8]>, String> {
+        match &message.data {
+            Payload::Owned(data) => Ok(Cow::Borrowed(data)),
+            Payload::Stored(key) => self
+                .store
+                .as_ref()
+                .ok_or("The recording has no payload store".to_string())?
+                .read(*key),
+        }
+    }
+
+    fn decode(&self, topic: usize, data: &[u8]) -> Option<GenericMessage> {
+        let topic = &self.topics[topic];
+        let registry = topic.registry.as_ref().ok()?;
+        cdr::deserialize(registry, &topic.type_name, data).ok()
+    }
+
+    fn first_log_time(&self) -> u64 {
+        self.messages.first().map_or(0, |m| m.log_time)
+    }
+
+    fn last_log_time(&self) -> u64 {
+        self.messages.last().map_or(0, |m| m.log_time)
+    }
+}
+
+fn to_system_time(log_time: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_nanos(log_time)
+}
+
+fn to_log_time(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+struct PlayerState {
+    /// Callbacks of each topic, with the id of their subscription.
+    callbacks: HashMap<usize, Vec<(u64, Callback)>>,
+    next_subscription_id: u64,
+    /// Playback position, in the log time of the recording.
+    position: u64,
+    /// Index of the next message to deliver.
+    next_index: usize,
+    playing: bool,
+    rate: f64,
+}
+
+impl PlayerState {
+    /// Move past the message at `next_index`, returning its index and the subscribers of its
+    /// topic, to be called once the state is unlocked.
+    fn take_next(&mut self, recording: &Recording) -> (usize, Vec<Callback>) {
+        let index = self.next_index;
+        let message = &recording.messages[index];
+        self.next_index += 1;
+        self.position = self.position.max(message.log_time);
+
+        let callbacks = self
+            .callbacks
+            .get(&message.topic)
+            .map(|callbacks| callbacks.iter().map(|(_, c)| c.clone()).collect())
+            .unwrap_or_default();
+        (index, callbacks)
+    }
+}
+
+/// Deliver the message at `index` to `callbacks`.
+fn deliver(recording: &Recording, index: usize, callbacks: &[Callback]) {
+    if callbacks.is_empty() {
+        return;
+    }
+    let message = &recording.messages[index];
+    // A payload that can't be read is skipped, like one that can't be decoded
+    let Ok(data) = recording.payload(message) else {
+        return;
+    };
+    // Only decode when someone wants the decoded message, and only once.
+    let mut generic_message = None;
+    for callback in callbacks {
+        let metadata = MessageMetadata {
+            received_time: to_system_time(message.log_time),
+            serialized_size: Some(data.len()),
+        };
+        match callback {
+            Callback::Message(callback) => {
+                if generic_message.is_none() {
+                    generic_message = Some(recording.decode(message.topic, &data));
+                }
+                if let Some(Some(generic_message)) = &generic_message {
+                    callback(generic_message.clone(), metadata);
+                }
+            }
+            Callback::Raw(callback) => callback(data.to_vec(), metadata),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlaybackStatus {
+    pub position: SystemTime,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub playing: bool,
+    pub rate: f64,
+}
+
+/// Transport controls of a player. Cheap to clone, all clones drive the same clock.
+#[derive(Clone)]
+pub struct Playback {
+    recording: Arc<Recording>,
+    state: Arc<Mutex<PlayerState>>,
+    /// Held while callbacks run, instead of `state`, so that subscribers get messages in order
+    /// and can still query and drive the playback.
+    delivery: Arc<Mutex<()>>,
+}
+
+impl Playback {
+    pub fn status(&self) -> PlaybackStatus {
+        let state = self.state.lock().unwrap();
+        PlaybackStatus {
+            position: to_system_time(state.position),
+            start: to_system_time(self.recording.first_log_time()),
+            end: to_system_time(self.recording.last_log_time()),
+            playing: state.playing,
+            rate: state.rate,
+        }
+    }
+
+    /// Current playback position.
+    pub fn now(&self) -> SystemTime {
+        to_system_time(self.state.lock().unwrap().position)
+    }
+
+    pub fn toggle_pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.next_index >= self.recording.messages.len() {
+            // Restart from the beginning once the end was reached.
+            state.next_index = 0;
+            state.position = self.recording.first_log_time();
+            state.playing = true;
+        } else {
+            state.playing = !state.playing;
+        }
+    }
+
+    /// Pause and deliver the next message.
+    pub fn step(&self) {
+        let _delivery = self.delivery.lock().unwrap();
+        let next = {
+            let mut state = self.state.lock().unwrap();
+            state.playing = false;
+            (state.next_index < self.recording.messages.len())
+                .then(|| state.take_next(&self.recording))
+        };
+        if let Some((index, callbacks)) = next {
+            deliver(&self.recording, index, &callbacks);
+        }
+    }
+
+    /// Jump to `time`, clamped to the recording. Messages between the previous position and
+    /// `time` are skipped.
+    pub fn seek(&self, time: SystemTime) {
+        let target = to_log_time(time).clamp(
+            self.recording.first_log_time(),
+            self.recording.last_log_time(),
+        );
+        let mut state = self.state.lock().unwrap();
+        state.position = target;
+        state.next_index = self
+            .recording
+            .messages
+            .partition_point(|message| message.log_time < target);
+    }
+
+    /// Seek `offset` seconds forward (or backward when negative) from the current position.
+    pub fn seek_relative(&self, offset: f64) {
+        let position = self.now();
+        let target = if offset >= 0.0 {
+            position + Duration::from_secs_f64(offset)
+        } else {
+            position
+                .checked_sub(Duration::from_secs_f64(-offset))
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        };
+        self.seek(target);
+    }
+
+    pub fn set_rate(&self, rate: f64) {
+        self.state.lock().unwrap().rate = rate;
+    }
+}
+
+/// Replays a `Recording` on a background thread, following a playback clock that can be paused,
+/// stepped, seeked and sped up through `Playback`.
+///
+/// Messages are delivered with their log time as `received_time`, and `Connection::now` returns
+/// the playback position, so views follow the recording instead of the wall clock.
+pub struct Player {
+    playback: Playback,
+    stop: Arc<AtomicBool>,
+    #[allow(unused)]
+    thread: JoinHandle<()>,
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Player {
+    /// Start playing `recording` from its first message.
+    pub fn new(recording: Recording) -> Self {
+        let playback = Playback {
+            state: Arc::new(Mutex::new(PlayerState {
+                callbacks: HashMap::new(),
+                next_subscription_id: 0,
+                position: recording.first_log_time(),
+                next_index: 0,
+                playing: true,
+                rate: 1.0,
+            })),
+            recording: Arc::new(recording),
+            delivery: Arc::new(Mutex::new(())),
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let playback = playback.clone();
+            let stop = stop.clone();
+            spawn(move || {
+                let mut last_tick = Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(PLAYER_TICK);
+                    let elapsed = last_tick.elapsed();
+                    last_tick = Instant::now();
+
+                    let recording = &playback.recording;
+                    let _delivery = playback.delivery.lock().unwrap();
+                    let mut pending = Vec::new();
+                    {
+                        let mut state = playback.state.lock().unwrap();
+                        if !state.playing {
+                            continue;
+                        }
+                        state.position += (elapsed.as_nanos() as f64 * state.rate) as u64;
+                        while state.next_index < recording.messages.len()
+                            && recording.messages[state.next_index].log_time <= state.position
+                        {
+                            pending.push(state.take_next(recording));
+                        }
+                        if state.next_index >= recording.messages.len() {
+                            state.playing = false;
+                            state.position = recording.last_log_time();
+                        }
+                    }
+                    for (index, callbacks) in pending {
+                        deliver(recording, index, &callbacks);
+                    }
+                }
+            })
+        };
+
+        Self {
+            playback,
+            stop,
+            thread,
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.playback.recording
+    }
+
+    pub fn playback(&self) -> Playback {
+        self.playback.clone()
+    }
+
+    fn topic_index(&self, topic: &str) -> Result<usize, String> {
+        self.recording()
+            .topic_index(topic)
+            .ok_or(format!("Topic '{}' is not in the recording", topic))
+    }
+
+    /// Register `callback` for the topic at `index`, until the returned handle is dropped.
+    fn add_callback(
+        &self,
+        state: &mut PlayerState,
+        index: usize,
+        callback: Callback,
+    ) -> SubscriptionHandle {
+        let id = state.next_subscription_id;
+        state.next_subscription_id += 1;
+        state
+            .callbacks
+            .entry(index)
+            .or_default()
+            .push((id, callback));
+
+        let state = Arc::downgrade(&self.playback.state);
+        SubscriptionHandle::new(move || {
+            if let Some(state) = state.upgrade() {
+                if let Some(callbacks) = state.lock().unwrap().callbacks.get_mut(&index) {
+                    callbacks.retain(|(callback_id, _)| *callback_id != id);
+                }
+            }
+        })
+    }
+
+    pub fn subscribe(
+        &self,
+        topic: &str,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        let recording = self.recording();
+        let index = self.topic_index(topic)?;
+        if let Err(e) = &recording.topics[index].registry {
+            return Err(format!("Cannot decode topic '{}': {}", topic, e));
+        }
+
+        // Nothing is delivered until the latest message was handed over
+        let _delivery = self.playback.delivery.lock().unwrap();
+        let callback: Arc<dyn Fn(GenericMessage, MessageMetadata) + Send + Sync> =
+            Arc::new(callback);
+        let (latest, handle) = {
+            let mut state = self.playback.state.lock().unwrap();
+            let latest = recording.messages[..state.next_index]
+                .iter()
+                .rev()
+                .find(|message| message.topic == index);
+            let handle = self.add_callback(&mut state, index, Callback::Message(callback.clone()));
+            (latest, handle)
+        };
+
+        // Hand the latest message over straight away, like a transient local publisher would.
+        // Otherwise topics recorded only once (e.g. /tf_static) would never show up.
+        if let Some(message) = latest {
+            let data = recording.payload(message).unwrap_or_default();
+            if let Some(generic_message) = recording.decode(index, &data) {
+                callback(
+                    generic_message,
+                    MessageMetadata {
+                        received_time: to_system_time(message.log_time),
+                        serialized_size: Some(data.len()),
+                    },
+                );
+            }
+        }
+
+        Ok(handle)
+    }
+
+    /// Subscribe to the recorded payload of `topic`, without decoding it.
+    pub fn subscribe_raw(
+        &self,
+        topic: &str,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        let index = self.topic_index(topic)?;
+        let mut state = self.playback.state.lock().unwrap();
+        Ok(self.add_callback(&mut state, index, Callback::Raw(Arc::new(callback))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::common::message_definition::MessageDefinition;
+
+    fn recording() -> Recording {
+        let type_name = InterfaceType::new("std_msgs/msg/UInt8").unwrap();
+        let mut registry = MessageRegistry::new();
+        registry.insert(MessageDefinition::parse(type_name.clone(), "uint8 data\n").unwrap());
+        let topics = vec![RecordedTopic {
+            name: "/counter".to_string(),
+            type_name,
+            registry: Ok(registry),
+        }];
+        let messages = (0..3)
+            .map(|i| RecordedMessage {
+                topic: 0,
+                log_time: 1_000_000_000 * (i + 1),
+                data: Payload::Owned(vec![0x00, 0x01, 0x00, 0x00, i as u8]),
+            })
+            .collect();
+        Recording::new(topics, messages)
+    }
+
+    #[test]
+    fn test_step_and_seek() {
+        let player = Player::new(recording());
+        let playback = player.playback();
+        playback.toggle_pause();
+        playback.seek(SystemTime::UNIX_EPOCH);
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let subscription = player
+            .subscribe("/counter", move |_, metadata| {
+                let _ = tx.lock().unwrap().send(metadata.received_time);
+            })
+            .unwrap();
+
+        playback.step();
+        assert_eq!(rx.try_recv().unwrap(), to_system_time(1_000_000_000));
+        assert_eq!(playback.now(), to_system_time(1_000_000_000));
+        assert!(!playback.status().playing);
+
+        playback.seek(to_system_time(2_500_000_000));
+        playback.step();
+        assert_eq!(rx.try_recv().unwrap(), to_system_time(3_000_000_000));
+        assert!(rx.try_recv().is_err());
+
+        drop(subscription);
+        playback.seek(SystemTime::UNIX_EPOCH);
+        playback.step();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_callback_uses_playback() {
+        let player = Player::new(recording());
+        let playback = player.playback();
+        playback.toggle_pause();
+        playback.seek(to_system_time(1_500_000_000));
+
+        // Callbacks run without the player state locked, so they can read the playback clock
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let clock = player.playback();
+        let _subscription = player
+            .subscribe("/counter", move |_, _| {
+                let _ = tx.lock().unwrap().send(clock.now());
+            })
+            .unwrap();
+        // The latest message before the position is handed over on subscription
+        assert_eq!(rx.try_recv().unwrap(), to_system_time(1_500_000_000));
+
+        playback.step();
+        assert_eq!(rx.try_recv().unwrap(), to_system_time(2_000_000_000));
+    }
+}
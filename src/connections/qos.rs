@@ -0,0 +1,347 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use super::TopicEndpointInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reliability {
+    Reliable,
+    BestEffort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    Volatile,
+    TransientLocal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum History {
+    KeepLast(u32),
+    KeepAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liveliness {
+    Automatic,
+    ManualByTopic,
+}
+
+/// Quality of service of a publisher or a subscription. Durations are `None` when infinite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QosProfile {
+    pub reliability: Reliability,
+    pub durability: Durability,
+    pub history: History,
+    pub deadline: Option<Duration>,
+    pub lifespan: Option<Duration>,
+    pub liveliness: Liveliness,
+    pub liveliness_lease_duration: Option<Duration>,
+}
+
+impl Default for QosProfile {
+    /// The default profile of rclcpp and rclpy.
+    fn default() -> Self {
+        Self {
+            reliability: Reliability::Reliable,
+            durability: Durability::Volatile,
+            history: History::KeepLast(10),
+            deadline: None,
+            lifespan: None,
+            liveliness: Liveliness::Automatic,
+            liveliness_lease_duration: None,
+        }
+    }
+}
+
+impl QosProfile {
+    /// Profile of sensor drivers, which favor the latest samples over reliability.
+    pub fn sensor_data() -> Self {
+        Self {
+            reliability: Reliability::BestEffort,
+            history: History::KeepLast(5),
+            ..Self::default()
+        }
+    }
+
+    /// Reliable profile keeping the last messages for late-joining subscriptions, like the
+    /// latched topics of ROS 1.
+    pub fn transient_local() -> Self {
+        Self {
+            durability: Durability::TransientLocal,
+            ..Self::default()
+        }
+    }
+
+    /// Subscription profile receiving from all of `publishers`: the strongest reliability and
+    /// durability they all offer. The default profile when there is no publisher.
+    pub fn matching(publishers: &[QosProfile]) -> Self {
+        if publishers.is_empty() {
+            return Self::default();
+        }
+        let all_reliable = publishers
+            .iter()
+            .all(|qos| qos.reliability == Reliability::Reliable);
+        let all_transient_local = publishers
+            .iter()
+            .all(|qos| qos.durability == Durability::TransientLocal);
+        Self {
+            reliability: if all_reliable {
+                Reliability::Reliable
+            } else {
+                Reliability::BestEffort
+            },
+            durability: if all_transient_local {
+                Durability::TransientLocal
+            } else {
+                Durability::Volatile
+            },
+            ..Self::default()
+        }
+    }
+}
+
+/// Whether a finite duration offered by a publisher satisfies the one requested by a
+/// subscription, `None` being infinite.
+fn offers_at_most(offered: Option<Duration>, requested: Option<Duration>) -> bool {
+    match (offered, requested) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(offered), Some(requested)) => offered <= requested,
+    }
+}
+
+/// Reasons why a subscription with the `subscription` QoS doesn't receive from a publisher with
+/// the `publisher` QoS, following the compatibility rules of rmw. Empty when they are compatible.
+pub fn incompatibilities(publisher: &QosProfile, subscription: &QosProfile) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if publisher.reliability == Reliability::BestEffort
+        && subscription.reliability == Reliability::Reliable
+    {
+        reasons.push("best_effort publisher, reliable subscription".to_string());
+    }
+    if publisher.durability == Durability::Volatile
+        && subscription.durability == Durability::TransientLocal
+    {
+        reasons.push("volatile publisher, transient_local subscription".to_string());
+    }
+    if !offers_at_most(publisher.deadline, subscription.deadline) {
+        reasons.push(format!(
+            "publisher deadline {} longer than subscription deadline {}",
+            format_duration(publisher.deadline),
+            format_duration(subscription.deadline)
+        ));
+    }
+    if publisher.liveliness == Liveliness::Automatic
+        && subscription.liveliness == Liveliness::ManualByTopic
+    {
+        reasons.push("automatic publisher liveliness, manual_by_topic subscription".to_string());
+    }
+    if !offers_at_most(
+        publisher.liveliness_lease_duration,
+        subscription.liveliness_lease_duration,
+    ) {
+        reasons.push(format!(
+            "publisher lease duration {} longer than subscription lease duration {}",
+            format_duration(publisher.liveliness_lease_duration),
+            format_duration(subscription.liveliness_lease_duration)
+        ));
+    }
+    reasons
+}
+
+/// Publisher and subscription pairs of a topic that don't communicate because of their QoS, as
+/// `(publisher index, subscription index, reasons)`. Endpoints of unknown QoS are skipped.
+pub fn incompatible_pairs(
+    publishers: &[TopicEndpointInfo],
+    subscriptions: &[TopicEndpointInfo],
+) -> Vec<(usize, usize, Vec<String>)> {
+    let mut pairs = Vec::new();
+    for (publisher_index, publisher) in publishers.iter().enumerate() {
+        for (subscription_index, subscription) in subscriptions.iter().enumerate() {
+            let (Some(publisher_qos), Some(subscription_qos)) = (&publisher.qos, &subscription.qos)
+            else {
+                continue;
+            };
+            let reasons = incompatibilities(publisher_qos, subscription_qos);
+            if !reasons.is_empty() {
+                pairs.push((publisher_index, subscription_index, reasons));
+            }
+        }
+    }
+    pairs
+}
+
+impl Display for Reliability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reliability::Reliable => write!(f, "reliable"),
+            Reliability::BestEffort => write!(f, "best_effort"),
+        }
+    }
+}
+
+impl Display for Durability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Durability::Volatile => write!(f, "volatile"),
+            Durability::TransientLocal => write!(f, "transient_local"),
+        }
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            History::KeepLast(depth) => write!(f, "keep_last({})", depth),
+            History::KeepAll => write!(f, "keep_all"),
+        }
+    }
+}
+
+impl Display for Liveliness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Liveliness::Automatic => write!(f, "automatic"),
+            Liveliness::ManualByTopic => write!(f, "manual_by_topic"),
+        }
+    }
+}
+
+/// Format an optional duration, `None` being infinite.
+pub fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        None => "infinite".to_string(),
+        Some(duration) if duration.subsec_millis() == 0 => format!("{}s", duration.as_secs()),
+        Some(duration) => format!("{}ms", duration.as_millis()),
+    }
+}
+
+impl Display for QosProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}",
+            self.reliability, self.durability, self.history
+        )?;
+        if let Some(deadline) = self.deadline {
+            write!(f, ", deadline {}", format_duration(Some(deadline)))?;
+        }
+        if let Some(lifespan) = self.lifespan {
+            write!(f, ", lifespan {}", format_duration(Some(lifespan)))?;
+        }
+        if self.liveliness != Liveliness::Automatic || self.liveliness_lease_duration.is_some() {
+            write!(
+                f,
+                ", liveliness {} (lease {})",
+                self.liveliness,
+                format_duration(self.liveliness_lease_duration)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_publishers() {
+        assert_eq!(QosProfile::matching(&[]), QosProfile::default());
+        assert_eq!(
+            QosProfile::matching(&[QosProfile::transient_local()]),
+            QosProfile::transient_local()
+        );
+
+        let matching =
+            QosProfile::matching(&[QosProfile::transient_local(), QosProfile::sensor_data()]);
+        assert_eq!(matching.reliability, Reliability::BestEffort);
+        assert_eq!(matching.durability, Durability::Volatile);
+        assert_eq!(matching.history, History::KeepLast(10));
+    }
+
+    #[test]
+    fn test_incompatibilities() {
+        assert!(incompatibilities(&QosProfile::default(), &QosProfile::default()).is_empty());
+        assert!(
+            incompatibilities(&QosProfile::transient_local(), &QosProfile::sensor_data())
+                .is_empty()
+        );
+        assert_eq!(
+            incompatibilities(&QosProfile::sensor_data(), &QosProfile::transient_local()),
+            vec![
+                "best_effort publisher, reliable subscription".to_string(),
+                "volatile publisher, transient_local subscription".to_string(),
+            ]
+        );
+
+        let subscription = QosProfile {
+            deadline: Some(Duration::from_millis(100)),
+            ..QosProfile::default()
+        };
+        assert_eq!(
+            incompatibilities(&QosProfile::default(), &subscription),
+            vec!["publisher deadline infinite longer than subscription deadline 100ms".to_string()]
+        );
+        let publisher = QosProfile {
+            deadline: Some(Duration::from_millis(50)),
+            ..QosProfile::default()
+        };
+        assert!(incompatibilities(&publisher, &subscription).is_empty());
+        assert_eq!(incompatibilities(&subscription, &publisher).len(), 1);
+
+        let subscription = QosProfile {
+            liveliness: Liveliness::ManualByTopic,
+            ..QosProfile::default()
+        };
+        assert_eq!(
+            incompatibilities(&QosProfile::default(), &subscription).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_incompatible_pairs() {
+        use crate::connections::NodeName;
+
+        let endpoint = |name: &str, qos: Option<QosProfile>| TopicEndpointInfo {
+            qos,
+            ..NodeName::new("/", name).into()
+        };
+        let publishers = [
+            endpoint("camera", Some(QosProfile::sensor_data())),
+            endpoint("bridge", None),
+            endpoint("map_server", Some(QosProfile::transient_local())),
+        ];
+        let subscriptions = [
+            endpoint("rviz", Some(QosProfile::default())),
+            endpoint("logger", Some(QosProfile::sensor_data())),
+        ];
+        assert_eq!(
+            incompatible_pairs(&publishers, &subscriptions),
+            vec![(
+                0,
+                0,
+                vec!["best_effort publisher, reliable subscription".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            QosProfile::default().to_string(),
+            "reliable, volatile, keep_last(10)"
+        );
+        let qos = QosProfile {
+            deadline: Some(Duration::from_millis(100)),
+            lifespan: Some(Duration::from_secs(2)),
+            ..QosProfile::sensor_data()
+        };
+        assert_eq!(
+            qos.to_string(),
+            "best_effort, volatile, keep_last(5), deadline 100ms, lifespan 2s"
+        );
+    }
+}
@@ -1,47 +1,171 @@
+use std::any::Any;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
-use std::sync::Arc;
 use std::thread::{spawn, JoinHandle};
 use std::time::{Duration, SystemTime};
 
+use crate::common::cdr;
 use crate::common::generic_message::{
     ArrayField, BoundedSequenceField, GenericField, GenericMessage, InterfaceType, MessageMetadata,
     SequenceField, SimpleField,
 };
-use crate::connections::{Connection, NamedInterface, NodeName, Parameters};
+use crate::connections::parameter_descriptor::{ParameterDescriptor, ParameterRange};
+use crate::connections::pending::{call_timeout, PendingCall};
+use crate::connections::qos::{Durability, History, Liveliness, QosProfile, Reliability};
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, SubscriptionHandle, TopicEndpointInfo,
+};
 
+use indexmap::IndexMap;
 use rcl_interfaces::msg::ParameterValue;
 use rclrs::*;
 use rosidl_runtime_rs::{Sequence, SequenceAlloc};
 
 use rcl_interfaces::srv::{
-    GetParameters, GetParameters_Request, GetParameters_Response, ListParameters,
-    ListParameters_Request, ListParameters_Response,
+    DescribeParameters, DescribeParameters_Request, DescribeParameters_Response, GetParameters,
+    GetParameters_Request, GetParameters_Response, ListParameters, ListParameters_Request,
+    ListParameters_Response,
 };
 
-const DEFAULT_SERVICE_TIMEOUT: Duration = Duration::from_millis(500);
+/// rmw reports infinite durations as the largest representable time.
+const RMW_DURATION_INFINITE: Duration = Duration::from_secs(9_223_372_036);
+
+fn qos_to_rclrs(qos: &QosProfile) -> QoSProfile {
+    let duration = |duration: Option<Duration>| match duration {
+        Some(duration) => QoSDuration::Custom(duration),
+        None => QoSDuration::Infinite,
+    };
+    QoSProfile {
+        history: match qos.history {
+            History::KeepLast(depth) => QoSHistoryPolicy::KeepLast { depth },
+            History::KeepAll => QoSHistoryPolicy::KeepAll,
+        },
+        reliability: match qos.reliability {
+            Reliability::Reliable => QoSReliabilityPolicy::Reliable,
+            Reliability::BestEffort => QoSReliabilityPolicy::BestEffort,
+        },
+        durability: match qos.durability {
+            Durability::Volatile => QoSDurabilityPolicy::Volatile,
+            Durability::TransientLocal => QoSDurabilityPolicy::TransientLocal,
+        },
+        deadline: duration(qos.deadline),
+        lifespan: duration(qos.lifespan),
+        liveliness: match qos.liveliness {
+            Liveliness::Automatic => QoSLivelinessPolicy::Automatic,
+            Liveliness::ManualByTopic => QoSLivelinessPolicy::ManualByTopic,
+        },
+        liveliness_lease_duration: duration(qos.liveliness_lease_duration),
+        ..QOS_PROFILE_DEFAULT
+    }
+}
+
+/// Convert the QoS reported by the graph. System defaults are read as the defaults of
+/// `QosProfile`.
+fn qos_from_rclrs(qos: &QoSProfile) -> QosProfile {
+    let default = QosProfile::default();
+    let duration = |duration: &QoSDuration| match duration {
+        QoSDuration::Custom(duration) if *duration < RMW_DURATION_INFINITE => Some(*duration),
+        _ => None,
+    };
+    QosProfile {
+        reliability: match qos.reliability {
+            QoSReliabilityPolicy::BestEffort => Reliability::BestEffort,
+            QoSReliabilityPolicy::Reliable => Reliability::Reliable,
+            _ => default.reliability,
+        },
+        durability: match qos.durability {
+            QoSDurabilityPolicy::TransientLocal => Durability::TransientLocal,
+            QoSDurabilityPolicy::Volatile => Durability::Volatile,
+            _ => default.durability,
+        },
+        history: match qos.history {
+            QoSHistoryPolicy::KeepLast { depth } => History::KeepLast(depth),
+            QoSHistoryPolicy::KeepAll => History::KeepAll,
+            _ => default.history,
+        },
+        deadline: duration(&qos.deadline),
+        lifespan: duration(&qos.lifespan),
+        liveliness: match qos.liveliness {
+            QoSLivelinessPolicy::ManualByTopic => Liveliness::ManualByTopic,
+            _ => Liveliness::Automatic,
+        },
+        liveliness_lease_duration: duration(&qos.liveliness_lease_duration),
+    }
+}
+
+fn endpoint_from_rclrs(info: rclrs::TopicEndpointInfo) -> TopicEndpointInfo {
+    TopicEndpointInfo {
+        node_name: NodeName {
+            name: info.node_name,
+            namespace: info.node_namespace,
+        },
+        topic_type: InterfaceType::new(&info.topic_type).ok(),
+        qos: Some(qos_from_rclrs(&info.qos_profile)),
+        gid: Some(info.endpoint_gid.to_vec()),
+        type_hash: Some(info.topic_type_hash.to_string()),
+    }
+}
 
-/// Submit an async task to the already-spinning background executor and block
-/// the calling thread until the result arrives (or the timeout expires).
-fn run_blocking<T: Send + 'static>(
+/// Submit an async task to the already-spinning background executor. Its result arrives in the
+/// returned call, so the UI thread never waits for a service. The task stops when the call is
+/// dropped or times out, instead of waiting forever for a service that isn't there.
+fn run_pending<T: Send + 'static>(
     node: &Node,
-    timeout: Duration,
-    f: impl Future<Output = T> + Send + 'static,
-) -> Result<T, String> {
-    let (tx, rx) = std::sync::mpsc::channel();
+    f: impl Future<Output = Result<T, String>> + Send + 'static,
+) -> PendingCall<T> {
+    let (pending, sender) = PendingCall::new();
+    let task = pending.bound(f, call_timeout());
     let _promise = node.commands().run(async move {
-        let result = f.await;
-        let _ = tx.send(result);
+        let _ = sender.send(task.await);
     });
-    rx.recv_timeout(timeout)
-        .map_err(|e| format!("Service call failed (timeout: {timeout:?}): {e}"))
+    pending
+}
+
+fn service_error(service_name: &str, error: impl Debug) -> String {
+    format!("Service '{}' failed: {:?}", service_name, error)
+}
+
+/// Name of one of the parameter services of `node`, e.g. `/ns/node/get_parameters`.
+fn parameter_service(node: &NodeName, service: &str) -> String {
+    format!("{}/{}", node.full_name(), service)
+}
+
+/// Most service clients kept by a connection, the least recently used ones are dropped beyond.
+const MAX_CLIENTS: usize = 64;
+
+/// Service clients by service name and type, the most recently used last.
+type ClientCache = IndexMap<(String, String), Box<dyn Any>>;
+
+/// Client cached in `clients` under `key`, created by `create` on first use. The least recently
+/// used client is dropped when there are more than `MAX_CLIENTS`.
+fn cached<C: Clone + 'static>(
+    clients: &mut ClientCache,
+    key: (String, String),
+    create: impl FnOnce() -> Result<C, String>,
+) -> Result<C, String> {
+    if let Some(client) = clients.shift_remove(&key) {
+        if let Some(cached) = client.downcast_ref::<C>().cloned() {
+            clients.insert(key, client);
+            return Ok(cached);
+        }
+    }
+    let client = create()?;
+    clients.insert(key, Box::new(client.clone()));
+    if clients.len() > MAX_CLIENTS {
+        clients.shift_remove_index(0);
+    }
+    Ok(client)
 }
 
 pub struct ConnectionROS2 {
     // Fields for the ROS2 connection
+    name: String,
     node: Node,
-    subscriptions: Vec<Arc<DynamicSubscriptionState<Arc<NodeState>>>>,
+    /// Service clients reused by the views refreshing periodically. Bounded, the nodes whose
+    /// services they call may leave the graph.
+    clients: RefCell<ClientCache>,
 
     #[allow(unused)]
     thread: JoinHandle<()>,
@@ -63,20 +187,58 @@ impl ConnectionROS2 {
     /// Create a new ROS2 connection.
     pub fn new() -> Self {
         let context = Context::default_from_env().unwrap();
+        Self::from_context("ROS2 Connection".to_string(), context).unwrap()
+    }
+
+    /// Create a new ROS2 connection on `domain_id`, regardless of `ROS_DOMAIN_ID`.
+    pub fn with_domain_id(domain_id: usize) -> Result<Self, String> {
+        let context = Context::new(
+            std::env::args(),
+            InitOptions::new().with_domain_id(Some(domain_id)),
+        )
+        .map_err(|e| format!("Failed to initialize ROS2 on domain {}: {}", domain_id, e))?;
+        Self::from_context(format!("ROS2 Connection (domain {})", domain_id), context)
+    }
+
+    fn from_context(name: String, context: Context) -> Result<Self, String> {
         let mut executor = context.create_basic_executor();
         let node = executor
             .create_node("splinter".start_parameter_services(false))
-            .unwrap();
-        ConnectionROS2 {
+            .map_err(|e| format!("Failed to create node: {}", e))?;
+        Ok(ConnectionROS2 {
+            name,
             node,
-            subscriptions: Vec::new(),
+            clients: RefCell::new(IndexMap::new()),
             thread: spawn(move || {
                 let result = executor.spin(SpinOptions::default()).first_error();
                 if let Err(e) = result {
                     eprintln!("Error in ROS2 executor: {}", e);
                 }
             }),
-        }
+        })
+    }
+
+    /// Client of `service_name` with the type `service_type`, created by `create` on first use.
+    fn cached_client<C: Clone + 'static>(
+        &self,
+        service_name: &str,
+        service_type: &str,
+        create: impl FnOnce() -> Result<C, String>,
+    ) -> Result<C, String> {
+        let key = (service_name.to_string(), service_type.to_string());
+        cached(&mut self.clients.borrow_mut(), key, create)
+    }
+
+    /// Client of the `S` service `service_name`, shared by the calls to that service.
+    fn client<S: rosidl_runtime_rs::Service>(
+        &self,
+        service_name: &str,
+    ) -> Result<Client<S>, String> {
+        self.cached_client(service_name, std::any::type_name::<S>(), || {
+            self.node
+                .create_client::<S>(service_name)
+                .map_err(|_| format!("Failed to create client for service: {}", service_name))
+        })
     }
 }
 
@@ -346,6 +508,46 @@ impl From<&rcl_interfaces::msg::ParameterValue> for Parameters {
     }
 }
 
+impl From<&rcl_interfaces::msg::ParameterDescriptor> for ParameterDescriptor {
+    fn from(descriptor: &rcl_interfaces::msg::ParameterDescriptor) -> Self {
+        use rcl_interfaces::msg::ParameterType;
+        let type_name = match descriptor.type_ {
+            ParameterType::PARAMETER_BOOL => Some("bool"),
+            ParameterType::PARAMETER_INTEGER => Some("integer"),
+            ParameterType::PARAMETER_DOUBLE => Some("double"),
+            ParameterType::PARAMETER_STRING => Some("string"),
+            ParameterType::PARAMETER_BYTE_ARRAY => Some("byte_array"),
+            ParameterType::PARAMETER_BOOL_ARRAY => Some("bool_array"),
+            ParameterType::PARAMETER_INTEGER_ARRAY => Some("integer_array"),
+            ParameterType::PARAMETER_DOUBLE_ARRAY => Some("double_array"),
+            ParameterType::PARAMETER_STRING_ARRAY => Some("string_array"),
+            // Not set: dynamically typed
+            _ => None,
+        };
+        ParameterDescriptor {
+            type_name: type_name.map(str::to_string),
+            description: descriptor.description.clone(),
+            additional_constraints: descriptor.additional_constraints.clone(),
+            read_only: descriptor.read_only,
+            integer_range: descriptor
+                .integer_range
+                .first()
+                .map(|range| ParameterRange {
+                    from: range.from_value,
+                    to: range.to_value,
+                    step: range.step as i64,
+                }),
+            floating_point_range: descriptor.floating_point_range.first().map(|range| {
+                ParameterRange {
+                    from: range.from_value,
+                    to: range.to_value,
+                    step: range.step,
+                }
+            }),
+        }
+    }
+}
+
 impl From<Parameters> for rcl_interfaces::msg::ParameterValue {
     fn from(param: Parameters) -> Self {
         match param {
@@ -401,7 +603,7 @@ impl From<Parameters> for rcl_interfaces::msg::ParameterValue {
 impl Connection for ConnectionROS2 {
     /// Get the name of the connection.
     fn name(&self) -> &str {
-        "ROS2 Connection"
+        &self.name
     }
 
     /// Get the type of the connection.
@@ -540,12 +742,17 @@ impl Connection for ConnectionROS2 {
     fn subscribe(
         &mut self,
         topic: &str,
+        qos: Option<QosProfile>,
         callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
-    ) -> Result<(), String> {
+    ) -> Result<SubscriptionHandle, String> {
         // Get topic type
         let topic_type = self
             .get_topic_type(topic)
             .ok_or(format!("Failed to get topic type for topic: {}", topic))?;
+        let qos = match qos {
+            Some(qos) => qos,
+            None => QosProfile::matching(&self.get_publishers_qos_by_topic(topic)?),
+        };
         let topic_type2 = topic_type.clone();
         // Create subscription
         let subscription = self
@@ -555,12 +762,15 @@ impl Connection for ConnectionROS2 {
                     package_name: topic_type.package_name,
                     type_name: topic_type.type_name,
                 },
-                topic,
+                topic.qos(qos_to_rclrs(&qos)),
                 move |msg: DynamicMessage, msg_info: MessageInfo| {
+                    let generic_message = GenericMessage::from(msg.view());
+                    // Dynamic subscriptions don't expose the received payload, its length is the
+                    // one of the CDR encoding of the message, counted without encoding it.
                     let metadata = MessageMetadata {
                         received_time: msg_info.received_timestamp.unwrap_or(SystemTime::now()),
+                        serialized_size: Some(cdr::serialized_size(&generic_message)),
                     };
-                    let generic_message = GenericMessage::from(msg.view());
                     callback(generic_message, metadata);
                 },
             )
@@ -570,16 +780,47 @@ impl Connection for ConnectionROS2 {
                     e, topic, topic_type2
                 )
             })?;
-        self.subscriptions.push(subscription);
 
-        Ok(())
+        // The node only keeps a weak reference, dropping the subscription removes it.
+        Ok(SubscriptionHandle::new(move || drop(subscription)))
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        // Dynamic subscriptions only hand out deserialized messages, so the payload is encoded
+        // again from the received message.
+        self.subscribe(topic, qos, move |message, metadata| {
+            let data = cdr::serialize(&message);
+            let metadata = MessageMetadata {
+                serialized_size: Some(data.len()),
+                ..metadata
+            };
+            callback(data, metadata)
+        })
     }
 
     fn create_publisher(
         &mut self,
         topic: &str,
         message_type: &InterfaceType,
+        qos: Option<QosProfile>,
     ) -> Result<Box<dyn Fn(&GenericMessage) -> Result<Vec<String>, String>>, String> {
+        let qos = match qos {
+            Some(qos) => qos,
+            None => {
+                let publishers = self.get_publishers_qos_by_topic(topic)?;
+                if publishers.is_empty() {
+                    // Keep the last messages for the subscriptions created later
+                    QosProfile::transient_local()
+                } else {
+                    QosProfile::matching(&publishers)
+                }
+            }
+        };
         let publisher = self
             .node
             .create_dynamic_publisher(
@@ -587,7 +828,7 @@ impl Connection for ConnectionROS2 {
                     package_name: message_type.package_name.clone(),
                     type_name: message_type.type_name.clone(),
                 },
-                topic.reliable().transient_local(),
+                topic.qos(qos_to_rclrs(&qos)),
             )
             .map_err(|e| format!("Failed to create publisher: {}", e))?;
 
@@ -607,62 +848,90 @@ impl Connection for ConnectionROS2 {
     fn get_parameters_by_node(
         &self,
         node_name: &NodeName,
-    ) -> Result<HashMap<String, Parameters>, String> {
-        let namespace = if node_name.namespace.is_empty() {
-            "/".to_string()
-        } else {
-            node_name.namespace.clone()
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        let list_service = parameter_service(node_name, "list_parameters");
+        let list_client = match self.client::<ListParameters>(&list_service) {
+            Ok(client) => client,
+            Err(e) => return PendingCall::ready(Err(e)),
+        };
+        let get_service = parameter_service(node_name, "get_parameters");
+        let get_client = match self.client::<GetParameters>(&get_service) {
+            Ok(client) => client,
+            Err(e) => return PendingCall::ready(Err(e)),
         };
 
-        // List parameters
-        let service_name = format!("{}{}/list_parameters", namespace, &node_name.name);
-        let client = self
-            .node
-            .create_client::<ListParameters>(&service_name)
-            .map_err(|_| format!("Failed to create client for service: {}", &service_name))?;
-
-        let param_names = run_blocking(&self.node, DEFAULT_SERVICE_TIMEOUT, async move {
-            client.notify_on_service_ready().await.unwrap();
+        run_pending(&self.node, async move {
+            // List parameters
+            list_client
+                .notify_on_service_ready()
+                .await
+                .map_err(|e| service_error(&list_service, e))?;
             let request = ListParameters_Request {
                 prefixes: vec![],
                 depth: ListParameters_Request::DEPTH_RECURSIVE,
             };
-            client
+            let param_names = list_client
                 .call::<_, ListParameters_Response>(&request)
-                .unwrap()
+                .map_err(|e| service_error(&list_service, e))?
                 .await
-                .unwrap()
-        })?
-        .result
-        .names;
-
-        // Get parameter values
-        let service_name = format!("{}{}/get_parameters", namespace, &node_name.name);
-        let client = self
-            .node
-            .create_client::<GetParameters>(&service_name)
-            .map_err(|_| format!("Failed to create client for service: {}", &service_name))?;
+                .map_err(|e| service_error(&list_service, e))?
+                .result
+                .names;
 
-        let request = GetParameters_Request {
-            names: param_names.clone(),
-        };
+            // Get parameter values
+            get_client
+                .notify_on_service_ready()
+                .await
+                .map_err(|e| service_error(&get_service, e))?;
+            let request = GetParameters_Request {
+                names: param_names.clone(),
+            };
+            let response = get_client
+                .call::<_, GetParameters_Response>(&request)
+                .map_err(|e| service_error(&get_service, e))?
+                .await
+                .map_err(|e| service_error(&get_service, e))?;
 
-        let response: GetParameters_Response =
-            run_blocking(&self.node, DEFAULT_SERVICE_TIMEOUT, async move {
-                client.notify_on_service_ready().await.unwrap();
-                client
-                    .call::<_, GetParameters_Response>(&request)
-                    .unwrap()
-                    .await
-                    .unwrap()
-            })?;
+            let mut params_map: HashMap<String, Parameters> = HashMap::new();
+            for (name, value) in param_names.iter().zip(response.values.iter()) {
+                params_map.insert(name.clone(), value.into());
+            }
 
-        let mut params_map: HashMap<String, Parameters> = HashMap::new();
-        for (name, value) in param_names.iter().zip(response.values.iter()) {
-            params_map.insert(name.clone(), value.into());
-        }
+            Ok(params_map)
+        })
+    }
+
+    fn describe_parameters_by_node(
+        &self,
+        node_name: &NodeName,
+        names: &[String],
+    ) -> PendingCall<HashMap<String, ParameterDescriptor>> {
+        let service_name = parameter_service(node_name, "describe_parameters");
+        let client = match self.client::<DescribeParameters>(&service_name) {
+            Ok(client) => client,
+            Err(e) => return PendingCall::ready(Err(e)),
+        };
+        let request = DescribeParameters_Request {
+            names: names.to_vec(),
+        };
 
-        Ok(params_map)
+        run_pending(&self.node, async move {
+            client
+                .notify_on_service_ready()
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
+            let response = client
+                .call::<_, DescribeParameters_Response>(&request)
+                .map_err(|e| service_error(&service_name, e))?
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
+            Ok(request
+                .names
+                .iter()
+                .cloned()
+                .zip(response.descriptors.iter().map(ParameterDescriptor::from))
+                .collect())
+        })
     }
 
     fn set_parameter_by_node(
@@ -670,19 +939,13 @@ impl Connection for ConnectionROS2 {
         node_name: &NodeName,
         parameter_name: &str,
         parameter: Parameters,
-    ) -> Result<(), String> {
-        let namespace = if node_name.namespace.is_empty() {
-            "/".to_string()
-        } else {
-            node_name.namespace.clone()
+    ) -> PendingCall<()> {
+        let service_name = parameter_service(node_name, "set_parameters");
+        let client = match self.client::<rcl_interfaces::srv::SetParameters>(&service_name) {
+            Ok(client) => client,
+            Err(e) => return PendingCall::ready(Err(e)),
         };
 
-        let service_name = format!("{}{}/set_parameters", namespace, &node_name.name);
-        let client = self
-            .node
-            .create_client::<rcl_interfaces::srv::SetParameters>(&service_name)
-            .map_err(|_| format!("Failed to create client for service: {}", &service_name))?;
-
         let request = rcl_interfaces::srv::SetParameters_Request {
             parameters: vec![rcl_interfaces::msg::Parameter {
                 name: parameter_name.to_string(),
@@ -690,41 +953,43 @@ impl Connection for ConnectionROS2 {
             }],
         };
 
-        let response: rcl_interfaces::srv::SetParameters_Response =
-            run_blocking(&self.node, DEFAULT_SERVICE_TIMEOUT, async move {
-                client.notify_on_service_ready().await.unwrap();
-                client
-                    .call::<_, rcl_interfaces::srv::SetParameters_Response>(&request)
-                    .unwrap()
-                    .await
-                    .unwrap()
-            })?;
+        run_pending(&self.node, async move {
+            client
+                .notify_on_service_ready()
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
+            let response = client
+                .call::<_, rcl_interfaces::srv::SetParameters_Response>(&request)
+                .map_err(|e| service_error(&service_name, e))?
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
 
-        for result in response.results {
-            if !result.successful {
-                return Err(format!("Failed to set parameter: {}", result.reason));
+            for result in response.results {
+                if !result.successful {
+                    // The reason is shown next to the parameter
+                    return Err(if result.reason.is_empty() {
+                        "The node rejected the value".to_string()
+                    } else {
+                        result.reason
+                    });
+                }
             }
-        }
 
-        Ok(())
+            Ok(())
+        })
     }
 
-    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<NodeName>, String> {
+    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
         self.node
             .get_publishers_info_by_topic(topic)
             .map_err(|e| format!("Failed to get publishers info for topic '{}': {}", topic, e))
-            .map(|infos| {
-                infos
-                    .into_iter()
-                    .map(|i| NodeName {
-                        name: i.node_name,
-                        namespace: i.node_namespace,
-                    })
-                    .collect()
-            })
+            .map(|infos| infos.into_iter().map(endpoint_from_rclrs).collect())
     }
 
-    fn get_subscriptions_info_by_topic(&self, topic: &str) -> Result<Vec<NodeName>, String> {
+    fn get_subscriptions_info_by_topic(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
         self.node
             .get_subscriptions_info_by_topic(topic)
             .map_err(|e| {
@@ -733,15 +998,7 @@ impl Connection for ConnectionROS2 {
                     topic, e
                 )
             })
-            .map(|infos| {
-                infos
-                    .into_iter()
-                    .map(|i| NodeName {
-                        name: i.node_name,
-                        namespace: i.node_namespace,
-                    })
-                    .collect()
-            })
+            .map(|infos| infos.into_iter().map(endpoint_from_rclrs).collect())
     }
 
     fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
@@ -775,13 +1032,12 @@ impl Connection for ConnectionROS2 {
         &self,
         service_type: &InterfaceType,
     ) -> Result<GenericMessage, String> {
-        let service_type_name: ServiceTypeName = format!(
-            "{}/srv/{}",
-            service_type.package_name, service_type.type_name
-        )
-        .as_str()
-        .try_into()
-        .map_err(|e| format!("Invalid service type: {e:?}"))?;
+        // Services of actions, e.g. `pkg/action/Fibonacci_SendGoal`, are in the action category
+        let service_type_name: ServiceTypeName = service_type
+            .to_string()
+            .as_str()
+            .try_into()
+            .map_err(|e| format!("Invalid service type: {e:?}"))?;
 
         let metadata = DynamicServiceMetadata::new(service_type_name)
             .map_err(|e| format!("Failed to load service metadata: {e:?}"))?;
@@ -799,40 +1055,55 @@ impl Connection for ConnectionROS2 {
         service_name: &str,
         service_type: &InterfaceType,
         request: &GenericMessage,
-    ) -> Result<(GenericMessage, Vec<String>), String> {
-        let service_type_name: ServiceTypeName = format!(
-            "{}/srv/{}",
-            service_type.package_name, service_type.type_name
-        )
-        .as_str()
-        .try_into()
-        .map_err(|e| format!("Invalid service type: {e:?}"))?;
-
-        let metadata = DynamicServiceMetadata::new(service_type_name)
-            .map_err(|e| format!("Failed to load service metadata: {e:?}"))?;
-
-        let request_metadata = metadata.request_metadata.clone();
-
-        let client = self
-            .node
-            .create_dynamic_client(metadata, service_name)
-            .map_err(|e| format!("Failed to create dynamic client: {e}"))?;
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        // Everything up to sending the request is synchronous, its errors are reported at once.
+        let prepare = || -> Result<_, String> {
+            let service_type_name: ServiceTypeName =
+                service_type
+                    .to_string()
+                    .as_str()
+                    .try_into()
+                    .map_err(|e| format!("Invalid service type: {e:?}"))?;
+
+            let metadata = DynamicServiceMetadata::new(service_type_name)
+                .map_err(|e| format!("Failed to load service metadata: {e:?}"))?;
+
+            let request_metadata = metadata.request_metadata.clone();
+
+            let client = self.cached_client(service_name, &service_type.to_string(), || {
+                self.node
+                    .create_dynamic_client(metadata, service_name)
+                    .map_err(|e| format!("Failed to create dynamic client: {e}"))
+            })?;
 
-        // Build the DynamicMessage request from the GenericMessage
-        let mut dynamic_request = request_metadata
-            .create()
-            .map_err(|e| format!("Failed to create request message: {e:?}"))?;
-        let request_warnings = {
-            let mut view = dynamic_request.view_mut();
-            populate_message(&mut view, request)
+            // Build the DynamicMessage request from the GenericMessage
+            let mut dynamic_request = request_metadata
+                .create()
+                .map_err(|e| format!("Failed to create request message: {e:?}"))?;
+            let request_warnings = {
+                let mut view = dynamic_request.view_mut();
+                populate_message(&mut view, request)
+            };
+            Ok((client, dynamic_request, request_warnings))
+        };
+        let (client, dynamic_request, request_warnings) = match prepare() {
+            Ok(prepared) => prepared,
+            Err(e) => return PendingCall::ready(Err(e)),
         };
 
-        let (response, _info) = run_blocking(&self.node, DEFAULT_SERVICE_TIMEOUT, async move {
-            client.notify_on_service_ready().await.unwrap();
-            client.call(dynamic_request).unwrap().await.unwrap()
-        })?;
-
-        Ok((GenericMessage::from(response.view()), request_warnings))
+        let service_name = service_name.to_string();
+        run_pending(&self.node, async move {
+            client
+                .notify_on_service_ready()
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
+            let (response, _info) = client
+                .call(dynamic_request)
+                .map_err(|e| service_error(&service_name, e))?
+                .await
+                .map_err(|e| service_error(&service_name, e))?;
+            Ok((GenericMessage::from(response.view()), request_warnings))
+        })
     }
 }
 
@@ -841,7 +1112,6 @@ impl Connection for ConnectionROS2 {
 mod tests {
     use super::*;
     use crate::common::generic_message::InterfaceType;
-    use indexmap::IndexMap;
     use rclrs::{DynamicMessage, MessageTypeName};
 
     fn make_message(package: &str, type_name: &str) -> DynamicMessage {
@@ -862,6 +1132,40 @@ mod tests {
         assert_eq!(generic, generic2, "Round-trip GenericMessage mismatch");
     }
 
+    // ── Client cache ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_client_cache_drops_least_recently_used() {
+        let key = |i: usize| {
+            (
+                format!("/node_{}/get_parameters", i),
+                "GetParameters".to_string(),
+            )
+        };
+        let mut clients = ClientCache::new();
+        for i in 0..MAX_CLIENTS {
+            assert_eq!(cached(&mut clients, key(i), || Ok(i)), Ok(i));
+        }
+        // Reused, not created again
+        assert_eq!(
+            cached::<usize>(&mut clients, key(0), || Err("created".to_string())),
+            Ok(0)
+        );
+
+        assert_eq!(
+            cached(&mut clients, key(MAX_CLIENTS), || Ok(MAX_CLIENTS)),
+            Ok(MAX_CLIENTS)
+        );
+        assert_eq!(clients.len(), MAX_CLIENTS);
+        // The least recently used client was dropped, the one just reused was kept
+        assert!(!clients.contains_key(&key(1)));
+        assert!(clients.contains_key(&key(0)));
+
+        // Failures are not cached
+        assert!(cached::<usize>(&mut clients, key(1), || Err("failed".to_string())).is_err());
+        assert!(!clients.contains_key(&key(1)));
+    }
+
     // ── Scalar string ─────────────────────────────────────────────────────────
 
     #[test]
@@ -1225,4 +1529,16 @@ mod tests {
             "Expected TryFrom to fail for an unknown message type"
         );
     }
+
+    #[test]
+    fn test_parameter_service() {
+        assert_eq!(
+            parameter_service(&NodeName::new("", "talker"), "get_parameters"),
+            "/talker/get_parameters"
+        );
+        assert_eq!(
+            parameter_service(&NodeName::new("/ns", "camera"), "set_parameters"),
+            "/ns/camera/set_parameters"
+        );
+    }
 }
@@ -0,0 +1,562 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::message_definition::{installed_schema, resolve_type_name, MessageRegistry};
+use crate::connections::pending::PendingCall;
+use crate::connections::playback::{
+    Payload, PayloadStore, Playback, Player, RecordedMessage, RecordedTopic, Recording,
+};
+use crate::connections::qos::QosProfile;
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, PublisherFunc, SubscriptionHandle,
+    TopicEndpointInfo,
+};
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    rosbag2_bagfile_information: BagInformation,
+}
+
+#[derive(Debug, Deserialize)]
+struct BagInformation {
+    storage_identifier: String,
+    relative_file_paths: Vec<String>,
+}
+
+/// Replays a rosbag2 bag recorded with the `sqlite3` storage plugin as if the recorded topics
+/// were live.
+///
+/// The messages of all the `.db3` files listed in `metadata.yaml` are indexed on open, then played
+/// back by a `Player` whose transport controls are exposed through `Connection::playback`. Their
+/// payloads stay in the files until they are played.
+pub struct ConnectionRosbag2 {
+    name: String,
+    path: PathBuf,
+    player: Player,
+    /// `ros2msg` schemas stored in the bag, by type.
+    schemas: HashMap<InterfaceType, String>,
+}
+
+impl Debug for ConnectionRosbag2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionRosbag2")
+            .field("path", &self.path)
+            .field("topics", &self.player.recording().topics.len())
+            .field("messages", &self.player.recording().messages.len())
+            .finish()
+    }
+}
+
+fn read_metadata(path: &Path) -> Result<BagInformation, String> {
+    let metadata_path = path.join("metadata.yaml");
+    let text = std::fs::read_to_string(&metadata_path)
+        .map_err(|e| format!("Failed to read '{}': {}", metadata_path.display(), e))?;
+    let metadata: Metadata = serde_yaml::from_str(&text)
+        .map_err(|e| format!("Invalid '{}': {}", metadata_path.display(), e))?;
+    let information = metadata.rosbag2_bagfile_information;
+    if information.storage_identifier != "sqlite3" {
+        return Err(format!(
+            "Unsupported storage '{}', only sqlite3 bags can be opened",
+            information.storage_identifier
+        ));
+    }
+    Ok(information)
+}
+
+/// Read the `ros2msg` definitions stored in the bag. Only bags recorded with Iron or later have
+/// them.
+fn read_schemas(
+    database: &rusqlite::Connection,
+) -> Result<HashMap<InterfaceType, String>, rusqlite::Error> {
+    let has_definitions: bool = database.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'message_definitions'",
+        [],
+        |row| row.get::<_, i64>(0).map(|count| count > 0),
+    )?;
+    if !has_definitions {
+        return Ok(HashMap::new());
+    }
+
+    let mut statement = database.prepare(
+        "SELECT topic_type, encoded_message_definition FROM message_definitions \
+        WHERE encoding = 'ros2msg'",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut schemas = HashMap::new();
+    for row in rows {
+        let (type_name, definition) = row?;
+        if let Ok(type_name) = resolve_type_name(&type_name, "") {
+            schemas.insert(type_name, definition);
+        }
+    }
+    Ok(schemas)
+}
+
+fn load_registry(
+    type_name: &InterfaceType,
+    serialization_format: &str,
+    schemas: &HashMap<InterfaceType, String>,
+) -> Result<MessageRegistry, String> {
+    if serialization_format != "cdr" {
+        return Err(format!(
+            "Unsupported serialization format '{}'",
+            serialization_format
+        ));
+    }
+    match schemas.get(type_name) {
+        Some(schema) => MessageRegistry::from_schema(type_name, schema),
+        // Older bags don't store definitions, use the interfaces installed locally.
+        None => {
+            let mut registry = MessageRegistry::new();
+            registry.load_installed(type_name)?;
+            Ok(registry)
+        }
+    }
+}
+
+/// Payloads of the messages of a bag, read by row id from its `.db3` files as they are played
+/// back.
+struct Rosbag2Store {
+    /// Open `.db3` files, in the order of `metadata.yaml`.
+    databases: Vec<Mutex<rusqlite::Connection>>,
+    /// File and row id of the message of each key.
+    locations: Vec<(usize, i64)>,
+}
+
+impl PayloadStore for Rosbag2Store {
+    fn read(&self, key: usize) -> Result<Cow<'_, [u8]>, String> {
+        let (file, id) = *self
+            .locations
+            .get(key)
+            .ok_or_else(|| format!("No message {}", key))?;
+        let database = self.databases[file].lock().unwrap();
+        let mut statement = database
+            .prepare_cached("SELECT data FROM messages WHERE id = ?1")
+            .map_err(|e| format!("Failed to read message {}: {}", id, e))?;
+        statement
+            .query_row([id], |row| row.get::<_, Vec<u8>>(0))
+            .map(Cow::Owned)
+            .map_err(|e| format!("Failed to read message {}: {}", id, e))
+    }
+}
+
+/// Append the topics and messages of `database`, the `.db3` file `file` of the bag, and the
+/// location of its messages to `locations`. Topic ids are local to each file, topics are matched
+/// across files by name. Topics whose type can't be resolved are left out.
+fn read_database(
+    path: &Path,
+    database: &rusqlite::Connection,
+    file: usize,
+    topics: &mut Vec<RecordedTopic>,
+    messages: &mut Vec<RecordedMessage>,
+    locations: &mut Vec<(usize, i64)>,
+    schemas: &mut HashMap<InterfaceType, String>,
+) -> Result<(), String> {
+    let sqlite_error = |e: rusqlite::Error| format!("Failed to read '{}': {}", path.display(), e);
+
+    schemas.extend(read_schemas(database).map_err(sqlite_error)?);
+
+    let mut topic_indices: HashMap<i64, usize> = HashMap::new();
+    let mut skipped_topics = HashSet::new();
+    let mut statement = database
+        .prepare("SELECT id, name, type, serialization_format FROM topics")
+        .map_err(sqlite_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(sqlite_error)?;
+    for row in rows {
+        let (id, name, type_name, serialization_format) = row.map_err(sqlite_error)?;
+        let index = match topics.iter().position(|topic| topic.name == name) {
+            Some(index) => index,
+            None => {
+                let Ok(type_name) = resolve_type_name(&type_name, "") else {
+                    skipped_topics.insert(id);
+                    continue;
+                };
+                let registry = load_registry(&type_name, &serialization_format, schemas);
+                topics.push(RecordedTopic {
+                    name,
+                    type_name,
+                    registry,
+                });
+                topics.len() - 1
+            }
+        };
+        topic_indices.insert(id, index);
+    }
+
+    let mut statement = database
+        .prepare("SELECT id, topic_id, timestamp FROM messages")
+        .map_err(sqlite_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(sqlite_error)?;
+    for row in rows {
+        let (id, topic_id, timestamp) = row.map_err(sqlite_error)?;
+        if skipped_topics.contains(&topic_id) {
+            continue;
+        }
+        let topic = *topic_indices.get(&topic_id).ok_or_else(|| {
+            format!(
+                "Message of unknown topic {} in '{}'",
+                topic_id,
+                path.display()
+            )
+        })?;
+        messages.push(RecordedMessage {
+            topic,
+            log_time: timestamp.max(0) as u64,
+            data: Payload::Stored(locations.len()),
+        });
+        locations.push((file, id));
+    }
+
+    Ok(())
+}
+
+fn read_recording(path: &Path) -> Result<(Recording, HashMap<InterfaceType, String>), String> {
+    let information = read_metadata(path)?;
+
+    let mut topics = Vec::new();
+    let mut messages = Vec::new();
+    let mut schemas = HashMap::new();
+    let mut store = Rosbag2Store {
+        databases: Vec::new(),
+        locations: Vec::new(),
+    };
+    for (file, relative_path) in information.relative_file_paths.iter().enumerate() {
+        let path = path.join(relative_path);
+        let database = rusqlite::Connection::open_with_flags(
+            &path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+        read_database(
+            &path,
+            &database,
+            file,
+            &mut topics,
+            &mut messages,
+            &mut store.locations,
+            &mut schemas,
+        )?;
+        store.databases.push(Mutex::new(database));
+    }
+
+    Ok((Recording::new(topics, messages).with_store(store), schemas))
+}
+
+impl ConnectionRosbag2 {
+    /// Open a rosbag2 bag directory and start replaying it.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (recording, schemas) = read_recording(path)?;
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            path: path.to_path_buf(),
+            player: Player::new(recording),
+            schemas,
+        })
+    }
+
+    fn unsupported<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("{} is not supported for rosbag2 bags", what))
+    }
+}
+
+impl Connection for ConnectionRosbag2 {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_topics(&self) -> Result<Vec<NamedInterface>, String> {
+        Ok(self
+            .player
+            .recording()
+            .topics
+            .iter()
+            .map(|topic| NamedInterface {
+                name: topic.name.clone(),
+                type_name: topic.type_name.clone(),
+            })
+            .collect())
+    }
+
+    fn list_nodes(&self) -> Result<Vec<NodeName>, String> {
+        // Bags don't carry any graph information.
+        Ok(Vec::new())
+    }
+
+    fn get_topic_type(&self, topic: &str) -> Option<InterfaceType> {
+        let recording = self.player.recording();
+        recording
+            .topic_index(topic)
+            .map(|index| recording.topics[index].type_name.clone())
+    }
+
+    fn subscribe(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.player.subscribe(topic, callback)
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        self.player.subscribe_raw(topic, callback)
+    }
+
+    fn create_publisher(
+        &mut self,
+        _topic: &str,
+        _message_type: &InterfaceType,
+        _qos: Option<QosProfile>,
+    ) -> Result<Box<PublisherFunc>, String> {
+        self.unsupported("Publishing")
+    }
+
+    fn can_publish(&self) -> bool {
+        false
+    }
+
+    fn get_publisher_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_subscription_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_client_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_service_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_parameters_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        PendingCall::ready(self.unsupported("Parameters"))
+    }
+
+    fn set_parameter_by_node(
+        &mut self,
+        _node_name: &NodeName,
+        _parameter_name: &str,
+        _parameter: Parameters,
+    ) -> PendingCall<()> {
+        PendingCall::ready(self.unsupported("Parameters"))
+    }
+
+    fn get_publishers_info_by_topic(&self, _topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_subscriptions_info_by_topic(
+        &self,
+        _topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_service_type(&self, _service_name: &str) -> Option<InterfaceType> {
+        None
+    }
+
+    fn get_service_request_template(
+        &self,
+        _service_type: &InterfaceType,
+    ) -> Result<GenericMessage, String> {
+        self.unsupported("Services")
+    }
+
+    fn call_service(
+        &self,
+        _service_name: &str,
+        _service_type: &InterfaceType,
+        _request: &GenericMessage,
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        PendingCall::ready(self.unsupported("Services"))
+    }
+
+    fn get_message_definition(&self, message_type: &InterfaceType) -> Result<String, String> {
+        match self.schemas.get(message_type) {
+            Some(schema) => Ok(schema.clone()),
+            None => installed_schema(message_type),
+        }
+    }
+
+    fn now(&self) -> SystemTime {
+        self.player.playback().now()
+    }
+
+    fn playback(&self) -> Option<Playback> {
+        Some(self.player.playback())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use crate::common::generic_message::{GenericField, SimpleField};
+
+    const METADATA: &str = "\
+rosbag2_bagfile_information:
+  version: 8
+  storage_identifier: sqlite3
+  duration:
+    nanoseconds: 1000000
+  starting_time:
+    nanoseconds_since_epoch: 1000000
+  message_count: 2
+  relative_file_paths:
+    - test_0.db3
+";
+
+    fn write_test_bag(path: &Path) {
+        std::fs::create_dir_all(path).unwrap();
+        std::fs::write(path.join("metadata.yaml"), METADATA).unwrap();
+
+        let database = rusqlite::Connection::open(path.join("test_0.db3")).unwrap();
+        database
+            .execute_batch(
+                "CREATE TABLE topics(id INTEGER PRIMARY KEY, name TEXT NOT NULL, \
+                type TEXT NOT NULL, serialization_format TEXT NOT NULL, \
+                offered_qos_profiles TEXT NOT NULL);
+                CREATE TABLE messages(id INTEGER PRIMARY KEY, topic_id INTEGER NOT NULL, \
+                timestamp INTEGER NOT NULL, data BLOB NOT NULL);
+                CREATE TABLE message_definitions(id INTEGER PRIMARY KEY, \
+                topic_type TEXT NOT NULL, encoding TEXT NOT NULL, \
+                encoded_message_definition TEXT NOT NULL, type_description_hash TEXT NOT NULL);
+                INSERT INTO topics VALUES(1, '/chatter', 'std_msgs/msg/String', 'cdr', '');
+                INSERT INTO topics VALUES(2, '/unknown', 'a/b/c/d', 'cdr', '');
+                INSERT INTO messages(topic_id, timestamp, data) VALUES(2, 500000, x'00');
+                INSERT INTO message_definitions \
+                VALUES(1, 'std_msgs/msg/String', 'ros2msg', 'string data', '');",
+            )
+            .unwrap();
+
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&6u32.to_le_bytes());
+        data.extend_from_slice(b"hello\0");
+        for timestamp in [1_000_000i64, 2_000_000] {
+            database
+                .execute(
+                    "INSERT INTO messages(topic_id, timestamp, data) VALUES(1, ?1, ?2)",
+                    rusqlite::params![timestamp, data],
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_list_topics_and_subscribe() {
+        let path = std::env::temp_dir().join(format!("splinter_test_bag_{}", std::process::id()));
+        write_test_bag(&path);
+
+        let mut connection = ConnectionRosbag2::open(&path).unwrap();
+
+        // The topic of unknown type is left out
+        let topics = connection.list_topics().unwrap();
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].name, "/chatter");
+        assert_eq!(
+            connection.get_topic_type("/chatter"),
+            Some(InterfaceType::new("std_msgs/msg/String").unwrap())
+        );
+        assert_eq!(
+            connection
+                .get_message_definition(&InterfaceType::new("std_msgs/msg/String").unwrap())
+                .unwrap(),
+            "string data"
+        );
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let subscription = connection
+            .subscribe("/chatter", None, move |message, _| {
+                let _ = tx.lock().unwrap().send(message);
+            })
+            .unwrap();
+
+        let message = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            message["data"],
+            GenericField::Simple(SimpleField::String("hello".to_string()))
+        );
+
+        drop(subscription);
+        drop(connection);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reject_other_storage() {
+        let path =
+            std::env::temp_dir().join(format!("splinter_test_mcap_bag_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(
+            path.join("metadata.yaml"),
+            METADATA.replace("sqlite3", "mcap"),
+        )
+        .unwrap();
+
+        let error = ConnectionRosbag2::open(&path).unwrap_err();
+        std::fs::remove_dir_all(&path).unwrap();
+        assert!(error.contains("Unsupported storage 'mcap'"));
+    }
+}
@@ -0,0 +1,1122 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{spawn, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+use crate::common::cdr;
+use crate::common::generic_message::{GenericMessage, InterfaceType, MessageMetadata};
+use crate::common::json_message::{from_json, to_json};
+use crate::common::message_definition::{resolve_type_name, MessageDefinition, MessageRegistry};
+use crate::connections::pending::{call_timeout, PendingCall};
+use crate::connections::qos::QosProfile;
+use crate::connections::websocket;
+use crate::connections::{
+    Connection, NamedInterface, NodeName, Parameters, PublisherFunc, SubscriptionHandle,
+    TopicEndpointInfo,
+};
+
+struct Subscriber {
+    id: u64,
+    type_name: InterfaceType,
+    registry: Arc<MessageRegistry>,
+    callback: Box<dyn Fn(GenericMessage, MessageMetadata) + Send + Sync>,
+}
+
+/// How long a graph response from rosapi is used before being refreshed, the views query the
+/// graph far more often than it changes.
+const GRAPH_CACHE_DURATION: Duration = Duration::from_secs(1);
+
+/// State shared with the socket thread.
+#[derive(Default)]
+struct Shared {
+    subscribers: Mutex<HashMap<String, Vec<Subscriber>>>,
+    /// Service calls waiting for their `service_response`, by call id.
+    pending: Mutex<HashMap<String, Sender<Value>>>,
+    next_id: AtomicU64,
+    connected: AtomicBool,
+}
+
+impl Shared {
+    fn handle_incoming(&self, text: &str) {
+        let Ok(message) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+        match message["op"].as_str() {
+            Some("publish") => {
+                let Some(topic) = message["topic"].as_str() else {
+                    return;
+                };
+                let subscribers = self.subscribers.lock().unwrap();
+                for subscriber in subscribers.get(topic).into_iter().flatten() {
+                    if let Ok(generic_message) =
+                        from_json(&subscriber.registry, &subscriber.type_name, &message["msg"])
+                    {
+                        // Messages come as JSON, what went over the wire is the JSON text
+                        let metadata = MessageMetadata {
+                            received_time: SystemTime::now(),
+                            serialized_size: Some(text.len()),
+                        };
+                        (subscriber.callback)(generic_message, metadata);
+                    }
+                }
+            }
+            Some("service_response") => {
+                let Some(id) = message["id"].as_str() else {
+                    return;
+                };
+                if let Some(sender) = self.pending.lock().unwrap().remove(id) {
+                    let _ = sender.send(message);
+                }
+            }
+            // Status messages and anything else are not handled
+            _ => {}
+        }
+    }
+}
+
+/// Map the IDL type names used by the ROS 2 rosapi to their `.msg` equivalent.
+fn msg_field_type(field_type: &str) -> &str {
+    let field_type = field_type
+        .strip_prefix("sequence<")
+        .and_then(|inner| inner.split([',', '>']).next())
+        .unwrap_or(field_type);
+    match field_type {
+        "double" => "float64",
+        "float" => "float32",
+        "boolean" => "bool",
+        "octet" => "byte",
+        _ => field_type,
+    }
+}
+
+/// Build a registry from the `typedefs` returned by the rosapi `*_details` services. The first
+/// typedef is the root type, registered under `root`.
+fn registry_from_typedefs(
+    root: &InterfaceType,
+    typedefs: &Value,
+) -> Result<MessageRegistry, String> {
+    let typedefs = typedefs
+        .as_array()
+        .filter(|typedefs| !typedefs.is_empty())
+        .ok_or_else(|| format!("No type definition for '{}'", root))?;
+
+    let mut registry = MessageRegistry::new();
+    for (index, typedef) in typedefs.iter().enumerate() {
+        let type_name = match index {
+            0 => root.clone(),
+            _ => resolve_type_name(
+                typedef["type"].as_str().unwrap_or_default(),
+                &root.package_name,
+            )?,
+        };
+        let invalid = || format!("Invalid type definition of '{}'", type_name);
+        let names = typedef["fieldnames"].as_array().ok_or_else(invalid)?;
+        let types = typedef["fieldtypes"].as_array().ok_or_else(invalid)?;
+        let lengths = typedef["fieldarraylen"].as_array().ok_or_else(invalid)?;
+
+        let mut text = String::new();
+        for ((name, field_type), length) in names.iter().zip(types).zip(lengths) {
+            let (Some(name), Some(field_type)) = (name.as_str(), field_type.as_str()) else {
+                return Err(invalid());
+            };
+            // -1 for single values, 0 for sequences and the size of fixed arrays otherwise
+            let suffix = match length.as_i64() {
+                Some(0) => "[]".to_string(),
+                Some(length) if length > 0 => format!("[{}]", length),
+                _ => String::new(),
+            };
+            text.push_str(&format!(
+                "{}{} {}\n",
+                msg_field_type(field_type),
+                suffix,
+                name
+            ));
+        }
+        registry.insert(MessageDefinition::parse(type_name, &text)?);
+    }
+    Ok(registry)
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sends operations to the server. Cheap to clone, so that the calls waiting for a response can
+/// run on threads of their own.
+#[derive(Clone)]
+struct Client {
+    name: String,
+    outgoing: Sender<Message>,
+    shared: Arc<Shared>,
+}
+
+impl Client {
+    fn next_id(&self) -> u64 {
+        self.shared.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn send(&self, operation: Value) -> Result<(), String> {
+        if !self.shared.connected.load(Ordering::Relaxed) {
+            return Err(format!("Connection to '{}' is closed", self.name));
+        }
+        self.outgoing
+            .send(Message::Text(operation.to_string()))
+            .map_err(|_| format!("Connection to '{}' is closed", self.name))
+    }
+
+    /// Send a service call, its response arrives in the returned receiver.
+    fn start_call(&self, service: &str, args: Value) -> (String, Result<Receiver<Value>, String>) {
+        let id = format!("splinter_call_{}", self.next_id());
+        let (sender, receiver) = channel();
+        self.shared
+            .pending
+            .lock()
+            .unwrap()
+            .insert(id.clone(), sender);
+
+        let sent = self.send(json!({
+            "op": "call_service",
+            "id": id,
+            "service": service,
+            "args": args,
+        }));
+        (id, sent.map(|()| receiver))
+    }
+
+    /// Wait for the response values of a call sent by `start_call`, until `deadline`.
+    fn finish_call(
+        &self,
+        service: &str,
+        id: &str,
+        receiver: Result<Receiver<Value>, String>,
+        deadline: Instant,
+    ) -> Result<Value, String> {
+        let response = receiver.and_then(|receiver| {
+            receiver
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .map_err(|_| format!("No response from service '{}'", service))
+        });
+        self.shared.pending.lock().unwrap().remove(id);
+        let response = response?;
+
+        if response["result"].as_bool() == Some(false) {
+            return Err(format!(
+                "Service '{}' failed: {}",
+                service, response["values"]
+            ));
+        }
+        Ok(response["values"].clone())
+    }
+
+    /// Call a service through rosbridge and wait for its response values, for at most the
+    /// configured call timeout.
+    fn call(&self, service: &str, args: Value) -> Result<Value, String> {
+        let deadline = Instant::now() + call_timeout();
+        let (id, receiver) = self.start_call(service, args);
+        self.finish_call(service, &id, receiver, deadline)
+    }
+
+    fn rosapi(&self, service: &str, args: Value) -> Result<Value, String> {
+        self.call(&format!("/rosapi/{}", service), args)
+    }
+
+    /// Call a rosapi service once for each of `args` and wait for all the responses, in the
+    /// order of `args`. The calls are all sent before waiting, so they take a single round trip.
+    fn rosapi_batch(&self, service: &str, args: Vec<Value>) -> Vec<Result<Value, String>> {
+        let service = format!("/rosapi/{}", service);
+        let deadline = Instant::now() + call_timeout();
+        let calls = args
+            .into_iter()
+            .map(|args| self.start_call(&service, args))
+            .collect::<Vec<_>>();
+        calls
+            .into_iter()
+            .map(|(id, receiver)| self.finish_call(&service, &id, receiver, deadline))
+            .collect()
+    }
+}
+
+/// A rosapi graph response and its refresh state.
+struct GraphEntry {
+    /// Last response, `None` until the first one arrives.
+    response: Option<Result<Value, String>>,
+    updated: Instant,
+    refreshing: bool,
+}
+
+/// Rosapi graph responses, by query. Queries are answered with the last response straight away
+/// and refreshed on a thread of their own once older than `GRAPH_CACHE_DURATION`, so that a slow
+/// server doesn't block the UI thread.
+#[derive(Default)]
+struct GraphCache {
+    entries: Mutex<HashMap<String, GraphEntry>>,
+    /// Notified when a response arrives.
+    updated: Condvar,
+}
+
+impl GraphCache {
+    /// Last response to the query `key`, `None` if there is none yet. A refresh through `fetch`
+    /// is started when the response is missing or stale.
+    fn get(
+        self: &Arc<Self>,
+        client: &Client,
+        key: &str,
+        fetch: impl FnOnce(&Client) -> Result<Value, String> + Send + 'static,
+    ) -> Option<Result<Value, String>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(key.to_string()).or_insert(GraphEntry {
+            response: None,
+            updated: Instant::now(),
+            refreshing: false,
+        });
+        let stale = entry.response.is_none() || entry.updated.elapsed() >= GRAPH_CACHE_DURATION;
+        if stale && !entry.refreshing {
+            entry.refreshing = true;
+            let cache = self.clone();
+            let client = client.clone();
+            let key = key.to_string();
+            spawn(move || {
+                let response = fetch(&client);
+                if let Some(entry) = cache.entries.lock().unwrap().get_mut(&key) {
+                    entry.response = Some(response);
+                    entry.updated = Instant::now();
+                    entry.refreshing = false;
+                }
+                cache.updated.notify_all();
+            });
+        }
+        entry.response.clone()
+    }
+
+    /// Like `get`, but wait for the first response when there is none yet, for at most the
+    /// configured call timeout.
+    fn wait(
+        self: &Arc<Self>,
+        client: &Client,
+        key: &str,
+        fetch: impl FnOnce(&Client) -> Result<Value, String> + Send + 'static,
+    ) -> Option<Result<Value, String>> {
+        let deadline = Instant::now() + call_timeout();
+        if let Some(response) = self.get(client, key, fetch) {
+            return Some(response);
+        }
+        let mut entries = self.entries.lock().unwrap();
+        loop {
+            if let Some(response) = entries.get(key).and_then(|entry| entry.response.clone()) {
+                return Some(response);
+            }
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout.is_zero() {
+                return None;
+            }
+            entries = self.updated.wait_timeout(entries, timeout).unwrap().0;
+        }
+    }
+}
+
+/// Types of the services `names`, the ones missing from `known` fetched in a single batch and
+/// added to it. Services whose type can't be found are left out.
+fn service_types(
+    client: &Client,
+    known: &Mutex<HashMap<String, InterfaceType>>,
+    names: &[String],
+) -> HashMap<String, InterfaceType> {
+    let mut types = known.lock().unwrap().clone();
+    let missing = names
+        .iter()
+        .filter(|name| !types.contains_key(*name))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let args = missing
+            .iter()
+            .map(|name| json!({ "service": name }))
+            .collect();
+        let responses = client.rosapi_batch("service_type", args);
+        let mut known = known.lock().unwrap();
+        for (name, response) in missing.into_iter().zip(responses) {
+            let type_name = response
+                .ok()
+                .and_then(|values| values["type"].as_str().and_then(service_type_from_rosapi));
+            if let Some(type_name) = type_name {
+                known.insert(name.clone(), type_name.clone());
+                types.insert(name.clone(), type_name);
+            }
+        }
+    }
+    types.retain(|name, _| names.contains(name));
+    types
+}
+
+/// The services of a rosapi response completed by `with_service_types`, with their types.
+fn typed_services(values: &Value) -> Vec<(String, InterfaceType)> {
+    string_list(&values["services"])
+        .into_iter()
+        .zip(string_list(&values["service_types"]))
+        .filter_map(|(name, type_name)| Some((name, InterfaceType::new(&type_name).ok()?)))
+        .collect()
+}
+
+/// Add the types of the services of a rosapi response to it, as `service_types`, leaving out
+/// the services whose type can't be found.
+fn with_service_types(
+    client: &Client,
+    known: &Mutex<HashMap<String, InterfaceType>>,
+    mut values: Value,
+) -> Value {
+    let names = string_list(&values["services"]);
+    let mut types = service_types(client, known, &names);
+    let (names, types): (Vec<String>, Vec<String>) = names
+        .into_iter()
+        .filter_map(|name| {
+            let type_name = types.remove(&name)?.to_string();
+            Some((name, type_name))
+        })
+        .unzip();
+    values["services"] = json!(names);
+    values["service_types"] = json!(types);
+    values
+}
+
+/// Types of the topics of a rosapi `topics` response, by topic.
+fn topic_types(values: &Value) -> HashMap<String, InterfaceType> {
+    string_list(&values["topics"])
+        .into_iter()
+        .zip(string_list(&values["types"]))
+        .filter_map(|(name, type_name)| Some((name, resolve_type_name(&type_name, "").ok()?)))
+        .collect()
+}
+
+/// Convert a service type reported by rosapi, with or without the `srv` category.
+fn service_type_from_rosapi(type_name: &str) -> Option<InterfaceType> {
+    let parts: Vec<&str> = type_name.split('/').collect();
+    match parts.as_slice() {
+        [package, type_name] => InterfaceType::new(&format!("{}/srv/{}", package, type_name)).ok(),
+        _ => InterfaceType::new(type_name).ok(),
+    }
+}
+
+/// Connection to a rosbridge server, speaking the rosbridge v2 JSON protocol.
+///
+/// Graph queries go through the rosapi services and message definitions are fetched from
+/// rosapi as well, so nothing needs to be installed locally.
+pub struct ConnectionRosbridge {
+    client: Client,
+    /// Definitions fetched from rosapi, by message type or service request/response type.
+    registries: Mutex<HashMap<String, Arc<MessageRegistry>>>,
+    /// Service types fetched from rosapi, by service name.
+    service_types: Arc<Mutex<HashMap<String, InterfaceType>>>,
+    /// Rosapi graph responses, by service and arguments.
+    graph_cache: Arc<GraphCache>,
+    #[allow(unused)]
+    thread: JoinHandle<()>,
+}
+
+impl Debug for ConnectionRosbridge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionRosbridge")
+            .field("url", &self.client.name)
+            .field(
+                "connected",
+                &self.client.shared.connected.load(Ordering::Relaxed),
+            )
+            .finish()
+    }
+}
+
+impl ConnectionRosbridge {
+    /// Connect to the rosbridge server at `url`, e.g. `ws://localhost:9090`.
+    pub fn new(url: &str) -> Result<Self, String> {
+        let socket = websocket::connect(url, None)?;
+
+        let shared = Arc::new(Shared::default());
+        shared.connected.store(true, Ordering::Relaxed);
+        let (outgoing, outgoing_receiver) = channel();
+        let thread = {
+            let shared = shared.clone();
+            spawn(move || {
+                websocket::run(socket, outgoing_receiver, |message| {
+                    if let Message::Text(text) = message {
+                        shared.handle_incoming(&text);
+                    }
+                });
+                shared.connected.store(false, Ordering::Relaxed);
+                // Dropping the senders wakes up the pending calls.
+                shared.pending.lock().unwrap().clear();
+            })
+        };
+
+        let connection = Self {
+            client: Client {
+                name: url.to_string(),
+                outgoing,
+                shared,
+            },
+            registries: Mutex::new(HashMap::new()),
+            service_types: Arc::new(Mutex::new(HashMap::new())),
+            graph_cache: Arc::new(GraphCache::default()),
+            thread,
+        };
+        // Start fetching the graph, the topic and node lists are the first views shown
+        let _ = connection.list_topics();
+        let _ = connection.list_nodes();
+        Ok(connection)
+    }
+
+    /// Definitions of `type_name`, fetched from `/rosapi/<details_service>` on first use.
+    fn registry(
+        &self,
+        type_name: &InterfaceType,
+        details_service: &str,
+        interface_type: &str,
+    ) -> Result<Arc<MessageRegistry>, String> {
+        let key = type_name.to_string();
+        if let Some(registry) = self.registries.lock().unwrap().get(&key) {
+            return Ok(registry.clone());
+        }
+        let details = self
+            .client
+            .rosapi(details_service, json!({ "type": interface_type }))?;
+        let registry = Arc::new(registry_from_typedefs(type_name, &details["typedefs"])?);
+        self.registries
+            .lock()
+            .unwrap()
+            .insert(key, registry.clone());
+        Ok(registry)
+    }
+
+    fn message_registry(&self, type_name: &InterfaceType) -> Result<Arc<MessageRegistry>, String> {
+        self.registry(type_name, "message_details", &type_name.to_string())
+    }
+
+    /// Request or response (`part`) type of a service, and its definitions.
+    fn service_registry(
+        &self,
+        service_type: &InterfaceType,
+        part: &str,
+    ) -> Result<(InterfaceType, Arc<MessageRegistry>), String> {
+        let type_name = InterfaceType {
+            package_name: service_type.package_name.clone(),
+            category: service_type.category.clone(),
+            type_name: format!("{}_{}", service_type.type_name, part),
+        };
+        let details_service = format!("service_{}_details", part.to_lowercase());
+        let registry = self.registry(&type_name, &details_service, &service_type.to_string())?;
+        Ok((type_name, registry))
+    }
+
+    fn add_subscriber(
+        &self,
+        topic: &str,
+        subscriber: Subscriber,
+    ) -> Result<SubscriptionHandle, String> {
+        let id = subscriber.id;
+        let type_name = subscriber.type_name.to_string();
+        let subscription_id = format!("splinter_subscribe_{}", topic);
+        let mut subscribers = self.client.shared.subscribers.lock().unwrap();
+        let topic_subscribers = subscribers.entry(topic.to_string()).or_default();
+        // A single rosbridge subscription serves all the local subscribers of a topic.
+        if topic_subscribers.is_empty() {
+            self.client.send(json!({
+                "op": "subscribe",
+                "id": subscription_id,
+                "topic": topic,
+                "type": type_name,
+            }))?;
+        }
+        topic_subscribers.push(subscriber);
+
+        let shared = Arc::downgrade(&self.client.shared);
+        let outgoing = self.client.outgoing.clone();
+        let topic = topic.to_string();
+        Ok(SubscriptionHandle::new(move || {
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            let mut subscribers = shared.subscribers.lock().unwrap();
+            let Some(topic_subscribers) = subscribers.get_mut(&topic) else {
+                return;
+            };
+            topic_subscribers.retain(|subscriber| subscriber.id != id);
+            // The last local subscriber is gone, stop the rosbridge subscription.
+            if topic_subscribers.is_empty() {
+                subscribers.remove(&topic);
+                let operation = json!({
+                    "op": "unsubscribe",
+                    "id": subscription_id,
+                    "topic": topic,
+                });
+                let _ = outgoing.send(Message::Text(operation.to_string()));
+            }
+        }))
+    }
+
+    /// Response of a rosapi graph service from `graph_cache`, passed through `complete` on the
+    /// thread that fetched it. An error until the first response arrives, unless `wait` is set.
+    fn graph_query(
+        &self,
+        service: &str,
+        args: Value,
+        wait: bool,
+        complete: impl FnOnce(&Client, Value) -> Value + Send + 'static,
+    ) -> Result<Value, String> {
+        let key = format!("{} {}", service, args);
+        let query = service.to_string();
+        let fetch = move |client: &Client| -> Result<Value, String> {
+            Ok(complete(client, client.rosapi(&query, args)?))
+        };
+        let response = if wait {
+            self.graph_cache.wait(&self.client, &key, fetch)
+        } else {
+            self.graph_cache.get(&self.client, &key, fetch)
+        };
+        response.unwrap_or_else(|| Err(format!("Waiting for '/rosapi/{}'", service)))
+    }
+
+    fn cached_rosapi(&self, service: &str, args: Value) -> Result<Value, String> {
+        self.graph_query(service, args, false, |_, values| values)
+    }
+
+    /// Details of a node, with the types of its services.
+    fn node_details(&self, node_name: &NodeName) -> Result<Value, String> {
+        let known = self.service_types.clone();
+        self.graph_query(
+            "node_details",
+            json!({ "node": node_name.full_name() }),
+            false,
+            move |client, values| with_service_types(client, &known, values),
+        )
+    }
+
+    /// Attach the type of each topic of `topics` using the topic list.
+    fn with_topic_types(&self, topics: &Value) -> Result<Vec<NamedInterface>, String> {
+        let types = topic_types(&self.cached_rosapi("topics", json!({}))?);
+        Ok(string_list(topics)
+            .into_iter()
+            .filter_map(|name| {
+                let type_name = types.get(&name)?.clone();
+                Some(NamedInterface { name, type_name })
+            })
+            .collect())
+    }
+
+    /// Name of a parameter in the rosapi parameter services.
+    fn parameter_name(node_name: &NodeName, parameter_name: &str) -> String {
+        format!("{}:{}", node_name.full_name(), parameter_name)
+    }
+}
+
+impl Connection for ConnectionRosbridge {
+    fn name(&self) -> &str {
+        &self.client.name
+    }
+
+    fn list_topics(&self) -> Result<Vec<NamedInterface>, String> {
+        let values = self.cached_rosapi("topics", json!({}))?;
+        Ok(string_list(&values["topics"])
+            .into_iter()
+            .zip(string_list(&values["types"]))
+            .filter_map(|(name, type_name)| {
+                Some(NamedInterface {
+                    name,
+                    type_name: resolve_type_name(&type_name, "").ok()?,
+                })
+            })
+            .collect())
+    }
+
+    fn list_nodes(&self) -> Result<Vec<NodeName>, String> {
+        let values = self.cached_rosapi("nodes", json!({}))?;
+        Ok(string_list(&values["nodes"])
+            .iter()
+            .map(|name| NodeName::from_full_name(name))
+            .collect())
+    }
+
+    fn get_topic_type(&self, topic: &str) -> Option<InterfaceType> {
+        let values = self.cached_rosapi("topics", json!({})).ok()?;
+        topic_types(&values).remove(topic)
+    }
+
+    fn subscribe(
+        &mut self,
+        topic: &str,
+        _qos: Option<QosProfile>,
+        callback: impl Fn(GenericMessage, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        // Only the first subscriptions wait, for the topic list requested on connection
+        let topics = self.graph_query("topics", json!({}), true, |_, values| values)?;
+        let type_name = topic_types(&topics)
+            .remove(topic)
+            .ok_or(format!("Failed to get topic type for topic: {}", topic))?;
+        let registry = self.message_registry(&type_name)?;
+        self.add_subscriber(
+            topic,
+            Subscriber {
+                id: self.client.next_id(),
+                type_name,
+                registry,
+                callback: Box::new(callback),
+            },
+        )
+    }
+
+    fn subscribe_raw(
+        &mut self,
+        topic: &str,
+        qos: Option<QosProfile>,
+        callback: impl Fn(Vec<u8>, MessageMetadata) + Send + Sync + 'static,
+    ) -> Result<SubscriptionHandle, String> {
+        // rosbridge only sends JSON, the payload is the decoded message encoded again.
+        self.subscribe(topic, qos, move |message, metadata| {
+            let data = cdr::serialize(&message);
+            let metadata = MessageMetadata {
+                serialized_size: Some(data.len()),
+                ..metadata
+            };
+            callback(data, metadata)
+        })
+    }
+
+    fn create_publisher(
+        &mut self,
+        topic: &str,
+        message_type: &InterfaceType,
+        _qos: Option<QosProfile>,
+    ) -> Result<Box<PublisherFunc>, String> {
+        self.client.send(json!({
+            "op": "advertise",
+            "topic": topic,
+            "type": message_type.to_string(),
+        }))?;
+
+        let topic = topic.to_string();
+        let outgoing = self.client.outgoing.clone();
+        Ok(Box::new(move |message: &GenericMessage| {
+            let operation = json!({
+                "op": "publish",
+                "topic": topic,
+                "msg": to_json(message),
+            });
+            outgoing
+                .send(Message::Text(operation.to_string()))
+                .map_err(|_| "Connection is closed".to_string())?;
+            Ok(Vec::new())
+        }))
+    }
+
+    fn get_publisher_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        self.with_topic_types(&self.node_details(node_name)?["publishing"])
+    }
+
+    fn get_subscription_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        self.with_topic_types(&self.node_details(node_name)?["subscribing"])
+    }
+
+    fn get_client_names_and_types_by_node(
+        &self,
+        _node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        // rosapi doesn't expose service clients.
+        Ok(Vec::new())
+    }
+
+    fn get_service_names_and_types_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> Result<Vec<NamedInterface>, String> {
+        Ok(typed_services(&self.node_details(node_name)?)
+            .into_iter()
+            .map(|(name, type_name)| NamedInterface { name, type_name })
+            .collect())
+    }
+
+    fn get_parameters_by_node(
+        &self,
+        node_name: &NodeName,
+    ) -> PendingCall<HashMap<String, Parameters>> {
+        let prefix = Self::parameter_name(node_name, "");
+        let client = self.client.clone();
+        // rosapi gets one parameter per call, they are sent in a single batch.
+        PendingCall::spawn(move || {
+            let names = client.rosapi("get_param_names", json!({}))?;
+            let names = string_list(&names["names"])
+                .into_iter()
+                .filter(|name| name.starts_with(&prefix))
+                .collect::<Vec<_>>();
+            let args = names
+                .iter()
+                .map(|name| json!({ "name": name, "default": "" }))
+                .collect();
+            let responses = client.rosapi_batch("get_param", args);
+            let mut parameters = HashMap::new();
+            for (name, values) in names.iter().zip(responses) {
+                // A parameter that can't be read is left out, like one that can't be parsed
+                let Ok(values) = values else {
+                    continue;
+                };
+                // The value is sent as a JSON encoded string
+                let value = values["value"]
+                    .as_str()
+                    .and_then(|value| serde_json::from_str(value).ok())
+                    .and_then(|value| Parameters::from_json(&value));
+                if let Some(value) = value {
+                    parameters.insert(name[prefix.len()..].to_string(), value);
+                }
+            }
+            Ok(parameters)
+        })
+    }
+
+    fn set_parameter_by_node(
+        &mut self,
+        node_name: &NodeName,
+        parameter_name: &str,
+        parameter: Parameters,
+    ) -> PendingCall<()> {
+        let name = Self::parameter_name(node_name, parameter_name);
+        let client = self.client.clone();
+        PendingCall::spawn(move || {
+            client
+                .rosapi(
+                    "set_param",
+                    json!({ "name": name, "value": parameter.to_json().to_string() }),
+                )
+                .map(|_| ())
+        })
+    }
+
+    fn get_publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>, String> {
+        let values = self.cached_rosapi("publishers", json!({ "topic": topic }))?;
+        Ok(string_list(&values["publishers"])
+            .iter()
+            .map(|name| NodeName::from_full_name(name).into())
+            .collect())
+    }
+
+    fn get_subscriptions_info_by_topic(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<TopicEndpointInfo>, String> {
+        let values = self.cached_rosapi("subscribers", json!({ "topic": topic }))?;
+        Ok(string_list(&values["subscribers"])
+            .iter()
+            .map(|name| NodeName::from_full_name(name).into())
+            .collect())
+    }
+
+    fn list_services(&self) -> Result<Vec<(String, InterfaceType)>, String> {
+        let known = self.service_types.clone();
+        let values = self.graph_query("services", json!({}), false, move |client, values| {
+            with_service_types(client, &known, values)
+        })?;
+        Ok(typed_services(&values))
+    }
+
+    fn get_service_type(&self, service_name: &str) -> Option<InterfaceType> {
+        service_types(
+            &self.client,
+            &self.service_types,
+            &[service_name.to_string()],
+        )
+        .remove(service_name)
+    }
+
+    fn get_service_request_template(
+        &self,
+        service_type: &InterfaceType,
+    ) -> Result<GenericMessage, String> {
+        let (type_name, registry) = self.service_registry(service_type, "Request")?;
+        from_json(&registry, &type_name, &Value::Null)
+    }
+
+    fn call_service(
+        &self,
+        service_name: &str,
+        service_type: &InterfaceType,
+        request: &GenericMessage,
+    ) -> PendingCall<(GenericMessage, Vec<String>)> {
+        // The definitions are cached, only the first call of a service type waits for rosapi.
+        let (type_name, registry) = match self.service_registry(service_type, "Response") {
+            Ok(response) => response,
+            Err(e) => return PendingCall::ready(Err(e)),
+        };
+        let client = self.client.clone();
+        let service_name = service_name.to_string();
+        let args = to_json(request);
+        PendingCall::spawn(move || {
+            let values = client.call(&service_name, args)?;
+            Ok((from_json(&registry, &type_name, &values)?, Vec::new()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use crate::common::generic_message::{GenericField, SimpleField};
+
+    fn typedef(type_name: &str, fields: &[(&str, &str)]) -> Value {
+        json!({
+            "type": type_name,
+            "fieldnames": fields.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            "fieldtypes": fields.iter().map(|(_, field_type)| *field_type).collect::<Vec<_>>(),
+            "fieldarraylen": fields.iter().map(|_| -1).collect::<Vec<_>>(),
+        })
+    }
+
+    fn service_values(service: &str, args: &Value) -> Value {
+        match service {
+            "/rosapi/topics" => json!({
+                "topics": ["/chatter"],
+                "types": ["std_msgs/msg/String"],
+            }),
+            "/rosapi/message_details" => {
+                json!({ "typedefs": [typedef("std_msgs/String", &[("data", "string")])] })
+            }
+            "/rosapi/service_request_details" => json!({
+                "typedefs": [typedef("example_interfaces/AddTwoInts_Request", &[("a", "int64"), ("b", "int64")])]
+            }),
+            "/rosapi/service_response_details" => json!({
+                "typedefs": [typedef("example_interfaces/AddTwoInts_Response", &[("sum", "int64")])]
+            }),
+            "/add_two_ints" => json!({
+                "sum": args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()
+            }),
+            "/rosapi/node_details" => json!({
+                "publishing": ["/chatter"],
+                "subscribing": [],
+                "services": ["/add_two_ints", "/talker/get_parameters"],
+            }),
+            "/rosapi/service_type" => match args["service"].as_str().unwrap() {
+                "/add_two_ints" => json!({ "type": "example_interfaces/AddTwoInts" }),
+                _ => json!({ "type": "rcl_interfaces/srv/GetParameters" }),
+            },
+            "/rosapi/get_param_names" => {
+                json!({ "names": ["/talker:rate", "/talker:topic", "/talker:broken", "/listener:rate"] })
+            }
+            "/rosapi/get_param" => match args["name"].as_str().unwrap() {
+                "/talker:topic" => json!({ "value": "\"chatter\"" }),
+                // Fails, see `mock_server`
+                "/talker:broken" => Value::Null,
+                _ => json!({ "value": "10" }),
+            },
+            _ => json!({}),
+        }
+    }
+
+    /// Serve a single client, answering the services used by the tests and publishing one
+    /// message on each subscription. Published messages are forwarded to `published`.
+    fn mock_server(published: Sender<Value>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            while let Ok(Message::Text(text)) = socket.read() {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let response = match request["op"].as_str().unwrap() {
+                    "call_service" => {
+                        let service = request["service"].as_str().unwrap();
+                        let values = service_values(service, &request["args"]);
+                        json!({
+                            "op": "service_response",
+                            "id": request["id"],
+                            "service": service,
+                            "result": !values.is_null(),
+                            "values": values,
+                        })
+                    }
+                    "subscribe" => json!({
+                        "op": "publish",
+                        "topic": request["topic"],
+                        "msg": { "data": "hello" },
+                    }),
+                    "publish" => {
+                        let _ = published.send(request["msg"].clone());
+                        continue;
+                    }
+                    _ => continue,
+                };
+                socket.send(Message::Text(response.to_string())).unwrap();
+            }
+        });
+        format!("ws://{}", address)
+    }
+
+    /// Result of `query` once the graph responses it needs arrived.
+    fn eventually<T>(query: impl Fn() -> Result<T, String>) -> T {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        loop {
+            match query() {
+                Ok(result) => return result,
+                Err(e) if Instant::now() > deadline => panic!("{}", e),
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_topics_subscribe_and_publish() {
+        let (published_sender, published) = mpsc::channel();
+        let mut connection = ConnectionRosbridge::new(&mock_server(published_sender)).unwrap();
+
+        let topics = eventually(|| connection.list_topics());
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].name, "/chatter");
+        assert_eq!(
+            topics[0].type_name,
+            InterfaceType::new("std_msgs/msg/String").unwrap()
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let _subscription = connection
+            .subscribe("/chatter", None, move |message, _| {
+                let _ = sender.lock().unwrap().send(message);
+            })
+            .unwrap();
+        let message = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            message["data"],
+            GenericField::Simple(SimpleField::String("hello".to_string()))
+        );
+
+        let publisher = connection
+            .create_publisher(
+                "/chatter",
+                &InterfaceType::new("std_msgs/msg/String").unwrap(),
+                None,
+            )
+            .unwrap();
+        publisher(&message).unwrap();
+        assert_eq!(
+            published.recv_timeout(Duration::from_secs(1)).unwrap(),
+            json!({ "data": "hello" })
+        );
+    }
+
+    #[test]
+    fn test_call_service() {
+        let (published_sender, _published) = mpsc::channel();
+        let connection = ConnectionRosbridge::new(&mock_server(published_sender)).unwrap();
+        let service_type = InterfaceType::new("example_interfaces/srv/AddTwoInts").unwrap();
+
+        let request = connection
+            .get_service_request_template(&service_type)
+            .unwrap();
+        assert_eq!(request["a"], GenericField::Simple(SimpleField::Int64(0)));
+
+        let request = from_json(
+            &connection
+                .service_registry(&service_type, "Request")
+                .unwrap()
+                .1,
+            request.type_name(),
+            &json!({ "a": 2, "b": 3 }),
+        )
+        .unwrap();
+        let (response, warnings) = connection
+            .call_service("/add_two_ints", &service_type, &request)
+            .wait(Duration::from_secs(1))
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(response["sum"], GenericField::Simple(SimpleField::Int64(5)));
+    }
+
+    #[test]
+    fn test_node_services_and_parameters() {
+        let (published_sender, _published) = mpsc::channel();
+        let connection = ConnectionRosbridge::new(&mock_server(published_sender)).unwrap();
+        let node = NodeName::from_full_name("/talker");
+
+        // Answered from the cache, once fetched in the background
+        assert!(connection
+            .get_service_names_and_types_by_node(&node)
+            .is_err());
+        let services = eventually(|| connection.get_service_names_and_types_by_node(&node));
+        assert_eq!(services.len(), 2);
+        assert_eq!(
+            services[0].type_name,
+            InterfaceType::new("example_interfaces/srv/AddTwoInts").unwrap()
+        );
+        assert_eq!(
+            services[1].type_name,
+            InterfaceType::new("rcl_interfaces/srv/GetParameters").unwrap()
+        );
+        // Known types are not fetched again
+        assert_eq!(connection.service_types.lock().unwrap().len(), 2);
+        assert_eq!(
+            connection.get_service_type("/add_two_ints"),
+            Some(services[0].type_name.clone())
+        );
+
+        // The parameter that can't be read is left out
+        let parameters = connection
+            .get_parameters_by_node(&node)
+            .wait(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(parameters.len(), 2);
+        assert_eq!(parameters["rate"], Parameters::Integer(10));
+        assert_eq!(
+            parameters["topic"],
+            Parameters::String("chatter".to_string())
+        );
+    }
+
+    #[test]
+    fn test_registry_from_rosapi_typedefs() {
+        let root = InterfaceType::new("geometry_msgs/msg/Polygon").unwrap();
+        let typedefs = json!([
+            {
+                "type": "geometry_msgs/Polygon",
+                "fieldnames": ["points"],
+                "fieldtypes": ["geometry_msgs/Point32"],
+                "fieldarraylen": [0],
+            },
+            {
+                "type": "geometry_msgs/Point32",
+                "fieldnames": ["x", "y", "z"],
+                "fieldtypes": ["float", "float", "float"],
+                "fieldarraylen": [-1, -1, -1],
+            },
+        ]);
+        let registry = registry_from_typedefs(&root, &typedefs).unwrap();
+        assert!(registry.contains(&InterfaceType::new("geometry_msgs/msg/Point32").unwrap()));
+
+        let message = from_json(
+            &registry,
+            &root,
+            &json!({ "points": [{ "x": 1.0, "y": 2.0, "z": 3.0 }] }),
+        )
+        .unwrap();
+        assert_eq!(
+            to_json(&message),
+            json!({ "points": [{ "x": 1.0, "y": 2.0, "z": 3.0 }] })
+        );
+    }
+}
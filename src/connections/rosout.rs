@@ -0,0 +1,162 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use crate::common::{generic_message::GenericMessage, json_message::to_json};
+
+use super::NodeName;
+
+/// Topic on which the nodes publish their log messages.
+pub const ROSOUT: &str = "/rosout";
+
+/// Severity of a log message, `rcl_interfaces/msg/Log` levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+        LogLevel::Fatal,
+    ];
+
+    /// Level of a `Log` message, levels in between round down.
+    pub fn from_level(level: u64) -> Self {
+        match level {
+            0..=19 => LogLevel::Debug,
+            20..=29 => LogLevel::Info,
+            30..=39 => LogLevel::Warn,
+            40..=49 => LogLevel::Error,
+            _ => LogLevel::Fatal,
+        }
+    }
+
+    /// Severity of the level in `Log` messages and logger services.
+    pub fn severity(&self) -> u32 {
+        match self {
+            LogLevel::Debug => 10,
+            LogLevel::Info => 20,
+            LogLevel::Warn => 30,
+            LogLevel::Error => 40,
+            LogLevel::Fatal => 50,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+            LogLevel::Fatal => "FATAL",
+        }
+    }
+}
+
+/// A `rcl_interfaces/msg/Log` message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub level: LogLevel,
+    /// Name of the logger, `ns.camera` for the node `/ns/camera`.
+    pub name: String,
+    pub msg: String,
+    pub file: String,
+    pub function: String,
+    pub line: u32,
+}
+
+/// Name of the logger of `node`: its full name with dots, without the leading one.
+pub fn logger_name(node: &NodeName) -> String {
+    node.full_name().trim_start_matches('/').replace('/', ".")
+}
+
+/// Read a `rcl_interfaces/msg/Log` message.
+pub fn log_entry(message: &GenericMessage) -> Result<LogEntry, String> {
+    let log = to_json(message);
+    let string = |name: &str| {
+        log.get(name)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("Invalid Log message: missing {}", name))
+    };
+    let stamp = log.get("stamp");
+    let sec = stamp.and_then(|s| s.get("sec")).and_then(Value::as_u64);
+    let nanosec = stamp.and_then(|s| s.get("nanosec")).and_then(Value::as_u64);
+    Ok(LogEntry {
+        time: UNIX_EPOCH
+            + Duration::from_secs(sec.unwrap_or_default())
+            + Duration::from_nanos(nanosec.unwrap_or_default()),
+        level: LogLevel::from_level(log.get("level").and_then(Value::as_u64).unwrap_or_default()),
+        name: string("name")?,
+        msg: string("msg")?,
+        file: string("file")?,
+        function: string("function")?,
+        line: log
+            .get("line")
+            .and_then(Value::as_u64)
+            .and_then(|line| u32::try_from(line).ok())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::{
+        generic_message::InterfaceType, json_message::from_json,
+        message_definition::MessageRegistry,
+    };
+
+    const LOG: &str = "\
+builtin_interfaces/Time stamp
+uint8 level
+string name
+string msg
+string file
+string function
+uint32 line
+================================================================================
+MSG: builtin_interfaces/Time
+int32 sec
+uint32 nanosec";
+
+    #[test]
+    fn test_log_entry() {
+        let type_name = InterfaceType::new("rcl_interfaces/msg/Log").unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, LOG).unwrap();
+        let message = from_json(
+            &registry,
+            &type_name,
+            &json!({
+                "stamp": { "sec": 12, "nanosec": 500000000 },
+                "level": 30,
+                "name": "ns.camera",
+                "msg": "Dropped a frame",
+                "file": "camera.cpp",
+                "function": "on_frame",
+                "line": 42,
+            }),
+        )
+        .unwrap();
+
+        let entry = log_entry(&message).unwrap();
+        assert_eq!(entry.time, UNIX_EPOCH + Duration::from_millis(12500));
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.name, logger_name(&NodeName::new("/ns", "camera")));
+        assert_eq!(entry.line, 42);
+        assert!(LogLevel::Warn > LogLevel::Info);
+        assert_eq!(LogLevel::from_level(10), LogLevel::Debug);
+    }
+}
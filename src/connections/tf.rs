@@ -0,0 +1,439 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Value;
+
+use crate::common::{generic_message::GenericMessage, json_message::to_json};
+
+/// Topics on which the transforms between frames are published, `tf2_msgs/msg/TFMessage`.
+pub const TF: &str = "/tf";
+pub const TF_STATIC: &str = "/tf_static";
+
+/// Receive times kept per frame to estimate its publishing rate.
+const RATE_SAMPLES: usize = 50;
+
+/// Rigid transform, the rotation as a unit quaternion `[x, y, z, w]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: [f64; 3],
+    pub rotation: [f64; 4],
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        translation: [0.0; 3],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+    };
+
+    fn rotate(&self, v: [f64; 3]) -> [f64; 3] {
+        // v + 2w(u × v) + 2u × (u × v)
+        let [x, y, z, w] = self.rotation;
+        let u = [x, y, z];
+        let uv = cross(u, v);
+        let uuv = cross(u, uv);
+        [0, 1, 2].map(|i| v[i] + 2.0 * (w * uv[i] + uuv[i]))
+    }
+
+    /// The transform `self * other`: `other` expressed in the parent frame of `self`.
+    pub fn compose(&self, other: &Transform) -> Transform {
+        let [x1, y1, z1, w1] = self.rotation;
+        let [x2, y2, z2, w2] = other.rotation;
+        let rotated = self.rotate(other.translation);
+        Transform {
+            translation: [0, 1, 2].map(|i| self.translation[i] + rotated[i]),
+            rotation: [
+                w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+                w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+                w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+                w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+            ],
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let [x, y, z, w] = self.rotation;
+        let conjugate = Transform {
+            translation: [0.0; 3],
+            rotation: [-x, -y, -z, w],
+        };
+        let translation = conjugate.rotate(self.translation);
+        Transform {
+            translation: translation.map(|t| -t),
+            rotation: conjugate.rotation,
+        }
+    }
+
+    /// Roll, pitch and yaw of the rotation in radians, applied in this order around the fixed
+    /// axes.
+    pub fn rpy(&self) -> [f64; 3] {
+        let [x, y, z, w] = self.rotation;
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+        [roll, pitch, yaw]
+    }
+}
+
+/// Transform of a `geometry_msgs/msg/TransformStamped`, from `child` to `parent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StampedTransform {
+    pub parent: String,
+    pub child: String,
+    pub stamp: SystemTime,
+    pub transform: Transform,
+}
+
+/// Frame names without the leading slash of ROS 1, which tf2 ignores.
+fn frame_id(name: &str) -> String {
+    name.trim_start_matches('/').to_string()
+}
+
+/// Transforms of a `tf2_msgs/msg/TFMessage`.
+pub fn tf_message(message: &GenericMessage) -> Result<Vec<StampedTransform>, String> {
+    let message = to_json(message);
+    let Some(transforms) = message.get("transforms").and_then(Value::as_array) else {
+        return Err("Invalid TFMessage".to_string());
+    };
+    transforms
+        .iter()
+        .map(|transform| {
+            let invalid = || "Invalid transform in TFMessage".to_string();
+            let header = transform.get("header").ok_or_else(invalid)?;
+            let string = |value: &Value, name: &str| {
+                value
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .map(frame_id)
+                    .ok_or_else(invalid)
+            };
+            let number = |value: &Value, name: &str| {
+                value.get(name).and_then(Value::as_f64).ok_or_else(invalid)
+            };
+            let stamp = header.get("stamp").ok_or_else(invalid)?;
+            let values = transform.get("transform").ok_or_else(invalid)?;
+            let translation = values.get("translation").ok_or_else(invalid)?;
+            let rotation = values.get("rotation").ok_or_else(invalid)?;
+            Ok(StampedTransform {
+                parent: string(header, "frame_id")?,
+                child: string(transform, "child_frame_id")?,
+                stamp: UNIX_EPOCH
+                    + Duration::from_secs(number(stamp, "sec")?.max(0.0) as u64)
+                    + Duration::from_nanos(number(stamp, "nanosec")? as u64),
+                transform: Transform {
+                    translation: [
+                        number(translation, "x")?,
+                        number(translation, "y")?,
+                        number(translation, "z")?,
+                    ],
+                    rotation: [
+                        number(rotation, "x")?,
+                        number(rotation, "y")?,
+                        number(rotation, "z")?,
+                        number(rotation, "w")?,
+                    ],
+                },
+            })
+        })
+        .collect()
+}
+
+/// Last transform received for a frame, from its parent.
+#[derive(Debug, Clone)]
+pub struct FrameInfo {
+    pub parent: String,
+    pub transform: Transform,
+    /// Published on `/tf_static`, these don't go stale.
+    pub is_static: bool,
+    /// Receive times of the last transforms.
+    received: VecDeque<SystemTime>,
+}
+
+impl FrameInfo {
+    pub fn last_received(&self) -> Option<SystemTime> {
+        self.received.back().copied()
+    }
+
+    /// Publishing rate in Hz over the last transforms, `None` until two were received.
+    pub fn rate(&self) -> Option<f64> {
+        let first = self.received.front()?;
+        let last = self.received.back()?;
+        let span = last.duration_since(*first).ok()?.as_secs_f64();
+        (span > 0.0).then(|| (self.received.len() - 1) as f64 / span)
+    }
+}
+
+/// Frames by name, each with the transform from its parent, like the buffer of tf2 without the
+/// history.
+#[derive(Debug, Clone, Default)]
+pub struct TfTree {
+    frames: HashMap<String, FrameInfo>,
+}
+
+impl TfTree {
+    pub fn insert(&mut self, transform: StampedTransform, is_static: bool, received: SystemTime) {
+        let frame = self
+            .frames
+            .entry(transform.child)
+            .or_insert_with(|| FrameInfo {
+                parent: transform.parent.clone(),
+                transform: transform.transform,
+                is_static,
+                received: VecDeque::new(),
+            });
+        frame.parent = transform.parent;
+        frame.transform = transform.transform;
+        frame.is_static = is_static;
+        frame.received.push_back(received);
+        while frame.received.len() > RATE_SAMPLES {
+            frame.received.pop_front();
+        }
+    }
+
+    pub fn frame(&self, name: &str) -> Option<&FrameInfo> {
+        self.frames.get(name)
+    }
+
+    /// Names of all the frames, the parents without a transform included.
+    pub fn frame_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .frames
+            .iter()
+            .flat_map(|(child, frame)| [child.clone(), frame.parent.clone()])
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Frames without a parent, sorted: more than one means the tree is broken in pieces.
+    pub fn roots(&self) -> Vec<String> {
+        self.frame_names()
+            .into_iter()
+            .filter(|name| !self.frames.contains_key(name))
+            .collect()
+    }
+
+    /// Children of `parent`, sorted.
+    pub fn children(&self, parent: &str) -> Vec<String> {
+        let mut children: Vec<String> = self
+            .frames
+            .iter()
+            .filter(|(_, frame)| frame.parent == parent)
+            .map(|(child, _)| child.clone())
+            .collect();
+        children.sort();
+        children
+    }
+
+    /// Frames from each root down, with their depth in the tree.
+    pub fn depth_first(&self) -> Vec<(usize, String)> {
+        let mut rows = Vec::new();
+        let mut stack: Vec<(usize, String)> = self
+            .roots()
+            .into_iter()
+            .rev()
+            .map(|root| (0, root))
+            .collect();
+        while let Some((depth, name)) = stack.pop() {
+            for child in self.children(&name).into_iter().rev() {
+                stack.push((depth + 1, child));
+            }
+            rows.push((depth, name));
+        }
+        rows
+    }
+
+    /// Root of the tree of `frame`, and the transform of `frame` in it.
+    fn to_root(&self, frame: &str) -> Result<(String, Transform), String> {
+        let mut name = frame.to_string();
+        let mut transform = Transform::IDENTITY;
+        let mut visited = HashSet::new();
+        while let Some(info) = self.frames.get(&name) {
+            if !visited.insert(name.clone()) {
+                return Err(format!("Loop in the tree through '{}'", name));
+            }
+            transform = info.transform.compose(&transform);
+            name = info.parent.clone();
+        }
+        Ok((name, transform))
+    }
+
+    /// Transform of `source` in the `target` frame, like `lookupTransform(target, source)`.
+    pub fn lookup(&self, target: &str, source: &str) -> Result<Transform, String> {
+        for frame in [target, source] {
+            if !self.frames.contains_key(frame) && self.children(frame).is_empty() {
+                return Err(format!("Unknown frame '{}'", frame));
+            }
+        }
+        let (target_root, target_transform) = self.to_root(target)?;
+        let (source_root, source_transform) = self.to_root(source)?;
+        if target_root != source_root {
+            return Err(format!(
+                "'{}' and '{}' are not connected: their trees start from '{}' and '{}'",
+                target, source, target_root, source_root
+            ));
+        }
+        Ok(target_transform.inverse().compose(&source_transform))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::common::{
+        generic_message::InterfaceType, json_message::from_json,
+        message_definition::MessageRegistry,
+    };
+
+    const TF_MESSAGE: &str = "\
+geometry_msgs/TransformStamped[] transforms
+================================================================================
+MSG: geometry_msgs/TransformStamped
+std_msgs/Header header
+string child_frame_id
+Transform transform
+================================================================================
+MSG: std_msgs/Header
+builtin_interfaces/Time stamp
+string frame_id
+================================================================================
+MSG: builtin_interfaces/Time
+int32 sec
+uint32 nanosec
+================================================================================
+MSG: geometry_msgs/Transform
+Vector3 translation
+Quaternion rotation
+================================================================================
+MSG: geometry_msgs/Vector3
+float64 x
+float64 y
+float64 z
+================================================================================
+MSG: geometry_msgs/Quaternion
+float64 x
+float64 y
+float64 z
+float64 w";
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert!(
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn transform(parent: &str, child: &str, translation: [f64; 3], yaw: f64) -> StampedTransform {
+        StampedTransform {
+            parent: parent.to_string(),
+            child: child.to_string(),
+            stamp: UNIX_EPOCH,
+            transform: Transform {
+                translation,
+                rotation: [0.0, 0.0, (yaw / 2.0).sin(), (yaw / 2.0).cos()],
+            },
+        }
+    }
+
+    #[test]
+    fn test_tf_message() {
+        let type_name = InterfaceType::new("tf2_msgs/msg/TFMessage").unwrap();
+        let registry = MessageRegistry::from_schema(&type_name, TF_MESSAGE).unwrap();
+        let message = from_json(
+            &registry,
+            &type_name,
+            &json!({ "transforms": [{
+                "header": { "stamp": { "sec": 3, "nanosec": 0 }, "frame_id": "/odom" },
+                "child_frame_id": "base_link",
+                "transform": {
+                    "translation": { "x": 1.0, "y": 2.0, "z": 0.0 },
+                    "rotation": { "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 },
+                },
+            }] }),
+        )
+        .unwrap();
+
+        let transforms = tf_message(&message).unwrap();
+        assert_eq!(transforms.len(), 1);
+        assert_eq!(transforms[0].parent, "odom");
+        assert_eq!(transforms[0].child, "base_link");
+        assert_eq!(transforms[0].stamp, UNIX_EPOCH + Duration::from_secs(3));
+        assert_eq!(transforms[0].transform.translation, [1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let mut tree = TfTree::default();
+        tree.insert(
+            transform("map", "odom", [1.0, 0.0, 0.0], 0.0),
+            true,
+            UNIX_EPOCH,
+        );
+        tree.insert(
+            transform("odom", "base_link", [0.0, 2.0, 0.0], quarter),
+            false,
+            UNIX_EPOCH,
+        );
+        tree.insert(
+            transform("base_link", "laser", [1.0, 0.0, 0.0], 0.0),
+            true,
+            UNIX_EPOCH,
+        );
+        tree.insert(
+            transform("world", "gps", [0.0, 0.0, 0.0], 0.0),
+            false,
+            UNIX_EPOCH,
+        );
+
+        // The laser is 1m in front of the base, which faces +y
+        let laser = tree.lookup("map", "laser").unwrap();
+        assert_close(&laser.translation, &[1.0, 3.0, 0.0]);
+        assert_close(&laser.rpy(), &[0.0, 0.0, quarter]);
+        let map = tree.lookup("laser", "map").unwrap();
+        assert_close(&map.translation, &[-3.0, 1.0, 0.0]);
+        assert_close(&map.rpy(), &[0.0, 0.0, -quarter]);
+
+        assert_eq!(tree.roots(), vec!["map".to_string(), "world".to_string()]);
+        assert_eq!(tree.children("odom"), vec!["base_link".to_string()]);
+        assert_eq!(
+            tree.depth_first()
+                .iter()
+                .map(|(depth, name)| format!("{}{}", depth, name))
+                .collect::<Vec<_>>(),
+            vec!["0map", "1odom", "2base_link", "3laser", "0world", "1gps"]
+        );
+        assert!(tree.lookup("map", "gps").is_err());
+        assert!(tree.lookup("map", "camera").is_err());
+    }
+
+    #[test]
+    fn test_rate() {
+        let mut tree = TfTree::default();
+        for i in 0..11 {
+            tree.insert(
+                transform("odom", "base_link", [0.0; 3], 0.0),
+                false,
+                UNIX_EPOCH + Duration::from_millis(100 * i),
+            );
+        }
+        let rate = tree.frame("base_link").unwrap().rate().unwrap();
+        assert!((rate - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,79 @@
+// Plain `ws://` client socket shared by the bridge connections. The socket is owned by a single
+// thread which alternates between sending the queued messages and reading incoming ones.
+
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::HeaderValue;
+use tungstenite::{Message, WebSocket};
+
+/// Read timeout of the socket, bounds the latency of outgoing messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Open a WebSocket to `url`, requesting `subprotocol` if given.
+pub(crate) fn connect(
+    url: &str,
+    subprotocol: Option<&str>,
+) -> Result<WebSocket<TcpStream>, String> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if let Some(subprotocol) = subprotocol {
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_str(subprotocol).map_err(|e| e.to_string())?,
+        );
+    }
+    let host = request
+        .uri()
+        .host()
+        .ok_or_else(|| format!("Missing host in '{}'", url))?
+        .to_string();
+    let port = request.uri().port_u16().unwrap_or(80);
+
+    let stream = TcpStream::connect((host.as_str(), port))
+        .map_err(|e| format!("Failed to connect to '{}': {}", url, e))?;
+    let (socket, _) = tungstenite::client(request, stream)
+        .map_err(|e| format!("WebSocket handshake with '{}' failed: {}", url, e))?;
+    socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(|e| format!("Failed to configure socket: {}", e))?;
+    Ok(socket)
+}
+
+/// Send the messages queued in `outgoing` and hand the incoming ones to `on_message`, until the
+/// socket is closed or all the senders of `outgoing` are dropped.
+pub(crate) fn run(
+    mut socket: WebSocket<TcpStream>,
+    outgoing: Receiver<Message>,
+    mut on_message: impl FnMut(Message),
+) {
+    loop {
+        loop {
+            match outgoing.try_recv() {
+                Ok(message) => {
+                    if socket.send(message).is_err() {
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    return;
+                }
+            }
+        }
+
+        match socket.read() {
+            Ok(Message::Close(_)) => return,
+            Ok(message) => on_message(message),
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+    }
+}
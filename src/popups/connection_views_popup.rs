@@ -0,0 +1,186 @@
+use std::{cell::RefCell, rc::Rc};
+
+use indexmap::IndexMap;
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+use ratatui::widgets::{Block, BorderType, Widget};
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use crate::{
+    common::event::Event,
+    popups::TuiPopup,
+    views::{
+        action_list::ActionListState, node_list::NodeListState,
+        parameter_events::ParameterEventsState, rosout::RosoutState, tf_tree::TfTreeState,
+        topic_list::TopicListState, ConnectionInfo, FromConnection, TuiView,
+    },
+    widgets::select_view_widget::SelectViewWidget,
+};
+
+// TODO: Make this configurable via plugins
+type ConnectionFactoryClosure = dyn Fn(ConnectionInfo) -> Rc<RefCell<dyn TuiView>> + Send + Sync;
+
+fn new_view(view: impl TuiView + 'static) -> Rc<RefCell<dyn TuiView>> {
+    Rc::new(RefCell::new(view))
+}
+
+pub static FROM_CONNECTION_FACTORIES: once_cell::sync::Lazy<
+    IndexMap<&'static str, Box<ConnectionFactoryClosure>>,
+> = once_cell::sync::Lazy::new(|| {
+    let mut m = IndexMap::new();
+    m.insert(
+        "topic_list",
+        Box::new(|info: ConnectionInfo| new_view(TopicListState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m.insert(
+        "node_list",
+        Box::new(|info: ConnectionInfo| new_view(NodeListState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m.insert(
+        "action_list",
+        Box::new(|info: ConnectionInfo| new_view(ActionListState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m.insert(
+        "parameter_events",
+        Box::new(|info: ConnectionInfo| new_view(ParameterEventsState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m.insert(
+        "rosout",
+        Box::new(|info: ConnectionInfo| new_view(RosoutState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m.insert(
+        "tf_tree",
+        Box::new(|info: ConnectionInfo| new_view(TfTreeState::from_connection(info)))
+            as Box<ConnectionFactoryClosure>,
+    );
+    m
+});
+
+/// Lists the views showing a whole connection, to open one for the connection of the active tab.
+pub struct ConnectionViewsPopupState {
+    connection: ConnectionInfo,
+    selected: usize,
+
+    needs_redraw: bool,
+}
+
+impl ConnectionViewsPopupState {
+    pub fn new(connection: ConnectionInfo) -> Self {
+        Self {
+            connection,
+            selected: 0,
+            needs_redraw: true,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return event;
+            }
+            match key_event.code {
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.selected = self.selected.saturating_sub(1);
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    if self.selected < FROM_CONNECTION_FACTORIES.len() - 1 {
+                        self.selected += 1;
+                    }
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Enter => {
+                    let (_, factory) = FROM_CONNECTION_FACTORIES
+                        .get_index(self.selected)
+                        .expect("Factory index out of bounds");
+                    return Event::NewView(factory(self.connection.clone()));
+                }
+                KeyCode::Esc => {
+                    return Event::ClosePopup;
+                }
+                _ => {}
+            }
+        }
+        event
+    }
+
+    pub fn needs_redraw(&mut self) -> bool {
+        if self.needs_redraw {
+            self.needs_redraw = false;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl TuiPopup for ConnectionViewsPopupState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        ConnectionViewsPopupState::handle_event(self, event)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        ConnectionViewsPopupState::needs_redraw(self)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        ConnectionViewsPopupState::render(self, area, buf);
+    }
+}
+
+impl ConnectionViewsPopupState {
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let views: Vec<(usize, String)> = FROM_CONNECTION_FACTORIES
+            .keys()
+            .enumerate()
+            .map(|(i, k)| (i, format!("New {}", k)))
+            .collect();
+        let select_view_widget = SelectViewWidget::new(&views)
+            .with_selection(self.selected)
+            .with_new_option(true)
+            .block(
+                Block::default()
+                    .border_type(BorderType::Rounded)
+                    .title("Select Connection View")
+                    .borders(ratatui::widgets::Borders::ALL),
+            );
+        select_view_widget.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{key, mock_connection, PARAMETER_EVENTS_CONFIG};
+
+    #[test]
+    fn test_open_view() {
+        let connection = mock_connection(PARAMETER_EVENTS_CONFIG);
+        let mut popup = ConnectionViewsPopupState::new(ConnectionInfo { connection });
+
+        for _ in 0..FROM_CONNECTION_FACTORIES.len() + 2 {
+            assert!(matches!(
+                popup.handle_event(key(KeyCode::Down)),
+                Event::None
+            ));
+        }
+        // The selection stops at the last view
+        match popup.handle_event(key(KeyCode::Enter)) {
+            Event::NewView(view) => assert_eq!(view.borrow().name(), "TF Tree"),
+            _ => panic!("Expected a new view"),
+        }
+        assert!(matches!(
+            popup.handle_event(key(KeyCode::Esc)),
+            Event::ClosePopup
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    common::{event::Event, style::HEADER_STYLE},
+    popups::TuiPopup,
+};
+
+type SubmitClosure = dyn FnMut(&str) -> Result<Event, String>;
+
+/// Single-line text prompt. On Enter the input is handed to `on_submit`: its event is forwarded
+/// to the app on success, its error is shown below the input and the prompt stays open.
+pub struct InputPopup {
+    title: String,
+    hint: String,
+    input: String,
+    error: Option<String>,
+    on_submit: Box<SubmitClosure>,
+
+    needs_redraw: bool,
+}
+
+impl InputPopup {
+    pub fn new(
+        title: impl Into<String>,
+        hint: impl Into<String>,
+        on_submit: impl FnMut(&str) -> Result<Event, String> + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            hint: hint.into(),
+            input: String::new(),
+            error: None,
+            on_submit: Box::new(on_submit),
+            needs_redraw: true,
+        }
+    }
+
+    /// Pre-fill the input.
+    pub fn with_input(mut self, input: impl Into<String>) -> Self {
+        self.input = input.into();
+        self
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return Event::None;
+            }
+            self.needs_redraw = true;
+            match key_event.code {
+                KeyCode::Esc => return Event::ClosePopup,
+                KeyCode::Enter => match (self.on_submit)(self.input.trim()) {
+                    Ok(event) => return event,
+                    Err(e) => self.error = Some(e),
+                },
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) => self.input.push(c),
+                _ => {}
+            }
+            return Event::None;
+        }
+        event
+    }
+
+    pub fn needs_redraw(&mut self) -> bool {
+        if self.needs_redraw {
+            self.needs_redraw = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let mut lines = vec![
+            Line::raw(self.hint.clone()),
+            Line::raw(""),
+            Line::from(vec![
+                Span::raw("> "),
+                Span::raw(self.input.clone()),
+                Span::styled("█", HEADER_STYLE),
+            ]),
+        ];
+        if let Some(error) = &self.error {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(error.clone(), Style::new().fg(Color::Red)));
+        }
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(self.title.clone())
+                    .border_style(HEADER_STYLE)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .render(area, buf);
+    }
+}
+
+impl TuiPopup for InputPopup {
+    fn handle_event(&mut self, event: Event) -> Event {
+        InputPopup::handle_event(self, event)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        InputPopup::needs_redraw(self)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        InputPopup::render(self, area, buf);
+    }
+}
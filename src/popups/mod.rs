@@ -1,6 +1,10 @@
+pub mod connection_views_popup;
+pub mod input_popup;
+pub mod new_connection_popup;
 pub mod new_field_popup;
 pub mod new_node_popup;
 pub mod new_topic_popup;
+pub mod qos_popup;
 pub mod text_popup;
 
 use ratatui::{buffer::Buffer, layout::Rect};
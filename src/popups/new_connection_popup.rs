@@ -0,0 +1,197 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+use ratatui::widgets::{Block, BorderType, Widget};
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use crate::{
+    common::event::Event,
+    connections::{
+        foxglove::ConnectionFoxglove, mcap::ConnectionMcap, mock::ConnectionMock,
+        ros2::ConnectionROS2, rosbag2::ConnectionRosbag2, rosbridge::ConnectionRosbridge,
+        ConnectionType,
+    },
+    popups::{input_popup::InputPopup, TuiPopup},
+    views::ConnectionInfo,
+    widgets::select_view_widget::SelectViewWidget,
+};
+
+type OpenConnectionFn = fn(&str) -> Result<ConnectionType, String>;
+
+struct Backend {
+    name: &'static str,
+    hint: &'static str,
+    default_input: fn() -> String,
+    open: OpenConnectionFn,
+}
+
+const BACKENDS: &[Backend] = &[
+    Backend {
+        name: "ROS2",
+        hint: "ROS_DOMAIN_ID of the ROS2 graph:",
+        default_input: || std::env::var("ROS_DOMAIN_ID").unwrap_or("0".to_string()),
+        open: |input| {
+            let domain_id = input
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a valid domain id", input))?;
+            ConnectionROS2::with_domain_id(domain_id).map(ConnectionType::ROS2)
+        },
+    },
+    Backend {
+        name: "MCAP file",
+        hint: "Path of the MCAP file to replay:",
+        default_input: String::new,
+        open: |input| ConnectionMcap::open(Path::new(input)).map(ConnectionType::Mcap),
+    },
+    Backend {
+        name: "rosbag2 bag",
+        hint: "Directory of the rosbag2 sqlite3 bag to replay:",
+        default_input: String::new,
+        open: |input| ConnectionRosbag2::open(Path::new(input)).map(ConnectionType::Rosbag2),
+    },
+    Backend {
+        name: "rosbridge",
+        hint: "URL of the rosbridge server:",
+        default_input: || "ws://localhost:9090".to_string(),
+        open: |input| ConnectionRosbridge::new(input).map(ConnectionType::Rosbridge),
+    },
+    Backend {
+        name: "Foxglove WebSocket",
+        hint: "URL of the Foxglove WebSocket server:",
+        default_input: || "ws://localhost:8765".to_string(),
+        open: |input| ConnectionFoxglove::new(input).map(ConnectionType::Foxglove),
+    },
+    Backend {
+        name: "Mock graph",
+        hint: "Path of the YAML configuration of the mock graph:",
+        default_input: String::new,
+        open: |input| ConnectionMock::open(Path::new(input)).map(ConnectionType::Mock),
+    },
+];
+
+/// Select a connection backend, then prompt for its parameter (domain id, path or URL).
+pub struct NewConnectionPopupState {
+    selected: usize,
+    input: Option<InputPopup>,
+
+    needs_redraw: bool,
+}
+
+impl Default for NewConnectionPopupState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NewConnectionPopupState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            input: None,
+            needs_redraw: true,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> Event {
+        if let Some(input) = &mut self.input {
+            return match input.handle_event(event) {
+                // Go back to the backend selection
+                Event::ClosePopup => {
+                    self.input = None;
+                    self.needs_redraw = true;
+                    Event::None
+                }
+                event => event,
+            };
+        }
+
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return event;
+            }
+            match key_event.code {
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.selected = self.selected.saturating_sub(1);
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.selected = (self.selected + 1).min(BACKENDS.len() - 1);
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Enter => {
+                    let backend = &BACKENDS[self.selected];
+                    let open = backend.open;
+                    self.input = Some(
+                        InputPopup::new(
+                            format!("New {} Connection", backend.name),
+                            backend.hint,
+                            move |input| {
+                                let connection = open(input)?;
+                                Ok(Event::NewConnection(ConnectionInfo {
+                                    connection: Rc::new(RefCell::new(connection)),
+                                }))
+                            },
+                        )
+                        .with_input((backend.default_input)()),
+                    );
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Esc => {
+                    return Event::ClosePopup;
+                }
+                _ => {}
+            }
+        }
+        event
+    }
+
+    pub fn needs_redraw(&mut self) -> bool {
+        let input_needs_redraw = self.input.as_mut().is_some_and(|i| i.needs_redraw());
+        if self.needs_redraw || input_needs_redraw {
+            self.needs_redraw = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(input) = &self.input {
+            input.render(area, buf);
+            return;
+        }
+
+        let backends: Vec<(usize, String)> = BACKENDS
+            .iter()
+            .enumerate()
+            .map(|(i, backend)| (i, backend.name.to_string()))
+            .collect();
+        SelectViewWidget::new(&backends)
+            .with_selection(self.selected)
+            .block(
+                Block::default()
+                    .border_type(BorderType::Rounded)
+                    .title("New Connection")
+                    .borders(ratatui::widgets::Borders::ALL),
+            )
+            .render(area, buf);
+    }
+}
+
+impl TuiPopup for NewConnectionPopupState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        NewConnectionPopupState::handle_event(self, event)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        NewConnectionPopupState::needs_redraw(self)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        NewConnectionPopupState::render(self, area, buf);
+    }
+}
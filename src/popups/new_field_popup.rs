@@ -10,8 +10,8 @@ use crate::{
     common::event::Event,
     popups::{text_popup::TextPopup, TuiPopup},
     views::{
-        live_plot::LivePlotState, state_graph::StateGraphViewState, FieldInfo, FieldInfoType,
-        FromField, TuiView,
+        interface_definition::InterfaceDefinitionState, live_plot::LivePlotState,
+        state_graph::StateGraphViewState, FieldInfo, FieldInfoType, FromField, TuiView,
     },
     widgets::select_view_widget::SelectViewWidget,
 };
@@ -48,6 +48,17 @@ static FROM_NEW_FIELD_FACTORIES: once_cell::sync::Lazy<
             }) as Box<NewFieldFactoryClosure>,
         ),
     );
+    m.insert(
+        "definition",
+        (
+            Box::new(|_: &FieldInfoType| true) as Box<NewFieldFactoryPredicate>,
+            Box::new(|field_info: FieldInfo| {
+                Rc::new(RefCell::new(InterfaceDefinitionState::from_field(
+                    field_info,
+                ))) as Rc<RefCell<dyn TuiView>>
+            }) as Box<NewFieldFactoryClosure>,
+        ),
+    );
     m
 });
 
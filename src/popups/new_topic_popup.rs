@@ -8,16 +8,24 @@ use ratatui::{buffer::Buffer, layout::Rect};
 
 use crate::{
     common::event::Event,
-    popups::TuiPopup,
+    connections::{qos::QosProfile, Connection},
+    popups::{qos_popup::QosPopupState, TuiPopup},
     views::{
-        hz_plot::HzPlotState, raw_message::RawMessageState, topic_graph::TopicGraphState,
-        topic_publisher::TopicPublisherState, FromTopic, TopicInfo, TuiView,
+        bitrate_plot::BitratePlotState, delay_plot::DelayPlotState, hz_plot::HzPlotState,
+        interface_definition::InterfaceDefinitionState, raw_message::RawMessageState,
+        topic_graph::TopicGraphState, topic_publisher::TopicPublisherState, FromTopic, TopicInfo,
+        TuiView,
     },
     widgets::select_view_widget::SelectViewWidget,
 };
 
 // TODO: Make this configurable via plugins
-type NewTopicFactoryClosure = dyn Fn(TopicInfo) -> Rc<RefCell<dyn TuiView>> + Send + Sync;
+type NewTopicFactoryClosure =
+    dyn Fn(TopicInfo) -> Result<Rc<RefCell<dyn TuiView>>, String> + Send + Sync;
+
+fn new_view(view: impl TuiView + 'static) -> Result<Rc<RefCell<dyn TuiView>>, String> {
+    Ok(Rc::new(RefCell::new(view)))
+}
 
 static FROM_NEW_TOPIC_FACTORIES: once_cell::sync::Lazy<
     IndexMap<&'static str, Box<NewTopicFactoryClosure>>,
@@ -25,53 +33,98 @@ static FROM_NEW_TOPIC_FACTORIES: once_cell::sync::Lazy<
     let mut m = IndexMap::new();
     m.insert(
         "raw_message",
-        Box::new(|topic_info: TopicInfo| {
-            Rc::new(RefCell::new(RawMessageState::from_topic(topic_info)))
-                as Rc<RefCell<dyn TuiView>>
-        }) as Box<NewTopicFactoryClosure>,
+        Box::new(|topic_info: TopicInfo| new_view(RawMessageState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
     );
     m.insert(
         "topic_publisher",
         Box::new(|topic_info: TopicInfo| {
-            Rc::new(RefCell::new(TopicPublisherState::from_topic(topic_info)))
-                as Rc<RefCell<dyn TuiView>>
+            TopicPublisherState::from_topic(topic_info).and_then(new_view)
         }) as Box<NewTopicFactoryClosure>,
     );
     m.insert(
         "hz_plot",
-        Box::new(|topic_info: TopicInfo| {
-            Rc::new(RefCell::new(HzPlotState::from_topic(topic_info))) as Rc<RefCell<dyn TuiView>>
-        }) as Box<NewTopicFactoryClosure>,
+        Box::new(|topic_info: TopicInfo| new_view(HzPlotState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
+    );
+    m.insert(
+        "delay_plot",
+        Box::new(|topic_info: TopicInfo| new_view(DelayPlotState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
+    );
+    m.insert(
+        "bitrate_plot",
+        Box::new(|topic_info: TopicInfo| new_view(BitratePlotState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
+    );
+    m.insert(
+        "interface_definition",
+        Box::new(|topic_info: TopicInfo| new_view(InterfaceDefinitionState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
     );
     m.insert(
         "topic_graph",
-        Box::new(|topic_info: TopicInfo| {
-            Rc::new(RefCell::new(TopicGraphState::from_topic(topic_info)))
-                as Rc<RefCell<dyn TuiView>>
-        }) as Box<NewTopicFactoryClosure>,
+        Box::new(|topic_info: TopicInfo| new_view(TopicGraphState::from_topic(topic_info)))
+            as Box<NewTopicFactoryClosure>,
     );
     m
 });
 
+// Views subscribing or publishing with the QoS of `TopicInfo`
+const QOS_FACTORIES: [&str; 2] = ["raw_message", "topic_publisher"];
+
+// Views only offered on connections that can publish
+const PUBLISHER_FACTORIES: [&str; 1] = ["topic_publisher"];
+
+fn new_view_event(view: Result<Rc<RefCell<dyn TuiView>>, String>) -> Event {
+    match view {
+        Ok(view) => Event::NewView(view),
+        Err(e) => Event::Error(e),
+    }
+}
+
 pub struct NewTopicPopupState {
     topic: TopicInfo,
+    /// Keys of the `FROM_NEW_TOPIC_FACTORIES` offered for the connection of the topic.
+    factories: Vec<&'static str>,
     views: Vec<Rc<RefCell<dyn TuiView>>>,
     selected: usize,
+    qos_popup: Option<QosPopupState>,
 
     needs_redraw: bool,
 }
 
 impl NewTopicPopupState {
     pub fn new(topic: TopicInfo, candidate_views: Vec<Rc<RefCell<dyn TuiView>>>) -> Self {
+        let can_publish = topic.connection.borrow().can_publish();
+        let factories = FROM_NEW_TOPIC_FACTORIES
+            .keys()
+            .copied()
+            .filter(|key| can_publish || !PUBLISHER_FACTORIES.contains(key))
+            .collect();
         Self {
             topic,
+            factories,
             views: candidate_views,
             selected: 0,
+            qos_popup: None,
             needs_redraw: true,
         }
     }
 
     pub fn handle_event(&mut self, event: Event) -> Event {
+        if let Some(qos_popup) = &mut self.qos_popup {
+            return match qos_popup.handle_event(event) {
+                // Go back to the view selection
+                Event::ClosePopup => {
+                    self.qos_popup = None;
+                    self.needs_redraw = true;
+                    Event::None
+                }
+                event => event,
+            };
+        }
+
         if let Event::Key(CrosstermEvent::Key(key_event)) = event {
             if key_event.kind != KeyEventKind::Press {
                 return event;
@@ -83,33 +136,31 @@ impl NewTopicPopupState {
                     return Event::None;
                 }
                 KeyCode::Char('j') | KeyCode::Down => {
-                    if self.selected < self.views.len() + FROM_NEW_TOPIC_FACTORIES.len() - 1 {
+                    if self.selected < self.views.len() + self.factories.len() - 1 {
                         self.selected += 1;
                     }
                     self.needs_redraw = true;
                     return Event::None;
                 }
                 KeyCode::Enter => {
-                    if self.selected < FROM_NEW_TOPIC_FACTORIES.len() {
-                        let factory_index = self.selected;
-                        let factory_key = FROM_NEW_TOPIC_FACTORIES
-                            .keys()
-                            .nth(factory_index)
-                            .expect("Factory index out of bounds");
+                    if let Some(factory_key) = self.factories.get(self.selected) {
                         let factory = FROM_NEW_TOPIC_FACTORIES
                             .get(factory_key)
                             .expect("Factory key not found");
-                        let new_view = factory(self.topic.clone());
-                        return Event::NewView(new_view);
+                        return new_view_event(factory(self.topic.clone()));
                     } else {
                         let mut view =
-                            self.views[self.selected - FROM_NEW_TOPIC_FACTORIES.len()].borrow_mut();
+                            self.views[self.selected - self.factories.len()].borrow_mut();
                         if let Some(accepts_topic) = view.as_topic_acceptor() {
                             accepts_topic.accepts_topic(self.topic.clone());
                         }
                         return Event::ClosePopup;
                     }
                 }
+                KeyCode::Char('q') => {
+                    self.open_qos_popup();
+                    return Event::None;
+                }
                 KeyCode::Esc => {
                     return Event::ClosePopup;
                 }
@@ -119,7 +170,41 @@ impl NewTopicPopupState {
         event
     }
 
+    /// Pick the QoS of the selected view before creating it, if it subscribes or publishes.
+    fn open_qos_popup(&mut self) {
+        let Some(factory_key) = self.factories.get(self.selected) else {
+            return;
+        };
+        let factory = FROM_NEW_TOPIC_FACTORIES
+            .get(factory_key)
+            .expect("Factory key not found");
+        if !QOS_FACTORIES.contains(factory_key) {
+            return;
+        }
+        let publishers_qos = self
+            .topic
+            .connection
+            .borrow()
+            .get_publishers_qos_by_topic(&self.topic.topic)
+            .unwrap_or_default();
+        let topic = self.topic.clone();
+        self.qos_popup = Some(QosPopupState::new(
+            format!("QoS of {} - {}", factory_key, topic.topic),
+            QosProfile::matching(&publishers_qos),
+            move |qos| {
+                new_view_event(factory(TopicInfo {
+                    qos,
+                    ..topic.clone()
+                }))
+            },
+        ));
+        self.needs_redraw = true;
+    }
+
     pub fn needs_redraw(&mut self) -> bool {
+        if let Some(qos_popup) = &mut self.qos_popup {
+            return qos_popup.needs_redraw() || std::mem::take(&mut self.needs_redraw);
+        }
         if self.needs_redraw {
             self.needs_redraw = false;
             true
@@ -145,14 +230,20 @@ impl TuiPopup for NewTopicPopupState {
 
 impl NewTopicPopupState {
     pub fn render(&self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
-        let mut views: Vec<(usize, String)> = FROM_NEW_TOPIC_FACTORIES
-            .keys()
+        if let Some(qos_popup) = &self.qos_popup {
+            qos_popup.render(area, buf);
+            return;
+        }
+
+        let mut views: Vec<(usize, String)> = self
+            .factories
+            .iter()
             .enumerate()
             .map(|(i, k)| (i, format!("New {}", k)))
             .collect();
         views.extend(self.views.iter().enumerate().map(|(i, v)| {
             (
-                i + FROM_NEW_TOPIC_FACTORIES.len(),
+                i + self.factories.len(),
                 format!("Add to existing {}", v.borrow().name()),
             )
         }));
@@ -162,7 +253,7 @@ impl NewTopicPopupState {
             .block(
                 Block::default()
                     .border_type(BorderType::Rounded)
-                    .title("Select View")
+                    .title("Select View ('q': pick the QoS first)")
                     .borders(ratatui::widgets::Borders::ALL),
             );
         select_view_widget.render(area, buf);
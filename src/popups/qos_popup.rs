@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    common::{
+        event::Event,
+        style::{HEADER_STYLE, SELECTED_STYLE},
+    },
+    connections::qos::{format_duration, Durability, History, Liveliness, QosProfile, Reliability},
+    popups::TuiPopup,
+};
+
+const HISTORIES: [History; 7] = [
+    History::KeepLast(1),
+    History::KeepLast(5),
+    History::KeepLast(10),
+    History::KeepLast(50),
+    History::KeepLast(100),
+    History::KeepLast(1000),
+    History::KeepAll,
+];
+
+const DURATIONS: [Option<Duration>; 9] = [
+    None,
+    Some(Duration::from_millis(10)),
+    Some(Duration::from_millis(50)),
+    Some(Duration::from_millis(100)),
+    Some(Duration::from_millis(200)),
+    Some(Duration::from_millis(500)),
+    Some(Duration::from_secs(1)),
+    Some(Duration::from_secs(2)),
+    Some(Duration::from_secs(5)),
+];
+
+const ROWS: [&str; 8] = [
+    "Profile",
+    "Reliability",
+    "Durability",
+    "History",
+    "Deadline",
+    "Lifespan",
+    "Liveliness",
+    "Lease duration",
+];
+
+/// Next (or previous) value of `options` after `current`, the first one if `current` isn't
+/// an option.
+fn cycle<T: PartialEq + Copy>(options: &[T], current: T, forward: bool) -> T {
+    let len = options.len();
+    match options.iter().position(|option| *option == current) {
+        Some(index) if forward => options[(index + 1) % len],
+        Some(index) => options[(index + len - 1) % len],
+        None => options[0],
+    }
+}
+
+type SubmitClosure = dyn FnMut(Option<QosProfile>) -> Event;
+
+/// Pick the QoS of a subscription or a publisher. The "match publishers" profile hands `None`
+/// over to `on_submit`, leaving the choice to the connection.
+pub struct QosPopupState {
+    title: String,
+    presets: Vec<(&'static str, QosProfile)>,
+    /// Index of the profile in `presets`, `None` once a policy is edited.
+    preset: Option<usize>,
+    qos: QosProfile,
+    selected: usize,
+    on_submit: Box<SubmitClosure>,
+
+    needs_redraw: bool,
+}
+
+impl QosPopupState {
+    /// `publishers_qos` is the profile matching the existing publishers of the topic.
+    pub fn new(
+        title: impl Into<String>,
+        publishers_qos: QosProfile,
+        on_submit: impl FnMut(Option<QosProfile>) -> Event + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            presets: vec![
+                ("match publishers", publishers_qos),
+                ("default", QosProfile::default()),
+                ("sensor_data", QosProfile::sensor_data()),
+                ("transient_local", QosProfile::transient_local()),
+            ],
+            preset: Some(0),
+            qos: publishers_qos,
+            selected: 0,
+            on_submit: Box::new(on_submit),
+            needs_redraw: true,
+        }
+    }
+
+    fn change_selected(&mut self, forward: bool) {
+        if self.selected == 0 {
+            let preset = match self.preset {
+                Some(index) if forward => (index + 1) % self.presets.len(),
+                Some(index) => (index + self.presets.len() - 1) % self.presets.len(),
+                None => 0,
+            };
+            self.preset = Some(preset);
+            self.qos = self.presets[preset].1;
+            return;
+        }
+
+        let qos = &mut self.qos;
+        match self.selected {
+            1 => {
+                qos.reliability = cycle(
+                    &[Reliability::Reliable, Reliability::BestEffort],
+                    qos.reliability,
+                    forward,
+                )
+            }
+            2 => {
+                qos.durability = cycle(
+                    &[Durability::Volatile, Durability::TransientLocal],
+                    qos.durability,
+                    forward,
+                )
+            }
+            3 => qos.history = cycle(&HISTORIES, qos.history, forward),
+            4 => qos.deadline = cycle(&DURATIONS, qos.deadline, forward),
+            5 => qos.lifespan = cycle(&DURATIONS, qos.lifespan, forward),
+            6 => {
+                qos.liveliness = cycle(
+                    &[Liveliness::Automatic, Liveliness::ManualByTopic],
+                    qos.liveliness,
+                    forward,
+                )
+            }
+            _ => {
+                qos.liveliness_lease_duration =
+                    cycle(&DURATIONS, qos.liveliness_lease_duration, forward)
+            }
+        }
+        self.preset = None;
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return Event::None;
+            }
+            self.needs_redraw = true;
+            match key_event.code {
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.selected = (self.selected + 1).min(ROWS.len() - 1);
+                }
+                KeyCode::Char('h') | KeyCode::Left => self.change_selected(false),
+                KeyCode::Char('l') | KeyCode::Right => self.change_selected(true),
+                KeyCode::Enter => {
+                    let qos = match self.preset {
+                        Some(0) => None,
+                        _ => Some(self.qos),
+                    };
+                    return (self.on_submit)(qos);
+                }
+                KeyCode::Esc => return Event::ClosePopup,
+                _ => {}
+            }
+            return Event::None;
+        }
+        event
+    }
+
+    pub fn needs_redraw(&mut self) -> bool {
+        if self.needs_redraw {
+            self.needs_redraw = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let qos = &self.qos;
+        let values = [
+            self.preset
+                .map_or("custom", |index| self.presets[index].0)
+                .to_string(),
+            qos.reliability.to_string(),
+            qos.durability.to_string(),
+            qos.history.to_string(),
+            format_duration(qos.deadline),
+            format_duration(qos.lifespan),
+            qos.liveliness.to_string(),
+            format_duration(qos.liveliness_lease_duration),
+        ];
+        let mut lines: Vec<Line> = ROWS
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(index, (row, value))| {
+                let style = if index == self.selected {
+                    SELECTED_STYLE
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:<16}", row)),
+                    Span::raw(format!("◀ {} ▶", value)),
+                ])
+                .style(style)
+            })
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("'h'/'l': change, 'Enter': confirm, 'Esc': back"));
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(self.title.clone())
+                    .border_style(HEADER_STYLE)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .render(area, buf);
+    }
+}
+
+impl TuiPopup for QosPopupState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        QosPopupState::handle_event(self, event)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        QosPopupState::needs_redraw(self)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        QosPopupState::render(self, area, buf);
+    }
+}
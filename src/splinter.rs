@@ -1,12 +1,51 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::{Args, Parser, Subcommand};
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
 use splinter::common::app::{App, AppArgs};
+use splinter::connections::foxglove::ConnectionFoxglove;
+use splinter::connections::mcap::ConnectionMcap;
+use splinter::connections::mock::ConnectionMock;
+use splinter::connections::parameter_file::{diff_parameters, dump_parameters, load_parameters};
+use splinter::connections::pending::{call_timeout, set_call_timeout};
+use splinter::connections::ros2::ConnectionROS2;
+use splinter::connections::rosbag2::ConnectionRosbag2;
+use splinter::connections::rosbridge::ConnectionRosbridge;
+use splinter::connections::{Connection, ConnectionType, NodeName, Parameters};
 
 #[derive(Debug, Parser)]
 #[command(name = "splinter")]
 #[command(about = "A TUI for ROS2")]
 #[command(version, about)]
 struct CliArgs {
+    /// Replay an MCAP file instead of connecting to the live ROS2 graph
+    #[arg(long, global = true)]
+    mcap: Option<PathBuf>,
+
+    /// Replay a rosbag2 sqlite3 bag directory instead of connecting to the live ROS2 graph
+    #[arg(long, global = true, conflicts_with = "mcap")]
+    bag: Option<PathBuf>,
+
+    /// Connect to a rosbridge server (e.g. ws://localhost:9090) instead of the live ROS2 graph
+    #[arg(long, global = true, conflicts_with_all = ["mcap", "bag"])]
+    rosbridge: Option<String>,
+
+    /// Connect to a Foxglove WebSocket server (e.g. ws://localhost:8765) instead of the live ROS2
+    /// graph
+    #[arg(long, global = true, conflicts_with_all = ["mcap", "bag", "rosbridge"])]
+    foxglove: Option<String>,
+
+    /// Serve the mock graph declared in a YAML configuration file instead of the live ROS2 graph
+    #[arg(long, global = true, conflicts_with_all = ["mcap", "bag", "rosbridge", "foxglove"])]
+    mock: Option<PathBuf>,
+
+    /// Seconds to wait for the response of a service call or a parameter request
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = 5.0)]
+    call_timeout: f64,
+
     #[command(subcommand)]
     commands: Option<Commands>,
 }
@@ -16,6 +55,19 @@ enum Commands {
     #[command(name = "topic")]
     Topic(TopicArgs),
     Node,
+    #[command(name = "action")]
+    Action(ActionArgs),
+    #[command(name = "param")]
+    Param(ParamArgs),
+    /// Follow the log messages published on /rosout
+    #[command(name = "log")]
+    Log {
+        /// Show only the messages of this node
+        node: Option<String>,
+    },
+    /// Show the frame tree built from /tf and /tf_static
+    #[command(name = "tf")]
+    Tf,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -34,6 +86,125 @@ enum TopicCommands {
     Pub { name: String, message: String },
     #[command(name = "hz")]
     Hz { name: String },
+    #[command(name = "delay")]
+    Delay { name: String },
+    #[command(name = "bw")]
+    Bw { name: String },
+}
+
+#[derive(Debug, Args, Clone)]
+struct ActionArgs {
+    #[command(subcommand)]
+    command: ActionCommands,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum ActionCommands {
+    #[command(name = "list")]
+    List,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ParamArgs {
+    #[command(subcommand)]
+    command: ParamCommands,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum ParamCommands {
+    /// Print the parameters of a node as a ROS 2 parameter file
+    #[command(name = "dump")]
+    Dump {
+        node: String,
+        /// Write the parameters to a file instead of the standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Set the parameters of a node from a ROS 2 parameter file, after showing the changes
+    #[command(name = "load")]
+    Load {
+        node: String,
+        file: PathBuf,
+        /// Apply the changes without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Follow the changes published on /parameter_events
+    #[command(name = "events")]
+    Events,
+}
+
+fn get_parameters(
+    connection: &ConnectionType,
+    node: &NodeName,
+) -> Result<BTreeMap<String, Parameters>> {
+    Ok(connection
+        .get_parameters_by_node(node)
+        .wait(call_timeout())
+        .map_err(color_eyre::eyre::Error::msg)?
+        .into_iter()
+        .collect())
+}
+
+fn dump_parameters_command(
+    connection: &ConnectionType,
+    node: &NodeName,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let parameters = get_parameters(connection, node)?;
+    let text = dump_parameters(node, &parameters).map_err(color_eyre::eyre::Error::msg)?;
+    match output {
+        Some(path) => std::fs::write(&path, text)?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+fn load_parameters_command(
+    connection: &mut ConnectionType,
+    node: &NodeName,
+    file: PathBuf,
+    yes: bool,
+) -> Result<()> {
+    let loaded = load_parameters(node, &std::fs::read_to_string(&file)?)
+        .map_err(color_eyre::eyre::Error::msg)?;
+    let current = get_parameters(connection, node)?;
+    let changes = diff_parameters(&current, &loaded).map_err(color_eyre::eyre::Error::msg)?;
+    if changes.is_empty() {
+        println!("No changes");
+        return Ok(());
+    }
+    for change in &changes {
+        println!("{}", change);
+    }
+    if !yes {
+        print!(
+            "Apply {} change(s) to {}? [y/N] ",
+            changes.len(),
+            node.full_name()
+        );
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for change in changes {
+        let result = connection
+            .set_parameter_by_node(node, &change.name, change.new)
+            .wait(call_timeout());
+        if let Err(e) = result {
+            eprintln!("Failed to set '{}': {}", change.name, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        bail!("{} parameter(s) could not be set", failed);
+    }
+    Ok(())
 }
 
 pub fn run(app: App) -> color_eyre::eyre::Result<()> {
@@ -48,19 +219,68 @@ pub fn run(app: App) -> color_eyre::eyre::Result<()> {
 fn main() -> Result<()> {
     // Get file from CLI arguments
     let args = CliArgs::parse();
+    set_call_timeout(
+        Duration::try_from_secs_f64(args.call_timeout).map_err(color_eyre::eyre::Error::msg)?,
+    );
+
+    let mut connection = if let Some(path) = &args.mcap {
+        ConnectionType::Mcap(ConnectionMcap::open(path).map_err(color_eyre::eyre::Error::msg)?)
+    } else if let Some(path) = &args.bag {
+        ConnectionType::Rosbag2(
+            ConnectionRosbag2::open(path).map_err(color_eyre::eyre::Error::msg)?,
+        )
+    } else if let Some(url) = &args.rosbridge {
+        ConnectionType::Rosbridge(
+            ConnectionRosbridge::new(url).map_err(color_eyre::eyre::Error::msg)?,
+        )
+    } else if let Some(url) = &args.foxglove {
+        ConnectionType::Foxglove(
+            ConnectionFoxglove::new(url).map_err(color_eyre::eyre::Error::msg)?,
+        )
+    } else if let Some(path) = &args.mock {
+        ConnectionType::Mock(ConnectionMock::open(path).map_err(color_eyre::eyre::Error::msg)?)
+    } else {
+        ConnectionType::ROS2(ConnectionROS2::new())
+    };
 
     // Handle commands
     let app = match args.commands {
         Some(Commands::Topic(topic_args)) => match topic_args.command {
-            TopicCommands::List => App::new(AppArgs::TopicList)?,
-            TopicCommands::Echo { name } => App::new(AppArgs::RawMessage(name))?,
+            TopicCommands::List => App::new(connection, AppArgs::TopicList)?,
+            TopicCommands::Echo { name } => App::new(connection, AppArgs::RawMessage(name))?,
             TopicCommands::Pub { name, message } => {
-                App::new(AppArgs::TopicPublisher(name, message))?
+                App::new(connection, AppArgs::TopicPublisher(name, message))?
+            }
+            TopicCommands::Hz { name } => App::new(connection, AppArgs::HzPlot(name))?,
+            TopicCommands::Delay { name } => App::new(connection, AppArgs::DelayPlot(name))?,
+            TopicCommands::Bw { name } => App::new(connection, AppArgs::BitratePlot(name))?,
+        },
+        Some(Commands::Node) => App::new(connection, AppArgs::NodeList)?,
+        Some(Commands::Action(action_args)) => match action_args.command {
+            ActionCommands::List => App::new(connection, AppArgs::ActionList)?,
+        },
+        // Parameter files are handled without the TUI
+        Some(Commands::Param(param_args)) => match param_args.command {
+            ParamCommands::Dump { node, output } => {
+                return dump_parameters_command(
+                    &connection,
+                    &NodeName::from_full_name(&node),
+                    output,
+                );
+            }
+            ParamCommands::Load { node, file, yes } => {
+                return load_parameters_command(
+                    &mut connection,
+                    &NodeName::from_full_name(&node),
+                    file,
+                    yes,
+                );
             }
-            TopicCommands::Hz { name } => App::new(AppArgs::HzPlot(name))?,
+            ParamCommands::Events => App::new(connection, AppArgs::ParameterEvents)?,
         },
-        Some(Commands::Node) => App::new(AppArgs::NodeList)?,
-        None => App::default(),
+        Some(Commands::Log { node }) => App::new(connection, AppArgs::Rosout(node))?,
+        Some(Commands::Tf) => App::new(connection, AppArgs::TfTree)?,
+        None => App::from_connection(connection),
     };
 
     run(app)?;
@@ -0,0 +1,744 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ratatui::{
+    layout::{Constraint, Layout, Spacing},
+    prelude::{Buffer, Rect},
+    symbols::{border, line},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{
+            AnyTypeMutableRef, BoundedSequenceField, GenericMessage, InterfaceType,
+            MessageMetadata, SequenceField,
+        },
+        generic_message_selector::{get_field_category, FieldCategory},
+        style::HEADER_STYLE,
+    },
+    connections::{
+        action::{
+            action_endpoint, action_part, cancel_error, cancel_goal_request, cancel_goal_type,
+            goal_accepted, goal_feedback, goal_from_request, goal_id_string, goal_result,
+            goal_status, new_goal_id, send_goal_request, set_goal_id, GoalId, GoalStatus,
+        },
+        pending::{call_timeout, spinner, PendingCall},
+        Connection, ConnectionType, SubscriptionHandle,
+    },
+    views::{
+        message_pane::{commit_field_edit, MessagePaneState},
+        TuiView,
+    },
+    widgets::message_widget::MessageWidget,
+};
+
+/// Which half of the split view has focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusPane {
+    Goal,
+    Result,
+}
+
+/// Left panel: rounded outer corners, T-junctions where it meets the right panel.
+const LEFT_BORDER_SET: border::Set = border::Set {
+    top_left: line::ROUNDED_TOP_LEFT,
+    top_right: line::HORIZONTAL_DOWN,
+    bottom_left: line::ROUNDED_BOTTOM_LEFT,
+    bottom_right: line::HORIZONTAL_UP,
+    vertical_left: line::VERTICAL,
+    vertical_right: line::VERTICAL,
+    horizontal_top: line::HORIZONTAL,
+    horizontal_bottom: line::HORIZONTAL,
+};
+
+/// Right panel: T-junctions where it meets the left panel, rounded outer corners.
+const RIGHT_BORDER_SET: border::Set = border::Set {
+    top_left: line::HORIZONTAL_DOWN,
+    top_right: line::ROUNDED_TOP_RIGHT,
+    bottom_left: line::HORIZONTAL_UP,
+    bottom_right: line::ROUNDED_BOTTOM_RIGHT,
+    vertical_left: line::VERTICAL,
+    vertical_right: line::VERTICAL,
+    horizontal_top: line::HORIZONTAL,
+    horizontal_bottom: line::HORIZONTAL,
+};
+
+type ServiceResponse = (GenericMessage, Vec<String>);
+
+/// Progress of the last goal sent.
+enum GoalCall {
+    /// Waiting for the server to accept or reject the goal.
+    Sending(PendingCall<ServiceResponse>),
+    /// Goal accepted, waiting for its result.
+    Running(PendingCall<ServiceResponse>),
+    /// The result arrived, or the goal failed.
+    Done,
+}
+
+/// Send goals to an action server, following their feedback until the result arrives.
+pub struct ActionGoalState {
+    action_name: String,
+    action_type: InterfaceType,
+    connection: Rc<RefCell<ConnectionType>>,
+
+    // ── Goal side (editable, like ServiceCall) ────────────────────
+    send_goal_template: GenericMessage,
+    goal: GenericMessage,
+    goal_pane: MessagePaneState,
+    is_editing: bool,
+    field_content: String,
+
+    // ── Result side ───────────────────────────────────────────────
+    goal_id: Option<GoalId>,
+    call: Option<GoalCall>,
+    cancel: Option<PendingCall<ServiceResponse>>,
+    /// Last feedback and status of the goal, written by the subscriptions.
+    feedback: Arc<Mutex<Option<GenericMessage>>>,
+    status: Arc<Mutex<Option<GoalStatus>>>,
+    _subscriptions: Vec<SubscriptionHandle>,
+    result: Option<GenericMessage>,
+    result_pane: MessagePaneState,
+    error: Option<String>,
+
+    focus: FocusPane,
+    needs_redraw: Arc<AtomicBool>,
+}
+
+impl ActionGoalState {
+    pub fn new(
+        action_name: String,
+        action_type: InterfaceType,
+        connection: Rc<RefCell<ConnectionType>>,
+    ) -> Result<Self, String> {
+        let send_goal_template = connection
+            .borrow()
+            .get_service_request_template(&action_part(&action_type, "SendGoal"))?;
+        let goal = goal_from_request(&send_goal_template)?;
+
+        Ok(Self {
+            action_name,
+            action_type,
+            connection,
+            send_goal_template,
+            goal,
+            goal_pane: MessagePaneState::new(),
+            is_editing: false,
+            field_content: String::new(),
+            goal_id: None,
+            call: None,
+            cancel: None,
+            feedback: Arc::new(Mutex::new(None)),
+            status: Arc::new(Mutex::new(None)),
+            _subscriptions: Vec::new(),
+            result: None,
+            result_pane: MessagePaneState::new(),
+            error: None,
+            focus: FocusPane::Goal,
+            needs_redraw: Arc::new(AtomicBool::new(true)),
+        })
+    }
+
+    fn set_needs_redraw(&self) {
+        self.needs_redraw.store(true, Ordering::Relaxed);
+    }
+
+    fn commit_edit(&mut self) -> Result<(), String> {
+        self.set_needs_redraw();
+        commit_field_edit(
+            &mut self.goal,
+            &self.goal_pane.selected_fields,
+            &self.field_content,
+        )
+    }
+
+    /// Send the goal with a new identifier, the previous goal is no longer followed.
+    fn send_goal(&mut self) -> Event {
+        self.set_needs_redraw();
+        self.reset_goal();
+
+        let goal_id = new_goal_id();
+        let request = match send_goal_request(&self.send_goal_template, &goal_id, &self.goal) {
+            Ok(request) => request,
+            Err(e) => return Event::Error(format!("Failed to build the goal request: {e}")),
+        };
+        self.goal_id = Some(goal_id);
+
+        // Subscribe before sending, not to miss the first feedback
+        let mut errors = Vec::new();
+        let feedback = self.feedback.clone();
+        let needs_redraw = self.needs_redraw.clone();
+        match self.connection.borrow_mut().subscribe(
+            &action_endpoint(&self.action_name, "feedback"),
+            None,
+            move |msg: GenericMessage, _msg_info: MessageMetadata| {
+                if let Some(goal_feedback) = goal_feedback(&msg, &goal_id) {
+                    *feedback.lock().unwrap() = Some(goal_feedback);
+                    needs_redraw.store(true, Ordering::Relaxed);
+                }
+            },
+        ) {
+            Ok(subscription) => self._subscriptions.push(subscription),
+            Err(e) => errors.push(format!("No feedback: {e}")),
+        }
+        let status = self.status.clone();
+        let needs_redraw = self.needs_redraw.clone();
+        match self.connection.borrow_mut().subscribe(
+            &action_endpoint(&self.action_name, "status"),
+            None,
+            move |msg: GenericMessage, _msg_info: MessageMetadata| {
+                if let Some(goal_status) = goal_status(&msg, &goal_id) {
+                    *status.lock().unwrap() = Some(goal_status);
+                    needs_redraw.store(true, Ordering::Relaxed);
+                }
+            },
+        ) {
+            Ok(subscription) => self._subscriptions.push(subscription),
+            Err(e) => errors.push(format!("No status: {e}")),
+        }
+
+        self.call = Some(GoalCall::Sending(self.connection.borrow().call_service(
+            &action_endpoint(&self.action_name, "send_goal"),
+            &action_part(&self.action_type, "SendGoal"),
+            &request,
+        )));
+
+        match self.poll_call() {
+            Event::None if !errors.is_empty() => Event::Error(errors.join("\n")),
+            event => event,
+        }
+    }
+
+    fn reset_goal(&mut self) {
+        self.goal_id = None;
+        self.call = None;
+        self.cancel = None;
+        self._subscriptions.clear();
+        *self.feedback.lock().unwrap() = None;
+        *self.status.lock().unwrap() = None;
+        self.result = None;
+        self.result_pane.selected_fields.clear();
+        self.error = None;
+    }
+
+    /// Ask the server to cancel the running goal.
+    fn cancel_goal(&mut self) -> Event {
+        let Some(goal_id) = self.goal_id else {
+            return Event::Error("No goal to cancel".to_string());
+        };
+        if matches!(self.call, Some(GoalCall::Done)) {
+            return Event::Error("The goal is already done".to_string());
+        }
+        let connection = self.connection.borrow();
+        let request = connection
+            .get_service_request_template(&cancel_goal_type())
+            .and_then(|template| cancel_goal_request(&template, &goal_id));
+        match request {
+            Ok(request) => {
+                self.cancel = Some(connection.call_service(
+                    &action_endpoint(&self.action_name, "cancel_goal"),
+                    &cancel_goal_type(),
+                    &request,
+                ));
+                drop(connection);
+                self.set_needs_redraw();
+                self.poll_cancel()
+            }
+            Err(e) => Event::Error(format!("Failed to build the cancel request: {e}")),
+        }
+    }
+
+    /// Stop waiting for the running call. The goal keeps running on the server.
+    fn stop_waiting(&mut self) {
+        self.call = Some(GoalCall::Done);
+        self.cancel = None;
+        self._subscriptions.clear();
+        self.error = Some("Stopped waiting for the goal".to_string());
+        self.set_needs_redraw();
+    }
+
+    fn is_waiting(&self) -> bool {
+        matches!(
+            self.call,
+            Some(GoalCall::Sending(_)) | Some(GoalCall::Running(_))
+        )
+    }
+
+    fn poll_call(&mut self) -> Event {
+        let result = match &self.call {
+            // The server accepts or rejects a goal right away
+            Some(GoalCall::Sending(pending)) => pending.poll_timeout(),
+            // The result only arrives once the goal is done, no timeout
+            Some(GoalCall::Running(pending)) => pending.poll(),
+            _ => return Event::None,
+        };
+        // Redraw the spinner
+        self.set_needs_redraw();
+        let Some(result) = result else {
+            return Event::None;
+        };
+        match (self.call.take(), result) {
+            (Some(GoalCall::Sending(_)), Ok((response, _))) => match goal_accepted(&response) {
+                Ok(true) => self.request_result(),
+                Ok(false) => self.fail("The server rejected the goal".to_string()),
+                Err(e) => self.fail(e),
+            },
+            (_, Ok((response, _))) => match goal_result(&response) {
+                Ok((status, result)) => {
+                    *self.status.lock().unwrap() = Some(status);
+                    self.result = Some(result);
+                    self.call = Some(GoalCall::Done);
+                    self._subscriptions.clear();
+                    Event::None
+                }
+                Err(e) => self.fail(e),
+            },
+            (_, Err(e)) => self.fail(e),
+        }
+    }
+
+    fn request_result(&mut self) -> Event {
+        let Some(goal_id) = self.goal_id else {
+            return Event::None;
+        };
+        let result_type = action_part(&self.action_type, "GetResult");
+        let connection = self.connection.borrow();
+        let request = connection
+            .get_service_request_template(&result_type)
+            .and_then(|mut request| {
+                set_goal_id(&mut request, "goal_id", &goal_id)?;
+                Ok(request)
+            });
+        let call = match request {
+            Ok(request) => connection.call_service(
+                &action_endpoint(&self.action_name, "get_result"),
+                &result_type,
+                &request,
+            ),
+            Err(e) => PendingCall::ready(Err(e)),
+        };
+        drop(connection);
+        self.call = Some(GoalCall::Running(call));
+        Event::None
+    }
+
+    fn fail(&mut self, error: String) -> Event {
+        self.call = Some(GoalCall::Done);
+        self.error = Some(error);
+        Event::None
+    }
+
+    fn poll_cancel(&mut self) -> Event {
+        let Some(result) = self
+            .cancel
+            .as_ref()
+            .and_then(|pending| pending.poll_timeout())
+        else {
+            return Event::None;
+        };
+        self.cancel = None;
+        self.set_needs_redraw();
+        match result {
+            Ok((response, _)) => match cancel_error(&response) {
+                Some(e) => Event::Error(format!("Failed to cancel the goal: {e}")),
+                None => Event::None,
+            },
+            Err(e) => Event::Error(format!("Failed to cancel the goal: {e}")),
+        }
+    }
+
+    /// Status line of the result pane.
+    fn status_text(&self) -> String {
+        let status = *self.status.lock().unwrap();
+        let goal = self
+            .goal_id
+            .map(|goal_id| goal_id_string(&goal_id))
+            .unwrap_or_default();
+        match &self.call {
+            None => "No goal sent yet. Press 's' to send.".to_string(),
+            Some(GoalCall::Sending(pending)) => {
+                let elapsed = pending.elapsed();
+                format!(
+                    "{} Sending goal {}... {:.1}s / {:.1}s\nPress 'Esc' to stop waiting.",
+                    spinner(elapsed),
+                    goal,
+                    elapsed.as_secs_f64(),
+                    call_timeout().as_secs_f64()
+                )
+            }
+            Some(GoalCall::Running(pending)) => {
+                let elapsed = pending.elapsed();
+                let cancel = if self.cancel.is_some() {
+                    "Cancel requested..."
+                } else {
+                    "Press 'c' to cancel the goal."
+                };
+                format!(
+                    "{} Goal {} {} {:.1}s\n{}",
+                    spinner(elapsed),
+                    goal,
+                    status.unwrap_or(GoalStatus::Accepted),
+                    elapsed.as_secs_f64(),
+                    cancel
+                )
+            }
+            Some(GoalCall::Done) => match (&self.error, status) {
+                (Some(e), Some(status)) => format!("Goal {} {}\nError: {}", goal, status, e),
+                (Some(e), None) => format!("Error: {e}"),
+                (None, status) => {
+                    format!("Goal {} {}", goal, status.unwrap_or(GoalStatus::Unknown))
+                }
+            },
+        }
+    }
+
+    // ── Event handling ───────────────────────────────────────────
+
+    fn handle_goal_event(&mut self, key_event: crossterm::event::KeyEvent) -> Event {
+        match key_event.code {
+            KeyCode::Char('s') if !self.is_editing => self.send_goal(),
+            KeyCode::Char('c') if !self.is_editing => self.cancel_goal(),
+            KeyCode::Char('l') | KeyCode::Right if !self.is_editing => {
+                self.focus = FocusPane::Result;
+                self.set_needs_redraw();
+                Event::None
+            }
+            KeyCode::Char('j')
+            | KeyCode::Down
+            | KeyCode::Char('k')
+            | KeyCode::Up
+            | KeyCode::Char('G')
+                if !self.is_editing =>
+            {
+                if self.goal_pane.handle_nav_key(key_event, &self.goal) {
+                    self.set_needs_redraw();
+                }
+                Event::None
+            }
+            KeyCode::Char(sign @ ('-' | '+')) if !self.is_editing => {
+                self.set_needs_redraw();
+                if let Ok(field) = self
+                    .goal
+                    .get_mut_deep_index(&self.goal_pane.selected_fields)
+                {
+                    let resize = |len: usize| {
+                        if sign == '+' {
+                            len + 1
+                        } else {
+                            len.saturating_sub(1)
+                        }
+                    };
+                    match field {
+                        AnyTypeMutableRef::Sequence(SequenceField::Message(_))
+                        | AnyTypeMutableRef::BoundedSequence(BoundedSequenceField::Message(_, _)) =>
+                        {
+                            return Event::Error("Cannot resize sequence of messages".to_string());
+                        }
+                        AnyTypeMutableRef::Sequence(seq) => seq.resize(resize(seq.len())),
+                        AnyTypeMutableRef::BoundedSequence(seq) => seq.resize(resize(seq.len())),
+                        _ => {}
+                    }
+                }
+                Event::None
+            }
+            KeyCode::Backspace if self.is_editing => {
+                self.set_needs_redraw();
+                self.field_content.pop();
+                Event::None
+            }
+            KeyCode::Enter => {
+                if self.is_editing {
+                    self.is_editing = false;
+                    let commit_result = self.commit_edit();
+                    self.field_content.clear();
+                    match commit_result {
+                        Ok(()) => Event::None,
+                        Err(e) => Event::Error(format!("Failed to commit edit: {e}")),
+                    }
+                } else if get_field_category(&self.goal, &self.goal_pane.selected_fields)
+                    == Some(FieldCategory::Base)
+                {
+                    self.is_editing = true;
+                    self.field_content.clear();
+                    self.set_needs_redraw();
+                    Event::None
+                } else {
+                    Event::Key(CrosstermEvent::Key(key_event))
+                }
+            }
+            KeyCode::Char(c) if self.is_editing => {
+                self.field_content.push(c);
+                self.set_needs_redraw();
+                Event::None
+            }
+            _ => Event::Key(CrosstermEvent::Key(key_event)),
+        }
+    }
+
+    fn handle_result_event(&mut self, key_event: crossterm::event::KeyEvent) -> Event {
+        match key_event.code {
+            KeyCode::Char('s') => self.send_goal(),
+            KeyCode::Char('c') => self.cancel_goal(),
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.focus = FocusPane::Goal;
+                self.set_needs_redraw();
+                Event::None
+            }
+            _ => {
+                if let Some(msg) = &self.result {
+                    if self.result_pane.handle_nav_key(key_event, msg) {
+                        self.set_needs_redraw();
+                        return Event::None;
+                    }
+                }
+                Event::Key(CrosstermEvent::Key(key_event))
+            }
+        }
+    }
+}
+
+impl TuiView for ActionGoalState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        match event {
+            Event::Key(CrosstermEvent::Key(key_event)) => {
+                if key_event.kind != KeyEventKind::Press {
+                    return event;
+                }
+                // Esc only stops waiting while a call is running, it quits otherwise.
+                if key_event.code == KeyCode::Esc && self.is_waiting() {
+                    self.stop_waiting();
+                    return Event::None;
+                }
+                match self.focus {
+                    FocusPane::Goal => self.handle_goal_event(key_event),
+                    FocusPane::Result => self.handle_result_event(key_event),
+                }
+            }
+            Event::None => match self.poll_call() {
+                Event::None => self.poll_cancel(),
+                event => event,
+            },
+            other => other,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("Action Goal - {}", self.action_name)
+    }
+
+    fn get_help_text(&self) -> String {
+        "Action Goal View Help:\n\
+        - 's': Send the goal, following its feedback until the result arrives.\n\
+        - 'c': Cancel the running goal.\n\
+        - 'Esc': Stop waiting for the running goal, it keeps running on the server.\n\
+        - 'l' or →: Switch focus to the result pane.\n\
+        - 'h' or ←: Switch focus to the goal pane.\n\
+        - 'j' or ↓: Move down in the message fields.\n\
+        - 'k' or ↑: Move up in the message fields.\n\
+        - 'G': Jump to the last field.\n\
+        - '+': Increase size of sequence field (goal only).\n\
+        - '-': Decrease size of sequence field (goal only).\n\
+        - 'Enter': Toggle edit mode for primitive fields (goal only).\n\
+        - 'Backspace': Remove last character when editing."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.needs_redraw.swap(false, Ordering::Relaxed)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let [goal_area, result_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .spacing(Spacing::Overlap(1))
+                .areas(area);
+
+        // ── Goal pane ────────────────────────────────────────────
+        let goal_border_style = if self.focus == FocusPane::Goal {
+            HEADER_STYLE
+        } else {
+            ratatui::style::Style::default()
+        };
+        let goal_block = Block::bordered()
+            .title(Line::from(" Goal (s to send) ").centered())
+            .border_style(goal_border_style)
+            .border_set(LEFT_BORDER_SET);
+
+        let mut goal_widget = MessageWidget::new(&self.goal).block(goal_block);
+        if !self.goal_pane.selected_fields.is_empty() {
+            goal_widget = goal_widget.with_selection(&self.goal_pane.selected_fields);
+            if self.is_editing {
+                goal_widget = goal_widget.with_edit(&self.field_content);
+            }
+        }
+        StatefulWidget::render(
+            goal_widget,
+            goal_area,
+            buf,
+            &mut self.goal_pane.widget_state,
+        );
+
+        // ── Result pane: status, live feedback, result ───────────
+        let result_border_style = if self.focus == FocusPane::Result {
+            HEADER_STYLE
+        } else {
+            ratatui::style::Style::default()
+        };
+        let result_block = Block::bordered()
+            .title(Line::from(" Progress ").centered())
+            .border_style(result_border_style)
+            .border_set(RIGHT_BORDER_SET);
+        let inner = result_block.inner(result_area);
+        Widget::render(result_block, result_area, buf);
+
+        let [status_area, feedback_area, message_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .areas(inner);
+
+        Widget::render(Paragraph::new(self.status_text()), status_area, buf);
+
+        let feedback_block = Block::new()
+            .borders(Borders::TOP)
+            .title(Line::from(" Feedback ").centered());
+        match &*self.feedback.lock().unwrap() {
+            Some(feedback) => Widget::render(
+                MessageWidget::new(feedback).block(feedback_block),
+                feedback_area,
+                buf,
+            ),
+            None => Widget::render(
+                Paragraph::new("No feedback yet.").block(feedback_block),
+                feedback_area,
+                buf,
+            ),
+        }
+
+        let message_block = Block::new()
+            .borders(Borders::TOP)
+            .title(Line::from(" Result ").centered());
+        match &self.result {
+            Some(result) => {
+                let result_widget = MessageWidget::new(result)
+                    .with_selection(&self.result_pane.selected_fields)
+                    .block(message_block);
+                StatefulWidget::render(
+                    result_widget,
+                    message_area,
+                    buf,
+                    &mut self.result_pane.widget_state,
+                );
+            }
+            None => Widget::render(
+                Paragraph::new("No result yet.").block(message_block),
+                message_area,
+                buf,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::generic_message::GenericField;
+    use crate::views::test_utils::{key, mock_connection};
+
+    const CONFIG: &str = "\
+definitions:
+  example_interfaces/action/Fibonacci_SendGoal: |
+    unique_identifier_msgs/UUID goal_id
+    Fibonacci_Goal goal
+    ================================================================================
+    MSG: unique_identifier_msgs/UUID
+    uint8[16] uuid
+    ================================================================================
+    MSG: example_interfaces/Fibonacci_Goal
+    int32 order
+    ---
+    bool accepted
+  example_interfaces/action/Fibonacci_GetResult: |
+    unique_identifier_msgs/UUID goal_id
+    ================================================================================
+    MSG: unique_identifier_msgs/UUID
+    uint8[16] uuid
+    ---
+    int8 status
+    Fibonacci_Result result
+    ================================================================================
+    MSG: example_interfaces/Fibonacci_Result
+    int32[] sequence
+services:
+  - name: /fibonacci/_action/send_goal
+    type: example_interfaces/action/Fibonacci_SendGoal
+    response: { accepted: true }
+  - name: /fibonacci/_action/get_result
+    type: example_interfaces/action/Fibonacci_GetResult
+    response: { status: 4, result: { sequence: [0, 1, 1, 2] } }
+";
+
+    fn fibonacci_view() -> ActionGoalState {
+        ActionGoalState::new(
+            "/fibonacci".to_string(),
+            InterfaceType::new("example_interfaces/action/Fibonacci").unwrap(),
+            mock_connection(CONFIG),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_send_goal() {
+        let mut view = fibonacci_view();
+        assert!(view.goal.get("order").is_some());
+        assert!(view.call.is_none());
+
+        // Accepted right away, the result is requested on the next tick
+        view.handle_event(key(KeyCode::Char('s')));
+        assert!(matches!(view.call, Some(GoalCall::Running(_))));
+        view.handle_event(Event::None);
+        assert!(matches!(view.call, Some(GoalCall::Done)));
+        assert_eq!(*view.status.lock().unwrap(), Some(GoalStatus::Succeeded));
+        assert_eq!(
+            view.result.as_ref().unwrap().get("sequence"),
+            Some(&GenericField::Sequence(SequenceField::Int32(vec![
+                0, 1, 1, 2
+            ])))
+        );
+        assert!(view.error.is_none());
+        assert!(view._subscriptions.is_empty());
+
+        // Done, nothing left to cancel
+        match view.handle_event(key(KeyCode::Char('c'))) {
+            Event::Error(e) => assert_eq!(e, "The goal is already done"),
+            _ => panic!("Expected an error"),
+        }
+    }
+
+    #[test]
+    fn test_rejected_goal() {
+        let config = CONFIG.replace("accepted: true", "accepted: false");
+        let mut view = ActionGoalState::new(
+            "/fibonacci".to_string(),
+            InterfaceType::new("example_interfaces/action/Fibonacci").unwrap(),
+            mock_connection(&config),
+        )
+        .unwrap();
+        view.handle_event(key(KeyCode::Char('s')));
+        assert!(matches!(view.call, Some(GoalCall::Done)));
+        assert_eq!(view.error.as_deref(), Some("The server rejected the goal"));
+        assert!(view.result.is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+    prelude::{Buffer, Rect},
+    text::Line,
+    widgets::{Block, BorderType, StatefulWidget},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode};
+
+use crate::{
+    common::{event::Event, style::HEADER_STYLE},
+    connections::{Connection, ConnectionType, NamedInterface},
+    views::{
+        action_goal::ActionGoalState, interface_definition::InterfaceDefinitionState,
+        ConnectionInfo, FromConnection, TuiView,
+    },
+    widgets::list_widget::{ListWidget, ListWidgetState},
+};
+
+pub struct ActionListWidget;
+
+pub struct ActionListState {
+    connection: Rc<RefCell<ConnectionType>>,
+
+    action_list_state: ListWidgetState<NamedInterface>,
+
+    last_update: std::time::Instant,
+    needs_redraw: bool,
+}
+
+impl ActionListState {
+    pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let actions = connection.borrow().list_actions().unwrap_or_default();
+
+        Self {
+            connection,
+            action_list_state: ListWidgetState::new(actions, None),
+            last_update: std::time::Instant::now(),
+            needs_redraw: true,
+        }
+    }
+
+    pub fn update(&mut self) {
+        const UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+        if self.last_update.elapsed() < UPDATE_INTERVAL {
+            return;
+        }
+        self.last_update = std::time::Instant::now();
+        let actions = self.connection.borrow().list_actions().unwrap_or_default();
+        self.action_list_state.update(actions);
+        self.needs_redraw = true;
+    }
+
+    fn handle_event(&mut self, event: Event) -> Event {
+        let new_event = self.action_list_state.handle_event(event);
+
+        if let Event::Key(CrosstermEvent::Key(key_event)) = &new_event {
+            if key_event.code == KeyCode::Enter {
+                if let Some(selected) = self.action_list_state.get_selected() {
+                    return match ActionGoalState::new(
+                        selected.name.clone(),
+                        selected.type_name.clone(),
+                        self.connection.clone(),
+                    ) {
+                        Ok(view) => Event::NewView(Rc::new(RefCell::new(view))),
+                        Err(e) => Event::Error(format!(
+                            "Failed to open action '{}': {}",
+                            selected.name, e
+                        )),
+                    };
+                }
+                self.needs_redraw = true;
+            }
+            if key_event.code == KeyCode::Char('i') {
+                if let Some(selected) = self.action_list_state.get_selected() {
+                    return Event::NewView(Rc::new(RefCell::new(InterfaceDefinitionState::new(
+                        selected.type_name.clone(),
+                    ))));
+                }
+            }
+        }
+
+        new_event
+    }
+}
+
+impl TuiView for ActionListState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        self.update();
+
+        self.handle_event(event)
+    }
+
+    fn name(&self) -> String {
+        "Actions".to_string()
+    }
+
+    fn get_help_text(&self) -> String {
+        "Action List View Help:\n\
+        Normal Mode:\n\
+        - 'j' or ↓: Move down in the action list.\n\
+        - 'k' or ↑: Move up in the action list.\n\
+        - 'Enter': Open the goal view for the selected action.\n\
+        - 'i': Show the definition of the selected action type.\n\
+        Search Mode:\n\
+        - '/': Enter search mode.\n\
+        - Type to filter actions.\n\
+        - 'Backspace': Remove the last character from the search filter.\n\
+        - 'Esc'/'Enter': Exit search mode."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        if self.needs_redraw || self.action_list_state.needs_redraw() {
+            self.needs_redraw = false;
+            return true;
+        }
+        false
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        ActionListWidget::render(area, buf, self);
+    }
+}
+
+impl FromConnection for ActionListState {
+    fn from_connection(connection_info: ConnectionInfo) -> Self {
+        ActionListState::new(connection_info.connection)
+    }
+}
+
+impl ActionListWidget {
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut ActionListState) {
+        let border = Block::bordered()
+            .title(Line::raw("Action List").centered())
+            .border_style(HEADER_STYLE)
+            .border_type(BorderType::Rounded);
+
+        let action_list = ListWidget::<NamedInterface>::new()
+            .block(border)
+            .auto_scroll(true)
+            .enable_search(true)
+            .show_mode(true);
+        StatefulWidget::render(action_list, area, buf, &mut state.action_list_state);
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use ratatui::{
+    layout::{Constraint, Layout},
+    prelude::{Buffer, Rect, Style, Stylize},
+    style::Color,
+    symbols::Marker,
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
+        style::HEADER_STYLE,
+    },
+    connections::{Connection, ConnectionType, SubscriptionHandle},
+    views::{AcceptsTopic, FromTopic, TopicInfo, TuiView},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+/// Window of the rolling average, in seconds.
+const AVERAGE_WINDOW: f64 = 1.0;
+
+/// `bytes` with a binary unit prefix, e.g. "1.5 KiB".
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{:.0} {}", value, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub struct BitratePlotWidget;
+
+pub struct BitrateLineState {
+    topic: String,
+
+    /// Receive time and serialized size of each message.
+    samples: Vec<(f64, usize)>,
+    error: Option<String>,
+}
+
+impl BitrateLineState {
+    fn new(topic: String) -> Self {
+        Self {
+            topic,
+            samples: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Bytes per second and message size at each message, either averaged over the previous
+    /// `AVERAGE_WINDOW` or from that message alone.
+    fn plots(&self, average: bool) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
+        let mut bitrate = Vec::with_capacity(self.samples.len());
+        let mut size = Vec::with_capacity(self.samples.len());
+        let mut window_start = 0;
+        let mut window_bytes = 0;
+        for (i, &(stamp, bytes)) in self.samples.iter().enumerate() {
+            if average {
+                window_bytes += bytes;
+                while self.samples[window_start].0 <= stamp - AVERAGE_WINDOW {
+                    window_bytes -= self.samples[window_start].1;
+                    window_start += 1;
+                }
+                let count = i + 1 - window_start;
+                bitrate.push((stamp, window_bytes as f64 / AVERAGE_WINDOW));
+                size.push((stamp, window_bytes as f64 / count as f64));
+            } else {
+                // The first message has no previous one to compute a rate from
+                let previous = i.checked_sub(1).map(|j| self.samples[j].0);
+                if let Some(previous) = previous.filter(|&previous| stamp > previous) {
+                    bitrate.push((stamp, bytes as f64 / (stamp - previous)));
+                }
+                size.push((stamp, bytes as f64));
+            }
+        }
+        (bitrate, size)
+    }
+}
+
+pub struct BitratePlotState {
+    connection: Rc<RefCell<ConnectionType>>,
+    lines: Vec<Arc<Mutex<BitrateLineState>>>,
+    _subscriptions: Vec<SubscriptionHandle>,
+    max_duration: f64, // Maximum duration for the plot
+    /// Rolling average over `AVERAGE_WINDOW`, or the value of each message.
+    average: bool,
+}
+
+impl BitratePlotState {
+    pub fn new(topic: String, connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self {
+            lines: vec![],
+            _subscriptions: vec![],
+            max_duration: 10.0, // Default maximum duration for the plot
+            average: true,
+            connection,
+        };
+        state.add_line(topic, state.connection.clone());
+        state
+    }
+
+    pub fn add_line(&mut self, topic: String, connection: Rc<RefCell<ConnectionType>>) {
+        let line_state = Arc::new(Mutex::new(BitrateLineState::new(topic.clone())));
+        let line_state_copy = line_state.clone();
+        let subscription = connection.borrow_mut().subscribe(
+            &topic,
+            None,
+            move |_: GenericMessage, msg_info: MessageMetadata| {
+                let mut mut_line_state = line_state.lock().unwrap();
+                let Some(size) = msg_info.serialized_size else {
+                    mut_line_state.error = Some("message sizes not reported".to_string());
+                    return;
+                };
+                let stamp = msg_info
+                    .received_time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                // Time going backwards means a recording was seeked, start over.
+                if mut_line_state
+                    .samples
+                    .last()
+                    .is_some_and(|&(last, _)| stamp < last)
+                {
+                    mut_line_state.samples.clear();
+                }
+                mut_line_state.samples.push((stamp, size));
+            },
+        );
+        match subscription {
+            Ok(subscription) => self._subscriptions.push(subscription),
+            Err(e) => line_state_copy.lock().unwrap().error = Some(e),
+        }
+        self.lines.push(line_state_copy);
+    }
+}
+
+impl TuiView for BitratePlotState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return event;
+            }
+            match key_event.code {
+                KeyCode::Char('+') => {
+                    self.max_duration += 1.0; // Increase the maximum duration
+                    Event::None
+                }
+                KeyCode::Char('-') => {
+                    if self.max_duration > 1.0 {
+                        self.max_duration -= 1.0; // Decrease the maximum duration
+                    }
+                    Event::None
+                }
+                KeyCode::Char('a') => {
+                    self.average = !self.average;
+                    Event::None
+                }
+                _ => event,
+            }
+        } else {
+            event
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("Bitrate - {}s", self.max_duration)
+    }
+
+    fn get_help_text(&self) -> String {
+        "Bitrate Plot View Help:\n\
+        - '+': Increase the time window for the plots.\n\
+        - '-': Decrease the time window for the plots.\n\
+        - 'a': Toggle between the average over the last second and the value of each message.\n\
+        \n\
+        Sizes are the ones reported by the connection: the CDR payloads, or the JSON text of the \
+        messages over rosbridge."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        BitratePlotWidget::render(area, buf, self);
+    }
+
+    fn as_topic_acceptor(&mut self) -> Option<&mut dyn AcceptsTopic> {
+        Some(self)
+    }
+}
+
+impl FromTopic for BitratePlotState {
+    fn from_topic(topic_info: TopicInfo) -> Self {
+        BitratePlotState::new(topic_info.topic, topic_info.connection)
+    }
+}
+
+impl AcceptsTopic for BitratePlotState {
+    fn accepts_topic(&mut self, topic_info: TopicInfo) {
+        self.add_line(topic_info.topic, topic_info.connection);
+    }
+}
+
+impl BitratePlotWidget {
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut BitratePlotState) {
+        let current_time = state
+            .connection
+            .borrow()
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Timestamp before UNIX EPOCH")
+            .as_secs_f64();
+
+        // Legend and plots of each line, bitrate then message size
+        let mut bitrate_lines = Vec::with_capacity(state.lines.len());
+        let mut size_lines = Vec::with_capacity(state.lines.len());
+        for line in &state.lines {
+            let mut bitrate_line = line.lock().unwrap();
+            // Keep one more window so the average is complete at the left edge
+            bitrate_line
+                .samples
+                .retain(|&(stamp, _)| current_time - stamp <= state.max_duration + AVERAGE_WINDOW);
+            let (bitrate, size) = bitrate_line.plots(state.average);
+            let visible = |plot: Vec<(f64, f64)>| {
+                plot.into_iter()
+                    .filter(|&(stamp, _)| current_time - stamp <= state.max_duration)
+                    .collect::<Vec<_>>()
+            };
+            let (bitrate, size) = (visible(bitrate), visible(size));
+            let (bitrate_legend, size_legend) = match &bitrate_line.error {
+                Some(error) => {
+                    let legend = format!("{} ({})", bitrate_line.topic, error);
+                    (legend.clone(), legend)
+                }
+                None => (
+                    match bitrate.last() {
+                        Some(&(_, value)) => {
+                            format!("{} {}/s", bitrate_line.topic, format_bytes(value))
+                        }
+                        None => bitrate_line.topic.clone(),
+                    },
+                    match size.last() {
+                        Some(&(_, value)) => {
+                            format!("{} {}", bitrate_line.topic, format_bytes(value))
+                        }
+                        None => bitrate_line.topic.clone(),
+                    },
+                ),
+            };
+            bitrate_lines.push((bitrate_legend, bitrate));
+            size_lines.push((size_legend, size));
+        }
+
+        let mode = if state.average {
+            format!("average over {}s", AVERAGE_WINDOW)
+        } else {
+            "per message".to_string()
+        };
+        let [bitrate_area, size_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+        Self::render_chart(
+            bitrate_area,
+            buf,
+            format!("Bitrate ({})", mode),
+            "/s",
+            &bitrate_lines,
+            current_time,
+            state.max_duration,
+        );
+        Self::render_chart(
+            size_area,
+            buf,
+            format!("Message size ({})", mode),
+            "",
+            &size_lines,
+            current_time,
+            state.max_duration,
+        );
+    }
+
+    fn render_chart(
+        area: Rect,
+        buf: &mut Buffer,
+        title: String,
+        unit_suffix: &str,
+        lines: &[(String, Vec<(f64, f64)>)],
+        current_time: f64,
+        max_duration: f64,
+    ) {
+        let block = Block::bordered()
+            .title(Line::raw(title).centered())
+            .border_style(HEADER_STYLE);
+
+        // Find the overall max for Y axis
+        let max_y = lines
+            .iter()
+            .flat_map(|(_, plot)| plot.iter().map(|&(_, y)| y))
+            .fold(0.0, f64::max);
+        let max_y = if max_y > 0.0 { max_y * 1.1 } else { 1024.0 };
+
+        let datasets = lines
+            .iter()
+            .enumerate()
+            .map(|(i, (legend, plot))| {
+                Dataset::default()
+                    .name(legend.clone())
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(match i % 6 {
+                        0 => Color::Red,
+                        1 => Color::Green,
+                        2 => Color::Yellow,
+                        3 => Color::Blue,
+                        4 => Color::Magenta,
+                        _ => Color::Cyan,
+                    }))
+                    .data(plot)
+            })
+            .collect::<Vec<_>>();
+
+        let x_axis = Axis::default()
+            .style(Style::default().white())
+            .bounds([current_time - max_duration, current_time])
+            .labels(
+                (0..=5)
+                    .rev()
+                    .map(|i| format!("{:.1}", current_time - i as f64 * max_duration / 5.0))
+                    .collect::<Vec<_>>(),
+            );
+
+        let y_axis = Axis::default()
+            .style(Style::default().white())
+            .bounds([0.0, max_y])
+            .labels(
+                (0..=5)
+                    .map(|i| format!("{}{}", format_bytes(i as f64 * max_y / 5.0), unit_suffix))
+                    .collect::<Vec<_>>(),
+            );
+
+        let chart = Chart::new(datasets)
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .show_grid(true)
+            .block(block);
+
+        Widget::render(chart, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::views::test_utils::{emit_at, key, mock_connection};
+
+    const CONFIG: &str = "\
+definitions:
+  std_msgs/msg/String: string data
+topics:
+  - { name: /chatter, type: std_msgs/msg/String, messages: [{ data: hello }] }
+";
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(14.0), "14 B");
+        assert_eq!(format_bytes(1536.0), "1.5 KiB");
+        assert_eq!(format_bytes(3.0 * 1024.0 * 1024.0), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_bitrate_and_sizes() {
+        let connection = mock_connection(CONFIG);
+        let mut view = BitratePlotState::new("/chatter".to_string(), connection.clone());
+        view.accepts_topic(TopicInfo {
+            topic: "/unknown".to_string(),
+            type_name: connection.borrow().get_topic_type("/chatter").unwrap(),
+            connection: connection.clone(),
+            qos: None,
+        });
+
+        let at = |millis: u64| SystemTime::UNIX_EPOCH + Duration::from_millis(100_000 + millis);
+        for millis in [0, 250, 500] {
+            emit_at(&connection, "/chatter", at(millis));
+        }
+        let values = |plot: Vec<(f64, f64)>| plot.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        {
+            let line = view.lines[0].lock().unwrap();
+            // 4 bytes of encapsulation, 4 of length and "hello\0"
+            assert!(line.samples.iter().all(|&(_, size)| size == 14));
+
+            // Averaged over the last second
+            let (bitrate, size) = line.plots(true);
+            assert_eq!(values(bitrate), [14.0, 28.0, 42.0]);
+            assert_eq!(values(size), [14.0, 14.0, 14.0]);
+
+            // From each message and the previous one
+            let (bitrate, size) = line.plots(false);
+            assert_eq!(values(bitrate).len(), 2);
+            assert!(values(bitrate)
+                .iter()
+                .all(|rate| (rate - 56.0).abs() < 1e-3));
+            assert_eq!(values(size), [14.0, 14.0, 14.0]);
+        }
+        assert_eq!(
+            view.lines[1].lock().unwrap().error.as_deref(),
+            Some("Unknown topic '/unknown'")
+        );
+
+        // Time going backwards starts over
+        emit_at(&connection, "/chatter", at(0));
+        assert_eq!(view.lines[0].lock().unwrap().samples.len(), 1);
+
+        view.handle_event(key(KeyCode::Char('a')));
+        assert!(!view.average);
+    }
+}
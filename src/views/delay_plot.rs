@@ -0,0 +1,366 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use ratatui::{
+    prelude::{Buffer, Rect, Style, Stylize},
+    style::Color,
+    symbols::Marker,
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
+        style::HEADER_STYLE,
+    },
+    connections::{Connection, ConnectionType, SubscriptionHandle},
+    views::{AcceptsTopic, FromTopic, TopicInfo, TuiView},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+pub struct DelayPlotWidget;
+
+pub struct DelayLineState {
+    topic: String,
+
+    /// Receive time and delay in milliseconds of each message.
+    plot: Vec<(f64, f64)>,
+    /// Why the line stays empty: no subscription, or messages without a header.
+    error: Option<String>,
+}
+
+impl DelayLineState {
+    fn new(topic: String) -> Self {
+        Self {
+            topic,
+            plot: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Topic with the current, mean and max delay of the messages shown.
+    fn legend(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("{} ({})", self.topic, error);
+        }
+        let Some(&(_, current)) = self.plot.last() else {
+            return self.topic.clone();
+        };
+        let mean = self.plot.iter().map(|&(_, delay)| delay).sum::<f64>() / self.plot.len() as f64;
+        let max = self
+            .plot
+            .iter()
+            .map(|&(_, delay)| delay)
+            .fold(f64::MIN, f64::max);
+        format!(
+            "{} cur {:.1} mean {:.1} max {:.1} ms",
+            self.topic, current, mean, max
+        )
+    }
+}
+
+pub struct DelayPlotState {
+    connection: Rc<RefCell<ConnectionType>>,
+    lines: Vec<Arc<Mutex<DelayLineState>>>,
+    _subscriptions: Vec<SubscriptionHandle>,
+    max_duration: f64, // Maximum duration for the plot
+}
+
+impl DelayPlotState {
+    pub fn new(topic: String, connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self {
+            lines: vec![],
+            _subscriptions: vec![],
+            max_duration: 10.0, // Default maximum duration for the plot
+            connection,
+        };
+        state.add_line(topic, state.connection.clone());
+        state
+    }
+
+    pub fn add_line(&mut self, topic: String, connection: Rc<RefCell<ConnectionType>>) {
+        let line_state = Arc::new(Mutex::new(DelayLineState::new(topic.clone())));
+        let line_state_copy = line_state.clone();
+        let subscription = connection.borrow_mut().subscribe(
+            &topic,
+            None,
+            move |msg: GenericMessage, msg_info: MessageMetadata| {
+                let mut mut_line_state = line_state.lock().unwrap();
+                let Some(header_stamp) = msg.header_stamp() else {
+                    mut_line_state.error = Some("no std_msgs/Header".to_string());
+                    return;
+                };
+                let as_secs = |time: SystemTime| {
+                    time.duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs_f64()
+                };
+                let received = as_secs(msg_info.received_time);
+                // Time going backwards means a recording was seeked, start over.
+                if mut_line_state
+                    .plot
+                    .last()
+                    .is_some_and(|&(last, _)| received < last)
+                {
+                    mut_line_state.plot.clear();
+                }
+                // Negative when the clocks of the publisher and splinter disagree
+                let delay = (received - as_secs(header_stamp)) * 1000.0;
+                mut_line_state.plot.push((received, delay));
+            },
+        );
+        match subscription {
+            Ok(subscription) => self._subscriptions.push(subscription),
+            Err(e) => line_state_copy.lock().unwrap().error = Some(e),
+        }
+        self.lines.push(line_state_copy);
+    }
+}
+
+impl TuiView for DelayPlotState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return event;
+            }
+            match key_event.code {
+                KeyCode::Char('+') => {
+                    self.max_duration += 1.0; // Increase the maximum duration
+                    Event::None
+                }
+                KeyCode::Char('-') => {
+                    if self.max_duration > 1.0 {
+                        self.max_duration -= 1.0; // Decrease the maximum duration
+                    }
+                    Event::None
+                }
+                _ => event,
+            }
+        } else {
+            event
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("Delay - {}s", self.max_duration)
+    }
+
+    fn get_help_text(&self) -> String {
+        "Delay Plot View Help:\n\
+        - '+': Increase the time window for the delay plot.\n\
+        - '-': Decrease the time window for the delay plot.\n\
+        \n\
+        The delay of a message is its receive time minus the stamp of its header, the legend \
+        shows the current, mean and max delay over the time window."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        DelayPlotWidget::render(area, buf, self);
+    }
+
+    fn as_topic_acceptor(&mut self) -> Option<&mut dyn AcceptsTopic> {
+        Some(self)
+    }
+}
+
+impl FromTopic for DelayPlotState {
+    fn from_topic(topic_info: TopicInfo) -> Self {
+        DelayPlotState::new(topic_info.topic, topic_info.connection)
+    }
+}
+
+impl AcceptsTopic for DelayPlotState {
+    fn accepts_topic(&mut self, topic_info: TopicInfo) {
+        self.add_line(topic_info.topic, topic_info.connection);
+    }
+}
+
+impl DelayPlotWidget {
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut DelayPlotState) {
+        let current_time = state
+            .connection
+            .borrow()
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Timestamp before UNIX EPOCH")
+            .as_secs_f64();
+        for line in &state.lines {
+            // Ensure the plot does not exceed the maximum duration
+            let mut delay_line = line.lock().unwrap();
+            delay_line
+                .plot
+                .retain(|&(stamp, _)| current_time - stamp <= state.max_duration);
+        }
+
+        let block = Block::bordered()
+            .title(Line::raw("Delay Plot").centered())
+            .border_style(HEADER_STYLE);
+
+        // Find the overall bounds for Y axis, delays can be negative
+        let (mut min_y, mut max_y) = (0.0_f64, f64::MIN);
+        for line in &state.lines {
+            let delay_line = line.lock().unwrap();
+            for &(_, y) in delay_line.plot.iter() {
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
+        }
+        if max_y == f64::MIN {
+            max_y = 10.0;
+        }
+        if (max_y - min_y).abs() < 0.001 {
+            max_y += 1.0;
+        }
+
+        let bindings = state
+            .lines
+            .iter()
+            .map(|line| {
+                let delay_line = line.lock().unwrap();
+                (delay_line.legend(), delay_line.plot.clone())
+            })
+            .collect::<Vec<_>>();
+        let datasets = bindings
+            .iter()
+            .enumerate()
+            .map(|(i, (legend, plot))| {
+                Dataset::default()
+                    .name(legend.clone())
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(match i % 6 {
+                        0 => Color::Red,
+                        1 => Color::Green,
+                        2 => Color::Yellow,
+                        3 => Color::Blue,
+                        4 => Color::Magenta,
+                        _ => Color::Cyan,
+                    }))
+                    .data(plot)
+            })
+            .collect::<Vec<_>>();
+
+        let x_axis = Axis::default()
+            .style(Style::default().white())
+            .bounds([current_time - state.max_duration, current_time])
+            .labels(
+                (0..=5)
+                    .rev()
+                    .map(|i| format!("{:.1}", current_time - i as f64 * state.max_duration / 5.0))
+                    .collect::<Vec<_>>(),
+            );
+
+        // Create the Y axis and define its properties
+        let y_axis = Axis::default()
+            .title("ms")
+            .style(Style::default().white())
+            .bounds([min_y, max_y])
+            .labels(
+                (0..=5)
+                    .map(|i| format!("{:.1}", min_y + i as f64 * (max_y - min_y) / 5.0))
+                    .collect::<Vec<_>>(),
+            );
+
+        let chart = Chart::new(datasets)
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .show_grid(true)
+            .block(block);
+
+        Widget::render(chart, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::views::test_utils::{emit_at, mock_connection};
+
+    const CONFIG: &str = "\
+definitions:
+  std_msgs/msg/String: string data
+  sensor_msgs/msg/Temperature: |
+    std_msgs/Header header
+    float64 temperature
+    ================================================================================
+    MSG: std_msgs/Header
+    builtin_interfaces/Time stamp
+    string frame_id
+    ================================================================================
+    MSG: builtin_interfaces/Time
+    int32 sec
+    uint32 nanosec
+topics:
+  - { name: /chatter, type: std_msgs/msg/String, messages: [{ data: hello }] }
+  - name: /temperature
+    type: sensor_msgs/msg/Temperature
+    messages: [{ header: { stamp: { sec: 100 } }, temperature: 20.0 }]
+";
+
+    fn add_topic(view: &mut DelayPlotState, topic: &str) {
+        view.accepts_topic(TopicInfo {
+            topic: topic.to_string(),
+            type_name: view.connection.borrow().get_topic_type("/chatter").unwrap(),
+            connection: view.connection.clone(),
+            qos: None,
+        });
+    }
+
+    #[test]
+    fn test_delays() {
+        let connection = mock_connection(CONFIG);
+        let mut view = DelayPlotState::new("/temperature".to_string(), connection.clone());
+        add_topic(&mut view, "/chatter");
+        add_topic(&mut view, "/unknown");
+
+        // Messages stamped at 100 s
+        let at = |millis: u64| SystemTime::UNIX_EPOCH + Duration::from_millis(100_000 + millis);
+        emit_at(&connection, "/temperature", at(250));
+        emit_at(&connection, "/temperature", at(500));
+        emit_at(&connection, "/chatter", at(500));
+        {
+            let line = view.lines[0].lock().unwrap();
+            let delays = line
+                .plot
+                .iter()
+                .map(|&(_, delay)| delay)
+                .collect::<Vec<_>>();
+            assert_eq!(delays.len(), 2);
+            assert!((delays[0] - 250.0).abs() < 1e-3);
+            assert!((delays[1] - 500.0).abs() < 1e-3);
+            assert_eq!(
+                line.legend(),
+                "/temperature cur 500.0 mean 375.0 max 500.0 ms"
+            );
+        }
+        let chatter = view.lines[1].lock().unwrap();
+        assert!(chatter.plot.is_empty());
+        assert_eq!(chatter.error.as_deref(), Some("no std_msgs/Header"));
+        drop(chatter);
+        assert_eq!(
+            view.lines[2].lock().unwrap().error.as_deref(),
+            Some("Unknown topic '/unknown'")
+        );
+
+        // Time going backwards starts over
+        emit_at(&connection, "/temperature", at(100));
+        let line = view.lines[0].lock().unwrap();
+        assert_eq!(line.plot.len(), 1);
+        assert!((line.plot[0].1 - 100.0).abs() < 1e-3);
+    }
+}
@@ -7,68 +7,92 @@ use std::{
 };
 
 use ratatui::{
+    layout::{Constraint, Layout},
     prelude::{Buffer, Rect, Style, Stylize},
+    style::Color,
     symbols::Marker,
-    text::Line,
-    widgets::{Axis, Block, Chart, Dataset, GraphType, Widget},
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Widget},
 };
 
 use crate::{
     common::{
         event::Event,
         generic_message::{GenericMessage, MessageMetadata},
+        rate::RateWindow,
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{Connection, ConnectionType, SubscriptionHandle},
     // generic_message::{GenericField, GenericMessage},
     views::{AcceptsTopic, FromTopic, TopicInfo, TuiView},
 };
 
 use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
 
+/// Number of messages the rate is computed over, changed with '[' and ']'.
+const WINDOW_LENGTHS: [usize; 8] = [5, 10, 20, 50, 100, 200, 500, 1000];
+/// Default window, 10 messages.
+const DEFAULT_WINDOW: usize = 1;
+
+const DEFAULT_GAP_FACTOR: f64 = 2.0;
+const MIN_GAP_FACTOR: f64 = 1.5;
+
 pub struct HzPlotWidget;
 
 pub struct HzLineState {
     topic: String,
 
-    stamps: Vec<f64>,
+    rate: RateWindow,
     plot: Vec<(f64, f64)>, // Stores the plots for each field
 }
 
 impl HzLineState {
-    fn new(topic: String) -> Self {
+    fn new(topic: String, window: usize, gap_factor: f64) -> Self {
         Self {
             topic,
-            stamps: Vec::new(),
+            rate: RateWindow::new(window, gap_factor),
             plot: Vec::new(),
         }
     }
 }
 
 pub struct HzPlotState {
-    _connection: Rc<RefCell<ConnectionType>>,
+    connection: Rc<RefCell<ConnectionType>>,
     lines: Vec<Arc<Mutex<HzLineState>>>,
+    _subscriptions: Vec<SubscriptionHandle>,
     max_duration: f64, // Maximum duration for the plot
+    /// Index in `WINDOW_LENGTHS`.
+    window: usize,
+    /// Intervals longer than this many mean periods are gaps.
+    gap_factor: f64,
 }
 
 impl HzPlotState {
     pub fn new(topic: String, connection: Rc<RefCell<ConnectionType>>) -> Self {
         let mut state = Self {
             lines: vec![],
+            _subscriptions: vec![],
             max_duration: 10.0, // Default maximum duration for the plot
-            _connection: connection,
+            window: DEFAULT_WINDOW,
+            gap_factor: DEFAULT_GAP_FACTOR,
+            connection,
         };
-        state.add_line(topic, state._connection.clone());
+        state.add_line(topic, state.connection.clone());
         state
     }
 
     pub fn add_line(&mut self, topic: String, connection: Rc<RefCell<ConnectionType>>) {
-        let line_state = Arc::new(Mutex::new(HzLineState::new(topic.clone())));
+        let line_state = Arc::new(Mutex::new(HzLineState::new(
+            topic.clone(),
+            WINDOW_LENGTHS[self.window],
+            self.gap_factor,
+        )));
         let line_state_copy = line_state.clone();
-        connection
+        let subscription = connection
             .borrow_mut()
             .subscribe(
                 &topic,
+                None,
                 move |_: GenericMessage, msg_info: MessageMetadata| {
                     let mut mut_line_state = line_state.lock().unwrap();
                     let stamp = msg_info
@@ -76,24 +100,37 @@ impl HzPlotState {
                         .duration_since(SystemTime::UNIX_EPOCH)
                         .expect("Timestamp before UNIX EPOCH")
                         .as_secs_f64();
-                    mut_line_state.stamps.push(stamp);
-                    // TODO: Make WINDOW_LENGTH configurable
-                    static WINDOW_LENGTH: usize = 10; // Number of stamps to consider for frequency calculation
-                    if mut_line_state.stamps.len() > WINDOW_LENGTH {
-                        mut_line_state.stamps.remove(0);
+                    // Time going backwards means a recording was seeked, start over.
+                    if mut_line_state
+                        .rate
+                        .last_stamp()
+                        .is_some_and(|last| stamp < last)
+                    {
+                        mut_line_state.rate.clear();
+                        mut_line_state.plot.clear();
                     }
-                    if mut_line_state.stamps.len() == WINDOW_LENGTH {
-                        let duration =
-                            mut_line_state.stamps[WINDOW_LENGTH - 1] - mut_line_state.stamps[0];
-                        if duration > 0.0 {
-                            let frequency = (WINDOW_LENGTH - 1) as f64 / duration;
-                            mut_line_state.plot.push((stamp, frequency));
-                        }
+                    if let Some(frequency) = mut_line_state.rate.push(stamp) {
+                        mut_line_state.plot.push((stamp, frequency));
                     }
                 },
             )
             .expect("Failed to subscribe to topic");
         self.lines.push(line_state_copy);
+        self._subscriptions.push(subscription);
+    }
+
+    fn set_window(&mut self, window: usize) {
+        self.window = window;
+        for line in &self.lines {
+            line.lock().unwrap().rate.set_window(WINDOW_LENGTHS[window]);
+        }
+    }
+
+    fn set_gap_factor(&mut self, gap_factor: f64) {
+        self.gap_factor = gap_factor;
+        for line in &self.lines {
+            line.lock().unwrap().rate.set_gap_factor(gap_factor);
+        }
     }
 }
 
@@ -114,6 +151,22 @@ impl TuiView for HzPlotState {
                     }
                     Event::None
                 }
+                KeyCode::Char('W') => {
+                    self.set_window((self.window + 1).min(WINDOW_LENGTHS.len() - 1));
+                    Event::None
+                }
+                KeyCode::Char('w') => {
+                    self.set_window(self.window.saturating_sub(1));
+                    Event::None
+                }
+                KeyCode::Char('T') => {
+                    self.set_gap_factor(self.gap_factor + 0.5);
+                    Event::None
+                }
+                KeyCode::Char('t') => {
+                    self.set_gap_factor((self.gap_factor - 0.5).max(MIN_GAP_FACTOR));
+                    Event::None
+                }
                 _ => event,
             }
         } else {
@@ -122,13 +175,25 @@ impl TuiView for HzPlotState {
     }
 
     fn name(&self) -> String {
-        format!("Frequency - {}s - window: 10 msg", self.max_duration)
+        format!(
+            "Frequency - {}s - window: {} msg",
+            self.max_duration, WINDOW_LENGTHS[self.window]
+        )
     }
 
     fn get_help_text(&self) -> String {
         "Hz Plot View Help:\n\
         - '+': Increase the time window for the frequency plot.\n\
-        - '-': Decrease the time window for the frequency plot."
+        - '-': Decrease the time window for the frequency plot.\n\
+        - 'W': Compute the rate over more messages.\n\
+        - 'w': Compute the rate over fewer messages.\n\
+        - 'T': Raise the gap threshold.\n\
+        - 't': Lower the gap threshold.\n\
+        \n\
+        The table shows the rate and the mean, min and max period of the messages in the window, \
+        their standard deviation and the jitter, the mean difference between consecutive periods. \
+        An interval longer than the gap threshold times the mean period is a dropout, it is \
+        boxed on the plot."
             .to_string()
     }
 
@@ -157,20 +222,44 @@ impl AcceptsTopic for HzPlotState {
     }
 }
 
+fn line_color(i: usize) -> Color {
+    match i % 6 {
+        0 => Color::Red,
+        1 => Color::Green,
+        2 => Color::Yellow,
+        3 => Color::Blue,
+        4 => Color::Magenta,
+        _ => Color::Cyan,
+    }
+}
+
 impl HzPlotWidget {
     pub fn render(area: Rect, buf: &mut Buffer, state: &mut HzPlotState) {
+        let current_time = state
+            .connection
+            .borrow()
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Timestamp before UNIX EPOCH")
+            .as_secs_f64();
         for line in &state.lines {
             // Ensure the plot does not exceed the maximum duration
             let mut hz_line = line.lock().unwrap();
-            let current_time = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .expect("Timestamp before UNIX EPOCH")
-                .as_secs_f64();
             hz_line
                 .plot
                 .retain(|&(stamp, _)| current_time - stamp <= state.max_duration);
+            hz_line
+                .rate
+                .gaps
+                .retain(|&(_, end)| current_time - end <= state.max_duration);
         }
 
+        let [chart_area, stats_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(state.lines.len() as u16 + 2),
+        ])
+        .areas(area);
+
         let block = Block::bordered()
             .title(Line::raw("Frequency Plot").centered())
             .border_style(HEADER_STYLE);
@@ -197,29 +286,43 @@ impl HzPlotWidget {
             .iter()
             .map(|hz_line| hz_line.lock().unwrap().plot.clone())
             .collect::<Vec<_>>();
-        let datasets = zip(state.lines.iter(), bindings.iter())
+        // Each gap is boxed, from the bottom to the top of the plot
+        let gaps = state
+            .lines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                let hz_line = line.lock().unwrap();
+                hz_line
+                    .rate
+                    .gaps
+                    .iter()
+                    .map(|&(start, end)| {
+                        let start = start.max(current_time - state.max_duration);
+                        (i, [(start, 0.0), (start, max_y), (end, max_y), (end, 0.0)])
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut datasets = zip(state.lines.iter(), bindings.iter())
             .enumerate()
             .map(|(i, (line, plot))| {
                 Dataset::default()
                     .name(line.lock().unwrap().topic.to_string())
                     .marker(Marker::Braille)
                     .graph_type(GraphType::Line)
-                    .style(Style::default().fg(match i % 6 {
-                        0 => ratatui::style::Color::Red,
-                        1 => ratatui::style::Color::Green,
-                        2 => ratatui::style::Color::Yellow,
-                        3 => ratatui::style::Color::Blue,
-                        4 => ratatui::style::Color::Magenta,
-                        _ => ratatui::style::Color::Cyan,
-                    }))
+                    .style(Style::default().fg(line_color(i)))
                     .data(plot)
             })
             .collect::<Vec<_>>();
+        datasets.extend(gaps.iter().map(|(i, gap)| {
+            Dataset::default()
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(line_color(*i)))
+                .data(gap)
+        }));
 
-        let current_time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Timestamp before UNIX EPOCH")
-            .as_secs_f64();
         let x_axis = Axis::default()
             .style(Style::default().white())
             .bounds([current_time - state.max_duration, current_time])
@@ -246,6 +349,144 @@ impl HzPlotWidget {
             .show_grid(true)
             .block(block);
 
-        Widget::render(chart, area, buf);
+        Widget::render(chart, chart_area, buf);
+
+        Self::render_stats(stats_area, buf, state);
+    }
+
+    /// Table of the period statistics of each line, like `ros2 topic hz`.
+    fn render_stats(area: Rect, buf: &mut Buffer, state: &HzPlotState) {
+        let topic_width = state
+            .lines
+            .iter()
+            .map(|line| line.lock().unwrap().topic.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Topic".len());
+        let header = format!(
+            "{:<topic_width$} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9} {:>8}",
+            "Topic", "Rate Hz", "Mean ms", "Min ms", "Max ms", "Std ms", "Jitter ms", "Dropouts",
+        );
+        let mut lines = vec![Line::styled(header, Style::default().fg(Color::DarkGray))];
+        for (i, line) in state.lines.iter().enumerate() {
+            let hz_line = line.lock().unwrap();
+            let mut spans = vec![Span::styled(
+                format!("{:<topic_width$}", hz_line.topic),
+                Style::default().fg(line_color(i)),
+            )];
+            match hz_line.rate.stats() {
+                Some(stats) => {
+                    spans.push(Span::raw(format!(
+                        " {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+                        stats.rate(),
+                        stats.mean * 1000.0,
+                        stats.min * 1000.0,
+                        stats.max * 1000.0,
+                        stats.std_dev * 1000.0,
+                        stats.jitter * 1000.0,
+                    )));
+                    let dropouts_style = if hz_line.rate.dropouts > 0 {
+                        Style::default().fg(Color::Red)
+                    } else {
+                        Style::default()
+                    };
+                    spans.push(Span::styled(
+                        format!(" {:>8}", hz_line.rate.dropouts),
+                        dropouts_style,
+                    ));
+                }
+                None => spans.push(Span::styled(
+                    " waiting for messages",
+                    Style::default().fg(Color::DarkGray),
+                )),
+            }
+            lines.push(Line::from(spans));
+        }
+        Paragraph::new(lines)
+            .block(
+                Block::new()
+                    .borders(Borders::TOP)
+                    .title(
+                        Line::raw(format!(
+                            "Statistics - gap threshold: {} × mean period",
+                            state.gap_factor
+                        ))
+                        .centered(),
+                    )
+                    .border_style(HEADER_STYLE),
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::views::test_utils::{emit_at, key, mock_connection, render_lines};
+
+    const CONFIG: &str = "\
+definitions:
+  std_msgs/msg/String: string data
+topics:
+  - { name: /chatter, type: std_msgs/msg/String, messages: [{ data: hello }] }
+";
+
+    #[test]
+    fn test_rate_and_gaps() {
+        let connection = mock_connection(CONFIG);
+        let mut view = HzPlotState::new("/chatter".to_string(), connection.clone());
+        // Recent enough to stay on the plot
+        let start = SystemTime::now() - Duration::from_secs(2);
+        let at = |millis: u64| start + Duration::from_millis(millis);
+
+        for i in 0..10 {
+            emit_at(&connection, "/chatter", at(100 * i));
+        }
+        {
+            let line = view.lines[0].lock().unwrap();
+            // The rate is plotted once the window of 10 messages is full
+            assert_eq!(line.plot.len(), 1);
+            assert!((line.plot[0].1 - 10.0).abs() < 1e-3);
+            let stats = line.rate.stats().unwrap();
+            assert!((stats.mean - 0.1).abs() < 1e-6);
+            assert!(stats.jitter < 1e-6);
+            assert_eq!(line.rate.dropouts, 0);
+        }
+
+        // More than twice the mean period is a gap
+        emit_at(&connection, "/chatter", at(1400));
+        render_lines(&mut view, 100, 20);
+        {
+            let line = view.lines[0].lock().unwrap();
+            assert_eq!(line.plot.len(), 2);
+            assert!((line.plot[1].1 - 9.0 / 1.3).abs() < 1e-3);
+            assert_eq!(line.rate.dropouts, 1);
+            let (gap_start, gap_end) = line.rate.gaps[0];
+            assert!((gap_end - gap_start - 0.5).abs() < 1e-6);
+        }
+
+        // Time going backwards starts over
+        emit_at(&connection, "/chatter", at(0));
+        {
+            let line = view.lines[0].lock().unwrap();
+            assert!(line.plot.is_empty());
+            assert!(line.rate.gaps.is_empty());
+            assert_eq!(line.rate.dropouts, 0);
+        }
+
+        view.handle_event(key(KeyCode::Char('T')));
+        assert_eq!(view.gap_factor, DEFAULT_GAP_FACTOR + 0.5);
+        view.handle_event(key(KeyCode::Char('W')));
+        assert_eq!(view.window, DEFAULT_WINDOW + 1);
+        assert_eq!(
+            view.name(),
+            format!(
+                "Frequency - 10s - window: {} msg",
+                WINDOW_LENGTHS[DEFAULT_WINDOW + 1]
+            )
+        );
     }
 }
@@ -0,0 +1,266 @@
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+use ratatui::{
+    prelude::{Buffer, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Widget},
+};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::InterfaceType,
+        message_definition::{
+            find_field_line, installed_definition, strip_comment, DefinitionLine,
+        },
+        style::HEADER_STYLE,
+    },
+    views::{FieldInfo, FromField, FromTopic, TopicInfo, TuiView},
+};
+
+/// Full definition of a message, service or action, as installed in `AMENT_PREFIX_PATH`, with
+/// its nested messages expanded.
+pub struct InterfaceDefinitionState {
+    type_name: InterfaceType,
+    definition: Result<Vec<DefinitionLine>, String>,
+    /// Line of the field the view was opened from.
+    highlight: Option<usize>,
+    /// First line shown, `None` until the first render brings the highlighted line into view.
+    scroll: Option<usize>,
+    /// Lines shown at once, for page up and down.
+    page: usize,
+
+    needs_redraw: bool,
+}
+
+impl InterfaceDefinitionState {
+    pub fn new(type_name: InterfaceType) -> Self {
+        Self {
+            definition: installed_definition(&type_name),
+            type_name,
+            highlight: None,
+            scroll: None,
+            page: 1,
+            needs_redraw: true,
+        }
+    }
+
+    /// Definition of `type_name` with the field at `field`, a path in the message, highlighted.
+    pub fn with_field(type_name: InterfaceType, field: &[usize]) -> Self {
+        let mut state = Self::new(type_name);
+        if let Ok(lines) = &state.definition {
+            state.highlight = find_field_line(lines, field);
+        }
+        state
+    }
+
+    fn line_count(&self) -> usize {
+        self.definition.as_ref().map_or(0, Vec::len)
+    }
+
+    fn scroll_to(&mut self, scroll: usize) {
+        self.scroll = Some(scroll.min(self.line_count().saturating_sub(1)));
+        self.needs_redraw = true;
+    }
+
+    fn to_line(line: &DefinitionLine, highlighted: bool) -> Line<'static> {
+        let code = strip_comment(&line.text);
+        let comment = &line.text[code.len()..];
+        let code_style = if line.constant {
+            Style::default().fg(Color::Yellow)
+        } else if code.trim() == "---" {
+            HEADER_STYLE
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::raw("    ".repeat(line.depth))];
+        match code.trim_start().split_once(char::is_whitespace) {
+            // The type of a field, then its name and default value
+            Some((type_token, rest)) if line.field.is_some() => {
+                let indent = &code[..code.len() - code.trim_start().len()];
+                spans.push(Span::raw(indent.to_string()));
+                spans.push(Span::styled(
+                    type_token.to_string(),
+                    Style::default().fg(Color::Cyan),
+                ));
+                spans.push(Span::raw(format!(" {}", rest)));
+            }
+            _ => spans.push(Span::styled(code.to_string(), code_style)),
+        }
+        spans.push(Span::styled(
+            comment.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ));
+        let line = Line::from(spans);
+        if highlighted {
+            line.style(Style::default().add_modifier(Modifier::REVERSED))
+        } else {
+            line
+        }
+    }
+}
+
+impl TuiView for InterfaceDefinitionState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            if key_event.kind != KeyEventKind::Press {
+                return event;
+            }
+            let scroll = self.scroll.unwrap_or(0);
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.scroll_to(scroll + 1),
+                KeyCode::Char('k') | KeyCode::Up => self.scroll_to(scroll.saturating_sub(1)),
+                KeyCode::PageDown => self.scroll_to(scroll + self.page),
+                KeyCode::PageUp => self.scroll_to(scroll.saturating_sub(self.page)),
+                KeyCode::Home => self.scroll_to(0),
+                KeyCode::Char('G') | KeyCode::End => {
+                    self.scroll_to(self.line_count().saturating_sub(self.page))
+                }
+                _ => return event,
+            }
+            return Event::None;
+        }
+        event
+    }
+
+    fn name(&self) -> String {
+        format!("Interface - {}", self.type_name)
+    }
+
+    fn get_help_text(&self) -> String {
+        "Interface Definition View Help:\n\
+        - 'j' or ↓: Scroll down.\n\
+        - 'k' or ↑: Scroll up.\n\
+        - 'PageDown'/'PageUp': Scroll by a page.\n\
+        - 'Home': Go to the top.\n\
+        - 'G' or 'End': Go to the bottom.\n\
+        \n\
+        Shows the installed .msg, .srv or .action file with the definition of each nested \
+        message indented under the field using it. Constants are in yellow, comments in gray, \
+        and the field the view was opened from is highlighted."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        if self.needs_redraw {
+            self.needs_redraw = false;
+            return true;
+        }
+        false
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(HEADER_STYLE)
+            .title(Line::raw(self.type_name.to_string()).centered());
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        self.page = (inner_area.height as usize).max(1);
+
+        let lines = match &self.definition {
+            Ok(lines) => lines,
+            Err(e) => {
+                Paragraph::new(e.as_str()).render(inner_area, buf);
+                return;
+            }
+        };
+        // Open with the highlighted field a third of the way down
+        let scroll = *self.scroll.get_or_insert_with(|| {
+            self.highlight
+                .map_or(0, |highlight| highlight.saturating_sub(self.page / 3))
+        });
+        let shown = lines
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(self.page)
+            .map(|(index, line)| Self::to_line(line, self.highlight == Some(index)))
+            .collect::<Vec<_>>();
+        Paragraph::new(shown).render(inner_area, buf);
+    }
+}
+
+impl FromTopic for InterfaceDefinitionState {
+    fn from_topic(topic_info: TopicInfo) -> Self {
+        InterfaceDefinitionState::new(topic_info.type_name)
+    }
+}
+
+impl FromField for InterfaceDefinitionState {
+    fn from_field(field_info: FieldInfo) -> Self {
+        InterfaceDefinitionState::with_field(field_info.type_name, &field_info.field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::message_definition::expand_definition;
+    use crate::views::test_utils::{key, render_lines};
+
+    const HEADER: &str = "\
+# Standard metadata
+builtin_interfaces/Time stamp
+string frame_id";
+
+    /// View of `std_msgs/msg/Header` opened from the field at `field`.
+    fn header_view(field: &[usize]) -> InterfaceDefinitionState {
+        let type_name = InterfaceType::new("std_msgs/msg/Header").unwrap();
+        let definition = expand_definition(&type_name, HEADER, &|_| {
+            Ok("int32 sec\nuint32 nanosec".to_string())
+        });
+        InterfaceDefinitionState {
+            type_name,
+            highlight: find_field_line(&definition, field),
+            definition: Ok(definition),
+            scroll: None,
+            page: 1,
+            needs_redraw: true,
+        }
+    }
+
+    #[test]
+    fn test_highlight_and_scroll() {
+        // The nanosec field of the stamp, in the expanded Time message
+        let mut view = header_view(&[0, 1]);
+        assert_eq!(view.highlight, Some(3));
+        assert_eq!(header_view(&[1]).highlight, Some(4));
+        assert_eq!(header_view(&[]).highlight, None);
+
+        // Opens with the highlighted field a third of the way down the 4 lines shown
+        render_lines(&mut view, 60, 6);
+        assert_eq!(view.page, 4);
+        assert_eq!(view.scroll, Some(2));
+
+        assert!(matches!(view.handle_event(key(KeyCode::Home)), Event::None));
+        assert_eq!(view.scroll, Some(0));
+        // The last page ends on the last line
+        view.handle_event(key(KeyCode::End));
+        assert_eq!(view.scroll, Some(1));
+        // Scrolling stops on the last line
+        for _ in 0..5 {
+            view.handle_event(key(KeyCode::Char('j')));
+        }
+        assert_eq!(view.scroll, Some(4));
+        view.handle_event(key(KeyCode::PageUp));
+        assert_eq!(view.scroll, Some(0));
+
+        // 'g' is left to the playback seek
+        assert!(matches!(
+            view.handle_event(key(KeyCode::Char('g'))),
+            Event::Key(_)
+        ));
+    }
+
+    #[test]
+    fn test_missing_definition() {
+        let mut view = header_view(&[]);
+        view.definition = Err("Interface definition for 'std_msgs/msg/Header' not found".into());
+        render_lines(&mut view, 80, 4);
+        assert_eq!(view.line_count(), 0);
+        view.handle_event(key(KeyCode::End));
+        assert_eq!(view.scroll, Some(0));
+    }
+}
@@ -20,7 +20,7 @@ use crate::{
         generic_message::{AnyTypeRef, GenericMessage, Length, MessageMetadata},
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{Connection, ConnectionType, SubscriptionHandle},
     // generic_message::{GenericField, GenericMessage},
     views::{AcceptsField, FieldInfo, FieldInfoType, FromField, TuiView},
 };
@@ -32,8 +32,9 @@ pub struct LivePlotWidget;
 pub struct GraphLineState {
     topic: String,
     field_name: String,
-    _connection: Rc<RefCell<ConnectionType>>,
+    connection: Rc<RefCell<ConnectionType>>,
     plot: Arc<Mutex<Vec<(f64, f64)>>>, // Stores the plots for each field
+    _subscription: SubscriptionHandle,
 }
 
 pub struct LivePlotState {
@@ -66,10 +67,11 @@ impl LivePlotState {
         let plot = Arc::new(Mutex::new(Vec::new()));
         let plot_copy = plot.clone();
         let selected_fields_copy = selected_fields.clone();
-        connection
+        let subscription = connection
             .borrow_mut()
             .subscribe(
                 &topic,
+                None,
                 move |msg: GenericMessage, msg_info: MessageMetadata| {
                     let mut mut_plot = plot_copy.lock().unwrap();
                     let stamp = msg_info
@@ -77,6 +79,10 @@ impl LivePlotState {
                         .duration_since(SystemTime::UNIX_EPOCH)
                         .expect("Timestamp before UNIX EPOCH")
                         .as_secs_f64();
+                    // Time going backwards means a recording was seeked, start over.
+                    if mut_plot.last().is_some_and(|&(last, _)| stamp < last) {
+                        mut_plot.clear();
+                    }
                     if let Some(value) = get_field(&msg, &selected_fields_copy) {
                         mut_plot.push((stamp, value));
                     }
@@ -86,8 +92,9 @@ impl LivePlotState {
         self.lines.push(GraphLineState {
             topic,
             field_name,
-            _connection: connection,
+            connection,
             plot,
+            _subscription: subscription,
         });
     }
 }
@@ -199,7 +206,10 @@ impl AcceptsField for LivePlotState {
 
 impl LivePlotWidget {
     pub fn render(area: Rect, buf: &mut Buffer, state: &mut LivePlotState) {
-        let current_time = SystemTime::now()
+        let current_time = state.lines[0]
+            .connection
+            .borrow()
+            .now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("Timestamp before UNIX EPOCH")
             .as_secs_f64();
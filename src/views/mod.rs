@@ -14,18 +14,31 @@ use crate::{
         style::SELECTED_STYLE,
         utils::{build_highlighted_spans, truncate_namespaces},
     },
-    connections::{ConnectionType, NamedInterface, NodeName},
+    connections::{
+        lifecycle::LifecycleTransition, logger_levels::LoggerLevel, qos::QosProfile,
+        ConnectionType, NamedInterface, NodeName, TopicEndpointInfo,
+    },
     widgets::list_widget::ListItemTrait,
 };
 
+pub mod action_goal;
+pub mod action_list;
+pub mod bitrate_plot;
+pub mod delay_plot;
 pub mod hz_plot;
+pub mod interface_definition;
 pub mod live_plot;
 pub mod message_pane;
 pub mod node_details;
 pub mod node_list;
+pub mod parameter_events;
 pub mod raw_message;
+pub mod rosout;
 pub mod service_call;
 pub mod state_graph;
+#[cfg(test)]
+pub mod test_utils;
+pub mod tf_tree;
 pub mod topic_graph;
 pub mod topic_list;
 pub mod topic_publisher;
@@ -88,6 +101,9 @@ pub struct TopicInfo {
     pub connection: Rc<RefCell<ConnectionType>>,
     pub topic: String,
     pub type_name: InterfaceType,
+    /// QoS picked for the views subscribing or publishing to the topic, `None` to match the
+    /// existing publishers.
+    pub qos: Option<QosProfile>,
 }
 
 pub trait FromTopic: TuiView {
@@ -191,3 +207,89 @@ impl ListItemTrait for NamedInterface {
         line
     }
 }
+
+impl ListItemTrait for LifecycleTransition {
+    fn search_text(&self) -> String {
+        self.label.clone()
+    }
+
+    fn to_line(&self, _width: usize, selected: bool, indices: Vec<u32>) -> Line<'_> {
+        let mut spans = build_highlighted_spans(self.label.clone(), indices);
+        spans.push(Span::styled(
+            format!(" → {}", self.goal_state),
+            Style::default().fg(Color::DarkGray),
+        ));
+        let mut line = Line::from(spans);
+        if selected {
+            line = line.set_style(SELECTED_STYLE);
+        }
+        line
+    }
+}
+
+impl ListItemTrait for LoggerLevel {
+    fn search_text(&self) -> String {
+        self.name.clone()
+    }
+
+    fn to_line(&self, _width: usize, selected: bool, indices: Vec<u32>) -> Line<'_> {
+        let mut spans = build_highlighted_spans(self.name.clone(), indices);
+        spans.push(Span::raw(" "));
+        spans.push(match self.level {
+            Some(level) => Span::styled(level.label(), rosout::level_style(level)),
+            None => Span::styled("unset", Style::default().fg(Color::DarkGray)),
+        });
+        let mut line = Line::from(spans);
+        if selected {
+            line = line.set_style(SELECTED_STYLE);
+        }
+        line
+    }
+}
+
+// ─── Topic endpoints ─────────────────────────────────────────────────────────
+
+/// Describe a publisher or a subscription of a topic, one line per piece of information the
+/// connection reports, followed by the endpoints of the other side it can't communicate with.
+pub fn endpoint_details(
+    info: &TopicEndpointInfo,
+    incompatibilities: &[(NodeName, Vec<String>)],
+) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("{:<10}", label),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![field("Node", info.node_name.full_name())];
+    if let Some(topic_type) = &info.topic_type {
+        lines.push(field("Type", topic_type.to_string()));
+    }
+    if let Some(type_hash) = &info.type_hash {
+        lines.push(field("Type hash", type_hash.clone()));
+    }
+    if let Some(gid) = info.gid_string() {
+        lines.push(field("GID", gid));
+    }
+    lines.push(field(
+        "QoS",
+        info.qos
+            .map_or("not reported by the connection".to_string(), |qos| {
+                qos.to_string()
+            }),
+    ));
+    for (node_name, reasons) in incompatibilities {
+        lines.push(Line::styled(
+            format!(
+                "⚠ Incompatible with {}: {}",
+                node_name.full_name(),
+                reasons.join(", ")
+            ),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    lines
+}
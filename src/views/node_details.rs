@@ -1,10 +1,19 @@
-use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use ratatui::{
-    prelude::{Buffer, Rect},
+    prelude::{Buffer, Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, StatefulWidget, Widget},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
 };
 
 use crossterm::event::{Event as CrosstermEvent, KeyCode};
@@ -12,23 +21,66 @@ use crossterm::event::{Event as CrosstermEvent, KeyCode};
 use crate::{
     common::{
         event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
         style::{HEADER_STYLE, SELECTED_STYLE},
     },
-    connections::{Connection, ConnectionType, NamedInterface, NodeName, Parameters},
-    views::{service_call::ServiceCallState, AcceptsNode, FromNode, NodeInfo, TopicInfo, TuiView},
+    connections::{
+        lifecycle::{
+            available_transitions, call_lifecycle_service, change_state_request,
+            change_state_succeeded, current_state, lifecycle_service, lifecycle_service_type,
+            LifecycleTransition, CHANGE_STATE, GET_AVAILABLE_TRANSITIONS, GET_STATE,
+        },
+        logger_levels::{
+            call_logger_service, get_logger_levels_request, logger_levels, logger_service,
+            logger_service_type, set_logger_levels_request, set_logger_levels_result, LoggerLevel,
+            GET_LOGGER_LEVELS, SET_LOGGER_LEVELS,
+        },
+        parameter_descriptor::ParameterDescriptor,
+        parameter_events::{parameter_event, PARAMETER_EVENTS},
+        parameter_file::{
+            diff_parameters, dump_parameters, load_parameters, parameter_file_path, ParameterChange,
+        },
+        pending::{spinner, PendingCall},
+        qos::incompatible_pairs,
+        rosout::{log_entry, logger_name, LogLevel, ROSOUT},
+        Connection, ConnectionType, NamedInterface, NodeName, Parameters, SubscriptionHandle,
+        TopicEndpointInfo,
+    },
+    views::{
+        endpoint_details, interface_definition::InterfaceDefinitionState, rosout::RosoutState,
+        service_call::ServiceCallState, AcceptsNode, FromNode, NodeInfo, TopicInfo, TuiView,
+    },
     widgets::{
+        array_editor_widget::ArrayEditorState,
         list_widget::{ListWidget, ListWidgetState},
         parameter_list_widget::ParameterListWidget,
     },
 };
 
+/// How often the parameters of the node are fetched again.
+const PARAMETER_REFRESH: Duration = Duration::from_secs(1);
+
+/// How often the parameters are fetched again when `/parameter_events` reports their changes,
+/// in case an event was missed.
+const PARAMETER_EVENTS_REFRESH: Duration = Duration::from_secs(30);
+
+/// How often the lifecycle state of a managed node is fetched again.
+const LIFECYCLE_REFRESH: Duration = Duration::from_secs(1);
+
+/// How often the logger levels of a node are fetched again.
+const LOGGER_REFRESH: Duration = Duration::from_secs(1);
+
+type ServiceCall = PendingCall<(GenericMessage, Vec<String>)>;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DetailSection {
+    Lifecycle,
     Publishers,
     Subscribers,
     Clients,
     Services,
     Parameters,
+    Loggers,
 }
 
 enum MainDetailSection {
@@ -40,15 +92,24 @@ enum MainDetailSection {
 enum ParameterListMode {
     Normal,
     Editing(String), // Current edit string
+    ArrayEditing(ArrayEditorState),
+    /// Changes loaded from a parameter file, waiting to be applied.
+    Reviewing(Vec<ParameterChange>),
 }
 
-#[derive(Clone)]
 struct ParameterListView {
     connection: Rc<RefCell<ConnectionType>>,
     node: NodeName,
     parameters: BTreeMap<String, Parameters>,
     selected: Option<usize>,
     mode: ParameterListMode,
+    /// Name of the parameter being set, and the call setting it.
+    pending_set: Option<(String, PendingCall<()>)>,
+    /// Changes from a parameter file still to set, one after the other.
+    pending_loads: VecDeque<ParameterChange>,
+    descriptors: HashMap<String, ParameterDescriptor>,
+    /// Why the node rejected the last value set, by parameter.
+    errors: HashMap<String, String>,
 }
 
 impl ParameterListView {
@@ -64,6 +125,10 @@ impl ParameterListView {
             parameters,
             selected,
             mode: ParameterListMode::Normal,
+            pending_set: None,
+            pending_loads: VecDeque::new(),
+            descriptors: HashMap::new(),
+            errors: HashMap::new(),
         }
     }
 
@@ -160,18 +225,39 @@ impl ParameterListView {
                 Parameters::Double(float_value)
             }
             Parameters::String(_) => Parameters::String(new_value),
-            Parameters::ByteArray(_) => Err("Editing byte array parameters is not supported")?,
-            Parameters::BoolArray(_) => Err("Editing bool array parameters is not supported")?,
-            Parameters::IntegerArray(_) => {
-                Err("Editing integer array parameters is not supported")?
-            }
-            Parameters::DoubleArray(_) => Err("Editing double array parameters is not supported")?,
-            Parameters::StringArray(_) => Err("Editing string array parameters is not supported")?,
+            Parameters::ByteArray(_)
+            | Parameters::BoolArray(_)
+            | Parameters::IntegerArray(_)
+            | Parameters::DoubleArray(_)
+            | Parameters::StringArray(_) => Err("Arrays are edited in the array editor")?,
         };
+        self.set_parameter(param_name, new_param)
+    }
 
-        self.connection
-            .borrow_mut()
-            .set_parameter_by_node(&self.node, &param_name, new_param)?;
+    fn commit_array_edit(&mut self) -> Result<(), String> {
+        let ParameterListMode::ArrayEditing(editor) = &self.mode else {
+            Err("Not in array editing mode")?
+        };
+        let new_param = editor.to_parameter()?;
+        let param_name = self
+            .selected
+            .and_then(|selected| self.parameters.keys().nth(selected))
+            .cloned()
+            .ok_or("No parameter selected")?;
+        self.set_parameter(param_name, new_param)
+    }
+
+    /// Validate `new_param` against the descriptor of the parameter, then send it to the node.
+    fn set_parameter(&mut self, param_name: String, new_param: Parameters) -> Result<(), String> {
+        if let Some(descriptor) = self.descriptors.get(&param_name) {
+            descriptor.validate(&new_param)?;
+        }
+
+        let pending =
+            self.connection
+                .borrow_mut()
+                .set_parameter_by_node(&self.node, &param_name, new_param);
+        self.pending_set = Some((param_name, pending));
         self.mode = ParameterListMode::Normal;
         Ok(())
     }
@@ -192,43 +278,39 @@ impl ParameterListView {
                     // Enter edit mode if a parameter is selected
                     if let Some(selected) = self.selected {
                         if let Some(param_name) = self.parameters.keys().nth(selected) {
+                            if self
+                                .descriptors
+                                .get(param_name)
+                                .is_some_and(|descriptor| descriptor.read_only)
+                            {
+                                return Event::Error(format!(
+                                    "Parameter '{}' is read-only",
+                                    param_name
+                                ));
+                            }
                             if let Some(param) = self.parameters.get(param_name) {
-                                self.mode = ParameterListMode::Editing(match param {
-                                    Parameters::Bool(value) => value.to_string(),
-                                    Parameters::Integer(value) => value.to_string(),
-                                    Parameters::Double(value) => value.to_string(),
-                                    Parameters::String(value) => value.clone(),
-                                    Parameters::ByteArray(_) => {
-                                        return Event::Error(
-                                            "Editing byte array parameters is not supported"
-                                                .to_string(),
-                                        );
+                                self.mode = match param {
+                                    Parameters::Bool(value) => {
+                                        ParameterListMode::Editing(value.to_string())
                                     }
-                                    Parameters::BoolArray(_) => {
-                                        return Event::Error(
-                                            "Editing bool array parameters is not supported"
-                                                .to_string(),
-                                        );
+                                    Parameters::Integer(value) => {
+                                        ParameterListMode::Editing(value.to_string())
                                     }
-                                    Parameters::IntegerArray(_) => {
-                                        return Event::Error(
-                                            "Editing integer array parameters is not supported"
-                                                .to_string(),
-                                        );
+                                    Parameters::Double(value) => {
+                                        ParameterListMode::Editing(value.to_string())
                                     }
-                                    Parameters::DoubleArray(_) => {
-                                        return Event::Error(
-                                            "Editing double array parameters is not supported"
-                                                .to_string(),
-                                        );
+                                    Parameters::String(value) => {
+                                        ParameterListMode::Editing(value.clone())
                                     }
-                                    Parameters::StringArray(_) => {
-                                        return Event::Error(
-                                            "Editing string array parameters is not supported"
-                                                .to_string(),
-                                        );
-                                    }
-                                });
+                                    array => match ArrayEditorState::new(array) {
+                                        Some(editor) => ParameterListMode::ArrayEditing(editor),
+                                        None => {
+                                            return Event::Error(
+                                                "Unsupported parameter type".to_string(),
+                                            )
+                                        }
+                                    },
+                                };
                                 return Event::None;
                             } else {
                                 return Event::Error("Selected parameter not found".to_string());
@@ -297,14 +379,177 @@ impl ParameterListView {
         event
     }
 
+    fn handle_event_in_array_editing(&mut self, event: Event) -> Event {
+        let ParameterListMode::ArrayEditing(editor) = &mut self.mode else {
+            return event;
+        };
+        let event = editor.handle_event(event);
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            match key_event.code {
+                KeyCode::Char('s') => {
+                    if let Err(err) = self.commit_array_edit() {
+                        return Event::Error(format!("Failed to set parameter: {}", err));
+                    }
+                    return Event::None;
+                }
+                KeyCode::Esc => {
+                    self.mode = ParameterListMode::Normal;
+                    return Event::None;
+                }
+                // Keep the other letters from moving between sections
+                KeyCode::Char(_) => return Event::None,
+                _ => {}
+            }
+        }
+        event
+    }
+
+    /// Send the next change loaded from a file. Changes the descriptor refuses are marked as
+    /// errors and skipped.
+    fn set_next_loaded(&mut self) {
+        while self.pending_set.is_none() {
+            let Some(change) = self.pending_loads.pop_front() else {
+                return;
+            };
+            if let Err(err) = self.set_parameter(change.name.clone(), change.new) {
+                self.errors.insert(change.name, err);
+            }
+        }
+    }
+
+    fn handle_event_in_reviewing(&mut self, event: Event) -> Event {
+        if let Event::Key(CrosstermEvent::Key(key_event)) = event {
+            match key_event.code {
+                KeyCode::Char('y') => {
+                    if let ParameterListMode::Reviewing(changes) =
+                        std::mem::replace(&mut self.mode, ParameterListMode::Normal)
+                    {
+                        self.pending_loads = changes.into();
+                        self.set_next_loaded();
+                    }
+                }
+                KeyCode::Esc => self.mode = ParameterListMode::Normal,
+                _ => {}
+            }
+            // Nothing else happens until the changes are applied or dropped
+            return Event::None;
+        }
+        event
+    }
+
     fn handle_event(&mut self, event: Event) -> Event {
         match &self.mode {
             ParameterListMode::Normal => self.handle_event_in_normal(event),
             ParameterListMode::Editing(_) => self.handle_event_in_editing(event),
+            ParameterListMode::ArrayEditing(_) => self.handle_event_in_array_editing(event),
+            ParameterListMode::Reviewing(_) => self.handle_event_in_reviewing(event),
         }
     }
 }
 
+/// Lifecycle state and available transitions of a managed node.
+struct LifecycleView {
+    state: Option<String>,
+    transitions: ListWidgetState<LifecycleTransition>,
+    error: Option<String>,
+    state_pending: Option<ServiceCall>,
+    transitions_pending: Option<ServiceCall>,
+    /// Label of the transition being triggered, and the call triggering it.
+    change_pending: Option<(String, ServiceCall)>,
+    next_fetch: Instant,
+}
+
+impl LifecycleView {
+    fn new() -> Self {
+        Self {
+            state: None,
+            transitions: ListWidgetState::new(vec![], None),
+            error: None,
+            state_pending: None,
+            transitions_pending: None,
+            change_pending: None,
+            next_fetch: Instant::now(),
+        }
+    }
+
+    fn set_transitions(&mut self, transitions: Vec<LifecycleTransition>) {
+        if self.transitions.items().eq(transitions.iter()) {
+            return;
+        }
+        // The list only reacts to length changes, rebuild it keeping the selected row
+        let selected = self
+            .transitions
+            .get_selected_index()
+            .filter(|_| !transitions.is_empty())
+            .map(|index| index.min(transitions.len() - 1));
+        self.transitions = ListWidgetState::new(transitions, selected);
+    }
+
+    /// Status shown next to the section title.
+    fn status(&self) -> Option<String> {
+        if let Some((transition, pending)) = &self.change_pending {
+            let elapsed = pending.elapsed();
+            return Some(format!(
+                "{} Triggering '{}'... {:.1}s (Esc to cancel)",
+                spinner(elapsed),
+                transition,
+                elapsed.as_secs_f64()
+            ));
+        }
+        self.error.clone()
+    }
+}
+
+/// Levels of the loggers of a node started with the logger service.
+struct LoggersView {
+    loggers: ListWidgetState<LoggerLevel>,
+    /// Loggers to show: the one of the node, and its child loggers seen on `/rosout`.
+    names: Arc<Mutex<BTreeSet<String>>>,
+    rosout: Option<SubscriptionHandle>,
+    error: Option<String>,
+    levels_pending: Option<ServiceCall>,
+    /// Number of loggers the last levels request asked for.
+    fetched_names: usize,
+    /// Logger and level being set, and the call setting it.
+    set_pending: Option<(String, LogLevel, ServiceCall)>,
+    next_fetch: Instant,
+}
+
+impl LoggersView {
+    fn new(node: &NodeName) -> Self {
+        Self {
+            loggers: ListWidgetState::new(vec![], None),
+            names: Arc::new(Mutex::new(BTreeSet::from([logger_name(node)]))),
+            rosout: None,
+            error: None,
+            levels_pending: None,
+            fetched_names: 0,
+            set_pending: None,
+            next_fetch: Instant::now(),
+        }
+    }
+
+    /// Whether loggers were seen on `/rosout` since the levels were requested.
+    fn has_new_loggers(&self) -> bool {
+        self.names.lock().unwrap().len() != self.fetched_names
+    }
+
+    /// Status shown next to the section title.
+    fn status(&self) -> Option<String> {
+        if let Some((name, level, pending)) = &self.set_pending {
+            let elapsed = pending.elapsed();
+            return Some(format!(
+                "{} Setting '{}' to {}... {:.1}s (Esc to cancel)",
+                spinner(elapsed),
+                name,
+                level.label(),
+                elapsed.as_secs_f64()
+            ));
+        }
+        self.error.clone()
+    }
+}
+
 pub struct NodeDetailState {
     connection: Rc<RefCell<ConnectionType>>,
     node: NodeName,
@@ -315,6 +560,28 @@ pub struct NodeDetailState {
     service_list_state: ListWidgetState<NamedInterface>,
     parameter_list_state: ParameterListView,
     parameter_list_error: Option<String>,
+    /// Parameter request in flight, and when to send the next one (`None` once cancelled).
+    parameters_pending: Option<PendingCall<HashMap<String, Parameters>>>,
+    next_parameter_fetch: Option<Instant>,
+    /// Descriptors requested for the parameters without one.
+    descriptors_pending: Option<(
+        Vec<String>,
+        PendingCall<HashMap<String, ParameterDescriptor>>,
+    )>,
+    /// Result of the last parameter file dump or load.
+    parameter_file_status: Option<String>,
+    /// Subscription to `/parameter_events`, setting `parameters_changed` when the node reports
+    /// a change. The parameters are polled without it.
+    parameter_events: Option<SubscriptionHandle>,
+    parameters_changed: Arc<AtomicBool>,
+    /// Set for managed nodes, those with the lifecycle services.
+    lifecycle: Option<LifecycleView>,
+    /// Set for nodes with the logger services.
+    loggers: Option<LoggersView>,
+
+    /// Endpoint of the selected publisher or subscription, with the endpoints of the other side
+    /// its QoS is incompatible with.
+    selected_endpoint: Option<(TopicEndpointInfo, Vec<(NodeName, Vec<String>)>)>,
 
     active_section: MainDetailSection,
 
@@ -332,10 +599,23 @@ impl NodeDetailState {
             service_list_state: ListWidgetState::new(vec![], None),
             parameter_list_state: ParameterListView::new(connection, node, BTreeMap::new(), None),
             parameter_list_error: None,
+            parameters_pending: None,
+            next_parameter_fetch: Some(Instant::now()),
+            descriptors_pending: None,
+            parameter_file_status: None,
+            parameter_events: None,
+            parameters_changed: Arc::new(AtomicBool::new(false)),
+            lifecycle: None,
+            loggers: None,
+            selected_endpoint: None,
             active_section: MainDetailSection::Section(DetailSection::Publishers),
             needs_redraw: true,
         };
         state.update();
+        state.subscribe_parameter_events();
+        state.poll_parameters();
+        state.poll_lifecycle();
+        state.poll_loggers();
         state
     }
 
@@ -362,29 +642,561 @@ impl NodeDetailState {
         self.publisher_list_state.update(publishers);
         self.subscriber_list_state.update(subscriptions);
         self.client_list_state.update(clients);
+        let is_managed = [GET_STATE, CHANGE_STATE].iter().all(|service| {
+            let name = lifecycle_service(&self.node, service);
+            services.iter().any(|s| s.name == name)
+        });
+        let has_logger_services = [GET_LOGGER_LEVELS, SET_LOGGER_LEVELS]
+            .iter()
+            .all(|service| {
+                let name = logger_service(&self.node, service);
+                services.iter().any(|s| s.name == name)
+            });
         self.service_list_state.update(services);
+        drop(connection);
+
+        match (is_managed, &self.lifecycle) {
+            (true, None) => self.lifecycle = Some(LifecycleView::new()),
+            (false, Some(_)) => {
+                self.lifecycle = None;
+                if matches!(
+                    self.active_section,
+                    MainDetailSection::Section(DetailSection::Lifecycle)
+                        | MainDetailSection::SubSection(DetailSection::Lifecycle)
+                ) {
+                    self.active_section = MainDetailSection::Section(DetailSection::Publishers);
+                }
+            }
+            _ => {}
+        }
+        match (has_logger_services, &self.loggers) {
+            (true, None) => self.loggers = Some(LoggersView::new(&self.node)),
+            (false, Some(_)) => {
+                self.loggers = None;
+                if matches!(
+                    self.active_section,
+                    MainDetailSection::Section(DetailSection::Loggers)
+                        | MainDetailSection::SubSection(DetailSection::Loggers)
+                ) {
+                    self.active_section = MainDetailSection::Section(DetailSection::Publishers);
+                }
+            }
+            _ => {}
+        }
 
-        // Update parameters
-        match self.connection.borrow().get_parameters_by_node(&self.node) {
-            Ok(params) => {
-                // Convert HashMap to BTreeMap for consistent ordering
-                let params = params.into_iter().collect();
-                self.parameter_list_state.update(params);
-                self.parameter_list_error = None;
-                self.needs_redraw = true;
+        self.needs_redraw = true;
+    }
+
+    fn call_lifecycle_service(
+        &self,
+        service: &str,
+        request: Option<GenericMessage>,
+    ) -> ServiceCall {
+        call_lifecycle_service(&self.connection.borrow(), &self.node, service, request)
+    }
+
+    /// Collect the results of the lifecycle calls, and fetch the state again when due. Returns an
+    /// error event when a transition failed.
+    fn poll_lifecycle(&mut self) -> Event {
+        let Some(lifecycle) = &mut self.lifecycle else {
+            return Event::None;
+        };
+        let mut event = Event::None;
+
+        if let Some((transition, pending)) = &lifecycle.change_pending {
+            if let Some(result) = pending.poll_timeout() {
+                let result = result.and_then(|(response, _)| change_state_succeeded(&response));
+                match result {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        event = Event::Error(format!(
+                            "The node refused the '{}' transition",
+                            transition
+                        ))
+                    }
+                    Err(e) => {
+                        event = Event::Error(format!(
+                            "Failed to trigger the '{}' transition: {}",
+                            transition, e
+                        ))
+                    }
+                }
+                lifecycle.change_pending = None;
+                // Show the new state right away
+                lifecycle.next_fetch = Instant::now();
+            }
+            self.needs_redraw = true;
+        }
+
+        if let Some(result) = lifecycle
+            .state_pending
+            .as_ref()
+            .and_then(|p| p.poll_timeout())
+        {
+            lifecycle.state_pending = None;
+            match result.and_then(|(response, _)| current_state(&response)) {
+                Ok(state) => {
+                    lifecycle.state = Some(state);
+                    lifecycle.error = None;
+                }
+                Err(e) => lifecycle.error = Some(format!("Failed to get the state: {}", e)),
+            }
+            self.needs_redraw = true;
+        }
+        if let Some(result) = lifecycle
+            .transitions_pending
+            .as_ref()
+            .and_then(|p| p.poll_timeout())
+        {
+            lifecycle.transitions_pending = None;
+            match result.and_then(|(response, _)| available_transitions(&response)) {
+                Ok(transitions) => lifecycle.set_transitions(transitions),
+                Err(e) => lifecycle.error = Some(format!("Failed to get the transitions: {}", e)),
+            }
+            self.needs_redraw = true;
+        }
+
+        if lifecycle.state_pending.is_none()
+            && lifecycle.transitions_pending.is_none()
+            && lifecycle.change_pending.is_none()
+            && lifecycle.next_fetch <= Instant::now()
+        {
+            let state_pending = self.call_lifecycle_service(GET_STATE, None);
+            let transitions_pending = self.call_lifecycle_service(GET_AVAILABLE_TRANSITIONS, None);
+            if let Some(lifecycle) = &mut self.lifecycle {
+                lifecycle.state_pending = Some(state_pending);
+                lifecycle.transitions_pending = Some(transitions_pending);
+                lifecycle.next_fetch = Instant::now() + LIFECYCLE_REFRESH;
+            }
+        }
+        event
+    }
+
+    /// Trigger the selected lifecycle transition.
+    fn change_state(&mut self) -> Event {
+        let Some(transition) = self
+            .lifecycle
+            .as_ref()
+            .and_then(|lifecycle| lifecycle.transitions.get_selected())
+            .cloned()
+        else {
+            return Event::None;
+        };
+        let request = self
+            .connection
+            .borrow()
+            .get_service_request_template(&lifecycle_service_type(CHANGE_STATE))
+            .and_then(|template| change_state_request(&template, &transition));
+        let pending = match request {
+            Ok(request) => self.call_lifecycle_service(CHANGE_STATE, Some(request)),
+            Err(e) => {
+                return Event::Error(format!(
+                    "Failed to trigger the '{}' transition: {}",
+                    transition.label, e
+                ))
+            }
+        };
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.change_pending = Some((transition.label, pending));
+        }
+        self.needs_redraw = true;
+        self.poll_lifecycle()
+    }
+
+    fn shows_lifecycle_call(&self) -> bool {
+        self.lifecycle
+            .as_ref()
+            .is_some_and(|lifecycle| lifecycle.change_pending.is_some())
+    }
+
+    /// Collect the results of the logger calls, and fetch the levels again when due or when new
+    /// loggers were seen. Returns an error event when a level couldn't be set.
+    fn poll_loggers(&mut self) -> Event {
+        let Some(loggers) = &mut self.loggers else {
+            return Event::None;
+        };
+        let mut event = Event::None;
+
+        if let Some((name, level, pending)) = &loggers.set_pending {
+            if let Some(result) = pending.poll_timeout() {
+                let result = result.and_then(|(response, _)| set_logger_levels_result(&response));
+                if let Err(e) = result {
+                    event = Event::Error(format!(
+                        "Failed to set '{}' to {}: {}",
+                        name,
+                        level.label(),
+                        e
+                    ));
+                }
+                loggers.set_pending = None;
+                // Show the new level right away
+                loggers.next_fetch = Instant::now();
+            }
+            self.needs_redraw = true;
+        }
+
+        if let Some(result) = loggers
+            .levels_pending
+            .as_ref()
+            .and_then(|p| p.poll_timeout())
+        {
+            loggers.levels_pending = None;
+            match result.and_then(|(response, _)| logger_levels(&response)) {
+                Ok(levels) => {
+                    loggers.loggers.update_items(levels);
+                    loggers.error = None;
+                }
+                Err(e) => loggers.error = Some(format!("Failed to get the levels: {}", e)),
             }
-            Err(err) => {
-                self.parameter_list_state.reset();
-                self.parameter_list_error =
-                    Some(format!("Failed to get parameters: {}", err).to_string());
+            self.needs_redraw = true;
+        }
+
+        if loggers.levels_pending.is_none()
+            && loggers.set_pending.is_none()
+            && (loggers.next_fetch <= Instant::now() || loggers.has_new_loggers())
+        {
+            // The topic may only appear once the node is discovered
+            if loggers.rosout.is_none() {
+                let prefix = format!("{}.", logger_name(&self.node));
+                let names = loggers.names.clone();
+                loggers.rosout = self
+                    .connection
+                    .borrow_mut()
+                    .subscribe(
+                        ROSOUT,
+                        None,
+                        move |msg: GenericMessage, _msg_info: MessageMetadata| {
+                            if let Ok(entry) = log_entry(&msg) {
+                                if entry.name.starts_with(&prefix) {
+                                    names.lock().unwrap().insert(entry.name);
+                                }
+                            }
+                        },
+                    )
+                    .ok();
+            }
+
+            let names: Vec<String> = loggers.names.lock().unwrap().iter().cloned().collect();
+            loggers.fetched_names = names.len();
+            loggers.next_fetch = Instant::now() + LOGGER_REFRESH;
+            let connection = self.connection.borrow();
+            let request = connection
+                .get_service_request_template(&logger_service_type(GET_LOGGER_LEVELS))
+                .and_then(|template| get_logger_levels_request(&template, &names));
+            match request {
+                Ok(request) => {
+                    loggers.levels_pending = Some(call_logger_service(
+                        &connection,
+                        &self.node,
+                        GET_LOGGER_LEVELS,
+                        &request,
+                    ));
+                }
+                Err(e) => loggers.error = Some(format!("Failed to get the levels: {}", e)),
+            }
+        }
+        event
+    }
+
+    /// Set the selected logger to `level`.
+    fn set_logger_level(&mut self, level: LogLevel) -> Event {
+        let Some(name) = self
+            .loggers
+            .as_ref()
+            .and_then(|loggers| loggers.loggers.get_selected())
+            .map(|logger| logger.name.clone())
+        else {
+            return Event::None;
+        };
+        let connection = self.connection.borrow();
+        let request = connection
+            .get_service_request_template(&logger_service_type(SET_LOGGER_LEVELS))
+            .and_then(|template| set_logger_levels_request(&template, &name, level));
+        let pending = match request {
+            Ok(request) => {
+                call_logger_service(&connection, &self.node, SET_LOGGER_LEVELS, &request)
+            }
+            Err(e) => {
+                return Event::Error(format!(
+                    "Failed to set '{}' to {}: {}",
+                    name,
+                    level.label(),
+                    e
+                ))
+            }
+        };
+        drop(connection);
+        if let Some(loggers) = &mut self.loggers {
+            loggers.set_pending = Some((name, level, pending));
+        }
+        self.needs_redraw = true;
+        self.poll_loggers()
+    }
+
+    fn shows_logger_call(&self) -> bool {
+        self.loggers
+            .as_ref()
+            .is_some_and(|loggers| loggers.set_pending.is_some())
+    }
+
+    /// Follow the parameter changes of the node on `/parameter_events`, instead of polling.
+    fn subscribe_parameter_events(&mut self) {
+        if self.parameter_events.is_some() {
+            return;
+        }
+        let node = self.node.clone();
+        let parameters_changed = self.parameters_changed.clone();
+        self.parameter_events = self
+            .connection
+            .borrow_mut()
+            .subscribe(
+                PARAMETER_EVENTS,
+                None,
+                move |msg: GenericMessage, _msg_info: MessageMetadata| {
+                    if parameter_event(&msg).is_ok_and(|(event_node, _)| event_node == node) {
+                        parameters_changed.store(true, Ordering::Relaxed);
+                    }
+                },
+            )
+            .ok();
+    }
+
+    /// Collect the results of the parameter calls, and request the parameters again when due.
+    /// The reason a value was rejected is shown next to the parameter.
+    fn poll_parameters(&mut self) {
+        // A change reported by the node is fetched right away, unless refreshing is paused
+        if self.parameters_changed.swap(false, Ordering::Relaxed)
+            && self.next_parameter_fetch.is_some()
+        {
+            self.next_parameter_fetch = Some(Instant::now());
+        }
+
+        if let Some((param_name, pending)) = &self.parameter_list_state.pending_set {
+            if let Some(result) = pending.poll_timeout() {
+                let errors = &mut self.parameter_list_state.errors;
+                match result {
+                    Ok(()) => errors.remove(param_name),
+                    Err(err) => errors.insert(param_name.clone(), err),
+                };
+                self.parameter_list_state.pending_set = None;
+                self.parameter_list_state.set_next_loaded();
+                // Show the new value right away
+                self.next_parameter_fetch = Some(Instant::now());
+            }
+            self.needs_redraw = true;
+        }
+
+        // Descriptors don't change, only describe the new parameters
+        if let Some((names, pending)) = &self.descriptors_pending {
+            if let Some(result) = pending.poll_timeout() {
+                // Without descriptors, edits are only checked by the node
+                let mut descriptors = result.unwrap_or_default();
+                for name in names {
+                    let descriptor = descriptors.remove(name).unwrap_or_default();
+                    self.parameter_list_state
+                        .descriptors
+                        .insert(name.clone(), descriptor);
+                }
+                self.descriptors_pending = None;
                 self.needs_redraw = true;
             }
+        } else {
+            let names = self.undescribed_parameters();
+            if !names.is_empty() {
+                let pending = self
+                    .connection
+                    .borrow()
+                    .describe_parameters_by_node(&self.node, &names);
+                self.descriptors_pending = Some((names, pending));
+            }
         }
 
+        if let Some(pending) = &self.parameters_pending {
+            let Some(result) = pending.poll_timeout() else {
+                self.needs_redraw |= self.shows_pending_call();
+                return;
+            };
+            self.parameters_pending = None;
+            match result {
+                Ok(params) => {
+                    // Convert HashMap to BTreeMap for consistent ordering
+                    let params = params.into_iter().collect();
+                    self.parameter_list_state.update(params);
+                    self.parameter_list_error = None;
+                }
+                Err(err) => {
+                    self.parameter_list_state.reset();
+                    self.parameter_list_error = Some(format!("Failed to get parameters: {}", err));
+                }
+            }
+            // The topic may only appear once the node is discovered
+            self.subscribe_parameter_events();
+            let refresh = match self.parameter_events {
+                Some(_) => PARAMETER_EVENTS_REFRESH,
+                None => PARAMETER_REFRESH,
+            };
+            self.next_parameter_fetch = Some(Instant::now() + refresh);
+            self.needs_redraw = true;
+        }
+
+        if self.parameters_pending.is_none()
+            && self
+                .next_parameter_fetch
+                .is_some_and(|next| next <= Instant::now())
+        {
+            self.parameters_pending =
+                Some(self.connection.borrow().get_parameters_by_node(&self.node));
+        }
+    }
+
+    fn undescribed_parameters(&self) -> Vec<String> {
+        let parameters = &self.parameter_list_state;
+        parameters
+            .parameters
+            .keys()
+            .filter(|name| !parameters.descriptors.contains_key(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether a parameter call is shown with a spinner. The periodic refresh only shows one
+    /// until the first parameters arrive.
+    fn shows_pending_call(&self) -> bool {
+        self.parameter_list_state.pending_set.is_some()
+            || (self.parameters_pending.is_some()
+                && self.parameter_list_state.parameters.is_empty())
+    }
+
+    /// Status of the parameter calls, shown next to the section title.
+    fn parameter_status(&self) -> Option<String> {
+        if let Some((param_name, pending)) = &self.parameter_list_state.pending_set {
+            let elapsed = pending.elapsed();
+            let remaining = match self.parameter_list_state.pending_loads.len() {
+                0 => String::new(),
+                count => format!(", {} more", count),
+            };
+            return Some(format!(
+                "{} Setting '{}'{}... {:.1}s (Esc to cancel)",
+                spinner(elapsed),
+                param_name,
+                remaining,
+                elapsed.as_secs_f64()
+            ));
+        }
+        if let ParameterListMode::Reviewing(changes) = &self.parameter_list_state.mode {
+            return Some(format!(
+                "{} change(s) from {} ('y' to apply, Esc to drop)",
+                changes.len(),
+                parameter_file_path(&self.node).display()
+            ));
+        }
+        match (&self.parameters_pending, self.next_parameter_fetch) {
+            (Some(pending), _) if self.shows_pending_call() => {
+                let elapsed = pending.elapsed();
+                Some(format!(
+                    "{} Fetching... {:.1}s (Esc to cancel)",
+                    spinner(elapsed),
+                    elapsed.as_secs_f64()
+                ))
+            }
+            (None, None) => Some("Paused ('r' to refresh)".to_string()),
+            _ => self.parameter_file_status.clone(),
+        }
+    }
+
+    /// Write the parameters shown to the parameter file of the node, in the current directory.
+    fn dump_parameter_file(&mut self) -> Result<(), String> {
+        let parameters = &self.parameter_list_state.parameters;
+        if parameters.is_empty() {
+            return Err("No parameters to save yet".to_string());
+        }
+        let path = parameter_file_path(&self.node);
+        let text = dump_parameters(&self.node, parameters)?;
+        std::fs::write(&path, text)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        self.parameter_file_status = Some(format!(
+            "Saved {} parameter(s) to {}",
+            parameters.len(),
+            path.display()
+        ));
+        Ok(())
+    }
+
+    /// Read the parameter file of the node, and show the changes it makes before applying them.
+    fn load_parameter_file(&mut self) -> Result<(), String> {
+        let path = parameter_file_path(&self.node);
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let loaded = load_parameters(&self.node, &text)?;
+        let changes = diff_parameters(&self.parameter_list_state.parameters, &loaded)?;
+        if changes.is_empty() {
+            self.parameter_file_status = Some(format!("No changes in {}", path.display()));
+            return Ok(());
+        }
+        self.parameter_file_status = None;
+        self.parameter_list_state.mode = ParameterListMode::Reviewing(changes);
+        self.active_section = MainDetailSection::SubSection(DetailSection::Parameters);
+        Ok(())
+    }
+
+    /// Drop the running parameter calls and stop refreshing the parameters.
+    fn cancel_parameter_calls(&mut self) {
+        self.parameters_pending = None;
+        self.parameter_list_state.pending_set = None;
+        self.parameter_list_state.pending_loads.clear();
+        self.next_parameter_fetch = None;
         self.needs_redraw = true;
     }
 
-    pub fn fetch_parameters_for_selected_node(&mut self) {}
+    fn update_selected_endpoint(&mut self) {
+        let (topic, is_publisher) = match &self.active_section {
+            MainDetailSection::SubSection(DetailSection::Publishers) => {
+                (self.publisher_list_state.get_selected(), true)
+            }
+            MainDetailSection::SubSection(DetailSection::Subscribers) => {
+                (self.subscriber_list_state.get_selected(), false)
+            }
+            _ => (None, false),
+        };
+        let Some(topic) = topic else {
+            self.selected_endpoint = None;
+            return;
+        };
+
+        let connection = self.connection.borrow();
+        let publishers = connection
+            .get_publishers_info_by_topic(&topic.name)
+            .unwrap_or_default();
+        let subscriptions = connection
+            .get_subscriptions_info_by_topic(&topic.name)
+            .unwrap_or_default();
+        let (endpoints, others) = if is_publisher {
+            (&publishers, &subscriptions)
+        } else {
+            (&subscriptions, &publishers)
+        };
+        let Some(index) = endpoints.iter().position(|e| e.node_name == self.node) else {
+            self.selected_endpoint = None;
+            return;
+        };
+
+        let incompatibilities = incompatible_pairs(&publishers, &subscriptions)
+            .into_iter()
+            .filter_map(|(publisher, subscription, reasons)| {
+                let (endpoint, other) = if is_publisher {
+                    (publisher, subscription)
+                } else {
+                    (subscription, publisher)
+                };
+                (endpoint == index).then(|| (others[other].node_name.clone(), reasons))
+            })
+            .collect();
+        let selected_endpoint = Some((endpoints[index].clone(), incompatibilities));
+        if self.selected_endpoint != selected_endpoint {
+            self.selected_endpoint = selected_endpoint;
+            self.needs_redraw = true;
+        }
+    }
 
     pub fn next_detail(&mut self) {
         match &mut self.active_section {
@@ -401,6 +1213,22 @@ impl NodeDetailState {
                 self.active_section = MainDetailSection::Section(DetailSection::Parameters);
             }
             MainDetailSection::Section(DetailSection::Parameters) => {
+                self.active_section = if self.loggers.is_some() {
+                    MainDetailSection::Section(DetailSection::Loggers)
+                } else if self.lifecycle.is_some() {
+                    MainDetailSection::Section(DetailSection::Lifecycle)
+                } else {
+                    MainDetailSection::Section(DetailSection::Publishers)
+                };
+            }
+            MainDetailSection::Section(DetailSection::Loggers) => {
+                self.active_section = if self.lifecycle.is_some() {
+                    MainDetailSection::Section(DetailSection::Lifecycle)
+                } else {
+                    MainDetailSection::Section(DetailSection::Publishers)
+                };
+            }
+            MainDetailSection::Section(DetailSection::Lifecycle) => {
                 self.active_section = MainDetailSection::Section(DetailSection::Publishers);
             }
             MainDetailSection::SubSection(_) => {
@@ -412,6 +1240,22 @@ impl NodeDetailState {
     pub fn previous_detail(&mut self) {
         match &mut self.active_section {
             MainDetailSection::Section(DetailSection::Publishers) => {
+                self.active_section = if self.lifecycle.is_some() {
+                    MainDetailSection::Section(DetailSection::Lifecycle)
+                } else if self.loggers.is_some() {
+                    MainDetailSection::Section(DetailSection::Loggers)
+                } else {
+                    MainDetailSection::Section(DetailSection::Parameters)
+                };
+            }
+            MainDetailSection::Section(DetailSection::Lifecycle) => {
+                self.active_section = if self.loggers.is_some() {
+                    MainDetailSection::Section(DetailSection::Loggers)
+                } else {
+                    MainDetailSection::Section(DetailSection::Parameters)
+                };
+            }
+            MainDetailSection::Section(DetailSection::Loggers) => {
                 self.active_section = MainDetailSection::Section(DetailSection::Parameters);
             }
             MainDetailSection::Section(DetailSection::Subscribers) => {
@@ -448,6 +1292,11 @@ impl NodeDetailState {
                 KeyCode::Char('l') | KeyCode::Right => {
                     if let MainDetailSection::Section(active_detail) = &self.active_section {
                         match active_detail {
+                            DetailSection::Lifecycle => {
+                                if let Some(lifecycle) = &mut self.lifecycle {
+                                    lifecycle.transitions.next_item();
+                                }
+                            }
                             DetailSection::Publishers => self.publisher_list_state.next_item(),
                             DetailSection::Subscribers => self.subscriber_list_state.next_item(),
                             DetailSection::Clients => self.client_list_state.next_item(),
@@ -455,6 +1304,11 @@ impl NodeDetailState {
                             DetailSection::Parameters => {
                                 self.parameter_list_state.next_item();
                             }
+                            DetailSection::Loggers => {
+                                if let Some(loggers) = &mut self.loggers {
+                                    loggers.loggers.next_item();
+                                }
+                            }
                         }
                         self.active_section = MainDetailSection::SubSection(*active_detail);
                         self.needs_redraw = true;
@@ -475,17 +1329,30 @@ impl NodeDetailState {
                 panic!("Not in sub detail section");
             };
         let new_event = match active_detail {
+            DetailSection::Lifecycle => match &mut self.lifecycle {
+                Some(lifecycle) => lifecycle.transitions.handle_event(event),
+                None => event,
+            },
             DetailSection::Publishers => self.publisher_list_state.handle_event(event),
             DetailSection::Subscribers => self.subscriber_list_state.handle_event(event),
             DetailSection::Clients => self.client_list_state.handle_event(event),
             DetailSection::Services => self.service_list_state.handle_event(event),
             DetailSection::Parameters => self.parameter_list_state.handle_event(event),
+            DetailSection::Loggers => match &mut self.loggers {
+                Some(loggers) => loggers.loggers.handle_event(event),
+                None => event,
+            },
         };
         if let Event::Key(CrosstermEvent::Key(key_event)) = new_event {
             match key_event.code {
                 KeyCode::Char('h') | KeyCode::Left => {
                     if let MainDetailSection::SubSection(active_detail) = &self.active_section {
                         match active_detail {
+                            DetailSection::Lifecycle => {
+                                if let Some(lifecycle) = &mut self.lifecycle {
+                                    lifecycle.transitions.unselect();
+                                }
+                            }
                             DetailSection::Publishers => self.publisher_list_state.unselect(),
                             DetailSection::Subscribers => self.subscriber_list_state.unselect(),
                             DetailSection::Clients => self.client_list_state.unselect(),
@@ -493,6 +1360,11 @@ impl NodeDetailState {
                             DetailSection::Parameters => {
                                 self.parameter_list_state.unselect();
                             }
+                            DetailSection::Loggers => {
+                                if let Some(loggers) = &mut self.loggers {
+                                    loggers.loggers.unselect();
+                                }
+                            }
                         }
                         self.active_section = MainDetailSection::Section(*active_detail);
                         self.needs_redraw = true;
@@ -503,14 +1375,17 @@ impl NodeDetailState {
                     /* If publisher is selected, open raw view for that publisher
                      * If subscriber is selected, open publisher for that subscriber
                      * If parameters is selected, edit that parameter
+                     * If a lifecycle transition is selected, trigger it
                      */
                     match active_detail {
+                        DetailSection::Lifecycle => return self.change_state(),
                         DetailSection::Publishers => {
                             if let Some(item) = self.publisher_list_state.get_selected() {
                                 return Event::NewTopic(TopicInfo {
                                     connection: self.connection.clone(),
                                     topic: item.name.clone(),
                                     type_name: item.type_name.clone(),
+                                    qos: None,
                                 });
                             }
                         }
@@ -520,6 +1395,7 @@ impl NodeDetailState {
                                     connection: self.connection.clone(),
                                     topic: item.name.clone(),
                                     type_name: item.type_name.clone(),
+                                    qos: None,
                                 });
                             }
                         }
@@ -538,11 +1414,40 @@ impl NodeDetailState {
                                 return Event::NewView(new_view);
                             }
                         }
-                        DetailSection::Parameters => {
+                        DetailSection::Parameters | DetailSection::Loggers => {
                             // TODO
                         }
                     }
                 }
+                KeyCode::Char('i') => {
+                    let type_name = match active_detail {
+                        DetailSection::Publishers => self
+                            .publisher_list_state
+                            .get_selected()
+                            .map(|item| item.type_name.clone()),
+                        DetailSection::Subscribers => self
+                            .subscriber_list_state
+                            .get_selected()
+                            .map(|item| item.type_name.clone()),
+                        DetailSection::Clients => self
+                            .client_list_state
+                            .get_selected()
+                            .map(|item| item.type_name.clone()),
+                        DetailSection::Services => self
+                            .service_list_state
+                            .get_selected()
+                            .map(|item| item.type_name.clone()),
+                        _ => None,
+                    };
+                    if let Some(type_name) = type_name {
+                        return Event::NewView(Rc::new(RefCell::new(
+                            InterfaceDefinitionState::new(type_name),
+                        )));
+                    }
+                }
+                KeyCode::Char(c @ '1'..='5') if active_detail == DetailSection::Loggers => {
+                    return self.set_logger_level(LogLevel::ALL[c as usize - '1' as usize]);
+                }
                 _ => {}
             }
         }
@@ -553,10 +1458,68 @@ impl NodeDetailState {
 impl TuiView for NodeDetailState {
     fn handle_event(&mut self, event: Event) -> Event {
         self.update();
+        self.poll_parameters();
+        let lifecycle_event = self.poll_lifecycle();
+        let logger_event = self.poll_loggers();
 
-        match &mut self.active_section {
+        let is_editing = self.parameter_list_state.mode != ParameterListMode::Normal;
+        if let Event::Key(CrosstermEvent::Key(key_event)) = &event {
+            match key_event.code {
+                // Esc only cancels while a call is shown, it quits otherwise.
+                KeyCode::Esc if !is_editing && self.shows_lifecycle_call() => {
+                    if let Some(lifecycle) = &mut self.lifecycle {
+                        lifecycle.change_pending = None;
+                    }
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Esc if !is_editing && self.shows_logger_call() => {
+                    if let Some(loggers) = &mut self.loggers {
+                        loggers.set_pending = None;
+                    }
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Esc if !is_editing && self.shows_pending_call() => {
+                    self.cancel_parameter_calls();
+                    return Event::None;
+                }
+                KeyCode::Char('r') if !is_editing => {
+                    self.next_parameter_fetch = Some(Instant::now());
+                    self.poll_parameters();
+                    self.needs_redraw = true;
+                    return Event::None;
+                }
+                KeyCode::Char('w') if !is_editing => {
+                    self.needs_redraw = true;
+                    return match self.dump_parameter_file() {
+                        Ok(()) => Event::None,
+                        Err(e) => Event::Error(format!("Failed to save the parameters: {}", e)),
+                    };
+                }
+                KeyCode::Char('o') if !is_editing => {
+                    self.needs_redraw = true;
+                    return match self.load_parameter_file() {
+                        Ok(()) => Event::None,
+                        Err(e) => Event::Error(format!("Failed to load the parameters: {}", e)),
+                    };
+                }
+                KeyCode::Char('L') if !is_editing => {
+                    let rosout = RosoutState::for_node(&self.node, self.connection.clone());
+                    return Event::NewView(Rc::new(RefCell::new(rosout)));
+                }
+                _ => {}
+            }
+        }
+
+        let event = match &mut self.active_section {
             MainDetailSection::Section(_) => self.handle_event(event),
             MainDetailSection::SubSection(_) => self.handle_event_in_sub_section(event),
+        };
+        self.update_selected_endpoint();
+        match (lifecycle_event, logger_event) {
+            (Event::None, Event::None) => event,
+            (Event::None, error) | (error, _) => error,
         }
     }
 
@@ -565,7 +1528,43 @@ impl TuiView for NodeDetailState {
     }
 
     fn get_help_text(&self) -> String {
-        "TODO".to_string()
+        "Node Details View Help:\n\
+        - 'j' or ↓: Move to the next section.\n\
+        - 'k' or ↑: Move to the previous section.\n\
+        - 'l' or →: Enter the selected section.\n\
+        - 'h' or ←: Leave the section.\n\
+        - 'Enter': Open the selected topic or service, edit the selected parameter, or trigger \
+        the selected lifecycle transition.\n\
+        - 'r': Fetch the parameters again.\n\
+        - 'w': Save the parameters to <node>.yaml in the current directory, as a ROS 2 parameter \
+        file.\n\
+        - 'o': Load <node>.yaml, review the changes, then 'y' to apply them or 'Esc' to drop \
+        them.\n\
+        - 'L': Open the /rosout log of the node.\n\
+        - 'i': Show the definition of the selected topic or service type.\n\
+        - 'Esc': Cancel the running parameter request, lifecycle transition or logger level \
+        change.\n\
+        \n\
+        Array Parameters:\n\
+        - 'j'/'k': Select an element.\n\
+        - 'J'/'K': Move the selected element down/up.\n\
+        - 'i': Insert an element after the selected one.\n\
+        - 'd': Delete the selected element.\n\
+        - 'Enter': Edit the selected element.\n\
+        - 'p': Paste the whole array in YAML flow syntax, e.g. [1, 2, 3].\n\
+        - 's': Send the array to the node.\n\
+        - 'Esc': Leave the array editor without sending.\n\
+        \n\
+        Loggers:\n\
+        - '1' to '5': Set the selected logger to DEBUG, INFO, WARN, ERROR or FATAL.\n\
+        \n\
+        Parameters are requested in the background and refreshed when the node reports a \
+        change on /parameter_events (every second without it), edits are \
+        checked against their type and range before being sent. Managed nodes \
+        show their lifecycle state and available transitions, refreshed every second. Nodes \
+        started with the logger service list the level of their logger, and of the child \
+        loggers seen on /rosout."
+            .to_string()
     }
 
     fn needs_redraw(&mut self) -> bool {
@@ -605,12 +1604,39 @@ impl NodeDetailWidget {
             .title(Line::raw("Node Details").centered())
             .border_style(HEADER_STYLE);
 
-        let inner_area = block.inner(area);
+        let mut inner_area = block.inner(area);
         Widget::render(block, area, buf);
 
+        // Endpoint info of the selected publisher or subscription at the bottom
+        if let Some((endpoint, incompatibilities)) = &state.selected_endpoint {
+            let lines = endpoint_details(endpoint, incompatibilities);
+            let panel_height = (lines.len() as u16 + 1).min(inner_area.height / 2);
+            let [list_area, panel_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(panel_height)])
+                    .areas(inner_area);
+            Paragraph::new(lines)
+                .block(
+                    Block::new()
+                        .borders(Borders::TOP)
+                        .title(Line::raw("Endpoint").centered())
+                        .border_style(HEADER_STYLE),
+                )
+                .render(panel_area, buf);
+            inner_area = list_area;
+        }
+
         let width = inner_area.width;
 
         // Pre-calculate the height of each section's list content.
+        let transition_list = ListWidget::<LifecycleTransition>::new()
+            .auto_scroll(false)
+            .enable_search(false)
+            .show_mode(false);
+        let lifecycle_height = match &state.lifecycle {
+            Some(lifecycle) => 1 + transition_list.height(&lifecycle.transitions) as u16,
+            None => 0,
+        };
+
         let publisher_list = ListWidget::<NamedInterface>::new()
             .auto_scroll(false)
             .enable_search(false)
@@ -635,29 +1661,61 @@ impl NodeDetailWidget {
             .show_mode(false);
         let service_list_height = service_list.height(&state.service_list_state) as u16;
 
-        let param_list_height =
-            ParameterListWidget::new(&state.parameter_list_state.parameters).height();
+        let array_editor = match &state.parameter_list_state.mode {
+            ParameterListMode::ArrayEditing(editor) => Some(editor),
+            _ => None,
+        };
+        let changes = match &state.parameter_list_state.mode {
+            ParameterListMode::Reviewing(changes) => Some(changes.as_slice()),
+            _ => None,
+        };
+        let param_list_height = ParameterListWidget::new(&state.parameter_list_state.parameters)
+            .array_editor(array_editor)
+            .changes(changes)
+            .height();
+
+        let logger_list = ListWidget::<LoggerLevel>::new()
+            .auto_scroll(false)
+            .enable_search(false)
+            .show_mode(false);
+        let loggers_height = match &state.loggers {
+            Some(loggers) => 1 + logger_list.height(&loggers.loggers) as u16,
+            None => 0,
+        };
 
         // Calculate the y-offset of each section's title within the full content.
         // Each section is: 1 line title + list_height lines.
-        let publishers_y: u16 = 0;
+        let lifecycle_y: u16 = 0;
+        let publishers_y: u16 = lifecycle_y + lifecycle_height;
         let subscribers_y: u16 = publishers_y + 1 + publisher_list_height;
         let clients_y: u16 = subscribers_y + 1 + subscriber_list_height;
         let services_y: u16 = clients_y + 1 + client_list_height;
         let parameters_y: u16 = services_y + 1 + service_list_height;
-        let total_height: u16 = parameters_y + 1 + param_list_height;
+        // Without parameters, a line tells why
+        let loggers_y: u16 = parameters_y + 1 + param_list_height.max(1);
+        let total_height: u16 = loggers_y + loggers_height;
 
         // Determine the y-position to center in view: the section title when navigating
         // sections, or the selected item row when navigating within a subsection.
         let active_section_y = match &state.active_section {
             MainDetailSection::Section(section) => match section {
+                DetailSection::Lifecycle => lifecycle_y,
                 DetailSection::Publishers => publishers_y,
                 DetailSection::Subscribers => subscribers_y,
                 DetailSection::Clients => clients_y,
                 DetailSection::Services => services_y,
                 DetailSection::Parameters => parameters_y,
+                DetailSection::Loggers => loggers_y,
             },
             MainDetailSection::SubSection(section) => match section {
+                DetailSection::Lifecycle => {
+                    let sel = state
+                        .lifecycle
+                        .as_ref()
+                        .and_then(|lifecycle| lifecycle.transitions.get_selected_index())
+                        .unwrap_or(0) as u16;
+                    lifecycle_y + 1 + sel
+                }
                 DetailSection::Publishers => {
                     let sel = state.publisher_list_state.get_selected_index().unwrap_or(0) as u16;
                     publishers_y + 1 + sel
@@ -679,7 +1737,18 @@ impl NodeDetailWidget {
                 }
                 DetailSection::Parameters => {
                     let sel = state.parameter_list_state.selected.unwrap_or(0) as u16;
-                    parameters_y + 1 + sel
+                    // The array editor rows are below the selected parameter
+                    let element = array_editor
+                        .map_or(0, |editor| editor.selected().map_or(0, |e| e as u16 + 1));
+                    parameters_y + 1 + sel + element
+                }
+                DetailSection::Loggers => {
+                    let sel = state
+                        .loggers
+                        .as_ref()
+                        .and_then(|loggers| loggers.loggers.get_selected_index())
+                        .unwrap_or(0) as u16;
+                    loggers_y + 1 + sel
                 }
             },
         };
@@ -696,6 +1765,49 @@ impl NodeDetailWidget {
             height: extended_height,
         });
 
+        // --- Lifecycle ---
+        if let Some(lifecycle) = &mut state.lifecycle {
+            let lifecycle_title_style = match &state.active_section {
+                MainDetailSection::Section(DetailSection::Lifecycle) => SELECTED_STYLE,
+                MainDetailSection::SubSection(DetailSection::Lifecycle) => {
+                    SELECTED_STYLE.fg(Color::DarkGray)
+                }
+                _ => Style::default(),
+            }
+            .bold();
+            let mut lifecycle_title =
+                Line::from(Span::raw("Lifecycle:").style(lifecycle_title_style));
+            lifecycle_title.push_span(Span::raw(format!(
+                " {}",
+                lifecycle.state.as_deref().unwrap_or("unknown")
+            )));
+            if let Some(status) = lifecycle.status() {
+                lifecycle_title.push_span(Span::raw(format!(" {}", status)).fg(Color::DarkGray));
+            }
+            lifecycle_title.render(
+                Rect {
+                    x: 0,
+                    y: lifecycle_y,
+                    width,
+                    height: 1,
+                },
+                &mut extended_buffer,
+            );
+            if lifecycle_height > 1 {
+                StatefulWidget::render(
+                    transition_list,
+                    Rect {
+                        x: 2,
+                        y: lifecycle_y + 1,
+                        width: width.saturating_sub(2),
+                        height: lifecycle_height - 1,
+                    },
+                    &mut extended_buffer,
+                    &mut lifecycle.transitions,
+                );
+            }
+        }
+
         // --- Publishers ---
         let publisher_title_style = match &state.active_section {
             MainDetailSection::Section(DetailSection::Publishers) => SELECTED_STYLE,
@@ -837,24 +1949,30 @@ impl NodeDetailWidget {
             _ => Style::default(),
         }
         .bold();
-        Span::raw("Parameters:")
-            .style(parameter_title_style)
-            .render(
-                Rect {
-                    x: 0,
-                    y: parameters_y,
-                    width,
-                    height: 1,
-                },
-                &mut extended_buffer,
-            );
+        let mut parameter_title = Line::from(Span::raw("Parameters:").style(parameter_title_style));
+        if let Some(status) = state.parameter_status() {
+            parameter_title.push_span(Span::raw(format!(" {}", status)).fg(Color::DarkGray));
+        }
+        parameter_title.render(
+            Rect {
+                x: 0,
+                y: parameters_y,
+                width,
+                height: 1,
+            },
+            &mut extended_buffer,
+        );
         if param_list_height > 0 {
             let param_widget = ParameterListWidget::new(&state.parameter_list_state.parameters)
+                .descriptors(&state.parameter_list_state.descriptors)
+                .errors(&state.parameter_list_state.errors)
                 .selected(state.parameter_list_state.selected)
                 .edit(match &state.parameter_list_state.mode {
                     ParameterListMode::Editing(edit) => Some(edit.clone()),
-                    ParameterListMode::Normal => None,
-                });
+                    _ => None,
+                })
+                .array_editor(array_editor)
+                .changes(changes);
             param_widget.render(
                 Rect {
                     x: 2,
@@ -890,6 +2008,44 @@ impl NodeDetailWidget {
             }
         }
 
+        // --- Loggers ---
+        if let Some(loggers) = &mut state.loggers {
+            let logger_title_style = match &state.active_section {
+                MainDetailSection::Section(DetailSection::Loggers) => SELECTED_STYLE,
+                MainDetailSection::SubSection(DetailSection::Loggers) => {
+                    SELECTED_STYLE.fg(Color::DarkGray)
+                }
+                _ => Style::default(),
+            }
+            .bold();
+            let mut logger_title = Line::from(Span::raw("Loggers:").style(logger_title_style));
+            if let Some(status) = loggers.status() {
+                logger_title.push_span(Span::raw(format!(" {}", status)).fg(Color::DarkGray));
+            }
+            logger_title.render(
+                Rect {
+                    x: 0,
+                    y: loggers_y,
+                    width,
+                    height: 1,
+                },
+                &mut extended_buffer,
+            );
+            if loggers_height > 1 {
+                StatefulWidget::render(
+                    logger_list,
+                    Rect {
+                        x: 2,
+                        y: loggers_y + 1,
+                        width: width.saturating_sub(2),
+                        height: loggers_height - 1,
+                    },
+                    &mut extended_buffer,
+                    &mut loggers.loggers,
+                );
+            }
+        }
+
         // Copy the visible slice of the extended buffer into the actual buffer.
         for row in 0..inner_area.height {
             for col in 0..inner_area.width {
@@ -902,3 +2058,49 @@ impl NodeDetailWidget {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{mock_connection, PARAMETER_EVENTS_CONFIG};
+
+    #[test]
+    fn test_refresh_on_parameter_event() {
+        let connection = mock_connection(PARAMETER_EVENTS_CONFIG);
+        let talker = NodeName::new("/", "talker");
+        let mut view = NodeDetailState::new(talker.clone(), connection.clone());
+        view.handle_event(Event::None);
+        assert_eq!(
+            view.parameter_list_state.parameters,
+            BTreeMap::from([
+                (
+                    "frame_id".to_string(),
+                    Parameters::String("base_link".to_string())
+                ),
+                ("rate".to_string(), Parameters::Double(2.0)),
+            ])
+        );
+        assert!(view.parameter_list_error.is_none());
+        // Followed on /parameter_events, not polled again any time soon
+        assert!(view.parameter_events.is_some());
+        assert!(view
+            .next_parameter_fetch
+            .is_some_and(|next| next > Instant::now() + PARAMETER_REFRESH));
+
+        // Set by someone else, the node reports it and it is fetched again
+        connection
+            .borrow_mut()
+            .set_parameter_by_node(&talker, "rate", Parameters::Double(5.0))
+            .poll()
+            .unwrap()
+            .unwrap();
+        assert!(view.parameters_changed.load(Ordering::Relaxed));
+        view.handle_event(Event::None);
+        view.handle_event(Event::None);
+        assert_eq!(
+            view.parameter_list_state.parameters.get("rate"),
+            Some(&Parameters::Double(5.0))
+        );
+    }
+}
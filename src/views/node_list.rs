@@ -1,56 +1,176 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use ratatui::{
     prelude::{Buffer, Rect},
-    text::Line,
+    style::{Color, Style, Styled},
+    text::{Line, Span},
     widgets::{Block, BorderType, StatefulWidget},
 };
 
 use crossterm::event::{Event as CrosstermEvent, KeyCode};
 
 use crate::{
-    common::{event::Event, style::HEADER_STYLE},
-    connections::{Connection, ConnectionType, NodeName},
+    common::{
+        event::Event,
+        generic_message::GenericMessage,
+        style::{HEADER_STYLE, SELECTED_STYLE},
+        utils::{build_highlighted_spans, truncate_namespaces},
+    },
+    connections::{
+        lifecycle::{call_lifecycle_service, current_state, managed_nodes, GET_STATE},
+        pending::PendingCall,
+        Connection, ConnectionType, NodeName,
+    },
     views::{ConnectionInfo, FromConnection, NodeInfo, TuiView},
-    widgets::list_widget::{ListWidget, ListWidgetState},
+    widgets::list_widget::{ListItemTrait, ListWidget, ListWidgetState},
 };
 
+/// How often the list of nodes is refreshed.
+const UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the lifecycle state of the managed nodes in view is fetched again.
+const LIFECYCLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Node of the list, with the lifecycle state of managed nodes.
+#[derive(Debug, Clone)]
+struct NodeListItem {
+    node: NodeName,
+    lifecycle_state: Option<String>,
+}
+
+impl ListItemTrait for NodeListItem {
+    fn search_text(&self) -> String {
+        self.node.full_name()
+    }
+
+    fn to_line(&self, width: usize, selected: bool, indices: Vec<u32>) -> Line<'_> {
+        let state = self
+            .lifecycle_state
+            .as_ref()
+            .map(|state| format!(" [{}]", state))
+            .unwrap_or_default();
+        let (truncated_name, new_indices) = truncate_namespaces(
+            &self.node.full_name(),
+            &indices,
+            width.saturating_sub(state.chars().count()),
+        );
+        let mut spans = build_highlighted_spans(truncated_name, new_indices);
+        spans.push(Span::styled(state, Style::default().fg(Color::DarkGray)));
+        let mut line = Line::from(spans);
+        if selected {
+            line = line.set_style(SELECTED_STYLE);
+        }
+        line
+    }
+}
+
 pub struct NodeListWidget;
 
 pub struct NodeListState {
     connection: Rc<RefCell<ConnectionType>>,
 
-    node_list_state: ListWidgetState<NodeName>,
+    node_list_state: ListWidgetState<NodeListItem>,
+    /// Last known state of the managed nodes, and the requests fetching them again.
+    lifecycle_states: HashMap<NodeName, String>,
+    lifecycle_pending: Vec<(NodeName, PendingCall<(GenericMessage, Vec<String>)>)>,
+    /// `None` until the states are first requested.
+    last_lifecycle_update: Option<Instant>,
 
-    last_update: std::time::Instant,
+    last_update: Instant,
     needs_redraw: bool,
 }
 
 impl NodeListState {
     pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
-        // Initialize node list from connection
-        let nodes = connection.borrow().list_nodes().unwrap_or_default();
-
-        Self {
+        let mut state = Self {
             connection,
-            node_list_state: ListWidgetState::new(nodes, None),
-            last_update: std::time::Instant::now(),
+            node_list_state: ListWidgetState::new(vec![], None),
+            lifecycle_states: HashMap::new(),
+            lifecycle_pending: Vec::new(),
+            last_lifecycle_update: None,
+            last_update: Instant::now(),
             needs_redraw: true,
-        }
+        };
+        state.refresh();
+        state
     }
 
     pub fn update(&mut self) {
-        const UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+        self.poll_lifecycle_states();
+        // Once the previous requests are answered
+        if self.lifecycle_pending.is_empty()
+            && self
+                .last_lifecycle_update
+                .is_none_or(|last| last.elapsed() >= LIFECYCLE_INTERVAL)
+        {
+            self.refresh_lifecycle_states();
+        }
         if self.last_update.elapsed() < UPDATE_INTERVAL {
             return;
         }
-        self.last_update = std::time::Instant::now();
-        let node_list_items: Vec<NodeName> =
-            self.connection.borrow().list_nodes().unwrap_or_default();
-        self.node_list_state.update(node_list_items);
+        self.last_update = Instant::now();
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let nodes = self.connection.borrow().list_nodes().unwrap_or_default();
+        let node_list_items = nodes
+            .into_iter()
+            .map(|node| NodeListItem {
+                lifecycle_state: self.lifecycle_states.get(&node).cloned(),
+                node,
+            })
+            .collect();
+        self.node_list_state.update_items(node_list_items);
         self.needs_redraw = true;
     }
 
+    /// Ask the managed nodes in view for their state.
+    fn refresh_lifecycle_states(&mut self) {
+        let visible: Vec<NodeName> = self
+            .node_list_state
+            .visible_items()
+            .map(|item| item.node.clone())
+            .collect();
+        // Nothing in view before the first render
+        if visible.is_empty() {
+            return;
+        }
+        self.last_lifecycle_update = Some(Instant::now());
+
+        let connection = self.connection.borrow();
+        let managed = managed_nodes(&connection.list_services().unwrap_or_default());
+        self.lifecycle_states
+            .retain(|node, _| managed.contains(node));
+        self.lifecycle_pending = visible
+            .into_iter()
+            .filter(|node| managed.contains(node))
+            .map(|node| {
+                let pending = call_lifecycle_service(&connection, &node, GET_STATE, None);
+                (node, pending)
+            })
+            .collect();
+    }
+
+    fn poll_lifecycle_states(&mut self) {
+        let lifecycle_states = &mut self.lifecycle_states;
+        self.lifecycle_pending.retain(|(node, pending)| {
+            let Some(result) = pending.poll_timeout() else {
+                return true;
+            };
+            match result.and_then(|(response, _)| current_state(&response)) {
+                Ok(state) => lifecycle_states.insert(node.clone(), state),
+                Err(_) => lifecycle_states.insert(node.clone(), "unknown".to_string()),
+            };
+            false
+        });
+    }
+
     fn handle_event(&mut self, event: Event) -> Event {
         // List view event handling
         let new_event = self.node_list_state.handle_event(event);
@@ -61,7 +181,7 @@ impl NodeListState {
                 if let Some(selected) = self.node_list_state.get_selected() {
                     // Switch to node details view
                     return Event::NewNode(NodeInfo {
-                        node_name: selected.clone(),
+                        node_name: selected.node.clone(),
                         connection: self.connection.clone(),
                     });
                 }
@@ -90,6 +210,8 @@ impl TuiView for NodeListState {
         - 'j' or ↓: Move down in the node list.\n\
         - 'k' or ↑: Move up in the node list.\n\
         - 'Enter': Open the details view for the selected node.\n\
+        Managed nodes show their lifecycle state next to their name, fetched every few \
+        seconds for the nodes in view.\n\
         Search Mode:\n\
         - '/': Enter search mode.\n\
         - Type to filter nodes.\n\
@@ -125,7 +247,7 @@ impl NodeListWidget {
             .border_type(BorderType::Rounded);
 
         // Create a List from all list items and highlight the currently selected one
-        let node_list = ListWidget::<NodeName>::new()
+        let node_list = ListWidget::<NodeListItem>::new()
             .block(border)
             .auto_scroll(true)
             .enable_search(true)
@@ -0,0 +1,381 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use ratatui::{
+    prelude::{Buffer, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Widget},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
+        style::HEADER_STYLE,
+        utils::format_time_of_day,
+    },
+    connections::{
+        parameter_events::{
+            parameter_event, ParameterEventKind, ParameterUpdate, PARAMETER_EVENTS,
+        },
+        Connection, ConnectionType, NodeName, Parameters, SubscriptionHandle,
+    },
+    views::{ConnectionInfo, FromConnection, TuiView},
+};
+
+/// Rows kept in the log, the oldest are dropped first.
+const MAX_ROWS: usize = 10000;
+
+/// How often to retry subscribing, until `/parameter_events` is known to the connection.
+const SUBSCRIBE_RETRY: Duration = Duration::from_secs(1);
+
+pub struct ParameterEventsWidget;
+
+/// Change of a parameter, with the value it had before when it is known.
+#[derive(Debug, Clone)]
+struct ParameterEventRow {
+    time: SystemTime,
+    node: NodeName,
+    kind: ParameterEventKind,
+    name: String,
+    old: Option<Parameters>,
+    new: Option<Parameters>,
+}
+
+impl ParameterEventRow {
+    /// Case-insensitive match of the node or the parameter name.
+    fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.node.full_name().to_lowercase().contains(&filter)
+            || self.name.to_lowercase().contains(&filter)
+    }
+
+    fn to_line(&self) -> Line<'static> {
+        let value = |value: &Option<Parameters>| {
+            value
+                .as_ref()
+                .map_or("?".to_string(), |value| value.to_string())
+        };
+        let change = match self.kind {
+            ParameterEventKind::New => Span::styled(
+                format!("{} (new)", value(&self.new)),
+                Style::default().fg(Color::Green),
+            ),
+            ParameterEventKind::Changed => Span::styled(
+                format!("{} → {}", value(&self.old), value(&self.new)),
+                Style::default().fg(Color::Yellow),
+            ),
+            ParameterEventKind::Deleted => Span::styled(
+                format!("{} (deleted)", value(&self.old)),
+                Style::default().fg(Color::Red),
+            ),
+        };
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", format_time_of_day(self.time)),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(format!("{} ", self.node.full_name())),
+            Span::styled(format!("{}: ", self.name), HEADER_STYLE),
+            change,
+        ])
+    }
+}
+
+/// Rows of the log, and the last known value of each parameter to show what it was before a
+/// change.
+#[derive(Default)]
+struct ParameterLog {
+    rows: VecDeque<ParameterEventRow>,
+    values: HashMap<(NodeName, String), Parameters>,
+}
+
+impl ParameterLog {
+    fn push(&mut self, time: SystemTime, node: NodeName, updates: Vec<ParameterUpdate>) {
+        for update in updates {
+            let key = (node.clone(), update.name.clone());
+            let old = match &update.value {
+                Some(value) => self.values.insert(key, value.clone()),
+                None => self.values.remove(&key),
+            };
+            self.rows.push_back(ParameterEventRow {
+                time,
+                node: node.clone(),
+                kind: update.kind,
+                name: update.name,
+                old,
+                new: update.value,
+            });
+        }
+        while self.rows.len() > MAX_ROWS {
+            self.rows.pop_front();
+        }
+    }
+}
+
+pub struct ParameterEventsState {
+    connection: Rc<RefCell<ConnectionType>>,
+    subscription: Option<SubscriptionHandle>,
+    /// Why the subscription failed, and when to try again.
+    error: Option<String>,
+    next_subscribe: Instant,
+    log: Arc<Mutex<ParameterLog>>,
+
+    filter: String,
+    filtering: bool,
+    /// Rows scrolled up from the end of the log, 0 follows the new events.
+    scroll: usize,
+    needs_redraw: Arc<AtomicBool>,
+}
+
+impl ParameterEventsState {
+    pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self {
+            connection,
+            subscription: None,
+            error: None,
+            next_subscribe: Instant::now(),
+            log: Arc::new(Mutex::new(ParameterLog::default())),
+            filter: String::new(),
+            filtering: false,
+            scroll: 0,
+            needs_redraw: Arc::new(AtomicBool::new(true)),
+        };
+        state.subscribe();
+        state
+    }
+
+    /// Subscribe to `/parameter_events`, unless already subscribed or waiting to retry.
+    fn subscribe(&mut self) {
+        if self.subscription.is_some() || Instant::now() < self.next_subscribe {
+            return;
+        }
+        let log = self.log.clone();
+        let needs_redraw = self.needs_redraw.clone();
+        let subscription = self.connection.borrow_mut().subscribe(
+            PARAMETER_EVENTS,
+            None,
+            move |msg: GenericMessage, msg_info: MessageMetadata| {
+                if let Ok((node, updates)) = parameter_event(&msg) {
+                    log.lock()
+                        .unwrap()
+                        .push(msg_info.received_time, node, updates);
+                    needs_redraw.store(true, Ordering::Relaxed);
+                }
+            },
+        );
+        match subscription {
+            Ok(subscription) => {
+                self.subscription = Some(subscription);
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(format!("Waiting for {}: {}", PARAMETER_EVENTS, e));
+                self.next_subscribe = Instant::now() + SUBSCRIBE_RETRY;
+            }
+        }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+    }
+
+    fn visible_rows(&self) -> Vec<ParameterEventRow> {
+        self.log
+            .lock()
+            .unwrap()
+            .rows
+            .iter()
+            .filter(|row| row.matches(&self.filter))
+            .cloned()
+            .collect()
+    }
+
+    fn handle_key_in_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => self.filter.push(c),
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => self.filtering = false,
+            _ => return,
+        }
+        self.scroll = 0;
+    }
+}
+
+impl TuiView for ParameterEventsState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        self.subscribe();
+
+        let Event::Key(CrosstermEvent::Key(key_event)) = event else {
+            return event;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return event;
+        }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+        if self.filtering {
+            self.handle_key_in_filter(key_event.code);
+            return Event::None;
+        }
+        match key_event.code {
+            KeyCode::Char('k') | KeyCode::Up => self.scroll += 1,
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('G') | KeyCode::End => self.scroll = 0,
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Char('c') => self.log.lock().unwrap().rows.clear(),
+            _ => return event,
+        }
+        Event::None
+    }
+
+    fn name(&self) -> String {
+        "Parameter Events".to_string()
+    }
+
+    fn get_help_text(&self) -> String {
+        "Parameter Events View Help:\n\
+        - 'k' or ↑: Scroll up, the log stops following the new events.\n\
+        - 'j' or ↓: Scroll down.\n\
+        - 'G' or End: Follow the new events again.\n\
+        - '/': Filter the events by node or parameter name, 'Enter'/'Esc' to stop typing.\n\
+        - 'c': Clear the log.\n\
+        \n\
+        Every change published on /parameter_events is logged with the value the parameter \
+        had before, when it was seen earlier."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.needs_redraw.swap(false, Ordering::Relaxed)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        ParameterEventsWidget::render(area, buf, self);
+    }
+}
+
+impl FromConnection for ParameterEventsState {
+    fn from_connection(connection_info: ConnectionInfo) -> Self {
+        ParameterEventsState::new(connection_info.connection)
+    }
+}
+
+impl ParameterEventsWidget {
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut ParameterEventsState) {
+        let mut block = Block::bordered()
+            .title(Line::raw("Parameter Events").centered())
+            .border_style(HEADER_STYLE)
+            .border_type(BorderType::Rounded);
+        if state.filtering || !state.filter.is_empty() {
+            block = block.title_bottom(Line::raw(format!(" /{} ", state.filter)));
+        }
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        if let Some(error) = &state.error {
+            Paragraph::new(error.as_str()).render(inner_area, buf);
+            return;
+        }
+
+        let rows = state.visible_rows();
+        let height = inner_area.height as usize;
+        // Don't scroll past the first row
+        state.scroll = state.scroll.min(rows.len().saturating_sub(height));
+        let end = rows.len() - state.scroll;
+        let start = end.saturating_sub(height);
+        let lines: Vec<Line> = rows[start..end].iter().map(|row| row.to_line()).collect();
+        if lines.is_empty() {
+            Paragraph::new(format!("No events on {} yet", PARAMETER_EVENTS))
+                .style(Style::default().fg(Color::DarkGray))
+                .render(inner_area, buf);
+        } else {
+            Paragraph::new(lines).render(inner_area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{key, mock_connection, PARAMETER_EVENTS_CONFIG};
+
+    type Change = (
+        String,
+        ParameterEventKind,
+        Option<Parameters>,
+        Option<Parameters>,
+    );
+
+    fn changes(view: &ParameterEventsState) -> Vec<Change> {
+        view.visible_rows()
+            .into_iter()
+            .map(|row| (row.name, row.kind, row.old, row.new))
+            .collect()
+    }
+
+    #[test]
+    fn test_events_and_filter() {
+        let connection = mock_connection(PARAMETER_EVENTS_CONFIG);
+        let mut view = ParameterEventsState::new(connection.clone());
+        assert!(view.error.is_none());
+        assert!(changes(&view).is_empty());
+
+        let talker = NodeName::new("/", "talker");
+        for (name, value) in [("rate", 5.0), ("gain", 0.5), ("rate", 10.0)] {
+            connection
+                .borrow_mut()
+                .set_parameter_by_node(&talker, name, Parameters::Double(value))
+                .poll()
+                .unwrap()
+                .unwrap();
+        }
+        assert!(view.needs_redraw());
+        // The value before the first change is unknown, the next ones have it
+        let double = |value| Some(Parameters::Double(value));
+        assert_eq!(
+            changes(&view),
+            [
+                (
+                    "rate".to_string(),
+                    ParameterEventKind::Changed,
+                    None,
+                    double(5.0)
+                ),
+                (
+                    "gain".to_string(),
+                    ParameterEventKind::New,
+                    None,
+                    double(0.5)
+                ),
+                (
+                    "rate".to_string(),
+                    ParameterEventKind::Changed,
+                    double(5.0),
+                    double(10.0)
+                ),
+            ]
+        );
+
+        view.handle_event(key(KeyCode::Char('/')));
+        for c in "GAIN".chars() {
+            view.handle_event(key(KeyCode::Char(c)));
+        }
+        assert!(view.filtering);
+        let names = changes(&view)
+            .into_iter()
+            .map(|(name, ..)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["gain"]);
+    }
+}
@@ -16,9 +16,10 @@ use crate::{
         generic_message::{FieldType, GenericMessage, MessageMetadata},
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{qos::QosProfile, Connection, ConnectionType, SubscriptionHandle},
     views::{
-        message_pane::MessagePaneState, FieldInfo, FieldInfoType, FromTopic, TopicInfo, TuiView,
+        interface_definition::InterfaceDefinitionState, message_pane::MessagePaneState, FieldInfo,
+        FieldInfoType, FromTopic, TopicInfo, TuiView,
     },
     widgets::message_widget::MessageWidget,
 };
@@ -31,12 +32,17 @@ pub struct RawMessageState {
     pub topic: String,
     pub message: Arc<Mutex<Option<GenericMessage>>>,
     _connection: Rc<RefCell<ConnectionType>>,
+    _subscription: SubscriptionHandle,
     pane: MessagePaneState,
     needs_redraw: Arc<AtomicBool>,
 }
 
 impl RawMessageState {
-    pub fn new(topic: String, connection: Rc<RefCell<ConnectionType>>) -> Self {
+    pub fn new(
+        topic: String,
+        qos: Option<QosProfile>,
+        connection: Rc<RefCell<ConnectionType>>,
+    ) -> Self {
         let message = Arc::new(Mutex::new(None));
         let message_copy = message.clone();
         let needs_redraw = Arc::new(AtomicBool::new(true));
@@ -56,10 +62,11 @@ impl RawMessageState {
             }
         }
 
-        connection
+        let subscription = connection
             .borrow_mut()
             .subscribe(
                 &topic,
+                qos,
                 move |msg: GenericMessage, _msg_info: MessageMetadata| {
                     let mut mut_message = message_copy.lock().unwrap();
                     needs_redraw_copy.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -72,6 +79,7 @@ impl RawMessageState {
             topic: topic.clone(),
             message,
             _connection: connection,
+            _subscription: subscription,
             pane: MessagePaneState::new(),
             needs_redraw,
         }
@@ -153,6 +161,15 @@ impl TuiView for RawMessageState {
                         Err(e) => Event::Error(format!("Failed to get field type: {}", e)),
                     }
                 }
+                KeyCode::Char('i') => match &*self.message.lock().unwrap() {
+                    Some(message) => {
+                        Event::NewView(Rc::new(RefCell::new(InterfaceDefinitionState::with_field(
+                            message.type_name().clone(),
+                            &self.pane.selected_fields,
+                        ))))
+                    }
+                    None => Event::Error("No message available".to_string()),
+                },
                 _ => event,
             }
         } else {
@@ -169,7 +186,8 @@ impl TuiView for RawMessageState {
         - 'j' or ↓: Move down in the message fields.\n\
         - 'k' or ↑: Move up in the message fields.\n\
         - 'G': Jump to the last field in the message.\n\
-        - 'Enter': Create a new plot for the selected primitive field."
+        - 'Enter': Create a new plot for the selected primitive field.\n\
+        - 'i': Show the definition of the message, at the selected field."
             .to_string()
     }
 
@@ -189,7 +207,7 @@ impl TuiView for RawMessageState {
 
 impl FromTopic for RawMessageState {
     fn from_topic(topic_info: TopicInfo) -> Self {
-        RawMessageState::new(topic_info.topic, topic_info.connection)
+        RawMessageState::new(topic_info.topic, topic_info.qos, topic_info.connection)
     }
 }
 
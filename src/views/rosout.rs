@@ -0,0 +1,530 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use ratatui::{
+    prelude::{Buffer, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Widget, Wrap},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
+        style::{HEADER_STYLE, SELECTED_STYLE},
+        utils::format_time_of_day,
+    },
+    connections::{
+        rosout::{log_entry, logger_name, LogEntry, LogLevel, ROSOUT},
+        Connection, ConnectionType, NodeName, SubscriptionHandle,
+    },
+    views::{ConnectionInfo, FromConnection, TuiView},
+    widgets::list_widget::search_pattern,
+};
+
+/// Messages kept in the log, the oldest are dropped first.
+const MAX_ENTRIES: usize = 10000;
+
+/// How often to retry subscribing, until `/rosout` is known to the connection.
+const SUBSCRIBE_RETRY: Duration = Duration::from_secs(1);
+
+pub struct RosoutWidget;
+
+/// Colour of the messages of a level.
+pub fn level_style(level: LogLevel) -> Style {
+    match level {
+        LogLevel::Debug => Style::default().fg(Color::DarkGray),
+        LogLevel::Info => Style::default(),
+        LogLevel::Warn => Style::default().fg(Color::Yellow),
+        LogLevel::Error => Style::default().fg(Color::Red),
+        LogLevel::Fatal => Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    }
+}
+
+/// Log messages in the order they were received, numbered from the first one.
+#[derive(Default)]
+struct RosoutLog {
+    entries: VecDeque<(u64, LogEntry)>,
+    received: u64,
+}
+
+impl RosoutLog {
+    fn push(&mut self, entry: LogEntry) {
+        self.entries.push_back((self.received, entry));
+        self.received += 1;
+        while self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+}
+
+/// What the keys are typed into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputMode {
+    Normal,
+    Search,
+    Node,
+}
+
+pub struct RosoutState {
+    connection: Rc<RefCell<ConnectionType>>,
+    subscription: Option<SubscriptionHandle>,
+    /// Why the subscription failed, and when to try again.
+    error: Option<String>,
+    next_subscribe: Instant,
+    log: Arc<Mutex<RosoutLog>>,
+
+    min_level: LogLevel,
+    /// Part of the logger name of the messages shown.
+    node_filter: String,
+    /// Fuzzy search in the messages, like the lists.
+    search: String,
+    matcher: Matcher,
+    input_mode: InputMode,
+    /// Number of the first message not shown while paused.
+    paused_at: Option<u64>,
+    /// Number of the selected message, `None` follows the new messages.
+    selected: Option<u64>,
+    show_details: bool,
+    needs_redraw: Arc<AtomicBool>,
+}
+
+impl RosoutState {
+    pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self {
+            connection,
+            subscription: None,
+            error: None,
+            next_subscribe: Instant::now(),
+            log: Arc::new(Mutex::new(RosoutLog::default())),
+            min_level: LogLevel::Debug,
+            node_filter: String::new(),
+            search: String::new(),
+            matcher: Matcher::new(Config::DEFAULT),
+            input_mode: InputMode::Normal,
+            paused_at: None,
+            selected: None,
+            show_details: false,
+            needs_redraw: Arc::new(AtomicBool::new(true)),
+        };
+        state.subscribe();
+        state
+    }
+
+    /// The log of a single node.
+    pub fn for_node(node: &NodeName, connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self::new(connection);
+        state.node_filter = logger_name(node);
+        state
+    }
+
+    /// Subscribe to `/rosout`, unless already subscribed or waiting to retry.
+    fn subscribe(&mut self) {
+        if self.subscription.is_some() || Instant::now() < self.next_subscribe {
+            return;
+        }
+        let log = self.log.clone();
+        let needs_redraw = self.needs_redraw.clone();
+        let subscription = self.connection.borrow_mut().subscribe(
+            ROSOUT,
+            None,
+            move |msg: GenericMessage, _msg_info: MessageMetadata| {
+                if let Ok(entry) = log_entry(&msg) {
+                    log.lock().unwrap().push(entry);
+                    needs_redraw.store(true, Ordering::Relaxed);
+                }
+            },
+        );
+        match subscription {
+            Ok(subscription) => {
+                self.subscription = Some(subscription);
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(format!("Waiting for {}: {}", ROSOUT, e));
+                self.next_subscribe = Instant::now() + SUBSCRIBE_RETRY;
+            }
+        }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+    }
+
+    /// Messages passing the filters, received before the pause.
+    fn visible_entries(&mut self) -> Vec<(u64, LogEntry)> {
+        let pattern = (!self.search.is_empty()).then(|| search_pattern(&self.search));
+        let node_filter = self.node_filter.to_lowercase();
+        let mut buffer = Vec::new();
+        let log = self.log.lock().unwrap();
+        log.entries
+            .iter()
+            .filter(|(number, entry)| {
+                self.paused_at.is_none_or(|paused_at| *number < paused_at)
+                    && entry.level >= self.min_level
+                    && entry.name.to_lowercase().contains(&node_filter)
+                    && pattern.as_ref().is_none_or(|pattern| {
+                        pattern
+                            .score(Utf32Str::new(&entry.msg, &mut buffer), &mut self.matcher)
+                            .is_some()
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Index of the selected message among `entries`, or of the closest one before it when it
+    /// is filtered out.
+    fn selected_index(&self, entries: &[(u64, LogEntry)]) -> Option<usize> {
+        let selected = self.selected?;
+        Some(
+            entries
+                .partition_point(|(number, _)| *number <= selected)
+                .saturating_sub(1),
+        )
+    }
+
+    fn select(&mut self, offset: isize) {
+        let entries = self.visible_entries();
+        if entries.is_empty() {
+            return;
+        }
+        let index = match self.selected_index(&entries) {
+            Some(index) => index as isize + offset,
+            // Scrolling back starts from the last message
+            None if offset < 0 => entries.len() as isize - 1,
+            None => return,
+        };
+        self.selected = if index >= entries.len() as isize {
+            // Past the last message, follow the new ones again
+            None
+        } else {
+            Some(entries[index.max(0) as usize].0)
+        };
+    }
+
+    fn selected_entry(&self) -> Option<LogEntry> {
+        let selected = self.selected?;
+        self.log
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|(number, _)| *number == selected)
+            .map(|(_, entry)| entry.clone())
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused_at = match self.paused_at {
+            Some(_) => None,
+            None => Some(self.log.lock().unwrap().received),
+        };
+    }
+
+    fn handle_key_in_input(&mut self, code: KeyCode) {
+        let text = match self.input_mode {
+            InputMode::Search => &mut self.search,
+            InputMode::Node => &mut self.node_filter,
+            InputMode::Normal => return,
+        };
+        match code {
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => self.input_mode = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    fn status(&self) -> String {
+        let mut parts = vec![format!("≥ {}", self.min_level.label())];
+        if !self.node_filter.is_empty() || self.input_mode == InputMode::Node {
+            parts.push(format!("node: {}", self.node_filter));
+        }
+        if !self.search.is_empty() || self.input_mode == InputMode::Search {
+            parts.push(format!("/{}", self.search));
+        }
+        if let Some(paused_at) = self.paused_at {
+            let received = self.log.lock().unwrap().received;
+            parts.push(format!("PAUSED ({} new)", received - paused_at));
+        }
+        format!(" {} ", parts.join(" | "))
+    }
+}
+
+impl TuiView for RosoutState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        self.subscribe();
+
+        let Event::Key(CrosstermEvent::Key(key_event)) = event else {
+            return event;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return event;
+        }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+        if self.show_details {
+            // Any key closes the details
+            self.show_details = false;
+            return Event::None;
+        }
+        if self.input_mode != InputMode::Normal {
+            self.handle_key_in_input(key_event.code);
+            return Event::None;
+        }
+        match key_event.code {
+            KeyCode::Char('k') | KeyCode::Up => self.select(-1),
+            KeyCode::Char('j') | KeyCode::Down => self.select(1),
+            KeyCode::Char('G') | KeyCode::End => self.selected = None,
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Char('/') => self.input_mode = InputMode::Search,
+            KeyCode::Char('n') => self.input_mode = InputMode::Node,
+            KeyCode::Char(c @ '1'..='5') => {
+                self.min_level = LogLevel::ALL[c as usize - '1' as usize];
+            }
+            KeyCode::Char('c') => self.log.lock().unwrap().entries.clear(),
+            KeyCode::Enter => match self.selected_entry() {
+                Some(_) => self.show_details = true,
+                None => return Event::Error("No message selected".to_string()),
+            },
+            _ => return event,
+        }
+        Event::None
+    }
+
+    fn name(&self) -> String {
+        "Log".to_string()
+    }
+
+    fn get_help_text(&self) -> String {
+        "Log View Help:\n\
+        - 'k' or ↑: Select the previous message, the log stops following the new messages.\n\
+        - 'j' or ↓: Select the next message, follow the new messages again past the last one.\n\
+        - 'G' or End: Follow the new messages.\n\
+        - 'p': Pause or resume the log, messages keep being received while paused.\n\
+        - '1' to '5': Show the messages from DEBUG, INFO, WARN, ERROR or FATAL up.\n\
+        - 'n': Filter by node (logger name), 'Enter'/'Esc' to stop typing.\n\
+        - '/': Fuzzy search in the messages, 'Enter'/'Esc' to stop typing.\n\
+        - 'Enter': Show the file, function and line of the selected message.\n\
+        - 'c': Clear the log."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.needs_redraw.swap(false, Ordering::Relaxed)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        RosoutWidget::render(area, buf, self);
+    }
+}
+
+impl FromConnection for RosoutState {
+    fn from_connection(connection_info: ConnectionInfo) -> Self {
+        RosoutState::new(connection_info.connection)
+    }
+}
+
+impl RosoutWidget {
+    fn entry_line(entry: &LogEntry, selected: bool) -> Line<'static> {
+        let style = level_style(entry.level);
+        let mut line = Line::from(vec![
+            Span::styled(
+                format!("{} ", format_time_of_day(entry.time)),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(format!("[{}] ", entry.level.label()), style),
+            Span::styled(format!("[{}] ", entry.name), HEADER_STYLE),
+            Span::styled(entry.msg.clone(), style),
+        ]);
+        if selected {
+            line = line.style(SELECTED_STYLE);
+        }
+        line
+    }
+
+    fn render_details(entry: &LogEntry, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).min(100);
+        let height = area.height.saturating_sub(4).min(12);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let field = |name: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{}: ", name), HEADER_STYLE),
+                Span::raw(value),
+            ])
+        };
+        let lines = vec![
+            field("Time", format_time_of_day(entry.time)),
+            field("Level", entry.level.label().to_string()),
+            field("Logger", entry.name.clone()),
+            field("File", format!("{}:{}", entry.file, entry.line)),
+            field("Function", entry.function.clone()),
+            Line::default(),
+            Line::styled(entry.msg.clone(), level_style(entry.level)),
+        ];
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::bordered()
+                    .title(Line::raw(" Log message ").centered())
+                    .border_style(HEADER_STYLE)
+                    .border_type(BorderType::Rounded),
+            )
+            .render(popup_area, buf);
+    }
+
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut RosoutState) {
+        let block = Block::bordered()
+            .title(Line::raw("Log").centered())
+            .title_bottom(Line::raw(state.status()))
+            .border_style(HEADER_STYLE)
+            .border_type(BorderType::Rounded);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        if let Some(error) = &state.error {
+            Paragraph::new(error.as_str()).render(inner_area, buf);
+            return;
+        }
+
+        let entries = state.visible_entries();
+        let height = inner_area.height as usize;
+        // Follow the last message, or keep the selected one in the middle
+        let end = match state.selected_index(&entries) {
+            Some(index) => {
+                (index + height.div_ceil(2)).clamp(height.min(entries.len()), entries.len())
+            }
+            None => entries.len(),
+        };
+        let start = end.saturating_sub(height);
+        if entries.is_empty() {
+            Paragraph::new(format!("No messages on {} yet", ROSOUT))
+                .style(Style::default().fg(Color::DarkGray))
+                .render(inner_area, buf);
+        } else {
+            let lines: Vec<Line> = entries[start..end]
+                .iter()
+                .map(|(number, entry)| Self::entry_line(entry, state.selected == Some(*number)))
+                .collect();
+            Paragraph::new(lines).render(inner_area, buf);
+        }
+
+        if state.show_details {
+            if let Some(entry) = state.selected_entry() {
+                Self::render_details(&entry, area, buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{emit, key, mock_connection};
+
+    const CONFIG: &str = "\
+definitions:
+  rcl_interfaces/msg/Log: |
+    builtin_interfaces/Time stamp
+    uint8 level
+    string name
+    string msg
+    string file
+    string function
+    uint32 line
+    ================================================================================
+    MSG: builtin_interfaces/Time
+    int32 sec
+    uint32 nanosec
+topics:
+  - name: /rosout
+    type: rcl_interfaces/msg/Log
+    messages:
+      - { level: 20, name: talker, msg: Publishing hello, file: talker.cpp, function: on_timer, line: 12 }
+      - { level: 30, name: ns.camera, msg: Dropped a frame, file: camera.cpp, function: on_frame, line: 42 }
+";
+
+    fn messages(view: &mut RosoutState) -> Vec<String> {
+        view.visible_entries()
+            .into_iter()
+            .map(|(_, entry)| entry.msg)
+            .collect()
+    }
+
+    fn type_keys(view: &mut RosoutState, text: &str) {
+        for c in text.chars() {
+            view.handle_event(key(KeyCode::Char(c)));
+        }
+        view.handle_event(key(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_filters_pause_and_details() {
+        let connection = mock_connection(CONFIG);
+        let mut view = RosoutState::new(connection.clone());
+        assert!(view.error.is_none());
+        assert!(messages(&mut view).is_empty());
+
+        emit(&connection, ROSOUT);
+        emit(&connection, ROSOUT);
+        assert!(view.needs_redraw());
+        assert_eq!(messages(&mut view), ["Publishing hello", "Dropped a frame"]);
+
+        // From WARN up
+        view.handle_event(key(KeyCode::Char('3')));
+        assert_eq!(view.min_level, LogLevel::Warn);
+        assert_eq!(messages(&mut view), ["Dropped a frame"]);
+        view.handle_event(key(KeyCode::Char('1')));
+
+        type_keys(&mut view, "/hello");
+        assert_eq!(messages(&mut view), ["Publishing hello"]);
+        view.search.clear();
+
+        // The logger name of a node
+        let mut node_view =
+            RosoutState::for_node(&NodeName::new("/ns", "camera"), connection.clone());
+        emit(&connection, ROSOUT);
+        emit(&connection, ROSOUT);
+        assert_eq!(node_view.node_filter, "ns.camera");
+        assert_eq!(messages(&mut node_view), ["Dropped a frame"]);
+
+        // Paused, new messages are counted but not shown
+        view.handle_event(key(KeyCode::Char('p')));
+        emit(&connection, ROSOUT);
+        assert_eq!(view.paused_at, Some(4));
+        assert_eq!(view.log.lock().unwrap().received, 5);
+        assert_eq!(messages(&mut view).len(), 4);
+        view.handle_event(key(KeyCode::Char('p')));
+        assert_eq!(messages(&mut view).len(), 5);
+
+        // Scrolling back selects the last message
+        view.handle_event(key(KeyCode::Char('k')));
+        assert_eq!(view.selected, Some(4));
+        view.handle_event(key(KeyCode::Char('k')));
+        view.handle_event(key(KeyCode::Enter));
+        assert!(view.show_details);
+        let entry = view.selected_entry().unwrap();
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!((entry.file.as_str(), entry.line), ("camera.cpp", 42));
+        assert_eq!(entry.function, "on_frame");
+    }
+}
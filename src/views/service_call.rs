@@ -20,8 +20,12 @@ use crate::{
         generic_message_selector::{get_field_category, FieldCategory},
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{
+        pending::{call_timeout, spinner, PendingCall},
+        Connection, ConnectionType,
+    },
     views::{
+        interface_definition::InterfaceDefinitionState,
         message_pane::{commit_field_edit, MessagePaneState},
         TuiView,
     },
@@ -75,6 +79,8 @@ pub struct ServiceCallState {
     response_pane: MessagePaneState,
     response_error: Option<String>,
     response_warnings: Vec<String>,
+    /// Call waiting for its response, polled on every event.
+    pending: Option<PendingCall<(GenericMessage, Vec<String>)>>,
 
     focus: FocusPane,
     needs_redraw: bool,
@@ -103,6 +109,7 @@ impl ServiceCallState {
             response_pane: MessagePaneState::new(),
             response_error: None,
             response_warnings: Vec::new(),
+            pending: None,
             focus: FocusPane::Request,
             needs_redraw: true,
         }
@@ -117,13 +124,36 @@ impl ServiceCallState {
         )
     }
 
+    /// Send the request, a call still waiting for its response is cancelled.
     fn call_service(&mut self) -> Event {
         self.needs_redraw = true;
-        match self.connection.borrow().call_service(
+        self.pending = Some(self.connection.borrow().call_service(
             &self.service_name,
             &self.service_type,
             &self.request,
-        ) {
+        ));
+        self.poll_pending()
+    }
+
+    fn cancel_call(&mut self) {
+        self.pending = None;
+        self.response = None;
+        self.response_error = Some("Call cancelled".to_string());
+        self.response_warnings.clear();
+        self.needs_redraw = true;
+    }
+
+    fn poll_pending(&mut self) -> Event {
+        let Some(pending) = &self.pending else {
+            return Event::None;
+        };
+        // Redraw the spinner
+        self.needs_redraw = true;
+        let Some(result) = pending.poll_timeout() else {
+            return Event::None;
+        };
+        self.pending = None;
+        match result {
             Ok((response, warnings)) => {
                 self.response = Some(response);
                 self.response_error = None;
@@ -313,11 +343,22 @@ impl TuiView for ServiceCallState {
                 if key_event.kind != KeyEventKind::Press {
                     return event;
                 }
+                // Esc only cancels while a call is running, it quits otherwise.
+                if key_event.code == KeyCode::Esc && self.pending.is_some() {
+                    self.cancel_call();
+                    return Event::None;
+                }
+                if key_event.code == KeyCode::Char('i') && !self.is_editing {
+                    return Event::NewView(Rc::new(RefCell::new(InterfaceDefinitionState::new(
+                        self.service_type.clone(),
+                    ))));
+                }
                 match self.focus {
                     FocusPane::Request => self.handle_request_event(key_event),
                     FocusPane::Response => self.handle_response_event(key_event),
                 }
             }
+            Event::None => self.poll_pending(),
             other => other,
         }
     }
@@ -329,6 +370,8 @@ impl TuiView for ServiceCallState {
     fn get_help_text(&self) -> String {
         "Service Call View Help:\n\
         - 'c': Call the service with the current request.\n\
+        - 'i': Show the definition of the service type.\n\
+        - 'Esc': Cancel the running call.\n\
         - 'l' or →: Switch focus to the response pane.\n\
         - 'h' or ←: Switch focus to the request pane.\n\
         - 'j' or ↓: Move down in the message fields.\n\
@@ -391,7 +434,18 @@ impl TuiView for ServiceCallState {
             .border_style(response_border_style)
             .border_set(RIGHT_BORDER_SET);
 
-        if let Some(response) = &self.response {
+        if let Some(pending) = &self.pending {
+            let elapsed = pending.elapsed();
+            let text = format!(
+                "{} Calling {}... {:.1}s / {:.1}s\nPress 'Esc' to cancel.",
+                spinner(elapsed),
+                self.service_name,
+                elapsed.as_secs_f64(),
+                call_timeout().as_secs_f64()
+            );
+            let paragraph = Paragraph::new(text).block(response_block);
+            Widget::render(paragraph, response_area, buf);
+        } else if let Some(response) = &self.response {
             let response_widget = MessageWidget::new(response)
                 .with_selection(&self.response_pane.selected_fields)
                 .block(response_block);
@@ -18,7 +18,7 @@ use crate::{
         generic_message::{AnyTypeRef, GenericMessage, MessageMetadata},
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{Connection, ConnectionType, SubscriptionHandle},
     views::{AcceptsField, FieldInfo, FieldInfoType, FromField, TuiView},
     widgets::state_graph_widget::{
         Axis, StateColorMap, StateDataPoint, StateDataset, StateGraphWidget, StateGraphWidgetState,
@@ -34,10 +34,11 @@ type StateBuffer = Arc<Mutex<Vec<(f64, String)>>>;
 struct StateLineState {
     topic: String,
     field_name: String,
-    _connection: Rc<RefCell<ConnectionType>>,
+    connection: Rc<RefCell<ConnectionType>>,
     buffer: StateBuffer,
     /// Persistent color map – once a state is assigned a color it keeps it forever.
     color_map: StateColorMap,
+    _subscription: SubscriptionHandle,
 }
 
 /// View state that drives the `StateGraphWidget`.
@@ -76,10 +77,11 @@ impl StateGraphViewState {
         let buffer_clone = buffer.clone();
         let selected_fields_clone = selected_fields.clone();
 
-        connection
+        let subscription = connection
             .borrow_mut()
             .subscribe(
                 &topic,
+                None,
                 move |msg: GenericMessage, msg_info: MessageMetadata| {
                     let stamp = msg_info
                         .received_time
@@ -88,6 +90,10 @@ impl StateGraphViewState {
                         .as_secs_f64();
                     if let Some(state_str) = get_state_string(&msg, &selected_fields_clone) {
                         let mut buf = buffer_clone.lock().unwrap();
+                        // Time going backwards means a recording was seeked, start over.
+                        if buf.last().is_some_and(|&(last, _)| stamp < last) {
+                            buf.clear();
+                        }
                         // Only record when the state actually changes
                         if buf.last().is_none_or(|(_, prev)| *prev != state_str) {
                             buf.push((stamp, state_str));
@@ -106,9 +112,10 @@ impl StateGraphViewState {
         self.lines.push(StateLineState {
             topic,
             field_name,
-            _connection: connection,
+            connection,
             buffer,
             color_map: StateColorMap::new(),
+            _subscription: subscription,
         });
     }
 }
@@ -208,7 +215,10 @@ impl TuiView for StateGraphViewState {
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer) {
-        let current_time = SystemTime::now()
+        let current_time = self.lines[0]
+            .connection
+            .borrow()
+            .now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("Timestamp before UNIX EPOCH")
             .as_secs_f64();
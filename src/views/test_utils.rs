@@ -0,0 +1,92 @@
+use std::{cell::RefCell, rc::Rc, time::SystemTime};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::{
+    common::event::Event,
+    connections::{mock::ConnectionMock, ConnectionType},
+    views::TuiView,
+};
+
+/// `/talker` publishing `/chatter`, with parameters reported on `/parameter_events` when set
+/// through the connection.
+pub const PARAMETER_EVENTS_CONFIG: &str = "\
+definitions:
+  std_msgs/msg/String: string data
+  rcl_interfaces/msg/ParameterEvent: |
+    builtin_interfaces/Time stamp
+    string node
+    Parameter[] new_parameters
+    Parameter[] changed_parameters
+    Parameter[] deleted_parameters
+    ================================================================================
+    MSG: builtin_interfaces/Time
+    int32 sec
+    uint32 nanosec
+    ================================================================================
+    MSG: rcl_interfaces/Parameter
+    string name
+    ParameterValue value
+    ================================================================================
+    MSG: rcl_interfaces/ParameterValue
+    uint8 type
+    bool bool_value
+    int64 integer_value
+    float64 double_value
+    string string_value
+    byte[] byte_array_value
+    bool[] bool_array_value
+    int64[] integer_array_value
+    float64[] double_array_value
+    string[] string_array_value
+nodes:
+  - name: /talker
+    publishers: [/chatter, /parameter_events]
+    parameters: { rate: 2.0, frame_id: base_link }
+topics:
+  - { name: /chatter, type: std_msgs/msg/String }
+  - { name: /parameter_events, type: rcl_interfaces/msg/ParameterEvent }
+";
+
+pub fn key(code: KeyCode) -> Event {
+    Event::Key(CrosstermEvent::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+}
+
+/// Connection serving the mock configuration `config`.
+pub fn mock_connection(config: &str) -> Rc<RefCell<ConnectionType>> {
+    let connection = ConnectionMock::from_yaml(config, "mock.yaml").unwrap();
+    Rc::new(RefCell::new(ConnectionType::Mock(connection)))
+}
+
+/// Publish the next message of `topic` on a mock connection.
+pub fn emit(connection: &Rc<RefCell<ConnectionType>>, topic: &str) {
+    match &*connection.borrow() {
+        ConnectionType::Mock(mock) => mock.emit(topic).unwrap(),
+        _ => panic!("Not a mock connection"),
+    }
+}
+
+/// Publish the next message of `topic` on a mock connection, received at `received_time`.
+pub fn emit_at(connection: &Rc<RefCell<ConnectionType>>, topic: &str, received_time: SystemTime) {
+    match &*connection.borrow() {
+        ConnectionType::Mock(mock) => mock.emit_at(topic, received_time).unwrap(),
+        _ => panic!("Not a mock connection"),
+    }
+}
+
+/// Text of each line of `view` rendered in a `width` by `height` terminal.
+pub fn render_lines(view: &mut dyn TuiView, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal
+        .draw(|frame| view.render(frame.area(), frame.buffer_mut()))
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
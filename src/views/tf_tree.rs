@@ -0,0 +1,525 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use ratatui::{
+    prelude::{Buffer, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+};
+
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
+
+use crate::{
+    common::{
+        event::Event,
+        generic_message::{GenericMessage, MessageMetadata},
+        style::{HEADER_STYLE, SELECTED_STYLE},
+    },
+    connections::{
+        qos::QosProfile,
+        tf::{tf_message, FrameInfo, TfTree, TF, TF_STATIC},
+        Connection, ConnectionType, SubscriptionHandle,
+    },
+    views::{ConnectionInfo, FromConnection, TuiView},
+};
+
+/// How often to retry subscribing, until the topics are known to the connection.
+const SUBSCRIBE_RETRY: Duration = Duration::from_secs(1);
+
+/// A dynamic transform is stale when not received for this long, or for three of its periods
+/// when it is published slower.
+const STALE_AGE: Duration = Duration::from_secs(1);
+
+pub struct TfTreeWidget;
+
+/// Time since `frame` was last received, and whether that makes it stale.
+fn frame_age(frame: &FrameInfo, now: SystemTime) -> Option<(Duration, bool)> {
+    let age = now
+        .duration_since(frame.last_received()?)
+        .unwrap_or_default();
+    let stale_age = frame.rate().map_or(STALE_AGE, |rate| {
+        STALE_AGE.max(Duration::from_secs_f64(3.0 / rate))
+    });
+    Some((age, !frame.is_static && age > stale_age))
+}
+
+pub struct TfTreeState {
+    connection: Rc<RefCell<ConnectionType>>,
+    /// Subscriptions to `/tf` and `/tf_static`, a recording may only have one of them.
+    subscriptions: [Option<SubscriptionHandle>; 2],
+    /// Why the subscriptions failed, and when to try again.
+    error: Option<String>,
+    next_subscribe: Instant,
+    tree: Arc<Mutex<TfTree>>,
+
+    selected: Option<usize>,
+    /// Frames picked with Enter: the transform of `source` is shown in `target`.
+    target: Option<String>,
+    source: Option<String>,
+    needs_redraw: Arc<AtomicBool>,
+}
+
+impl TfTreeState {
+    pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
+        let mut state = Self {
+            connection,
+            subscriptions: [None, None],
+            error: None,
+            next_subscribe: Instant::now(),
+            tree: Arc::new(Mutex::new(TfTree::default())),
+            selected: None,
+            target: None,
+            source: None,
+            needs_redraw: Arc::new(AtomicBool::new(true)),
+        };
+        state.subscribe();
+        state
+    }
+
+    fn is_subscribed(&self) -> bool {
+        self.subscriptions.iter().any(Option::is_some)
+    }
+
+    /// Subscribe to `/tf` and `/tf_static`, unless already subscribed or waiting to retry.
+    fn subscribe(&mut self) {
+        if self.subscriptions.iter().all(Option::is_some) || Instant::now() < self.next_subscribe {
+            return;
+        }
+        let mut errors = Vec::new();
+        // Static transforms are only published once, for the late subscriptions to get them
+        for (index, (topic, qos, is_static)) in [
+            (TF, None, false),
+            (TF_STATIC, Some(QosProfile::transient_local()), true),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if self.subscriptions[index].is_some() {
+                continue;
+            }
+            let tree = self.tree.clone();
+            let needs_redraw = self.needs_redraw.clone();
+            let subscription = self.connection.borrow_mut().subscribe(
+                topic,
+                qos,
+                move |msg: GenericMessage, msg_info: MessageMetadata| {
+                    if let Ok(transforms) = tf_message(&msg) {
+                        let mut tree = tree.lock().unwrap();
+                        for transform in transforms {
+                            tree.insert(transform, is_static, msg_info.received_time);
+                        }
+                        needs_redraw.store(true, Ordering::Relaxed);
+                    }
+                },
+            );
+            match subscription {
+                Ok(subscription) => self.subscriptions[index] = Some(subscription),
+                Err(e) => errors.push(format!("{}: {}", topic, e)),
+            }
+        }
+        if !errors.is_empty() {
+            self.next_subscribe = Instant::now() + SUBSCRIBE_RETRY;
+        }
+        self.error = (!self.is_subscribed()).then(|| format!("Waiting for {}", errors.join(", ")));
+        self.needs_redraw.store(true, Ordering::Relaxed);
+    }
+
+    fn selected_frame(&self) -> Option<String> {
+        let rows = self.tree.lock().unwrap().depth_first();
+        rows.get(self.selected?).map(|(_, name)| name.clone())
+    }
+
+    fn select(&mut self, offset: isize) {
+        let count = self.tree.lock().unwrap().depth_first().len();
+        if count == 0 {
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(selected) => selected.saturating_add_signed(offset).min(count - 1),
+            None => 0,
+        });
+    }
+
+    /// Pick the selected frame: the target frame first, then the source frame. Picking again
+    /// starts a new pair.
+    fn pick(&mut self) {
+        let Some(frame) = self.selected_frame() else {
+            return;
+        };
+        match (&self.target, &self.source) {
+            (Some(_), None) => self.source = Some(frame),
+            _ => {
+                self.target = Some(frame);
+                self.source = None;
+            }
+        }
+    }
+
+    fn clear_pick(&mut self) {
+        self.target = None;
+        self.source = None;
+    }
+}
+
+impl TuiView for TfTreeState {
+    fn handle_event(&mut self, event: Event) -> Event {
+        self.subscribe();
+
+        let Event::Key(CrosstermEvent::Key(key_event)) = event else {
+            // The ages of the transforms grow without new messages
+            if self.is_subscribed() {
+                self.needs_redraw.store(true, Ordering::Relaxed);
+            }
+            return event;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            return event;
+        }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+        match key_event.code {
+            KeyCode::Char('k') | KeyCode::Up => self.select(-1),
+            KeyCode::Char('j') | KeyCode::Down => self.select(1),
+            KeyCode::Enter => self.pick(),
+            KeyCode::Char('c') => self.clear_pick(),
+            // Esc only drops the picked frames, it quits otherwise.
+            KeyCode::Esc if self.target.is_some() => self.clear_pick(),
+            _ => return event,
+        }
+        Event::None
+    }
+
+    fn name(&self) -> String {
+        "TF Tree".to_string()
+    }
+
+    fn get_help_text(&self) -> String {
+        "TF Tree View Help:\n\
+        - 'j' or ↓: Select the next frame.\n\
+        - 'k' or ↑: Select the previous frame.\n\
+        - 'Enter': Pick the selected frame, the target frame first then the source frame, to \
+        follow the transform of the source in the target.\n\
+        - 'c' or 'Esc': Drop the picked frames.\n\
+        \n\
+        Frames are built from /tf and /tf_static, with the rate and age of their transform. \
+        Dynamic transforms are stale (red) when not received for a second, or for three of \
+        their periods. Several trees mean some frames are not connected."
+            .to_string()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.needs_redraw.swap(false, Ordering::Relaxed)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        TfTreeWidget::render(area, buf, self);
+    }
+}
+
+impl FromConnection for TfTreeState {
+    fn from_connection(connection_info: ConnectionInfo) -> Self {
+        TfTreeState::new(connection_info.connection)
+    }
+}
+
+impl TfTreeWidget {
+    fn frame_line(
+        depth: usize,
+        name: &str,
+        frame: Option<&FrameInfo>,
+        now: SystemTime,
+        state: &TfTreeState,
+        selected: bool,
+    ) -> Line<'static> {
+        let mut spans = vec![Span::raw(format!("{}{}", "  ".repeat(depth), name))];
+        let details = Style::default().fg(Color::DarkGray);
+        match frame.and_then(|frame| Some((frame, frame_age(frame, now)?))) {
+            Some((frame, _)) if frame.is_static => spans.push(Span::styled(" static", details)),
+            Some((frame, (age, stale))) => {
+                let rate = frame
+                    .rate()
+                    .map_or("?".to_string(), |rate| format!("{:.1}", rate));
+                let style = if stale {
+                    Style::default().fg(Color::Red)
+                } else {
+                    details
+                };
+                spans.push(Span::styled(
+                    format!(" {} Hz, {:.2}s ago", rate, age.as_secs_f64()),
+                    style,
+                ));
+            }
+            // Roots have no transform
+            None => {}
+        }
+        for (label, picked) in [("target", &state.target), ("source", &state.source)] {
+            if picked.as_deref() == Some(name) {
+                spans.push(Span::styled(
+                    format!(" [{}]", label),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+        }
+        let line = Line::from(spans);
+        if selected {
+            line.style(SELECTED_STYLE)
+        } else {
+            line
+        }
+    }
+
+    /// Parent of the selected frame, and the transform between the picked frames.
+    fn panel_lines(
+        tree: &TfTree,
+        selected: Option<&str>,
+        state: &TfTreeState,
+    ) -> Vec<Line<'static>> {
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<10}", label),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(value),
+            ])
+        };
+        let mut lines = Vec::new();
+        if let Some(selected) = selected {
+            let parent = tree
+                .frame(selected)
+                .map_or("none (root)".to_string(), |frame| frame.parent.clone());
+            lines.push(field("Parent", format!("{} ← {}", parent, selected)));
+        }
+        match (&state.target, &state.source) {
+            (Some(target), Some(source)) => {
+                lines.push(field("Lookup", format!("{} in {}", source, target)));
+                match tree.lookup(target, source) {
+                    Ok(transform) => {
+                        let [x, y, z] = transform.translation;
+                        let [roll, pitch, yaw] = transform.rpy().map(f64::to_degrees);
+                        lines.push(field("XYZ", format!("{:.4} {:.4} {:.4} m", x, y, z)));
+                        lines.push(field(
+                            "RPY",
+                            format!("{:.2} {:.2} {:.2} °", roll, pitch, yaw),
+                        ));
+                    }
+                    Err(e) => lines.push(Line::styled(e, Style::default().fg(Color::Red))),
+                }
+            }
+            (Some(target), None) => lines.push(field(
+                "Lookup",
+                format!("? in {} ('Enter' to pick the source frame)", target),
+            )),
+            _ => lines.push(field(
+                "Lookup",
+                "'Enter' to pick the target frame".to_string(),
+            )),
+        }
+        lines
+    }
+
+    pub fn render(area: Rect, buf: &mut Buffer, state: &mut TfTreeState) {
+        let now = state.connection.borrow().now();
+        let tree = state.tree.lock().unwrap().clone();
+        let rows = tree.depth_first();
+        if let Some(selected) = state.selected {
+            state.selected = (!rows.is_empty()).then(|| selected.min(rows.len() - 1));
+        }
+
+        let roots = tree.roots();
+        let mut block = Block::bordered()
+            .title(Line::raw("TF Tree").centered())
+            .border_style(HEADER_STYLE)
+            .border_type(BorderType::Rounded);
+        if roots.len() > 1 {
+            block = block.title_bottom(Line::styled(
+                format!(
+                    " {} trees, not connected: {} ",
+                    roots.len(),
+                    roots.join(", ")
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        if let Some(error) = &state.error {
+            Paragraph::new(error.as_str()).render(inner_area, buf);
+            return;
+        }
+
+        let selected = state
+            .selected
+            .and_then(|selected| rows.get(selected))
+            .map(|(_, name)| name.as_str());
+        let panel = Self::panel_lines(&tree, selected, state);
+        let [list_area, panel_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(panel.len() as u16 + 1),
+        ])
+        .areas(inner_area);
+        Paragraph::new(panel)
+            .block(
+                Block::new()
+                    .borders(Borders::TOP)
+                    .title(Line::raw("Transform").centered())
+                    .border_style(HEADER_STYLE),
+            )
+            .render(panel_area, buf);
+
+        if rows.is_empty() {
+            Paragraph::new(format!("No transforms on {} or {} yet", TF, TF_STATIC))
+                .style(Style::default().fg(Color::DarkGray))
+                .render(list_area, buf);
+            return;
+        }
+        // Keep the selected frame in view
+        let height = list_area.height as usize;
+        let start = state
+            .selected
+            .map_or(0, |selected| (selected + 1).saturating_sub(height));
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(index, (depth, name))| {
+                Self::frame_line(
+                    *depth,
+                    name,
+                    tree.frame(name),
+                    now,
+                    state,
+                    state.selected == Some(index),
+                )
+            })
+            .collect();
+        Paragraph::new(lines).render(list_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{emit_at, key, mock_connection};
+
+    const CONFIG: &str = "\
+definitions:
+  tf2_msgs/msg/TFMessage: |
+    geometry_msgs/TransformStamped[] transforms
+    ================================================================================
+    MSG: geometry_msgs/TransformStamped
+    std_msgs/Header header
+    string child_frame_id
+    Transform transform
+    ================================================================================
+    MSG: std_msgs/Header
+    builtin_interfaces/Time stamp
+    string frame_id
+    ================================================================================
+    MSG: builtin_interfaces/Time
+    int32 sec
+    uint32 nanosec
+    ================================================================================
+    MSG: geometry_msgs/Transform
+    Vector3 translation
+    Quaternion rotation
+    ================================================================================
+    MSG: geometry_msgs/Vector3
+    float64 x
+    float64 y
+    float64 z
+    ================================================================================
+    MSG: geometry_msgs/Quaternion
+    float64 x
+    float64 y
+    float64 z
+    float64 w
+topics:
+  - name: /tf
+    type: tf2_msgs/msg/TFMessage
+    messages:
+      - transforms:
+          - header: { frame_id: odom }
+            child_frame_id: base_link
+            transform: { translation: { y: 2.0 }, rotation: { w: 1.0 } }
+  - name: /tf_static
+    type: tf2_msgs/msg/TFMessage
+    messages:
+      - transforms:
+          - header: { frame_id: map }
+            child_frame_id: odom
+            transform: { translation: { x: 1.0 }, rotation: { w: 1.0 } }
+";
+
+    #[test]
+    fn test_tree_and_lookup() {
+        let connection = mock_connection(CONFIG);
+        let mut view = TfTreeState::new(connection.clone());
+        assert!(view.error.is_none());
+        // Nothing to select without frames
+        view.handle_event(key(KeyCode::Char('j')));
+        assert_eq!(view.selected, None);
+
+        view.needs_redraw();
+        let received = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        emit_at(&connection, TF_STATIC, received);
+        emit_at(&connection, TF, received);
+        assert!(view.needs_redraw());
+        {
+            let tree = view.tree.lock().unwrap();
+            assert_eq!(
+                tree.depth_first(),
+                [
+                    (0, "map".to_string()),
+                    (1, "odom".to_string()),
+                    (2, "base_link".to_string())
+                ]
+            );
+            // Only dynamic transforms go stale
+            let later = received + Duration::from_secs(5);
+            assert_eq!(
+                frame_age(tree.frame("odom").unwrap(), later),
+                Some((Duration::from_secs(5), false))
+            );
+            assert_eq!(
+                frame_age(tree.frame("base_link").unwrap(), later),
+                Some((Duration::from_secs(5), true))
+            );
+        }
+
+        // Pick map as the target frame, then base_link as the source frame
+        view.handle_event(key(KeyCode::Char('j')));
+        view.handle_event(key(KeyCode::Enter));
+        view.handle_event(key(KeyCode::Char('j')));
+        view.handle_event(key(KeyCode::Char('j')));
+        view.handle_event(key(KeyCode::Enter));
+        assert_eq!(view.target.as_deref(), Some("map"));
+        assert_eq!(view.source.as_deref(), Some("base_link"));
+        let transform = view
+            .tree
+            .lock()
+            .unwrap()
+            .lookup("map", "base_link")
+            .unwrap();
+        assert_eq!(transform.translation, [1.0, 2.0, 0.0]);
+
+        // The selection stops at the last frame
+        view.handle_event(key(KeyCode::Char('j')));
+        assert_eq!(view.selected_frame().as_deref(), Some("base_link"));
+
+        view.handle_event(key(KeyCode::Char('c')));
+        assert!(view.target.is_none() && view.source.is_none());
+    }
+}
@@ -4,16 +4,23 @@ use crossterm::event::{Event as CrosstermEvent, KeyCode};
 use ratatui::{
     layout::Spacing,
     prelude::{Buffer, Constraint, Layout, Rect},
+    style::{Color, Style},
     symbols::{border, line},
-    text::Line,
-    widgets::{Block, Paragraph, StatefulWidget, Widget},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, StatefulWidget, Widget},
 };
 
 use crate::{
     common::{event::Event, style::SELECTED_STYLE},
-    connections::{Connection, ConnectionType, NamedInterface, NodeName},
-    views::{AcceptsNode, AcceptsTopic, FromNode, FromTopic, NodeInfo, TopicInfo, TuiView},
-    widgets::list_widget::{ListWidget, ListWidgetState},
+    connections::{
+        qos::incompatible_pairs, Connection, ConnectionType, NamedInterface, NodeName,
+        TopicEndpointInfo,
+    },
+    views::{
+        endpoint_details, AcceptsNode, AcceptsTopic, FromNode, FromTopic, NodeInfo, TopicInfo,
+        TuiView,
+    },
+    widgets::list_widget::{ListItemTrait, ListWidget, ListWidgetState},
 };
 
 // ─── Border sets for collapsed panel borders ─────────────────────────────────
@@ -54,6 +61,36 @@ const RIGHT_BORDER_SET: border::Set = border::Set {
     horizontal_bottom: line::HORIZONTAL,
 };
 
+// ─── EndpointItem ─────────────────────────────────────────────────────────────
+
+/// Publisher or subscriber of the centre topic, with the endpoints of the other side it can't
+/// communicate with.
+#[derive(Clone)]
+struct EndpointItem {
+    info: TopicEndpointInfo,
+    /// Node of each incompatible endpoint with the reasons of the incompatibility.
+    incompatibilities: Vec<(NodeName, Vec<String>)>,
+}
+
+impl ListItemTrait for EndpointItem {
+    fn search_text(&self) -> String {
+        self.info.node_name.search_text()
+    }
+
+    fn to_line(&self, width: usize, selected: bool, indices: Vec<u32>) -> Line<'_> {
+        if self.incompatibilities.is_empty() {
+            return self.info.node_name.to_line(width, selected, indices);
+        }
+        let mut line = self
+            .info
+            .node_name
+            .to_line(width.saturating_sub(2), selected, indices);
+        line.spans
+            .insert(0, Span::styled("⚠ ", Style::default().fg(Color::Red)));
+        line
+    }
+}
+
 // ─── GraphFocus / GraphMode ───────────────────────────────────────────────────
 
 #[derive(Clone, Copy, PartialEq)]
@@ -79,8 +116,8 @@ pub struct TopicGraphState {
     focus: GraphFocus,
 
     // Topic mode panels
-    publisher_nodes: ListWidgetState<NodeName>,
-    subscriber_nodes: ListWidgetState<NodeName>,
+    publisher_nodes: ListWidgetState<EndpointItem>,
+    subscriber_nodes: ListWidgetState<EndpointItem>,
 
     // Node mode panels
     subscribed_topics: ListWidgetState<NamedInterface>,
@@ -129,13 +166,28 @@ impl TopicGraphState {
     fn fetch_topic_endpoints(
         connection: &Rc<RefCell<ConnectionType>>,
         topic: &str,
-    ) -> (Vec<NodeName>, Vec<NodeName>) {
+    ) -> (Vec<EndpointItem>, Vec<EndpointItem>) {
         let conn = connection.borrow();
         let publishers = conn.get_publishers_info_by_topic(topic).unwrap_or_default();
         let subscribers = conn
             .get_subscriptions_info_by_topic(topic)
             .unwrap_or_default();
-        (publishers, subscribers)
+
+        let to_item = |info: &TopicEndpointInfo| EndpointItem {
+            info: info.clone(),
+            incompatibilities: Vec::new(),
+        };
+        let mut publisher_items: Vec<EndpointItem> = publishers.iter().map(to_item).collect();
+        let mut subscriber_items: Vec<EndpointItem> = subscribers.iter().map(to_item).collect();
+        for (publisher, subscriber, reasons) in incompatible_pairs(&publishers, &subscribers) {
+            publisher_items[publisher]
+                .incompatibilities
+                .push((subscribers[subscriber].node_name.clone(), reasons.clone()));
+            subscriber_items[subscriber]
+                .incompatibilities
+                .push((publishers[publisher].node_name.clone(), reasons));
+        }
+        (publisher_items, subscriber_items)
     }
 
     fn fetch_node_topics(
@@ -208,6 +260,12 @@ impl TopicGraphState {
 
     // ─── Event helpers ───────────────────────────────────────────────────────
 
+    fn selected_node(endpoints: &ListWidgetState<EndpointItem>) -> Option<NodeName> {
+        endpoints
+            .get_selected()
+            .map(|item| item.info.node_name.clone())
+    }
+
     /// Returns `true` if the panel is non-empty (an item was selected).
     fn try_select_first_left(&mut self) -> bool {
         match &self.mode {
@@ -245,7 +303,7 @@ impl TopicGraphState {
     fn navigate_left_to_centre(&mut self) -> bool {
         match self.mode.clone() {
             GraphMode::Topic(ti) => {
-                if let Some(node_name) = self.publisher_nodes.get_selected().cloned() {
+                if let Some(node_name) = Self::selected_node(&self.publisher_nodes) {
                     self.apply_node(NodeInfo {
                         connection: ti.connection.clone(),
                         node_name,
@@ -259,6 +317,7 @@ impl TopicGraphState {
                         connection: ni.connection.clone(),
                         topic: topic.name,
                         type_name: topic.type_name,
+                        qos: None,
                     });
                     return true;
                 }
@@ -272,7 +331,7 @@ impl TopicGraphState {
     fn navigate_right_to_centre(&mut self) -> bool {
         match self.mode.clone() {
             GraphMode::Topic(ti) => {
-                if let Some(node_name) = self.subscriber_nodes.get_selected().cloned() {
+                if let Some(node_name) = Self::selected_node(&self.subscriber_nodes) {
                     self.apply_node(NodeInfo {
                         connection: ti.connection.clone(),
                         node_name,
@@ -286,6 +345,7 @@ impl TopicGraphState {
                         connection: ni.connection.clone(),
                         topic: topic.name,
                         type_name: topic.type_name,
+                        qos: None,
                     });
                     return true;
                 }
@@ -370,7 +430,7 @@ impl TopicGraphState {
                 let new_event = self.publisher_nodes.handle_event(event);
                 if let Event::Key(CrosstermEvent::Key(key_event)) = &new_event {
                     if key_event.code == KeyCode::Enter {
-                        if let Some(node_name) = self.publisher_nodes.get_selected().cloned() {
+                        if let Some(node_name) = Self::selected_node(&self.publisher_nodes) {
                             return Event::NewNode(NodeInfo {
                                 connection: topic_info.connection.clone(),
                                 node_name,
@@ -384,7 +444,7 @@ impl TopicGraphState {
                 let new_event = self.subscriber_nodes.handle_event(event);
                 if let Event::Key(CrosstermEvent::Key(key_event)) = &new_event {
                     if key_event.code == KeyCode::Enter {
-                        if let Some(node_name) = self.subscriber_nodes.get_selected().cloned() {
+                        if let Some(node_name) = Self::selected_node(&self.subscriber_nodes) {
                             return Event::NewNode(NodeInfo {
                                 connection: topic_info.connection.clone(),
                                 node_name,
@@ -416,6 +476,7 @@ impl TopicGraphState {
                                 connection: node_info.connection.clone(),
                                 topic: interface.name.clone(),
                                 type_name: interface.type_name.clone(),
+                                qos: None,
                             });
                         }
                     }
@@ -431,6 +492,7 @@ impl TopicGraphState {
                                 connection: node_info.connection.clone(),
                                 topic: interface.name.clone(),
                                 type_name: interface.type_name.clone(),
+                                qos: None,
                             });
                         }
                     }
@@ -492,7 +554,72 @@ impl TopicGraphState {
             .collect()
     }
 
+    /// Details of the selected endpoint, or the incompatible QoS pairs of the topic when the
+    /// centre is focused.
+    fn endpoint_panel(&self) -> (&'static str, Vec<Line<'static>>) {
+        let selected = match self.focus {
+            GraphFocus::Left => self
+                .publisher_nodes
+                .get_selected()
+                .map(|item| ("Publisher", item)),
+            GraphFocus::Right => self
+                .subscriber_nodes
+                .get_selected()
+                .map(|item| ("Subscriber", item)),
+            GraphFocus::Centre => None,
+        };
+        if let Some((title, item)) = selected {
+            return (title, endpoint_details(&item.info, &item.incompatibilities));
+        }
+
+        let mut lines: Vec<Line> = self
+            .publisher_nodes
+            .items()
+            .flat_map(|publisher| {
+                publisher
+                    .incompatibilities
+                    .iter()
+                    .map(move |(subscriber, reasons)| {
+                        Line::styled(
+                            format!(
+                                "⚠ {} → {}: {}",
+                                publisher.info.node_name.full_name(),
+                                subscriber.full_name(),
+                                reasons.join(", ")
+                            ),
+                            Style::default().fg(Color::Red),
+                        )
+                    })
+            })
+            .collect();
+        if lines.is_empty() {
+            let qos_known = self
+                .publisher_nodes
+                .items()
+                .chain(self.subscriber_nodes.items())
+                .any(|item| item.info.qos.is_some());
+            lines.push(Line::raw(if qos_known {
+                "No QoS incompatibility"
+            } else {
+                "QoS not reported by the connection"
+            }));
+        }
+        ("QoS compatibility", lines)
+    }
+
     fn render_topic_mode(area: Rect, buf: &mut Buffer, state: &mut Self, topic_info: &TopicInfo) {
+        let (panel_title, panel_lines) = state.endpoint_panel();
+        let panel_height = (panel_lines.len() as u16 + 2).min(area.height / 2);
+        let [area, panel_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(panel_height)]).areas(area);
+        Paragraph::new(panel_lines)
+            .block(
+                Block::bordered()
+                    .title(Line::raw(panel_title).centered())
+                    .border_type(BorderType::Rounded),
+            )
+            .render(panel_area, buf);
+
         let [left_area, center_area, right_area] = Layout::horizontal([
             Constraint::Percentage(40),
             Constraint::Percentage(20),
@@ -506,7 +633,7 @@ impl TopicGraphState {
             .title(Line::raw("Publishers").centered())
             .border_set(LEFT_BORDER_SET);
         StatefulWidget::render(
-            ListWidget::<NodeName>::new()
+            ListWidget::<EndpointItem>::new()
                 .block(left_block)
                 .auto_scroll(true),
             left_area,
@@ -530,7 +657,7 @@ impl TopicGraphState {
             .title(Line::raw("Subscribers").centered())
             .border_set(RIGHT_BORDER_SET);
         StatefulWidget::render(
-            ListWidget::<NodeName>::new()
+            ListWidget::<EndpointItem>::new()
                 .block(right_block)
                 .auto_scroll(true),
             right_area,
@@ -621,6 +748,8 @@ impl TuiView for TopicGraphState {
         - 'j' or ↓ / 'k' or ↑: Move up/down within the focused side panel.\n\
         - 'Enter': Open the NodeInfo/TopicInfo popup for the focused item.\n\
         In Topic mode: left = publisher nodes, right = subscriber nodes.\n\
+        The bottom panel shows the endpoint info (QoS, GID, type hash) of the selected node, or the\n\
+        publisher/subscriber pairs with incompatible QoS (marked ⚠) when the topic is focused.\n\
         In Node mode: left = subscribed topics, right = published topics."
             .to_string()
     }
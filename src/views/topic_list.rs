@@ -1,7 +1,14 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    path::PathBuf,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use ratatui::{
     prelude::{Buffer, Rect},
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, StatefulWidget},
 };
@@ -9,17 +16,63 @@ use ratatui::{
 use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind};
 
 use crate::{
-    common::{event::Event, style::HEADER_STYLE},
-    connections::{Connection, ConnectionType, NamedInterface},
+    common::{
+        event::Event,
+        recorder::{McapRecorder, RecorderStats},
+        style::HEADER_STYLE,
+    },
+    connections::{qos::QosProfile, Connection, ConnectionType, NamedInterface},
     views::{ConnectionInfo, FromConnection, TopicInfo, TuiView},
-    widgets::list_widget::{ListWidget, ListWidgetState},
+    widgets::list_widget::{ListItemTrait, ListWidget, ListWidgetState},
 };
 
 pub struct TopicList;
 
+/// Profiles the recorded topics can be subscribed with, `None` matches their publishers.
+const RECORD_QOS: [(&str, Option<fn() -> QosProfile>); 4] = [
+    ("match publishers", None),
+    ("default", Some(QosProfile::default)),
+    ("sensor_data", Some(QosProfile::sensor_data)),
+    ("transient_local", Some(QosProfile::transient_local)),
+];
+
+#[derive(Clone)]
+struct TopicItem {
+    topic: NamedInterface,
+    marked: bool,
+}
+
+impl ListItemTrait for TopicItem {
+    fn search_text(&self) -> String {
+        self.topic.search_text()
+    }
+
+    fn to_line(&self, width: usize, selected: bool, indices: Vec<u32>) -> Line<'_> {
+        if !self.marked {
+            return self.topic.to_line(width, selected, indices);
+        }
+        let mut line = self
+            .topic
+            .to_line(width.saturating_sub(2), selected, indices);
+        line.spans
+            .insert(0, Span::styled("● ", Style::default().fg(Color::Red)));
+        line
+    }
+}
+
 pub struct TopicListState {
     connection: Rc<RefCell<ConnectionType>>,
-    state: ListWidgetState<NamedInterface>,
+    state: ListWidgetState<TopicItem>,
+
+    // Topics to record
+    marked: HashSet<String>,
+    recorder: Option<McapRecorder>,
+    /// Output file being typed before recording, `None` when not prompting.
+    record_path: Option<String>,
+    /// Index in `RECORD_QOS` of the profile to record with.
+    record_qos: usize,
+    /// Recording status last drawn, to redraw only when it changes.
+    drawn_status: Option<(RecorderStats, u64, Option<String>)>,
 
     last_update: std::time::Instant,
     needs_redraw: bool,
@@ -27,11 +80,23 @@ pub struct TopicListState {
 
 impl TopicListState {
     pub fn new(connection: Rc<RefCell<ConnectionType>>) -> Self {
-        let topics = connection.borrow().list_topics().unwrap();
+        let topics = connection.borrow().list_topics().unwrap_or_default();
+        let items = topics
+            .into_iter()
+            .map(|topic| TopicItem {
+                topic,
+                marked: false,
+            })
+            .collect();
 
         Self {
             connection,
-            state: ListWidgetState::new(topics, Some(0)),
+            state: ListWidgetState::new(items, Some(0)),
+            marked: HashSet::new(),
+            recorder: None,
+            record_path: None,
+            record_qos: 0,
+            drawn_status: None,
             last_update: std::time::Instant::now(),
             needs_redraw: true,
         }
@@ -46,7 +111,130 @@ impl TopicListState {
 
         let mut new_topics = self.connection.borrow().list_topics().unwrap_or_default();
         new_topics.sort_by(|a, b| a.name.cmp(&b.name));
-        self.state.update(new_topics);
+        let items = new_topics
+            .into_iter()
+            .map(|topic| TopicItem {
+                marked: self.marked.contains(&topic.name),
+                topic,
+            })
+            .collect();
+        self.state.update(items);
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(item) = self.state.get_selected_mut() {
+            item.marked = !item.marked;
+            if item.marked {
+                self.marked.insert(item.topic.name.clone());
+            } else {
+                self.marked.remove(&item.topic.name);
+            }
+        }
+    }
+
+    /// Stop the recording if one is running, otherwise prompt for the file to record to.
+    fn toggle_recording(&mut self) -> Result<(), String> {
+        self.needs_redraw = true;
+        if let Some(mut recorder) = self.recorder.take() {
+            return recorder.stop();
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.record_path = Some(format!("splinter_{}.mcap", timestamp));
+        Ok(())
+    }
+
+    fn handle_key_in_record_path(&mut self, code: KeyCode) -> Result<(), String> {
+        let Some(path) = self.record_path.as_mut() else {
+            return Ok(());
+        };
+        self.needs_redraw = true;
+        match code {
+            KeyCode::Char(c) => path.push(c),
+            KeyCode::Backspace => {
+                path.pop();
+            }
+            KeyCode::Tab => self.record_qos = (self.record_qos + 1) % RECORD_QOS.len(),
+            KeyCode::Esc => self.record_path = None,
+            KeyCode::Enter => {
+                let path = self.record_path.take().unwrap_or_default();
+                return self.start_recording(PathBuf::from(path.trim()));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Start recording the marked topics, or the selected one if none is marked, to `path`.
+    fn start_recording(&mut self, path: PathBuf) -> Result<(), String> {
+        if path.as_os_str().is_empty() {
+            return Err("No file to record to".to_string());
+        }
+        let mut topics: Vec<String> = self.marked.iter().cloned().collect();
+        topics.sort();
+        if topics.is_empty() {
+            match self.state.get_selected() {
+                Some(item) => topics.push(item.topic.name.clone()),
+                None => return Err("No topic to record".to_string()),
+            }
+        }
+
+        let qos = RECORD_QOS[self.record_qos].1.map(|profile| profile());
+        self.recorder = Some(McapRecorder::start(
+            &path,
+            &mut self.connection.borrow_mut(),
+            &topics,
+            qos,
+        )?);
+        Ok(())
+    }
+
+    /// Statistics, elapsed seconds and error of the recording, what its status line shows.
+    fn recording_snapshot(&self) -> Option<(RecorderStats, u64, Option<String>)> {
+        self.recorder.as_ref().map(|recorder| {
+            (
+                recorder.stats(),
+                recorder.elapsed().as_secs(),
+                recorder.error(),
+            )
+        })
+    }
+
+    fn recording_status(&self) -> Option<Line<'static>> {
+        if let Some(path) = &self.record_path {
+            return Some(Line::from(vec![
+                Span::styled(" Record to: ", HEADER_STYLE),
+                Span::raw(path.clone()),
+                Span::styled("█", HEADER_STYLE),
+                Span::raw(format!(
+                    " | QoS: {} ('Tab' to change) ",
+                    RECORD_QOS[self.record_qos].0
+                )),
+            ]));
+        }
+        let recorder = self.recorder.as_ref()?;
+        let stats = recorder.stats();
+        let elapsed = recorder.elapsed().as_secs();
+        let mut spans = vec![
+            Span::styled(" ● REC ", Style::default().fg(Color::Red)),
+            Span::raw(format!(
+                "{} topic(s) | {} msgs | {:.1} MB | {:02}:{:02} | {} ",
+                recorder.topic_count(),
+                stats.message_count,
+                stats.bytes_written as f64 / 1_000_000.0,
+                elapsed / 60,
+                elapsed % 60,
+                recorder.path().display(),
+            )),
+        ];
+        if let Some(error) = recorder.error() {
+            spans.push(Span::styled(
+                format!("{} ", error),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        Some(Line::from(spans))
     }
 }
 
@@ -54,6 +242,17 @@ impl TuiView for TopicListState {
     fn handle_event(&mut self, event: Event) -> Event {
         self.update();
 
+        if self.record_path.is_some() {
+            if let Event::Key(CrosstermEvent::Key(key_event)) = &event {
+                if key_event.kind == KeyEventKind::Press {
+                    return match self.handle_key_in_record_path(key_event.code) {
+                        Ok(()) => Event::None,
+                        Err(e) => Event::Error(e),
+                    };
+                }
+            }
+        }
+
         let event = self.state.handle_event(event);
         if let Event::Key(CrosstermEvent::Key(key_event)) = event {
             if key_event.kind != KeyEventKind::Press {
@@ -61,16 +260,25 @@ impl TuiView for TopicListState {
             }
             match key_event.code {
                 KeyCode::Enter => {
-                    if let Some(topic) = self.state.get_selected() {
+                    if let Some(item) = self.state.get_selected() {
                         Event::NewTopic(TopicInfo {
                             connection: self.connection.clone(),
-                            topic: topic.name.clone(),
-                            type_name: topic.type_name.clone(),
+                            topic: item.topic.name.clone(),
+                            type_name: item.topic.type_name.clone(),
+                            qos: None,
                         })
                     } else {
                         event
                     }
                 }
+                KeyCode::Char('m') => {
+                    self.toggle_mark();
+                    Event::None
+                }
+                KeyCode::Char('r') => match self.toggle_recording() {
+                    Ok(()) => Event::None,
+                    Err(e) => Event::Error(e),
+                },
                 _ => event,
             }
         } else {
@@ -88,6 +296,10 @@ impl TuiView for TopicListState {
         - 'j' or ↓: Move down in the topic list.\n\
         - 'k' or ↑: Move up in the topic list.\n\
         - 'Enter': Execute the selected action on the highlighted topic.\n\
+        - 'm': Mark or unmark the highlighted topic for recording.\n\
+        - 'r': Record the marked topics (or the highlighted one) to an MCAP file, or stop the\n\
+        ongoing recording. Type the path of the file, 'Tab' to change the QoS, 'Enter' to start\n\
+        and 'Esc' to cancel.\n\
         Search Mode:\n\
         - Type to filter topics.\n\
         - 'Backspace': Remove the last character from the search filter.\n\
@@ -96,7 +308,11 @@ impl TuiView for TopicListState {
     }
 
     fn needs_redraw(&mut self) -> bool {
-        if (self.state.needs_redraw()) || self.needs_redraw {
+        // Keep the recording status up to date
+        let snapshot = self.recording_snapshot();
+        let status_changed = snapshot != self.drawn_status;
+        self.drawn_status = snapshot;
+        if (self.state.needs_redraw()) || self.needs_redraw || status_changed {
             self.needs_redraw = false;
             true
         } else {
@@ -119,13 +335,66 @@ impl TopicList {
     pub fn render(area: Rect, buf: &mut Buffer, state: &mut TopicListState) {
         let action_text = Line::from_iter([Span::raw(" Topic List ")]).centered();
 
-        let block = Block::bordered()
+        let mut block = Block::bordered()
             .title(action_text)
             .border_style(HEADER_STYLE)
             .border_type(BorderType::Rounded);
+        if let Some(status) = state.recording_status() {
+            block = block.title_bottom(status.left_aligned());
+        }
 
         let topic_list_widget = ListWidget::new().block(block).auto_scroll(true);
 
         topic_list_widget.render(area, buf, &mut state.state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::views::test_utils::{key, mock_connection, render_lines};
+
+    const CONFIG: &str = "\
+definitions:
+  std_msgs/msg/String: string data
+topics:
+  - { name: /chatter, type: std_msgs/msg/String }
+  - { name: /rosout, type: std_msgs/msg/String }
+";
+
+    fn mock_view() -> TopicListState {
+        TopicListState::new(mock_connection(CONFIG))
+    }
+
+    #[test]
+    fn test_render_mock_topics() {
+        let mut view = mock_view();
+        let lines = render_lines(&mut view, 40, 5);
+        assert!(lines[0].contains("Topic List"));
+        assert!(lines.iter().any(|line| line.contains("/chatter")));
+        assert!(lines.iter().any(|line| line.contains("/rosout")));
+    }
+
+    #[test]
+    fn test_record_path_prompt() {
+        let mut view = mock_view();
+        assert!(view.needs_redraw());
+        assert!(!view.needs_redraw());
+
+        view.handle_event(key(KeyCode::Char('r')));
+        assert!(view.needs_redraw());
+        let lines = render_lines(&mut view, 100, 5);
+        assert!(lines[4].contains("Record to: splinter_"));
+        assert!(lines[4].contains("QoS: match publishers"));
+
+        view.handle_event(key(KeyCode::Tab));
+        let lines = render_lines(&mut view, 100, 5);
+        assert!(lines[4].contains("QoS: default"));
+
+        // Esc cancels the prompt without recording
+        view.handle_event(key(KeyCode::Esc));
+        assert!(view.record_path.is_none());
+        assert!(view.recorder.is_none());
+    }
+}
@@ -11,16 +11,16 @@ use crate::{
     common::{
         event::Event,
         generic_message::{
-            AnyTypeMutableRef, BoundedSequenceField, GenericField, GenericMessage, InterfaceType,
-            Length, SequenceField, SimpleField,
+            AnyTypeMutableRef, BoundedSequenceField, GenericMessage, InterfaceType, Length,
+            SequenceField,
         },
         generic_message_selector::{get_field_category, FieldCategory},
         style::HEADER_STYLE,
     },
-    connections::{Connection, ConnectionType},
+    connections::{qos::QosProfile, Connection, ConnectionType},
     views::{
         message_pane::{commit_field_edit, MessagePaneState},
-        FromTopic, TopicInfo, TuiView,
+        TopicInfo, TuiView,
     },
     widgets::message_widget::MessageWidget,
 };
@@ -45,19 +45,21 @@ impl TopicPublisherState {
     pub fn new(
         topic: String,
         topic_type: InterfaceType,
+        qos: Option<QosProfile>,
         connection: Rc<RefCell<ConnectionType>>,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let message_type = MessageTypeName {
             package_name: topic_type.package_name.clone(),
             type_name: topic_type.type_name.clone(),
         };
-        let message = DynamicMessage::new(message_type.clone()).expect("Failed to create message");
+        let message = DynamicMessage::new(message_type.clone())
+            .map_err(|e| format!("Failed to create a '{}' message: {:?}", topic_type, e))?;
         let generic_message = GenericMessage::from(message.view());
         let publisher = connection
             .borrow_mut()
-            .create_publisher(&topic, &topic_type)
-            .expect("Failed to subscribe to topic");
-        Self {
+            .create_publisher(&topic, &topic_type, qos)
+            .map_err(|e| format!("Failed to publish on '{}': {}", topic, e))?;
+        Ok(Self {
             topic,
             _connection: connection,
             publisher,
@@ -67,17 +69,12 @@ impl TopicPublisherState {
             field_content: String::new(),
             needs_redraw: true,
             counter: 0,
-        }
+        })
     }
 
     /// Returns true if the message has a `header` field of type `std_msgs/msg/Header`.
     pub fn has_header_stamp(&self) -> bool {
-        if let Some(GenericField::Simple(SimpleField::Message(header))) = self.message.get("header")
-        {
-            let t = header.type_name();
-            return t.package_name == "std_msgs" && t.category == "msg" && t.type_name == "Header";
-        }
-        false
+        self.message.header().is_some()
     }
 
     /// Sets header.stamp.sec and header.stamp.nanosec to the current system time.
@@ -310,14 +307,20 @@ impl TuiView for TopicPublisherState {
     }
 }
 
-impl FromTopic for TopicPublisherState {
-    fn from_topic(topic_info: TopicInfo) -> Self {
+impl TopicPublisherState {
+    /// Unlike the `FromTopic` views, opening a publisher fails on connections that can't publish.
+    pub fn from_topic(topic_info: TopicInfo) -> Result<Self, String> {
         let message_type = topic_info
             .connection
             .borrow()
             .get_topic_type(&topic_info.topic)
-            .expect("Failed to get topic type");
-        TopicPublisherState::new(topic_info.topic, message_type, topic_info.connection)
+            .ok_or(format!("Unknown type for topic '{}'", topic_info.topic))?;
+        TopicPublisherState::new(
+            topic_info.topic,
+            message_type,
+            topic_info.qos,
+            topic_info.connection,
+        )
     }
 }
 
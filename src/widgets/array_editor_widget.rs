@@ -0,0 +1,413 @@
+use crossterm::event::{Event as CrosstermEvent, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Styled},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::{
+    common::{event::Event, style::SELECTED_STYLE},
+    connections::Parameters,
+};
+
+/// Text being typed in the array editor.
+#[derive(Debug, Clone, PartialEq)]
+enum ArrayInput {
+    /// New value of the selected element.
+    Element(String),
+    /// Whole array in YAML flow syntax, e.g. `[1, 2, 3]`.
+    Paste(String),
+}
+
+/// Elements of an array parameter being edited, as text until the array is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayEditorState {
+    /// The value being edited, gives the type of the elements.
+    original: Parameters,
+    elements: Vec<String>,
+    selected: Option<usize>,
+    input: Option<ArrayInput>,
+}
+
+fn parse_bool(text: &str) -> Result<bool, String> {
+    match text.to_lowercase().as_str() {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(format!("Invalid boolean value '{}'", text)),
+    }
+}
+
+fn parse_elements<T: std::str::FromStr>(elements: &[String], kind: &str) -> Result<Vec<T>, String> {
+    elements
+        .iter()
+        .map(|element| {
+            element
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid {} value '{}'", kind, element))
+        })
+        .collect()
+}
+
+impl ArrayEditorState {
+    /// Editor of an array parameter, `None` for scalar parameters.
+    pub fn new(parameter: &Parameters) -> Option<Self> {
+        let elements = match parameter {
+            Parameters::ByteArray(v) => v.iter().map(u8::to_string).collect(),
+            Parameters::BoolArray(v) => v.iter().map(bool::to_string).collect(),
+            Parameters::IntegerArray(v) => v.iter().map(i64::to_string).collect(),
+            Parameters::DoubleArray(v) => v.iter().map(f64::to_string).collect(),
+            Parameters::StringArray(v) => v.clone(),
+            _ => return None,
+        };
+        Some(Self {
+            original: parameter.clone(),
+            elements,
+            selected: None,
+            input: None,
+        })
+    }
+
+    /// The edited array, with the type of the original value.
+    pub fn to_parameter(&self) -> Result<Parameters, String> {
+        Ok(match self.original {
+            Parameters::ByteArray(_) => {
+                Parameters::ByteArray(parse_elements(&self.elements, "byte")?)
+            }
+            Parameters::BoolArray(_) => Parameters::BoolArray(
+                self.elements
+                    .iter()
+                    .map(|element| parse_bool(element.trim()))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Parameters::IntegerArray(_) => {
+                Parameters::IntegerArray(parse_elements(&self.elements, "integer")?)
+            }
+            Parameters::DoubleArray(_) => {
+                Parameters::DoubleArray(parse_elements(&self.elements, "double")?)
+            }
+            Parameters::StringArray(_) => Parameters::StringArray(self.elements.clone()),
+            _ => return Err("Not an array parameter".to_string()),
+        })
+    }
+
+    /// Replace the elements with an array in YAML flow syntax, e.g. `[1, 2, 3]` or `[a, "b c"]`.
+    pub fn paste(&mut self, text: &str) -> Result<(), String> {
+        let values: Vec<serde_yaml::Value> =
+            serde_yaml::from_str(text).map_err(|e| format!("Invalid array: {}", e))?;
+        let elements = values
+            .into_iter()
+            .map(|value| match value {
+                serde_yaml::Value::String(s) => Ok(s),
+                serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+                serde_yaml::Value::Number(n) => Ok(n.to_string()),
+                other => Err(format!("Invalid array element: {:?}", other)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let previous = std::mem::replace(&mut self.elements, elements);
+        if let Err(e) = self.to_parameter() {
+            self.elements = previous;
+            return Err(e);
+        }
+        self.selected = None;
+        Ok(())
+    }
+
+    /// Rows below the parameter: one per element, then the key hints or the input.
+    pub fn height(&self) -> u16 {
+        self.elements.len() as u16 + 1
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    fn select_next(&mut self) {
+        if !self.elements.is_empty() {
+            self.selected = Some(
+                self.selected
+                    .map_or(0, |selected| (selected + 1).min(self.elements.len() - 1)),
+            );
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if !self.elements.is_empty() {
+            self.selected = Some(self.selected.map_or(self.elements.len() - 1, |selected| {
+                selected.saturating_sub(1)
+            }));
+        }
+    }
+
+    /// Move the selected element by `offset` positions.
+    fn move_selected(&mut self, offset: isize) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let target = selected as isize + offset;
+        if target >= 0 && (target as usize) < self.elements.len() {
+            self.elements.swap(selected, target as usize);
+            self.selected = Some(target as usize);
+        }
+    }
+
+    /// Insert an empty element after the selected one, and start editing it.
+    fn insert(&mut self) {
+        let index = self
+            .selected
+            .map_or(self.elements.len(), |selected| selected + 1);
+        self.elements.insert(index, String::new());
+        self.selected = Some(index);
+        self.input = Some(ArrayInput::Element(String::new()));
+    }
+
+    fn delete(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        self.elements.remove(selected);
+        self.selected = if self.elements.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.elements.len() - 1))
+        };
+    }
+
+    fn commit_input(&mut self) -> Result<(), String> {
+        match self.input.take() {
+            Some(ArrayInput::Element(text)) => {
+                let Some(selected) = self.selected else {
+                    return Ok(());
+                };
+                let previous = std::mem::replace(&mut self.elements[selected], text.clone());
+                if let Err(e) = self.to_parameter() {
+                    self.elements[selected] = previous;
+                    self.input = Some(ArrayInput::Element(text));
+                    return Err(e);
+                }
+                Ok(())
+            }
+            Some(ArrayInput::Paste(text)) => self.paste(&text).inspect_err(|_| {
+                self.input = Some(ArrayInput::Paste(text.clone()));
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Handle the editing keys. Returns the keys left to the caller: 's' to send the array and
+    /// 'Esc' to leave the editor, outside of text input.
+    pub fn handle_event(&mut self, event: Event) -> Event {
+        let Event::Key(CrosstermEvent::Key(key_event)) = event else {
+            return event;
+        };
+        if let Some(ArrayInput::Element(text) | ArrayInput::Paste(text)) = &mut self.input {
+            match key_event.code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    if let Err(e) = self.commit_input() {
+                        return Event::Error(e);
+                    }
+                }
+                KeyCode::Esc => {
+                    // An element inserted but never typed is dropped
+                    if let (Some(ArrayInput::Element(_)), Some(selected)) =
+                        (&self.input, self.selected)
+                    {
+                        if self.elements[selected].is_empty()
+                            && !matches!(self.original, Parameters::StringArray(_))
+                        {
+                            self.delete();
+                        }
+                    }
+                    self.input = None;
+                }
+                _ => {}
+            }
+            return Event::None;
+        }
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+            KeyCode::Char('J') => self.move_selected(1),
+            KeyCode::Char('K') => self.move_selected(-1),
+            KeyCode::Char('i') => self.insert(),
+            KeyCode::Char('d') => self.delete(),
+            KeyCode::Char('p') => {
+                let flow = self
+                    .to_parameter()
+                    .map(|parameter| parameter.to_json().to_string())
+                    .unwrap_or_default();
+                self.input = Some(ArrayInput::Paste(flow));
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.selected {
+                    self.input = Some(ArrayInput::Element(self.elements[selected].clone()));
+                }
+            }
+            _ => return Event::Key(CrosstermEvent::Key(key_event)),
+        }
+        Event::None
+    }
+}
+
+/// Elements of an array parameter being edited, rendered below the parameter.
+pub struct ArrayEditorWidget<'a> {
+    state: &'a ArrayEditorState,
+}
+
+impl<'a> ArrayEditorWidget<'a> {
+    pub fn new(state: &'a ArrayEditorState) -> Self {
+        Self { state }
+    }
+}
+
+impl Widget for ArrayEditorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let state = self.state;
+        let hint_style = Style::default().fg(Color::DarkGray);
+        let rows = state.elements.iter().enumerate().map(|(index, element)| {
+            let is_selected = state.selected == Some(index);
+            let value = match (&state.input, is_selected) {
+                (Some(ArrayInput::Element(text)), true) => {
+                    Span::raw(text.clone()).set_style(SELECTED_STYLE.fg(Color::Green))
+                }
+                (_, true) => Span::raw(element.clone()).set_style(SELECTED_STYLE),
+                _ => Span::raw(element.clone()),
+            };
+            Line::from(vec![
+                Span::styled(format!("[{}] ", index), hint_style),
+                value,
+            ])
+        });
+        let last_row = match &state.input {
+            Some(ArrayInput::Paste(text)) => Line::from(vec![
+                Span::styled("paste: ", hint_style),
+                Span::raw(text.clone()).set_style(SELECTED_STYLE.fg(Color::Green)),
+            ]),
+            _ => Line::styled(
+                "i insert, d delete, J/K move, Enter edit, p paste, s send, Esc cancel",
+                hint_style,
+            ),
+        };
+
+        for (row, line) in rows.chain(std::iter::once(last_row)).enumerate() {
+            let y = area.y + row as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            line.render(
+                Rect {
+                    x: area.x,
+                    y,
+                    width: area.width,
+                    height: 1,
+                },
+                buf,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(CrosstermEvent::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn type_text(editor: &mut ArrayEditorState, text: &str) {
+        for c in text.chars() {
+            editor.handle_event(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_edit_elements() {
+        let mut editor = ArrayEditorState::new(&Parameters::IntegerArray(vec![1, 2, 3])).unwrap();
+        assert!(ArrayEditorState::new(&Parameters::Integer(1)).is_none());
+
+        // Move the first element to the end
+        editor.handle_event(key(KeyCode::Char('j')));
+        editor.handle_event(key(KeyCode::Char('J')));
+        editor.handle_event(key(KeyCode::Char('J')));
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::IntegerArray(vec![2, 3, 1]))
+        );
+
+        // Insert after the selected element, then delete the first one
+        editor.handle_event(key(KeyCode::Char('i')));
+        type_text(&mut editor, "42");
+        editor.handle_event(key(KeyCode::Enter));
+        editor.handle_event(key(KeyCode::Char('k')));
+        editor.handle_event(key(KeyCode::Char('k')));
+        editor.handle_event(key(KeyCode::Char('k')));
+        editor.handle_event(key(KeyCode::Char('d')));
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::IntegerArray(vec![3, 1, 42]))
+        );
+
+        // Invalid values are refused and stay in the input
+        editor.handle_event(key(KeyCode::Enter));
+        type_text(&mut editor, "x");
+        assert!(matches!(
+            editor.handle_event(key(KeyCode::Enter)),
+            Event::Error(_)
+        ));
+        editor.handle_event(key(KeyCode::Esc));
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::IntegerArray(vec![3, 1, 42]))
+        );
+
+        // Keys the editor doesn't use are left to the caller
+        assert!(matches!(
+            editor.handle_event(key(KeyCode::Char('s'))),
+            Event::Key(_)
+        ));
+    }
+
+    #[test]
+    fn test_paste() {
+        let mut editor =
+            ArrayEditorState::new(&Parameters::StringArray(vec!["a".to_string()])).unwrap();
+        editor.paste("[b, \"c d\", 3]").unwrap();
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::StringArray(vec![
+                "b".to_string(),
+                "c d".to_string(),
+                "3".to_string()
+            ]))
+        );
+
+        let mut editor = ArrayEditorState::new(&Parameters::DoubleArray(vec![])).unwrap();
+        assert!(editor.paste("[1.5, x]").is_err());
+        assert_eq!(editor.to_parameter(), Ok(Parameters::DoubleArray(vec![])));
+        editor.paste("[1.5, 2]").unwrap();
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::DoubleArray(vec![1.5, 2.0]))
+        );
+
+        let mut editor = ArrayEditorState::new(&Parameters::BoolArray(vec![true])).unwrap();
+        editor.handle_event(key(KeyCode::Char('p')));
+        assert_eq!(editor.input, Some(ArrayInput::Paste("[true]".to_string())));
+        editor.handle_event(key(KeyCode::Backspace));
+        type_text(&mut editor, ", no]");
+        editor.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            editor.to_parameter(),
+            Ok(Parameters::BoolArray(vec![true, false]))
+        );
+    }
+}
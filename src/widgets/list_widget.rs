@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use ratatui::{
     crossterm::event::{Event as CrosstermEvent, KeyCode},
     prelude::{BlockExt, Buffer, Rect},
@@ -13,6 +15,16 @@ use nucleo_matcher::{
 
 use crate::{common::event::Event, widgets::TuiWidget};
 
+/// Fuzzy pattern of a search filter, matched the same way by every searchable view.
+pub fn search_pattern(filter: &str) -> Pattern {
+    Pattern::new(
+        filter,
+        CaseMatching::Ignore,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+    )
+}
+
 pub trait ListItemTrait {
     fn search_text(&self) -> String;
 
@@ -48,6 +60,8 @@ where
     // Selection
     selected_index: Option<usize>,
     scroll_offset: usize,
+    /// Rows of the last render.
+    visible_rows: usize,
 
     // Search
     filter: String,
@@ -68,6 +82,7 @@ where
             items: items.iter().map(|item| (item.clone(), 1)).collect(),
             selected_index,
             scroll_offset: 0,
+            visible_rows: 0,
             filter: "".to_string(),
             hidden_nodes_count: 0,
             matcher: Matcher::new(Config::DEFAULT),
@@ -122,12 +137,7 @@ where
             self.items.iter_mut().for_each(|(_, rank)| *rank = 1);
             self.hidden_nodes_count = 0;
         } else {
-            let pattern = Pattern::new(
-                &self.filter,
-                CaseMatching::Ignore,
-                Normalization::Smart,
-                AtomKind::Fuzzy,
-            );
+            let pattern = search_pattern(&self.filter);
             self.items.iter_mut().for_each(|(item, rank)| {
                 *rank = pattern
                     .score(
@@ -175,6 +185,34 @@ where
         self.needs_redraw = true;
     }
 
+    /// Like `update`, also refreshing the items whose content changed while the list kept the
+    /// same entries, e.g. an extra column.
+    pub fn update_items(&mut self, new_items: Vec<ItemType>) {
+        let keys: HashSet<String> = self
+            .items
+            .iter()
+            .map(|(item, _)| item.search_text())
+            .collect();
+        let same_entries = new_items.len() == self.items.len()
+            && new_items
+                .iter()
+                .all(|new_item| keys.contains(&new_item.search_text()));
+        if !same_entries {
+            self.update(new_items);
+            return;
+        }
+        let mut new_items: HashMap<String, ItemType> = new_items
+            .into_iter()
+            .map(|new_item| (new_item.search_text(), new_item))
+            .collect();
+        for (item, _) in self.items.iter_mut() {
+            if let Some(new_item) = new_items.remove(&item.search_text()) {
+                *item = new_item;
+            }
+        }
+        self.needs_redraw = true;
+    }
+
     pub fn handle_event_in_normal(&mut self, event: Event) -> Event {
         match event {
             Event::Key(CrosstermEvent::Key(key)) => match key.code {
@@ -254,6 +292,27 @@ where
         }
     }
 
+    pub fn get_selected_mut(&mut self) -> Option<&mut ItemType> {
+        let selected_index = self.selected_index?;
+        self.needs_redraw = true;
+        self.items.get_mut(selected_index).map(|(item, _)| item)
+    }
+
+    /// Iterate over all the items, including those hidden by the search filter.
+    pub fn items(&self) -> impl Iterator<Item = &ItemType> {
+        self.items.iter().map(|(item, _)| item)
+    }
+
+    /// Items shown by the last render, none before the first one.
+    pub fn visible_items(&self) -> impl Iterator<Item = &ItemType> {
+        self.items
+            .iter()
+            .skip(self.scroll_offset)
+            .take(self.visible_rows)
+            .take_while(|(_, rank)| *rank != 0)
+            .map(|(item, _)| item)
+    }
+
     pub fn get_selected_index(&self) -> Option<usize> {
         self.selected_index
     }
@@ -367,15 +426,12 @@ impl<'a, ItemType: ListItemTrait> StatefulWidget for ListWidget<'a, ItemType> {
             }
         }
 
+        state.visible_rows = topic_list_area.height as usize;
+
         let pattern = if state.filter.is_empty() {
             None
         } else {
-            Some(Pattern::new(
-                &state.filter,
-                CaseMatching::Ignore,
-                Normalization::Smart,
-                AtomKind::Fuzzy,
-            ))
+            Some(search_pattern(&state.filter))
         };
 
         // Iterate through all elements in the `items` and stylize them.
@@ -639,4 +695,45 @@ mod tests {
 
         assert_eq!(buffer, expexted);
     }
+
+    #[test]
+    fn test_update_items() {
+        let item = |name: &str, interface_type: &str| TestItem {
+            name: name.to_string(),
+            interface_type: interface_type.to_string(),
+        };
+        let mut state = ListWidgetState::new(vec![item("a", "active"), item("b", "")], Some(1));
+
+        // Same entries, refreshed column
+        state.update_items(vec![item("b", "inactive"), item("a", "active")]);
+        assert_eq!(state.get_selected_index(), Some(1));
+        assert_eq!(state.get_selected().unwrap().interface_type, "inactive");
+
+        // New entries go through `update`
+        state.update_items(vec![item("a", ""), item("b", ""), item("c", "")]);
+        assert_eq!(state.items().count(), 3);
+        assert_eq!(state.get_selected().unwrap().name, "b");
+    }
+
+    #[test]
+    fn test_visible_items() {
+        let items: Vec<TestItem> = (0..10)
+            .map(|i| TestItem {
+                name: format!("topic{i}"),
+                interface_type: String::new(),
+            })
+            .collect();
+        let mut state = ListWidgetState::new(items, Some(0));
+        assert_eq!(state.visible_items().count(), 0);
+
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buffer = Buffer::empty(area);
+        ListWidget::<TestItem>::new().render(area, &mut buffer, &mut state);
+
+        let visible: Vec<_> = state
+            .visible_items()
+            .map(|item| item.name.clone())
+            .collect();
+        assert_eq!(visible, ["topic0", "topic1", "topic2", "topic3"]);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::common::event::Event;
 
+pub mod array_editor_widget;
 pub mod edit_value_widget;
 pub mod list_widget;
 pub mod message_widget;
@@ -1,22 +1,32 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Styled,
+    style::{Color, Style, Styled},
     text::{Line, Span},
     widgets::{Block, Widget},
 };
 
 use crate::{
-    common::style::SELECTED_STYLE, connections::Parameters,
-    widgets::edit_value_widget::EditableValueWidget,
+    common::style::SELECTED_STYLE,
+    connections::{
+        parameter_descriptor::ParameterDescriptor, parameter_file::ParameterChange, Parameters,
+    },
+    widgets::{
+        array_editor_widget::{ArrayEditorState, ArrayEditorWidget},
+        edit_value_widget::EditableValueWidget,
+    },
 };
 
 pub struct ParameterListWidget<'a> {
     parameters: &'a BTreeMap<String, Parameters>,
+    descriptors: Option<&'a HashMap<String, ParameterDescriptor>>,
+    errors: Option<&'a HashMap<String, String>>,
     selected: Option<usize>,
     edit: Option<String>,
+    array_editor: Option<&'a ArrayEditorState>,
+    changes: Option<&'a [ParameterChange]>,
     block: Option<Block<'a>>,
 }
 
@@ -24,12 +34,28 @@ impl<'a> ParameterListWidget<'a> {
     pub fn new(parameters: &'a BTreeMap<String, Parameters>) -> Self {
         Self {
             parameters,
+            descriptors: None,
+            errors: None,
             selected: None,
             edit: None,
+            array_editor: None,
+            changes: None,
             block: None,
         }
     }
 
+    /// Show the type, constraints and description of the parameters next to their value.
+    pub fn descriptors(mut self, descriptors: &'a HashMap<String, ParameterDescriptor>) -> Self {
+        self.descriptors = Some(descriptors);
+        self
+    }
+
+    /// Show why the node rejected a value next to the parameter.
+    pub fn errors(mut self, errors: &'a HashMap<String, String>) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+
     pub fn selected(mut self, selected: Option<usize>) -> Self {
         self.selected = selected;
         self
@@ -40,6 +66,26 @@ impl<'a> ParameterListWidget<'a> {
         self
     }
 
+    /// Show the elements of the selected array parameter being edited below it.
+    pub fn array_editor(mut self, array_editor: Option<&'a ArrayEditorState>) -> Self {
+        self.array_editor = array_editor;
+        self
+    }
+
+    /// Show the changes loaded from a parameter file in place of the values, the new parameters
+    /// after the others.
+    pub fn changes(mut self, changes: Option<&'a [ParameterChange]>) -> Self {
+        self.changes = changes;
+        self
+    }
+
+    fn new_parameters(&self) -> impl Iterator<Item = &'a ParameterChange> {
+        self.changes
+            .unwrap_or_default()
+            .iter()
+            .filter(|change| change.old.is_none())
+    }
+
     pub fn block(mut self, block: Block<'a>) -> Self {
         self.block = Some(block);
         self
@@ -47,11 +93,14 @@ impl<'a> ParameterListWidget<'a> {
 
     pub fn height(&self) -> u16 {
         self.parameters.len() as u16
+            + self.array_editor.map_or(0, ArrayEditorState::height)
+            + self.new_parameters().count() as u16
     }
 }
 
 impl Widget for ParameterListWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let new_parameters: Vec<_> = self.new_parameters().collect();
         let block = self.block.unwrap_or_default();
         let inner_area = block.inner(area);
         block.render(area, buf);
@@ -67,7 +116,16 @@ impl Widget for ParameterListWidget<'_> {
 
             let param_name_widget = Span::raw(format!("{}: ", name));
 
-            let value_widget = if is_selected {
+            let change = self
+                .changes
+                .and_then(|changes| changes.iter().find(|change| &change.name == name));
+
+            let value_widget = if let Some(change) = change {
+                Span::styled(
+                    format!("{} → {}", value, change.new),
+                    Style::default().fg(Color::Yellow),
+                )
+            } else if is_selected {
                 if let Some(edit) = &self.edit {
                     match value {
                         Parameters::Bool(v) => EditableValueWidget::new(v, edit).into(),
@@ -86,7 +144,23 @@ impl Widget for ParameterListWidget<'_> {
             } else {
                 Span::raw(value.to_string())
             };
-            Line::from(vec![param_name_widget, value_widget]).render(
+            let mut spans = vec![param_name_widget, value_widget];
+            if let Some(descriptor) = self.descriptors.and_then(|d| d.get(name)) {
+                let summary = descriptor.summary();
+                if !summary.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  ({})", summary),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+            if let Some(error) = self.errors.and_then(|e| e.get(name)) {
+                spans.push(Span::styled(
+                    format!("  ✗ {}", error),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            Line::from(spans).render(
                 Rect {
                     x: inner_area.x,
                     y,
@@ -98,6 +172,45 @@ impl Widget for ParameterListWidget<'_> {
 
             y += 1;
             index += 1;
+
+            if let (true, Some(array_editor)) = (is_selected, self.array_editor) {
+                let height = array_editor
+                    .height()
+                    .min(inner_area.bottom().saturating_sub(y));
+                ArrayEditorWidget::new(array_editor).render(
+                    Rect {
+                        x: inner_area.x + 2,
+                        y,
+                        width: inner_area.width.saturating_sub(2),
+                        height,
+                    },
+                    buf,
+                );
+                y += height;
+            }
+        }
+
+        for change in new_parameters {
+            if y >= inner_area.bottom() {
+                break;
+            }
+            Line::from(vec![
+                Span::raw(format!("{}: ", change.name)),
+                Span::styled(
+                    format!("{} (new)", change.new),
+                    Style::default().fg(Color::Green),
+                ),
+            ])
+            .render(
+                Rect {
+                    x: inner_area.x,
+                    y,
+                    width: inner_area.width,
+                    height: 1,
+                },
+                buf,
+            );
+            y += 1;
         }
     }
 }